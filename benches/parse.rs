@@ -1,14 +1,11 @@
-#![feature(test)]
-use criterion::{criterion_group, criterion_main, Criterion};
-use geojson;
-extern crate test;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
 
 fn parse_benchmark(c: &mut Criterion) {
     c.bench_function("parse (countries.geojson)", |b| {
         let geojson_str = include_str!("../tests/fixtures/countries.geojson");
 
         b.iter(|| {
-            let _ = test::black_box(geojson_str.parse::<geojson::GeoJson>());
+            let _ = black_box(geojson_str.parse::<geojson::GeoJson>());
         });
     });
 
@@ -16,7 +13,24 @@ fn parse_benchmark(c: &mut Criterion) {
         let geojson_str = include_str!("../tests/fixtures/geometry_collection.geojson");
 
         b.iter(|| {
-            let _ = test::black_box(geojson_str.parse::<geojson::GeoJson>());
+            let _ = black_box(geojson_str.parse::<geojson::GeoJson>());
+        });
+    });
+
+    c.bench_function("deserialize (countries.geojson)", |b| {
+        let geojson_str = include_str!("../tests/fixtures/countries.geojson");
+
+        b.iter(|| {
+            let _ = black_box(serde_json::from_str::<geojson::GeoJson>(geojson_str));
+        });
+    });
+
+    c.bench_function("from_slice (countries.geojson)", |b| {
+        let geojson_str = include_str!("../tests/fixtures/countries.geojson");
+
+        b.iter(|| {
+            let mut bytes = geojson_str.as_bytes().to_vec();
+            let _ = black_box(geojson::from_slice::<geojson::GeoJson>(&mut bytes));
         });
     });
 }
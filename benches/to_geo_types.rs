@@ -1,7 +1,4 @@
-#![feature(test)]
-use criterion::{criterion_group, criterion_main, Criterion};
-
-extern crate test;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
 
 fn parse_benchmark(c: &mut Criterion) {
     let geojson_str = include_str!("../tests/fixtures/countries.geojson");
@@ -10,7 +7,7 @@ fn parse_benchmark(c: &mut Criterion) {
     c.bench_function("quick_collection", move |b| {
         b.iter(|| {
             let _: Result<geo_types::GeometryCollection<f64>, _> =
-                test::black_box(geojson::quick_collection(&geojson));
+                black_box(geojson::quick_collection(&geojson));
         });
     });
 }
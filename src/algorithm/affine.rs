@@ -0,0 +1,229 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::PositionsMut;
+use crate::{Feature, FeatureCollection, GeoJson, Geometry, Value};
+use std::convert::Infallible;
+
+/// A 2D affine transformation, the matrix
+///
+/// ```text
+/// | a b xoff |
+/// | d e yoff |
+/// | 0 0 1    |
+/// ```
+///
+/// which maps `(x, y)` to `(a * x + b * y + xoff, d * x + e * y + yoff)`.
+///
+/// Transformations are built from a matrix with [`new`](#method.new) or `From<[[f64; 3]; 2]>`,
+/// or from the translation, scaling, rotation and skewing constructors, and combined with
+/// [`then`](#method.then).
+///
+/// # Example
+///
+/// ```
+/// use geojson::{position, AffineTransform, Value};
+///
+/// // Rotate a quarter turn counter-clockwise about (1, 1), then move 10 units east
+/// let transform = AffineTransform::rotate(90.0, (1.0, 1.0)).then(&AffineTransform::translate(10.0, 0.0));
+///
+/// let mut point = Value::Point(position![2.0, 1.0]);
+/// point.affine_transform(&transform);
+/// point.round_coords(9);
+/// assert_eq!(point, Value::Point(position![11.0, 2.0]));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AffineTransform([[f64; 3]; 2]);
+
+impl AffineTransform {
+    /// The transformation with the matrix `[[a, b, xoff], [d, e, yoff]]`
+    pub fn new(a: f64, b: f64, xoff: f64, d: f64, e: f64, yoff: f64) -> Self {
+        AffineTransform([[a, b, xoff], [d, e, yoff]])
+    }
+
+    /// The transformation which leaves every coordinate unchanged
+    pub fn identity() -> Self {
+        AffineTransform::new(1.0, 0.0, 0.0, 0.0, 1.0, 0.0)
+    }
+
+    /// Move every coordinate by `(dx, dy)`
+    pub fn translate(dx: f64, dy: f64) -> Self {
+        AffineTransform::new(1.0, 0.0, dx, 0.0, 1.0, dy)
+    }
+
+    /// Scale by `sx` horizontally and `sy` vertically, about `origin`
+    pub fn scale(sx: f64, sy: f64, origin: (f64, f64)) -> Self {
+        let (x, y) = origin;
+        AffineTransform::new(sx, 0.0, x - x * sx, 0.0, sy, y - y * sy)
+    }
+
+    /// Rotate counter-clockwise by `degrees` about `origin`
+    pub fn rotate(degrees: f64, origin: (f64, f64)) -> Self {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        let (x, y) = origin;
+        AffineTransform::new(
+            cos,
+            -sin,
+            x - x * cos + y * sin,
+            sin,
+            cos,
+            y - x * sin - y * cos,
+        )
+    }
+
+    /// Shear by `x_degrees` along the x axis and `y_degrees` along the y axis, about `origin`
+    pub fn skew(x_degrees: f64, y_degrees: f64, origin: (f64, f64)) -> Self {
+        let (tan_x, tan_y) = (x_degrees.to_radians().tan(), y_degrees.to_radians().tan());
+        let (x, y) = origin;
+        AffineTransform::new(1.0, tan_x, -y * tan_x, tan_y, 1.0, -x * tan_y)
+    }
+
+    /// The transformation which applies this one and then `next`
+    pub fn then(&self, next: &AffineTransform) -> Self {
+        let [[a, b, c], [d, e, f]] = self.0;
+        let [[na, nb, nc], [nd, ne, nf]] = next.0;
+        AffineTransform::new(
+            na * a + nb * d,
+            na * b + nb * e,
+            na * c + nb * f + nc,
+            nd * a + ne * d,
+            nd * b + ne * e,
+            nd * c + ne * f + nf,
+        )
+    }
+
+    /// The transformation which undoes this one, or `None` if it isn't invertible, e.g. because
+    /// it scales by zero
+    pub fn inverse(&self) -> Option<Self> {
+        let [[a, b, c], [d, e, f]] = self.0;
+        let determinant = a * e - b * d;
+        if determinant == 0.0 || !determinant.is_finite() {
+            return None;
+        }
+        let (a, b, d, e) = (
+            e / determinant,
+            -b / determinant,
+            -d / determinant,
+            a / determinant,
+        );
+        Some(AffineTransform::new(
+            a,
+            b,
+            -(a * c + b * f),
+            d,
+            e,
+            -(d * c + e * f),
+        ))
+    }
+
+    /// The matrix `[[a, b, xoff], [d, e, yoff]]` of this transformation
+    pub fn matrix(&self) -> [[f64; 3]; 2] {
+        self.0
+    }
+
+    /// Apply this transformation to `(x, y)`
+    pub fn apply(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        let [[a, b, c], [d, e, f]] = self.0;
+        (a * x + b * y + c, d * x + e * y + f)
+    }
+}
+
+impl Default for AffineTransform {
+    fn default() -> Self {
+        AffineTransform::identity()
+    }
+}
+
+impl From<[[f64; 3]; 2]> for AffineTransform {
+    fn from(matrix: [[f64; 3]; 2]) -> Self {
+        AffineTransform(matrix)
+    }
+}
+
+fn affine_transform(target: &mut impl PositionsMut, transform: &AffineTransform) {
+    target.for_each_bbox_mut(&mut |bbox| *bbox = None);
+    let _ = target.try_for_each_position_mut(&mut |position| {
+        if let [x, y, ..] = position.as_mut_slice() {
+            let (new_x, new_y) = transform.apply((*x, *y));
+            *x = new_x;
+            *y = new_y;
+        }
+        Ok::<_, Infallible>(())
+    });
+}
+
+macro_rules! impl_affine_transform {
+    ($($type:ty),*) => {
+        $(
+            impl $type {
+                /// Apply `transform` to the `(x, y)` of every position, in place, and clear any
+                /// `bbox`es made stale.
+                ///
+                /// See [`AffineTransform`](struct.AffineTransform.html).
+                pub fn affine_transform(&mut self, transform: &AffineTransform) {
+                    affine_transform(self, transform)
+                }
+            }
+        )*
+    };
+}
+
+impl_affine_transform!(Value, Geometry, Feature, FeatureCollection, GeoJson);
+
+#[cfg(test)]
+mod tests {
+    use super::AffineTransform;
+    use crate::{Feature, Geometry, Value};
+
+    fn assert_close(actual: (f64, f64), expected: (f64, f64)) {
+        assert!(
+            (actual.0 - expected.0).abs() < 1e-9 && (actual.1 - expected.1).abs() < 1e-9,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn constructors() {
+        assert_close(
+            AffineTransform::scale(2.0, 3.0, (1.0, 1.0)).apply((2.0, 2.0)),
+            (3.0, 4.0),
+        );
+        assert_close(
+            AffineTransform::skew(45.0, 0.0, (0.0, 1.0)).apply((0.0, 2.0)),
+            (1.0, 2.0),
+        );
+        let transform = AffineTransform::from([[2.0, 1.0, 5.0], [0.5, 3.0, -1.0]]);
+        let inverse = transform.inverse().unwrap();
+        assert_close(inverse.apply(transform.apply((7.0, -3.0))), (7.0, -3.0));
+        assert_close(transform.then(&inverse).apply((7.0, -3.0)), (7.0, -3.0));
+        assert_eq!(AffineTransform::scale(0.0, 1.0, (0.0, 0.0)).inverse(), None);
+    }
+
+    #[test]
+    fn transform_feature() {
+        let mut feature = Feature::from(Geometry::new(Value::LineString(vec![
+            position![0.0, 0.0, 100.0],
+            position![1.0, 1.0],
+        ])));
+        feature.bbox = Some(vec![0.0, 0.0, 1.0, 1.0]);
+        feature.affine_transform(&AffineTransform::new(2.0, 0.0, 1.0, 0.0, -1.0, 0.0));
+        assert_eq!(feature.bbox, None);
+        assert_eq!(
+            feature.geometry.unwrap().value,
+            Value::LineString(vec![position![1.0, 0.0, 100.0], position![3.0, -1.0]])
+        );
+    }
+}
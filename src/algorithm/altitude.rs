@@ -0,0 +1,151 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::PositionsMut;
+use crate::{Feature, FeatureCollection, GeoJson, Geometry, Value};
+use std::convert::Infallible;
+
+fn force_2d(target: &mut impl PositionsMut) {
+    target.for_each_bbox_mut(&mut |bbox| {
+        if let Some(corners) = bbox {
+            let dimensions = corners.len() / 2;
+            if dimensions > 2 {
+                // [min_x, min_y, min_z, max_x, max_y, max_z] -> [min_x, min_y, max_x, max_y]
+                *corners = vec![
+                    corners[0],
+                    corners[1],
+                    corners[dimensions],
+                    corners[dimensions + 1],
+                ];
+            }
+        }
+    });
+    let _ = target.try_for_each_position_mut(&mut |position| {
+        position.truncate(2);
+        Ok::<_, Infallible>(())
+    });
+}
+
+fn set_altitude_with(target: &mut impl PositionsMut, mut altitude: impl FnMut(f64, f64) -> f64) {
+    target.for_each_bbox_mut(&mut |bbox| *bbox = None);
+    let _ = target.try_for_each_position_mut(&mut |position| {
+        match position.len() {
+            0 | 1 => {}
+            2 => position.push(altitude(position[0], position[1])),
+            _ => position[2] = altitude(position[0], position[1]),
+        }
+        Ok::<_, Infallible>(())
+    });
+}
+
+macro_rules! impl_altitude {
+    ($($type:ty),*) => {
+        $(
+            impl $type {
+                /// Remove the altitude, and any further elements, from every position and from
+                /// any `bbox`, in place.
+                ///
+                /// Many renderers and databases only accept two-dimensional coordinates.
+                ///
+                /// ```
+                /// use geojson::{position, Value};
+                ///
+                /// let mut line = Value::LineString(vec![position![1.0, 2.0, 30.0], position![3.0, 4.0]]);
+                /// line.force_2d();
+                /// assert_eq!(line, Value::LineString(vec![position![1.0, 2.0], position![3.0, 4.0]]));
+                /// ```
+                #[doc(alias = "drop_altitude")]
+                pub fn force_2d(&mut self) {
+                    force_2d(self)
+                }
+
+                /// Set the altitude of every position to `altitude(x, y)`, in place, e.g. from a
+                /// lookup in a digital elevation model.
+                ///
+                /// Existing altitudes are replaced, and any further elements of the positions are
+                /// kept. `bbox`es are cleared, since their altitude range is no longer known.
+                ///
+                /// ```
+                /// use geojson::{position, Value};
+                ///
+                /// let mut line = Value::LineString(vec![position![1.0, 2.0], position![3.0, 4.0, 0.0]]);
+                /// line.set_altitude_with(|x, y| x + y);
+                /// assert_eq!(
+                ///     line,
+                ///     Value::LineString(vec![position![1.0, 2.0, 3.0], position![3.0, 4.0, 7.0]])
+                /// );
+                /// ```
+                pub fn set_altitude_with(&mut self, altitude: impl FnMut(f64, f64) -> f64) {
+                    set_altitude_with(self, altitude)
+                }
+            }
+        )*
+    };
+}
+
+impl_altitude!(Value, Geometry, Feature, FeatureCollection, GeoJson);
+
+#[cfg(test)]
+mod tests {
+    use crate::{Feature, Geometry, Value};
+
+    #[test]
+    fn force_2d_feature() {
+        let mut feature = Feature::from(Geometry {
+            bbox: Some(vec![0.0, 1.0, 10.0, 2.0, 3.0, 20.0]),
+            value: Value::MultiPoint(vec![
+                position![0.0, 1.0, 10.0, 0.5],
+                position![2.0, 3.0, 20.0],
+            ]),
+            foreign_members: None,
+        });
+        feature.bbox = Some(vec![0.0, 1.0, 2.0, 3.0]);
+        feature.force_2d();
+
+        assert_eq!(feature.bbox, Some(vec![0.0, 1.0, 2.0, 3.0]));
+        let geometry = feature.geometry.unwrap();
+        assert_eq!(geometry.bbox, Some(vec![0.0, 1.0, 2.0, 3.0]));
+        assert_eq!(
+            geometry.value,
+            Value::MultiPoint(vec![position![0.0, 1.0], position![2.0, 3.0]])
+        );
+    }
+
+    #[test]
+    fn set_altitude_of_collection() {
+        let mut geometry = Geometry {
+            bbox: Some(vec![0.0, 0.0, 1.0, 1.0]),
+            value: Value::GeometryCollection(vec![
+                Geometry::new(Value::Point(position![1.0, 1.0, 5.0, 0.25])),
+                Geometry::new(Value::Point(position![0.0])),
+            ]),
+            foreign_members: None,
+        };
+        let mut calls = 0;
+        geometry.set_altitude_with(|x, y| {
+            calls += 1;
+            x * 100.0 + y
+        });
+
+        assert_eq!(calls, 1);
+        assert_eq!(geometry.bbox, None);
+        assert_eq!(
+            geometry.value,
+            Value::GeometryCollection(vec![
+                Geometry::new(Value::Point(position![1.0, 1.0, 101.0, 0.25])),
+                Geometry::new(Value::Point(position![0.0])),
+            ])
+        );
+    }
+}
@@ -0,0 +1,162 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::for_each_position;
+use crate::{Bbox, Feature, FeatureCollection, GeoJson, Geometry, Position, Value};
+
+/// The running minimum and maximum of each dimension of the positions seen so far
+#[derive(Default)]
+struct Extent {
+    min: Vec<f64>,
+    max: Vec<f64>,
+}
+
+impl Extent {
+    /// Positions with fewer than two elements are ignored. The extent only covers the
+    /// dimensions shared by every position, up to three.
+    fn extend(&mut self, position: &Position) {
+        if position.len() < 2 {
+            return;
+        }
+        let dimensions = position.len().min(3);
+        if self.min.is_empty() {
+            self.min = position[..dimensions].to_vec();
+            self.max = position[..dimensions].to_vec();
+            return;
+        }
+        self.min.truncate(dimensions);
+        self.max.truncate(dimensions);
+        for (i, &coordinate) in position.iter().enumerate().take(self.min.len()) {
+            self.min[i] = self.min[i].min(coordinate);
+            self.max[i] = self.max[i].max(coordinate);
+        }
+    }
+
+    fn extend_value(&mut self, value: &Value) {
+        for_each_position(value, &mut |position| self.extend(position));
+    }
+
+    fn into_bbox(self) -> Option<Bbox> {
+        if self.min.is_empty() {
+            None
+        } else {
+            let mut bbox = self.min;
+            bbox.extend(self.max);
+            Some(bbox)
+        }
+    }
+}
+
+impl Value {
+    /// Compute the bounding box of this geometry's coordinates, ignoring any declared `bbox`.
+    ///
+    /// The bounding box is three dimensional, `[min_x, min_y, min_z, max_x, max_y, max_z]`,
+    /// if every position has an altitude, and `[min_x, min_y, max_x, max_y]` otherwise. It is
+    /// `None` if there are no positions.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geojson::{position, Value};
+    ///
+    /// let line = Value::LineString(vec![position![-2.0, 1.0, 10.0], position![3.0, -1.0, 20.0]]);
+    /// assert_eq!(line.compute_bbox(), Some(vec![-2.0, -1.0, 10.0, 3.0, 1.0, 20.0]));
+    /// ```
+    pub fn compute_bbox(&self) -> Option<Bbox> {
+        let mut extent = Extent::default();
+        extent.extend_value(self);
+        extent.into_bbox()
+    }
+}
+
+impl Geometry {
+    /// Compute the bounding box of this geometry's coordinates, ignoring any declared `bbox`.
+    ///
+    /// See [`Value::compute_bbox`](enum.Value.html#method.compute_bbox).
+    pub fn compute_bbox(&self) -> Option<Bbox> {
+        self.value.compute_bbox()
+    }
+}
+
+impl Feature {
+    /// Compute the bounding box of this feature's geometry, ignoring any declared `bbox`.
+    ///
+    /// See [`Value::compute_bbox`](enum.Value.html#method.compute_bbox).
+    pub fn compute_bbox(&self) -> Option<Bbox> {
+        self.geometry.as_ref()?.compute_bbox()
+    }
+}
+
+impl FeatureCollection {
+    /// Compute the bounding box of the geometries of all features, ignoring any declared
+    /// `bbox`.
+    ///
+    /// See [`Value::compute_bbox`](enum.Value.html#method.compute_bbox).
+    pub fn compute_bbox(&self) -> Option<Bbox> {
+        let mut extent = Extent::default();
+        for geometry in self.features.iter().filter_map(|f| f.geometry.as_ref()) {
+            extent.extend_value(&geometry.value);
+        }
+        extent.into_bbox()
+    }
+}
+
+impl GeoJson {
+    /// Compute the bounding box of this object's coordinates, ignoring any declared `bbox`.
+    ///
+    /// See [`Value::compute_bbox`](enum.Value.html#method.compute_bbox).
+    pub fn compute_bbox(&self) -> Option<Bbox> {
+        match self {
+            GeoJson::Geometry(geometry) => geometry.compute_bbox(),
+            GeoJson::Feature(feature) => feature.compute_bbox(),
+            GeoJson::FeatureCollection(collection) => collection.compute_bbox(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Feature, FeatureCollection, Geometry, Value};
+
+    #[test]
+    fn mixed_dimensions() {
+        let collection = FeatureCollection {
+            bbox: Some(vec![0.0, 0.0, 0.0, 0.0]),
+            features: vec![
+                Feature::from(Geometry::new(Value::Point(position![1.0, 5.0, 100.0]))),
+                Feature {
+                    bbox: None,
+                    geometry: None,
+                    id: None,
+                    properties: None,
+                    foreign_members: None,
+                },
+                Feature::from(Geometry::new(Value::Polygon(vec![vec![
+                    position![-2.0, 0.0],
+                    position![3.0, 1.0],
+                    position![0.0, 2.0],
+                    position![-2.0, 0.0],
+                ]]))),
+            ],
+            foreign_members: None,
+        };
+        assert_eq!(collection.compute_bbox(), Some(vec![-2.0, 0.0, 3.0, 5.0]));
+        assert_eq!(
+            collection.features[0].compute_bbox(),
+            Some(vec![1.0, 5.0, 100.0, 1.0, 5.0, 100.0])
+        );
+        assert_eq!(collection.features[1].compute_bbox(), None);
+        assert_eq!(Value::GeometryCollection(vec![]).compute_bbox(), None);
+    }
+}
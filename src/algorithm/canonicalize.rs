@@ -0,0 +1,206 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{signed_area, PositionsMut};
+use crate::{Feature, FeatureCollection, GeoJson, Geometry, Position, Value};
+use std::cmp::Ordering;
+use std::convert::Infallible;
+
+fn cmp_slices<T>(a: &[T], b: &[T], cmp: impl Fn(&T, &T) -> Ordering) -> Ordering {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| cmp(a, b))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or_else(|| a.len().cmp(&b.len()))
+}
+
+fn cmp_positions(a: &[f64], b: &[f64]) -> Ordering {
+    cmp_slices(a, b, f64::total_cmp)
+}
+
+fn cmp_lines(a: &[Position], b: &[Position]) -> Ordering {
+    cmp_slices(a, b, |a, b| cmp_positions(a, b))
+}
+
+fn cmp_polygons(a: &[Vec<Position>], b: &[Vec<Position>]) -> Ordering {
+    cmp_slices(a, b, |a, b| cmp_lines(a, b))
+}
+
+/// Wind a closed ring counter-clockwise, or clockwise if it is a hole, and start it at its
+/// lowest position
+fn canonicalize_ring(ring: &mut Vec<Position>, hole: bool) {
+    if ring.len() < 4 || ring.first() != ring.last() {
+        return;
+    }
+    ring.pop();
+    if (signed_area(ring) < 0.0) != hole {
+        ring.reverse();
+    }
+    let lowest = (0..ring.len())
+        .min_by(|a, b| cmp_positions(&ring[*a], &ring[*b]))
+        .unwrap_or(0);
+    ring.rotate_left(lowest);
+    ring.push(ring[0].clone());
+}
+
+fn canonicalize_polygon(rings: &mut [Vec<Position>]) {
+    if let Some((exterior, holes)) = rings.split_first_mut() {
+        canonicalize_ring(exterior, false);
+        holes
+            .iter_mut()
+            .for_each(|ring| canonicalize_ring(ring, true));
+        holes.sort_by(|a, b| cmp_lines(a, b));
+    }
+}
+
+fn canonicalize_value(value: &mut Value) {
+    match value {
+        Value::Point(_) | Value::LineString(_) => {}
+        Value::MultiPoint(points) => points.sort_by(|a, b| cmp_positions(a, b)),
+        Value::MultiLineString(lines) => lines.sort_by(|a, b| cmp_lines(a, b)),
+        Value::Polygon(rings) => canonicalize_polygon(rings),
+        Value::MultiPolygon(polygons) => {
+            polygons
+                .iter_mut()
+                .for_each(|rings| canonicalize_polygon(rings));
+            polygons.sort_by(|a, b| cmp_polygons(a, b));
+        }
+        Value::GeometryCollection(geometries) => geometries
+            .iter_mut()
+            .for_each(|geometry| canonicalize_value(&mut geometry.value)),
+    }
+}
+
+fn canonicalize(target: &mut impl PositionsMut) {
+    let _ = target.try_for_each_position_mut(&mut |position| {
+        for coordinate in position.iter_mut() {
+            // Equal to 0.0, but serialized differently
+            if *coordinate == 0.0 {
+                *coordinate = 0.0;
+            }
+        }
+        Ok::<_, Infallible>(())
+    });
+    target.for_each_value_mut(&mut canonicalize_value);
+}
+
+macro_rules! impl_canonicalize {
+    ($($type:ty),*) => {
+        $(
+            impl $type {
+                /// Rewrite every geometry into a canonical form, in place, so that geometries
+                /// describing the same shape compare, and serialize, equal.
+                ///
+                /// Polygon exteriors are wound counter-clockwise and holes clockwise, as
+                /// recommended by RFC 7946, and each ring starts at its lowest position.
+                /// Holes, and the parts of `MultiPoint`s, `MultiLineString`s and
+                /// `MultiPolygon`s, are sorted. The order of positions along a line, and of the
+                /// members of a `GeometryCollection`, are significant and kept, as are `bbox`es.
+                ///
+                /// ```
+                /// use geojson::{position, Value};
+                ///
+                /// let mut a = Value::Polygon(vec![vec![
+                ///     position![1.0, 1.0],
+                ///     position![0.0, 1.0],
+                ///     position![0.0, 0.0],
+                ///     position![1.0, 0.0],
+                ///     position![1.0, 1.0],
+                /// ]]);
+                /// let mut b = Value::Polygon(vec![vec![
+                ///     position![0.0, 0.0],
+                ///     position![0.0, 1.0],
+                ///     position![1.0, 1.0],
+                ///     position![1.0, 0.0],
+                ///     position![0.0, 0.0],
+                /// ]]);
+                /// assert_ne!(a, b);
+                /// a.canonicalize();
+                /// b.canonicalize();
+                /// assert_eq!(a, b);
+                /// ```
+                pub fn canonicalize(&mut self) {
+                    canonicalize(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_canonicalize!(Value, Geometry, Feature, FeatureCollection, GeoJson);
+
+#[cfg(test)]
+mod tests {
+    use crate::{Position, Value};
+
+    fn square(x: f64, y: f64, size: f64) -> Vec<Position> {
+        vec![
+            position![x, y],
+            position![x + size, y],
+            position![x + size, y + size],
+            position![x, y + size],
+            position![x, y],
+        ]
+    }
+
+    /// A clockwise square, starting at its lowest corner
+    fn hole(x: f64, y: f64) -> Vec<Position> {
+        let mut ring = square(x, y, 1.0);
+        ring.reverse();
+        ring
+    }
+
+    #[test]
+    fn canonicalize_multi_polygon() {
+        let mut a = Value::MultiPolygon(vec![
+            vec![square(20.0, 20.0, 1.0)],
+            vec![square(0.0, 0.0, 10.0), hole(5.0, 5.0), hole(1.0, 1.0)],
+        ]);
+        // The same shape, with its holes wound the wrong way, its exterior starting at another
+        // corner and a negative zero
+        let mut exterior = square(0.0, 0.0, 10.0);
+        exterior.pop();
+        exterior.rotate_left(2);
+        exterior.push(exterior[0].clone());
+        exterior[2][0] = -0.0;
+        let mut b = Value::MultiPolygon(vec![
+            vec![exterior, square(1.0, 1.0, 1.0), square(5.0, 5.0, 1.0)],
+            vec![square(20.0, 20.0, 1.0)],
+        ]);
+        a.canonicalize();
+        b.canonicalize();
+
+        let expected = Value::MultiPolygon(vec![
+            vec![square(0.0, 0.0, 10.0), hole(1.0, 1.0), hole(5.0, 5.0)],
+            vec![square(20.0, 20.0, 1.0)],
+        ]);
+        assert_eq!(a, expected);
+        assert_eq!(
+            serde_json::to_string(&b).unwrap(),
+            serde_json::to_string(&expected).unwrap()
+        );
+    }
+
+    #[test]
+    fn canonicalize_short_positions() {
+        // Accepted by the parser, but without an area to wind
+        let json = r#"{"type":"Polygon","coordinates":[[[0],[1],[2],[0]]]}"#;
+        let mut value = serde_json::from_str::<crate::Geometry>(json).unwrap().value;
+        value.canonicalize();
+        match value {
+            Value::Polygon(rings) => assert_eq!(rings[0].len(), 4),
+            _ => unreachable!(),
+        }
+    }
+}
@@ -0,0 +1,383 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::Rect;
+use crate::{Feature, FeatureCollection, Geometry, Position, Value};
+
+/// The position a fraction `t` of the way from `a` to `b`, interpolating every dimension the
+/// two share
+fn lerp(a: &Position, b: &Position, t: f64) -> Position {
+    if t <= 0.0 {
+        return a.clone();
+    }
+    if t >= 1.0 {
+        return b.clone();
+    }
+    a.iter().zip(b).map(|(a, b)| a + t * (b - a)).collect()
+}
+
+/// The fractions of the way from `a` to `b` at which the segment between them enters and
+/// leaves `rect`, using the Liang–Barsky algorithm
+fn clip_segment(rect: &Rect, a: &Position, b: &Position) -> Option<(f64, f64)> {
+    let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+    let (mut enter, mut leave) = (0.0_f64, 1.0_f64);
+    for (p, q) in [
+        (-dx, a[0] - rect.min_x),
+        (dx, rect.max_x - a[0]),
+        (-dy, a[1] - rect.min_y),
+        (dy, rect.max_y - a[1]),
+    ] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else {
+            let t = q / p;
+            if p < 0.0 {
+                enter = enter.max(t);
+            } else {
+                leave = leave.min(t);
+            }
+        }
+    }
+    if enter > leave {
+        None
+    } else {
+        Some((enter, leave))
+    }
+}
+
+/// The parts of `line` within `rect`
+fn clip_line(rect: &Rect, line: &[Position]) -> Vec<Vec<Position>> {
+    let line: Vec<&Position> = line.iter().filter(|p| p.len() >= 2).collect();
+    if let [point] = line.as_slice() {
+        return if rect.contains(point[0], point[1]) {
+            vec![vec![(*point).clone()]]
+        } else {
+            vec![]
+        };
+    }
+    let mut parts = Vec::new();
+    let mut part: Vec<Position> = Vec::new();
+    for segment in line.windows(2) {
+        let (a, b) = (segment[0], segment[1]);
+        match clip_segment(rect, a, b) {
+            Some((enter, leave)) => {
+                if part.is_empty() {
+                    part.push(lerp(a, b, enter));
+                }
+                part.push(lerp(a, b, leave));
+                if leave < 1.0 {
+                    parts.push(std::mem::take(&mut part));
+                }
+            }
+            None => {
+                if !part.is_empty() {
+                    parts.push(std::mem::take(&mut part));
+                }
+            }
+        }
+    }
+    parts.push(part);
+    parts.retain(|part| part.len() >= 2);
+    parts
+}
+
+/// Clip a closed `ring` to `rect` with the Sutherland–Hodgman algorithm, or `None` if less than
+/// a triangle remains
+fn clip_ring(rect: &Rect, ring: &[Position]) -> Option<Vec<Position>> {
+    let mut points: Vec<Position> = ring.iter().filter(|p| p.len() >= 2).cloned().collect();
+    // Clip the open ring, and close it again afterwards
+    if points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+    let edges: [(usize, f64, bool); 4] = [
+        (0, rect.min_x, true),
+        (0, rect.max_x, false),
+        (1, rect.min_y, true),
+        (1, rect.max_y, false),
+    ];
+    for (axis, bound, is_min) in edges {
+        let inside = |p: &Position| {
+            if is_min {
+                p[axis] >= bound
+            } else {
+                p[axis] <= bound
+            }
+        };
+        let mut clipped = Vec::with_capacity(points.len());
+        for (i, current) in points.iter().enumerate() {
+            let previous = &points[(i + points.len() - 1) % points.len()];
+            match (inside(previous), inside(current)) {
+                (true, true) => clipped.push(current.clone()),
+                (true, false) => clipped.push(crossing(previous, current, axis, bound)),
+                (false, true) => {
+                    clipped.push(crossing(previous, current, axis, bound));
+                    clipped.push(current.clone());
+                }
+                (false, false) => {}
+            }
+        }
+        points = clipped;
+    }
+    if points.len() < 3 {
+        return None;
+    }
+    points.push(points[0].clone());
+    Some(points)
+}
+
+/// The position where the segment from `a` to `b` crosses `axis == bound`
+fn crossing(a: &Position, b: &Position, axis: usize, bound: f64) -> Position {
+    let mut position = lerp(a, b, (bound - a[axis]) / (b[axis] - a[axis]));
+    // Avoid positions a rounding error outside of the rectangle
+    position[axis] = bound;
+    position
+}
+
+fn clip_polygon(rect: &Rect, rings: &[Vec<Position>]) -> Option<Vec<Vec<Position>>> {
+    let mut rings = rings.iter();
+    let exterior = clip_ring(rect, rings.next()?)?;
+    Some(
+        std::iter::once(exterior)
+            .chain(rings.filter_map(|ring| clip_ring(rect, ring)))
+            .collect(),
+    )
+}
+
+fn clip_value(rect: &Rect, value: &Value) -> Option<Value> {
+    let inside = |p: &&Position| p.len() >= 2 && rect.contains(p[0], p[1]);
+    match value {
+        Value::Point(point) => Some(point)
+            .filter(inside)
+            .map(|point| Value::Point(point.clone())),
+        Value::MultiPoint(points) => {
+            let points: Vec<_> = points.iter().filter(inside).cloned().collect();
+            Some(points)
+                .filter(|p| !p.is_empty())
+                .map(Value::MultiPoint)
+        }
+        Value::LineString(line) => {
+            let mut parts = clip_line(rect, line);
+            match parts.len() {
+                0 => None,
+                1 => Some(Value::LineString(parts.remove(0))),
+                _ => Some(Value::MultiLineString(parts)),
+            }
+        }
+        Value::MultiLineString(lines) => {
+            let parts: Vec<_> = lines
+                .iter()
+                .flat_map(|line| clip_line(rect, line))
+                .collect();
+            Some(parts)
+                .filter(|p| !p.is_empty())
+                .map(Value::MultiLineString)
+        }
+        Value::Polygon(rings) => clip_polygon(rect, rings).map(Value::Polygon),
+        Value::MultiPolygon(polygons) => {
+            let polygons: Vec<_> = polygons
+                .iter()
+                .filter_map(|rings| clip_polygon(rect, rings))
+                .collect();
+            Some(polygons)
+                .filter(|p| !p.is_empty())
+                .map(Value::MultiPolygon)
+        }
+        Value::GeometryCollection(geometries) => {
+            let geometries: Vec<_> = geometries
+                .iter()
+                .filter_map(|geometry| clip_geometry(rect, geometry))
+                .collect();
+            Some(geometries)
+                .filter(|g| !g.is_empty())
+                .map(Value::GeometryCollection)
+        }
+    }
+}
+
+fn clip_geometry(rect: &Rect, geometry: &Geometry) -> Option<Geometry> {
+    Some(Geometry {
+        bbox: None,
+        value: clip_value(rect, &geometry.value)?,
+        foreign_members: geometry.foreign_members.clone(),
+    })
+}
+
+impl Value {
+    /// Crop this geometry to the rectangle `[min_x, min_y, max_x, max_y]`, or return `None` if
+    /// none of it is within the rectangle.
+    ///
+    /// Lines are cut where they cross the rectangle's edges, and may be split into several
+    /// parts, so a `LineString` can become a `MultiLineString`. Polygon rings are clipped with
+    /// the Sutherland–Hodgman algorithm, which traces the rectangle's edges between the parts
+    /// of a concave polygon rather than splitting it. Altitudes and further elements of new
+    /// positions are interpolated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geojson::{position, Value};
+    ///
+    /// let line = Value::LineString(vec![position![-5.0, 0.0, 100.0], position![5.0, 0.0, 200.0]]);
+    /// assert_eq!(
+    ///     line.clip_to_bbox([0.0, -1.0, 10.0, 1.0]),
+    ///     Some(Value::LineString(vec![position![0.0, 0.0, 150.0], position![5.0, 0.0, 200.0]]))
+    /// );
+    /// assert_eq!(line.clip_to_bbox([20.0, 20.0, 30.0, 30.0]), None);
+    /// ```
+    pub fn clip_to_bbox(&self, bbox: [f64; 4]) -> Option<Value> {
+        clip_value(&Rect::from_bbox(bbox), self)
+    }
+}
+
+impl Geometry {
+    /// Crop this geometry to the rectangle `[min_x, min_y, max_x, max_y]`, or return `None` if
+    /// none of it is within the rectangle.
+    ///
+    /// The cropped geometry has no `bbox`. See
+    /// [`Value::clip_to_bbox`](enum.Value.html#method.clip_to_bbox).
+    pub fn clip_to_bbox(&self, bbox: [f64; 4]) -> Option<Geometry> {
+        clip_geometry(&Rect::from_bbox(bbox), self)
+    }
+}
+
+impl Feature {
+    /// Crop this feature's geometry to the rectangle `[min_x, min_y, max_x, max_y]`, or return
+    /// `None` if none of it, or no geometry at all, is within the rectangle.
+    ///
+    /// The cropped feature has no `bbox`. See
+    /// [`Value::clip_to_bbox`](enum.Value.html#method.clip_to_bbox).
+    pub fn clip_to_bbox(&self, bbox: [f64; 4]) -> Option<Feature> {
+        Some(Feature {
+            bbox: None,
+            geometry: Some(self.geometry.as_ref()?.clip_to_bbox(bbox)?),
+            id: self.id.clone(),
+            properties: self.properties.clone(),
+            foreign_members: self.foreign_members.clone(),
+        })
+    }
+}
+
+impl FeatureCollection {
+    /// Crop every feature to the rectangle `[min_x, min_y, max_x, max_y]`, dropping the
+    /// features which are entirely outside of it or have no geometry.
+    ///
+    /// The collection and its features have no `bbox`. See
+    /// [`Value::clip_to_bbox`](enum.Value.html#method.clip_to_bbox).
+    pub fn clip_to_bbox(&self, bbox: [f64; 4]) -> FeatureCollection {
+        FeatureCollection {
+            bbox: None,
+            features: self
+                .features
+                .iter()
+                .filter_map(|feature| feature.clip_to_bbox(bbox))
+                .collect(),
+            foreign_members: self.foreign_members.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Feature, FeatureCollection, Value};
+
+    const BBOX: [f64; 4] = [0.0, 0.0, 10.0, 10.0];
+
+    #[test]
+    fn clip_lines() {
+        // In, out through the top and back in, then along the bottom edge out to the right
+        let line = Value::LineString(vec![
+            position![5.0, 5.0],
+            position![5.0, 15.0],
+            position![8.0, 5.0],
+            position![8.0, 0.0],
+            position![20.0, 0.0],
+        ]);
+        let clipped = line.clip_to_bbox(BBOX).unwrap();
+        let expected_second_x = 5.0 + 3.0 * (5.0 / 10.0);
+        assert_eq!(
+            clipped,
+            Value::MultiLineString(vec![
+                vec![position![5.0, 5.0], position![5.0, 10.0]],
+                vec![
+                    position![expected_second_x, 10.0],
+                    position![8.0, 5.0],
+                    position![8.0, 0.0],
+                    position![10.0, 0.0]
+                ],
+            ])
+        );
+
+        let points = Value::MultiPoint(vec![position![1.0, 1.0], position![11.0, 1.0]]);
+        assert_eq!(
+            points.clip_to_bbox(BBOX),
+            Some(Value::MultiPoint(vec![position![1.0, 1.0]]))
+        );
+        assert_eq!(Value::Point(position![-1.0, 1.0]).clip_to_bbox(BBOX), None);
+    }
+
+    #[test]
+    fn clip_polygon_with_hole() {
+        let polygon = Value::Polygon(vec![
+            vec![
+                position![-10.0, -10.0],
+                position![5.0, -10.0],
+                position![5.0, 5.0],
+                position![-10.0, 5.0],
+                position![-10.0, -10.0],
+            ],
+            // Entirely outside, so dropped
+            vec![
+                position![-8.0, -8.0],
+                position![-8.0, -6.0],
+                position![-6.0, -6.0],
+                position![-8.0, -8.0],
+            ],
+        ]);
+        let clipped = polygon.clip_to_bbox(BBOX).unwrap();
+        let rings = match clipped {
+            Value::Polygon(rings) => rings,
+            value => panic!("expected a polygon, got {:?}", value),
+        };
+        assert_eq!(rings.len(), 1);
+        let mut corners = rings[0][..4].to_vec();
+        corners.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(
+            corners,
+            vec![
+                position![0.0, 0.0],
+                position![0.0, 5.0],
+                position![5.0, 0.0],
+                position![5.0, 5.0]
+            ]
+        );
+        assert_eq!(rings[0].first(), rings[0].last());
+    }
+
+    #[test]
+    fn clip_collection() {
+        let collection = FeatureCollection {
+            bbox: Some(vec![-20.0, -20.0, 5.0, 5.0]),
+            features: vec![
+                Feature::from(Value::Point(position![5.0, 5.0])),
+                Feature::from(Value::Point(position![-20.0, -20.0])),
+            ],
+            foreign_members: None,
+        };
+        let clipped = collection.clip_to_bbox(BBOX);
+        assert_eq!(clipped.bbox, None);
+        assert_eq!(clipped.features.len(), 1);
+    }
+}
@@ -0,0 +1,126 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::positions;
+use crate::{Feature, FeatureCollection, GeoJson, Geometry, Position, Value};
+
+impl Value {
+    /// An iterator over every position of this geometry, including those within
+    /// `GeometryCollection`s, in the order they are written.
+    ///
+    /// With the `geo` feature, this shadows `geo::CoordsIter::coords_iter`, which can still be
+    /// called as `CoordsIter::coords_iter(&value)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geojson::{position, Geometry, Value};
+    ///
+    /// let collection = Value::GeometryCollection(vec![
+    ///     Geometry::new(Value::Point(position![1.0, 2.0])),
+    ///     Geometry::new(Value::LineString(vec![position![3.0, 4.0], position![5.0, 6.0, 7.0]])),
+    /// ]);
+    ///
+    /// let xs: Vec<f64> = collection.coords_iter().map(|position| position[0]).collect();
+    /// assert_eq!(xs, vec![1.0, 3.0, 5.0]);
+    /// ```
+    pub fn coords_iter(&self) -> impl Iterator<Item = &Position> {
+        positions(self, false)
+    }
+}
+
+impl Geometry {
+    /// An iterator over every position of this geometry.
+    ///
+    /// See [`Value::coords_iter`](enum.Value.html#method.coords_iter).
+    pub fn coords_iter(&self) -> impl Iterator<Item = &Position> {
+        self.value.coords_iter()
+    }
+}
+
+impl Feature {
+    /// An iterator over every position of this feature's geometry, which is empty if it has
+    /// none.
+    ///
+    /// See [`Value::coords_iter`](enum.Value.html#method.coords_iter).
+    pub fn coords_iter(&self) -> impl Iterator<Item = &Position> {
+        self.geometry.iter().flat_map(Geometry::coords_iter)
+    }
+}
+
+impl FeatureCollection {
+    /// An iterator over every position of every feature of this collection.
+    ///
+    /// See [`Value::coords_iter`](enum.Value.html#method.coords_iter).
+    pub fn coords_iter(&self) -> impl Iterator<Item = &Position> {
+        self.features.iter().flat_map(Feature::coords_iter)
+    }
+}
+
+impl GeoJson {
+    /// An iterator over every position of this object.
+    ///
+    /// See [`Value::coords_iter`](enum.Value.html#method.coords_iter).
+    pub fn coords_iter(&self) -> impl Iterator<Item = &Position> {
+        let positions: Box<dyn Iterator<Item = &Position>> = match self {
+            GeoJson::Geometry(geometry) => Box::new(geometry.coords_iter()),
+            GeoJson::Feature(feature) => Box::new(feature.coords_iter()),
+            GeoJson::FeatureCollection(collection) => Box::new(collection.coords_iter()),
+        };
+        positions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Feature, FeatureCollection, GeoJson, Geometry, Value};
+
+    #[test]
+    fn collection_positions() {
+        let mut empty = Feature::from(Value::Point(position![9.0, 9.0]));
+        empty.geometry = None;
+        let geojson = GeoJson::from(FeatureCollection {
+            bbox: None,
+            features: vec![
+                Feature::from(Value::Polygon(vec![
+                    vec![
+                        position![0.0, 0.0],
+                        position![1.0, 0.0],
+                        position![0.0, 1.0],
+                        position![0.0, 0.0],
+                    ],
+                    vec![],
+                ])),
+                empty,
+                Feature::from(Value::GeometryCollection(vec![Geometry::new(
+                    Value::GeometryCollection(vec![Geometry::new(Value::Point(position![
+                        2.0, 3.0
+                    ]))]),
+                )])),
+            ],
+            foreign_members: None,
+        });
+        let positions: Vec<_> = geojson.coords_iter().cloned().collect();
+        assert_eq!(
+            positions,
+            vec![
+                position![0.0, 0.0],
+                position![1.0, 0.0],
+                position![0.0, 1.0],
+                position![0.0, 0.0],
+                position![2.0, 3.0]
+            ]
+        );
+    }
+}
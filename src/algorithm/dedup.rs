@@ -0,0 +1,178 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::json::JsonObject;
+use crate::{Feature, FeatureCollection};
+use std::collections::HashMap;
+
+/// The canonical JSON of the feature's geometry, which is the same for geometries describing
+/// the same shape
+fn geometry_key(feature: &Feature) -> String {
+    match &feature.geometry {
+        Some(geometry) => {
+            let mut value = geometry.value.clone();
+            value.canonicalize();
+            value.to_string()
+        }
+        None => "null".to_string(),
+    }
+}
+
+fn properties(feature: &Feature) -> Option<&JsonObject> {
+    feature.properties.as_ref().filter(|p| !p.is_empty())
+}
+
+/// Whether no property of `a` has a different value in `b`
+fn properties_mergeable(a: &Feature, b: &Feature) -> bool {
+    match (properties(a), properties(b)) {
+        (Some(a), Some(b)) => a
+            .iter()
+            .all(|(key, value)| b.get(key).map_or(true, |other| other == value)),
+        _ => true,
+    }
+}
+
+impl FeatureCollection {
+    fn dedup_with(&mut self, merge: bool) {
+        // The indices of the features kept so far, by geometry
+        let mut kept: HashMap<String, Vec<usize>> = HashMap::new();
+        let mut features: Vec<Feature> = Vec::with_capacity(self.features.len());
+        for feature in self.features.drain(..) {
+            let indices = kept.entry(geometry_key(&feature)).or_default();
+            let duplicate = indices.iter().copied().find(|&index| {
+                let other = &features[index];
+                if merge {
+                    properties_mergeable(other, &feature)
+                } else {
+                    properties(other) == properties(&feature)
+                }
+            });
+            match duplicate {
+                Some(index) => {
+                    if let Some(properties) = feature.properties {
+                        let merged = features[index]
+                            .properties
+                            .get_or_insert_with(JsonObject::new);
+                        for (key, value) in properties {
+                            merged.entry(key).or_insert(value);
+                        }
+                    }
+                }
+                None => {
+                    indices.push(features.len());
+                    features.push(feature);
+                }
+            }
+        }
+        self.features = features;
+    }
+
+    /// Remove the features which have the same geometry and properties as an earlier feature,
+    /// such as those from overlapping extracts of one dataset.
+    ///
+    /// Geometries are compared in their [canonical](#method.canonicalize) form, so polygons
+    /// with rings wound or started differently are the same. A feature without `properties`
+    /// has the same properties as one with none. The `id`, `bbox` and foreign members of
+    /// features are ignored, and those of the earliest are kept.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geojson::{position, Feature, FeatureCollection, Value};
+    ///
+    /// let mut feature = Feature::from(Value::Point(position![1.0, 2.0]));
+    /// feature.set_property("name", "well");
+    /// let mut collection = FeatureCollection {
+    ///     bbox: None,
+    ///     features: vec![feature.clone(), feature.clone(), feature],
+    ///     foreign_members: None,
+    /// };
+    ///
+    /// collection.dedup();
+    /// assert_eq!(collection.features.len(), 1);
+    /// ```
+    pub fn dedup(&mut self) {
+        self.dedup_with(false)
+    }
+
+    /// Remove the features which have the same geometry as an earlier feature, and no
+    /// property with a different value, adding any properties which the earlier feature lacks
+    /// to it.
+    ///
+    /// This also merges duplicates of which each copy has only some of the properties. See
+    /// [`dedup`](#method.dedup) for how features are compared.
+    pub fn dedup_merging_properties(&mut self) {
+        self.dedup_with(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Feature, FeatureCollection, Value};
+
+    #[test]
+    fn dedup() {
+        let square = |start: usize| {
+            let corners = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]];
+            let mut ring: Vec<_> = (0..4)
+                .map(|i| corners[(start + i) % 4])
+                .map(|[x, y]| position![x, y])
+                .collect();
+            ring.push(ring[0].clone());
+            ring
+        };
+        let feature = |value: Value, properties: serde_json::Value| Feature {
+            properties: properties.as_object().cloned(),
+            ..Feature::from(value)
+        };
+        let a = Value::Polygon(vec![square(0)]);
+        let b = Value::Polygon(vec![square(2)]);
+        let point = Value::Point(position![0.0, 0.0]);
+        let collection = FeatureCollection {
+            bbox: None,
+            features: vec![
+                feature(a.clone(), serde_json::json!({ "name": "x" })),
+                feature(b.clone(), serde_json::json!({ "name": "x" })),
+                feature(b, serde_json::json!({ "name": "y" })),
+                feature(a.clone(), serde_json::json!({ "name": "x", "height": 3 })),
+                feature(point.clone(), serde_json::json!({})),
+                feature(point, serde_json::Value::Null),
+            ],
+            foreign_members: None,
+        };
+
+        let mut deduped = collection.clone();
+        deduped.dedup();
+        assert_eq!(
+            deduped.features,
+            vec![
+                collection.features[0].clone(),
+                collection.features[2].clone(),
+                collection.features[3].clone(),
+                collection.features[4].clone(),
+            ]
+        );
+
+        let mut merged = collection.clone();
+        merged.dedup_merging_properties();
+        assert_eq!(
+            merged.features,
+            vec![
+                feature(a, serde_json::json!({ "name": "x", "height": 3 })),
+                collection.features[2].clone(),
+                collection.features[4].clone(),
+            ]
+        );
+    }
+}
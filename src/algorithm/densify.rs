@@ -0,0 +1,243 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::intersects::xy;
+use super::PositionsMut;
+use crate::{Feature, FeatureCollection, GeoJson, Geometry, Position, Value};
+
+/// The mean radius of the Earth, in metres
+const EARTH_RADIUS: f64 = 6_371_008.8;
+
+/// How segments are measured, and positions along them placed
+trait Metric {
+    fn length(&self, a: (f64, f64), b: (f64, f64)) -> f64;
+
+    /// The point a fraction `t` of the way from `a` to `b`
+    fn interpolate(&self, a: (f64, f64), b: (f64, f64), t: f64) -> (f64, f64);
+}
+
+struct Planar;
+
+impl Metric for Planar {
+    fn length(&self, a: (f64, f64), b: (f64, f64)) -> f64 {
+        (b.0 - a.0).hypot(b.1 - a.1)
+    }
+
+    fn interpolate(&self, a: (f64, f64), b: (f64, f64), t: f64) -> (f64, f64) {
+        (a.0 + t * (b.0 - a.0), a.1 + t * (b.1 - a.1))
+    }
+}
+
+/// Great circles on a spherical Earth, with positions in degrees of longitude and latitude
+struct Haversine;
+
+impl Haversine {
+    /// The angle between `a` and `b` from the centre of the Earth, in radians
+    fn angle(a: (f64, f64), b: (f64, f64)) -> f64 {
+        let (lat_a, lat_b) = (a.1.to_radians(), b.1.to_radians());
+        let half_d_lat = (lat_b - lat_a) / 2.0;
+        let half_d_lon = (b.0 - a.0).to_radians() / 2.0;
+        let h = half_d_lat.sin().powi(2) + lat_a.cos() * lat_b.cos() * half_d_lon.sin().powi(2);
+        2.0 * h.sqrt().min(1.0).asin()
+    }
+}
+
+impl Metric for Haversine {
+    fn length(&self, a: (f64, f64), b: (f64, f64)) -> f64 {
+        Haversine::angle(a, b) * EARTH_RADIUS
+    }
+
+    fn interpolate(&self, a: (f64, f64), b: (f64, f64), t: f64) -> (f64, f64) {
+        let angle = Haversine::angle(a, b);
+        let sin_angle = angle.sin();
+        if sin_angle.abs() < f64::EPSILON {
+            // Equal or antipodal points, between which there is no single great circle
+            return Planar.interpolate(a, b, t);
+        }
+        let (weight_a, weight_b) = (
+            ((1.0 - t) * angle).sin() / sin_angle,
+            (t * angle).sin() / sin_angle,
+        );
+        let (lon_a, lat_a) = (a.0.to_radians(), a.1.to_radians());
+        let (lon_b, lat_b) = (b.0.to_radians(), b.1.to_radians());
+        let x = weight_a * lat_a.cos() * lon_a.cos() + weight_b * lat_b.cos() * lon_b.cos();
+        let y = weight_a * lat_a.cos() * lon_a.sin() + weight_b * lat_b.cos() * lon_b.sin();
+        let z = weight_a * lat_a.sin() + weight_b * lat_b.sin();
+        (y.atan2(x).to_degrees(), z.atan2(x.hypot(y)).to_degrees())
+    }
+}
+
+fn densify_line(line: &mut Vec<Position>, max_length: f64, metric: &impl Metric) {
+    if line.len() < 2 {
+        return;
+    }
+    let mut densified = Vec::with_capacity(line.len());
+    for segment in line.windows(2) {
+        let (a, b) = (&segment[0], &segment[1]);
+        densified.push(a.clone());
+        let (start, end) = match (xy(a), xy(b)) {
+            (Some(start), Some(end)) => (start, end),
+            _ => continue,
+        };
+        let segments = (metric.length(start, end) / max_length).ceil();
+        if !segments.is_finite() || segments <= 1.0 {
+            continue;
+        }
+        for i in 1..segments as usize {
+            let t = i as f64 / segments;
+            let (x, y) = metric.interpolate(start, end, t);
+            let position = std::iter::once(x)
+                .chain(std::iter::once(y))
+                .chain(a.iter().zip(b).skip(2).map(|(a, b)| a + t * (b - a)))
+                .collect();
+            densified.push(position);
+        }
+    }
+    densified.extend(line.last().cloned());
+    *line = densified;
+}
+
+fn densify_value(value: &mut Value, max_length: f64, metric: &impl Metric) {
+    match value {
+        Value::Point(_) | Value::MultiPoint(_) => {}
+        Value::LineString(line) => densify_line(line, max_length, metric),
+        Value::MultiLineString(lines) | Value::Polygon(lines) => lines
+            .iter_mut()
+            .for_each(|line| densify_line(line, max_length, metric)),
+        Value::MultiPolygon(polygons) => polygons
+            .iter_mut()
+            .flatten()
+            .for_each(|ring| densify_line(ring, max_length, metric)),
+        Value::GeometryCollection(geometries) => geometries
+            .iter_mut()
+            .for_each(|geometry| densify_value(&mut geometry.value, max_length, metric)),
+    }
+}
+
+fn densify<T: PositionsMut + Clone>(target: &T, max_length: f64, metric: impl Metric) -> T {
+    let mut target = target.clone();
+    if max_length > 0.0 && max_length.is_finite() {
+        target.for_each_value_mut(&mut |value| densify_value(value, max_length, &metric));
+    }
+    target
+}
+
+macro_rules! impl_densify {
+    ($($type:ty),*) => {
+        $(
+            impl $type {
+                /// Return a copy with positions inserted along every line and ring segment which
+                /// is longer than `max_segment_length`, dividing it into equal parts no longer
+                /// than that.
+                ///
+                /// Lengths are planar and in the units of the coordinates. Altitudes and further
+                /// elements of the new positions are interpolated, and `bbox`es are kept.
+                /// A `max_segment_length` which is not positive and finite returns an unchanged
+                /// copy.
+                ///
+                /// ```
+                /// use geojson::{position, Value};
+                ///
+                /// let line = Value::LineString(vec![position![0.0, 0.0, 10.0], position![3.0, 0.0, 40.0]]);
+                /// assert_eq!(
+                ///     line.densify(1.5),
+                ///     Value::LineString(vec![
+                ///         position![0.0, 0.0, 10.0],
+                ///         position![1.5, 0.0, 25.0],
+                ///         position![3.0, 0.0, 40.0],
+                ///     ])
+                /// );
+                /// ```
+                pub fn densify(&self, max_segment_length: f64) -> Self {
+                    densify(self, max_segment_length, Planar)
+                }
+
+                /// Return a copy with positions inserted along the great circle of every line and
+                /// ring segment which is longer than `max_segment_length` metres.
+                ///
+                /// Positions are longitudes and latitudes in degrees, and lengths are measured
+                /// with the haversine formula on a sphere of the Earth's mean radius, so the
+                /// densified lines follow the shortest paths between their original positions,
+                /// for example to draw flight routes. `bbox`es are cleared, since these paths can
+                /// extend beyond them.
+                pub fn densify_haversine(&self, max_segment_length: f64) -> Self {
+                    let mut densified = densify(self, max_segment_length, Haversine);
+                    densified.for_each_bbox_mut(&mut |bbox| *bbox = None);
+                    densified
+                }
+            }
+        )*
+    };
+}
+
+impl_densify!(Value, Geometry, Feature, FeatureCollection, GeoJson);
+
+#[cfg(test)]
+mod tests {
+    use crate::{Feature, Value};
+
+    #[test]
+    fn densify_polygon() {
+        let mut feature = Feature::from(Value::Polygon(vec![vec![
+            position![0.0, 0.0],
+            position![2.0, 0.0],
+            position![2.0, 3.0],
+            position![0.0, 0.0],
+        ]]));
+        feature.bbox = Some(vec![0.0, 0.0, 2.0, 3.0]);
+        let densified = feature.densify(1.0);
+
+        assert_eq!(densified.bbox, feature.bbox);
+        assert_eq!(
+            densified.geometry.unwrap().value,
+            Value::Polygon(vec![vec![
+                position![0.0, 0.0],
+                position![1.0, 0.0],
+                position![2.0, 0.0],
+                position![2.0, 1.0],
+                position![2.0, 2.0],
+                position![2.0, 3.0],
+                position![1.5, 2.25],
+                position![1.0, 1.5],
+                position![0.5, 0.75],
+                position![0.0, 0.0],
+            ]])
+        );
+        assert_eq!(feature.densify(0.0), feature);
+    }
+
+    #[test]
+    fn densify_great_circle() {
+        // Along the equator, the great circle is the straight line
+        let equator = Value::LineString(vec![position![0.0, 0.0], position![2.0, 0.0]]);
+        match equator.densify_haversine(120_000.0) {
+            Value::LineString(line) => {
+                assert_eq!(line.len(), 3);
+                assert!((line[1][0] - 1.0).abs() < 1e-9 && line[1][1].abs() < 1e-9);
+            }
+            value => panic!("expected a line, got {:?}", value),
+        }
+
+        // Between two points at the same northern latitude, it bends towards the pole
+        let parallel = Value::LineString(vec![position![-60.0, 50.0], position![60.0, 50.0]]);
+        match parallel.densify_haversine(1_000_000.0) {
+            Value::LineString(line) => {
+                assert!(line.len() > 3);
+                let middle = &line[line.len() / 2];
+                assert!(middle[1] > 60.0, "{:?}", middle);
+            }
+            value => panic!("expected a line, got {:?}", value),
+        }
+    }
+}
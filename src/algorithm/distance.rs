@@ -0,0 +1,98 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Planar distances between points and GeoJSON geometries
+
+use super::intersects::{point_in_ring, xy};
+use super::segment_distance_2;
+use crate::{Position, Value};
+
+/// The squared planar distance from `(x, y)` to the nearest point of `value`, which is zero
+/// within polygons, or `None` if `value` contains no positions.
+pub(crate) fn distance_2(value: &Value, x: f64, y: f64) -> Option<f64> {
+    match value {
+        Value::Point(position) => position_distance_2(position, x, y),
+        Value::MultiPoint(positions) => min(positions.iter().map(|p| position_distance_2(p, x, y))),
+        Value::LineString(line) => line_distance_2(line, x, y),
+        Value::MultiLineString(lines) => min(lines.iter().map(|line| line_distance_2(line, x, y))),
+        Value::Polygon(rings) => polygon_distance_2(rings, x, y),
+        Value::MultiPolygon(polygons) => {
+            min(polygons.iter().map(|rings| polygon_distance_2(rings, x, y)))
+        }
+        Value::GeometryCollection(geometries) => min(geometries
+            .iter()
+            .map(|geometry| distance_2(&geometry.value, x, y))),
+    }
+}
+
+fn min(distances: impl Iterator<Item = Option<f64>>) -> Option<f64> {
+    distances.flatten().reduce(f64::min)
+}
+
+fn position_distance_2(position: &Position, x: f64, y: f64) -> Option<f64> {
+    xy(position).map(|(px, py)| (px - x).powi(2) + (py - y).powi(2))
+}
+
+fn line_distance_2(line: &[Position], x: f64, y: f64) -> Option<f64> {
+    let points: Vec<(f64, f64)> = line.iter().filter_map(xy).collect();
+    match points.as_slice() {
+        [] => None,
+        [(px, py)] => Some((px - x).powi(2) + (py - y).powi(2)),
+        _ => min(points
+            .windows(2)
+            .map(|segment| Some(segment_distance_2(segment[0], segment[1], x, y)))),
+    }
+}
+
+fn polygon_distance_2(rings: &[Vec<Position>], x: f64, y: f64) -> Option<f64> {
+    let (exterior, interiors) = rings.split_first()?;
+    if point_in_ring(x, y, exterior) && !interiors.iter().any(|ring| point_in_ring(x, y, ring)) {
+        return Some(0.0);
+    }
+    min(rings.iter().map(|ring| line_distance_2(ring, x, y)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::distance_2;
+    use crate::Value;
+
+    #[test]
+    fn distances() {
+        let line = Value::LineString(vec![position![0.0, 0.0], position![10.0, 0.0]]);
+        assert_eq!(distance_2(&line, 5.0, 3.0), Some(9.0));
+        assert_eq!(distance_2(&line, -3.0, 4.0), Some(25.0));
+
+        let polygon = Value::Polygon(vec![
+            vec![
+                position![0.0, 0.0],
+                position![10.0, 0.0],
+                position![10.0, 10.0],
+                position![0.0, 10.0],
+                position![0.0, 0.0],
+            ],
+            vec![
+                position![4.0, 4.0],
+                position![6.0, 4.0],
+                position![6.0, 6.0],
+                position![4.0, 6.0],
+                position![4.0, 4.0],
+            ],
+        ]);
+        assert_eq!(distance_2(&polygon, 2.0, 2.0), Some(0.0));
+        assert_eq!(distance_2(&polygon, 5.0, 5.0), Some(1.0));
+        assert_eq!(distance_2(&polygon, 12.0, 5.0), Some(4.0));
+        assert_eq!(distance_2(&Value::MultiPoint(vec![]), 0.0, 0.0), None);
+    }
+}
@@ -0,0 +1,138 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{Feature, FeatureCollection, Geometry, Value};
+
+impl Feature {
+    /// Split a feature with a `MultiPoint`, `MultiLineString` or `MultiPolygon` geometry into
+    /// one feature per part, each with a copy of the `id`, `properties` and foreign members of
+    /// this feature.
+    ///
+    /// Features with any other geometry, no geometry, or a multi-part geometry without any
+    /// parts are returned unchanged. The exploded features have no `bbox`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geojson::{position, Feature, Value};
+    ///
+    /// let mut feature = Feature::from(Value::MultiPoint(vec![position![0.0, 0.0], position![1.0, 1.0]]));
+    /// feature.set_property("name", "stops");
+    ///
+    /// let parts = feature.explode();
+    /// assert_eq!(parts.len(), 2);
+    /// assert_eq!(
+    ///     parts[1].geometry.as_ref().unwrap().value,
+    ///     Value::Point(position![1.0, 1.0])
+    /// );
+    /// assert_eq!(parts[1].property("name").unwrap(), "stops");
+    /// ```
+    pub fn explode(self) -> Vec<Feature> {
+        let geometry = match &self.geometry {
+            Some(geometry) => geometry,
+            None => return vec![self],
+        };
+        let parts: Vec<Value> = match &geometry.value {
+            Value::MultiPoint(points) => points.iter().cloned().map(Value::Point).collect(),
+            Value::MultiLineString(lines) => lines.iter().cloned().map(Value::LineString).collect(),
+            Value::MultiPolygon(polygons) => polygons.iter().cloned().map(Value::Polygon).collect(),
+            _ => return vec![self],
+        };
+        if parts.is_empty() {
+            return vec![self];
+        }
+        parts
+            .into_iter()
+            .map(|value| Feature {
+                bbox: None,
+                geometry: Some(Geometry {
+                    bbox: None,
+                    value,
+                    foreign_members: geometry.foreign_members.clone(),
+                }),
+                id: self.id.clone(),
+                properties: self.properties.clone(),
+                foreign_members: self.foreign_members.clone(),
+            })
+            .collect()
+    }
+}
+
+impl FeatureCollection {
+    /// Split every feature with a multi-part geometry into single-part features.
+    ///
+    /// See [`Feature::explode`](struct.Feature.html#method.explode). The exploded features
+    /// keep their order, and the collection's `bbox` is kept since the parts cover the same
+    /// extent.
+    pub fn explode(self) -> FeatureCollection {
+        FeatureCollection {
+            bbox: self.bbox,
+            features: self
+                .features
+                .into_iter()
+                .flat_map(Feature::explode)
+                .collect(),
+            foreign_members: self.foreign_members,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::feature::Id;
+    use crate::{Feature, FeatureCollection, Value};
+
+    #[test]
+    fn explode_collection() {
+        let square = vec![vec![
+            position![0.0, 0.0],
+            position![1.0, 0.0],
+            position![1.0, 1.0],
+            position![0.0, 0.0],
+        ]];
+        let mut multi = Feature::from(Value::MultiPolygon(vec![square.clone(), square.clone()]));
+        multi.id = Some(Id::Number(7.into()));
+        multi.bbox = Some(vec![0.0, 0.0, 1.0, 1.0]);
+        let collection = FeatureCollection {
+            bbox: None,
+            features: vec![
+                Feature::from(Value::LineString(vec![
+                    position![0.0, 0.0],
+                    position![1.0, 1.0],
+                ])),
+                multi,
+                Feature::from(Value::MultiLineString(vec![])),
+            ],
+            foreign_members: None,
+        }
+        .explode();
+
+        let values: Vec<_> = collection
+            .features
+            .iter()
+            .map(|f| f.geometry.as_ref().unwrap().value.clone())
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                Value::LineString(vec![position![0.0, 0.0], position![1.0, 1.0]]),
+                Value::Polygon(square.clone()),
+                Value::Polygon(square),
+                Value::MultiLineString(vec![]),
+            ]
+        );
+        assert_eq!(collection.features[2].id, Some(Id::Number(7.into())));
+        assert_eq!(collection.features[2].bbox, None);
+    }
+}
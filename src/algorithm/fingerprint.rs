@@ -0,0 +1,129 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::json::{JsonObject, JsonValue};
+use crate::ser::write_canonical;
+use crate::Feature;
+use std::io::Write;
+
+const FNV_OFFSET_BASIS: u128 = 0x6c62_272e_07bb_0142_62b8_2175_6295_c58d;
+const FNV_PRIME: u128 = 0x0000_0000_0100_0000_0000_0000_0000_013b;
+
+/// A 128-bit [FNV-1a](http://www.isthe.com/chongo/tech/comp/fnv/) hash of the bytes written to
+/// it, which, unlike the hashers of the standard library, is the same in every version of Rust
+struct Fnv1a(u128);
+
+impl Write for Fnv1a {
+    fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u128::from(byte)).wrapping_mul(FNV_PRIME);
+        }
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Feature {
+    /// A 128-bit hash of the [canonical](#method.canonicalize) geometry and the properties of
+    /// this feature, which is the same for features describing the same shape with the same
+    /// properties, for use as a key to find duplicates, cache or detect changes with.
+    ///
+    /// The hash is of the RFC 8785 canonical JSON of the geometry and properties, so it doesn't
+    /// depend on the order of properties, or how numbers are written, such as `1` or `1.0`. A
+    /// feature without `properties` has the same hash as one with none. The `id`, `bbox` and
+    /// foreign members of the feature and its geometry aren't hashed. The hash is stable across
+    /// platforms, runs and versions of this crate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geojson::Feature;
+    ///
+    /// let a: Feature = serde_json::from_str(
+    ///     r#"{"type":"Feature","geometry":{"type":"Point","coordinates":[1,2]},
+    ///         "properties":{"name":"well","depth":20}}"#,
+    /// )
+    /// .unwrap();
+    /// let b: Feature = serde_json::from_str(
+    ///     r#"{"type":"Feature","id":7,"geometry":{"type":"Point","coordinates":[1.0,2.0]},
+    ///         "properties":{"depth":20.0,"name":"well"}}"#,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(a.fingerprint(), b.fingerprint());
+    /// ```
+    pub fn fingerprint(&self) -> u128 {
+        let geometry = self.geometry.as_ref().map_or(JsonValue::Null, |geometry| {
+            let mut value = geometry.value.clone();
+            value.canonicalize();
+            JsonValue::from(JsonObject::from(&value))
+        });
+        let properties = JsonValue::Object(self.properties.clone().unwrap_or_default());
+        let mut hasher = Fnv1a(FNV_OFFSET_BASIS);
+        // Writing to the hasher can't fail
+        let _ = write_canonical(&mut hasher, &JsonValue::Array(vec![geometry, properties]));
+        hasher.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Fnv1a, FNV_OFFSET_BASIS};
+    use crate::{Feature, Value};
+    use std::io::Write;
+
+    fn hash(bytes: &[u8]) -> u128 {
+        let mut hasher = Fnv1a(FNV_OFFSET_BASIS);
+        hasher.write_all(bytes).unwrap();
+        hasher.0
+    }
+
+    #[test]
+    fn fingerprint() {
+        // Test vectors of the FNV-1a reference implementation
+        assert_eq!(hash(b""), 0x6c62272e07bb014262b821756295c58d);
+        assert_eq!(hash(b"a"), 0xd228cb696f1a8caf78912b704e4a8964);
+
+        let ring = |start: usize| {
+            let corners = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]];
+            let mut ring: Vec<_> = (0..4)
+                .map(|i| corners[(start + i) % 4])
+                .map(|[x, y]| position![x, y])
+                .collect();
+            ring.push(ring[0].clone());
+            ring
+        };
+        let mut a = Feature::from(Value::Polygon(vec![ring(0)]));
+        a.set_property("name", "x");
+        let mut b = Feature::from(Value::Polygon(vec![ring(1)]));
+        b.set_property("name", "x");
+        b.bbox = Some(vec![0.0, 0.0, 1.0, 1.0]);
+        assert_eq!(a.fingerprint(), b.fingerprint());
+
+        b.set_property("name", "y");
+        assert_ne!(a.fingerprint(), b.fingerprint());
+
+        let mut empty = Feature::from(Value::Point(position![0.0, -0.0]));
+        let origin = Feature::from(Value::Point(position![0.0, 0.0]));
+        assert_eq!(empty.fingerprint(), origin.fingerprint());
+        empty.properties = None;
+        assert_eq!(empty.fingerprint(), origin.fingerprint());
+        empty.geometry = None;
+        assert_ne!(empty.fingerprint(), origin.fingerprint());
+        // Pins the hash, which must stay the same between versions
+        assert_eq!(empty.fingerprint(), hash(b"[null,{}]"));
+    }
+}
@@ -0,0 +1,189 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::PositionsMut;
+use crate::{Feature, FeatureCollection, GeoJson, Geometry, Value};
+
+/// Move the members of every `GeometryCollection` within `geometries` up into it
+fn flatten_into(geometries: Vec<Geometry>, flattened: &mut Vec<Geometry>) {
+    for geometry in geometries {
+        match geometry.value {
+            Value::GeometryCollection(members) => flatten_into(members, flattened),
+            _ => flattened.push(geometry),
+        }
+    }
+}
+
+fn flatten_value(value: &mut Value) {
+    if let Value::GeometryCollection(geometries) = value {
+        let mut flattened = Vec::with_capacity(geometries.len());
+        flatten_into(std::mem::take(geometries), &mut flattened);
+        *geometries = flattened;
+    }
+}
+
+macro_rules! impl_flatten_collections {
+    ($($type:ty),*) => {
+        $(
+            impl $type {
+                /// Replace every `GeometryCollection` nested within a `GeometryCollection` with
+                /// its members, in place, leaving a single level of geometries.
+                ///
+                /// RFC 7946 discourages nested collections, and many consumers do not support
+                /// them. The nested collections' `bbox`es and foreign members are dropped, while
+                /// those of their members are kept.
+                ///
+                /// ```
+                /// use geojson::{position, Geometry, Value};
+                ///
+                /// let point = Geometry::new(Value::Point(position![1.0, 2.0]));
+                /// let mut collection = Value::GeometryCollection(vec![
+                ///     point.clone(),
+                ///     Geometry::new(Value::GeometryCollection(vec![point.clone()])),
+                /// ]);
+                /// collection.flatten_collections();
+                /// assert_eq!(collection, Value::GeometryCollection(vec![point.clone(), point]));
+                /// ```
+                pub fn flatten_collections(&mut self) {
+                    self.for_each_value_mut(&mut flatten_value)
+                }
+            }
+        )*
+    };
+}
+
+impl_flatten_collections!(Value, Geometry, Feature, FeatureCollection, GeoJson);
+
+impl Feature {
+    /// Split a feature with a `GeometryCollection` geometry into one feature per member,
+    /// including the members of nested collections, each with a copy of the `id`, `properties`
+    /// and foreign members of this feature.
+    ///
+    /// Features with any other geometry, no geometry, or a collection without any members other
+    /// than empty collections are returned unchanged, apart from flattening the collection. The split features have no `bbox`.
+    ///
+    /// See also [`explode`](#method.explode) to split multi-part geometries.
+    ///
+    /// ```
+    /// use geojson::{position, Feature, Geometry, Value};
+    ///
+    /// let feature = Feature::from(Value::GeometryCollection(vec![
+    ///     Geometry::new(Value::Point(position![1.0, 2.0])),
+    ///     Geometry::new(Value::LineString(vec![position![1.0, 2.0], position![3.0, 4.0]])),
+    /// ]));
+    /// let features = feature.split_collection();
+    /// assert_eq!(features.len(), 2);
+    /// assert_eq!(
+    ///     features[0].geometry,
+    ///     Some(Geometry::new(Value::Point(position![1.0, 2.0])))
+    /// );
+    /// ```
+    pub fn split_collection(mut self) -> Vec<Feature> {
+        let members = match self.geometry.as_mut().map(|geometry| &mut geometry.value) {
+            Some(Value::GeometryCollection(members)) => std::mem::take(members),
+            _ => return vec![self],
+        };
+        let mut flattened = Vec::with_capacity(members.len());
+        flatten_into(members, &mut flattened);
+        if flattened.is_empty() {
+            if let Some(geometry) = self.geometry.as_mut() {
+                geometry.value = Value::GeometryCollection(flattened);
+            }
+            return vec![self];
+        }
+        flattened
+            .into_iter()
+            .map(|geometry| Feature {
+                bbox: None,
+                geometry: Some(geometry),
+                id: self.id.clone(),
+                properties: self.properties.clone(),
+                foreign_members: self.foreign_members.clone(),
+            })
+            .collect()
+    }
+}
+
+impl FeatureCollection {
+    /// Split every feature with a `GeometryCollection` geometry into one feature per member.
+    ///
+    /// See [`Feature::split_collection`](struct.Feature.html#method.split_collection). The
+    /// split features keep their order, and the collection's `bbox` is kept.
+    pub fn split_collections(self) -> FeatureCollection {
+        FeatureCollection {
+            bbox: self.bbox,
+            features: self
+                .features
+                .into_iter()
+                .flat_map(Feature::split_collection)
+                .collect(),
+            foreign_members: self.foreign_members,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Feature, FeatureCollection, Geometry, Value};
+
+    fn point(x: f64) -> Geometry {
+        Geometry::new(Value::Point(position![x, 0.0]))
+    }
+
+    fn nested() -> Value {
+        Value::GeometryCollection(vec![
+            point(0.0),
+            Geometry {
+                bbox: Some(vec![1.0, 0.0, 3.0, 0.0]),
+                value: Value::GeometryCollection(vec![
+                    point(1.0),
+                    Geometry::new(Value::GeometryCollection(vec![point(2.0)])),
+                    Geometry::new(Value::GeometryCollection(vec![])),
+                    point(3.0),
+                ]),
+                foreign_members: None,
+            },
+        ])
+    }
+
+    #[test]
+    fn flatten_feature() {
+        let mut feature = Feature::from(nested());
+        feature.flatten_collections();
+        assert_eq!(
+            feature.geometry.unwrap().value,
+            Value::GeometryCollection(vec![point(0.0), point(1.0), point(2.0), point(3.0)])
+        );
+    }
+
+    #[test]
+    fn split_collections() {
+        let mut feature = Feature::from(nested());
+        feature.set_property("name", "points");
+        let collection = FeatureCollection {
+            bbox: None,
+            features: vec![feature, Feature::from(Value::GeometryCollection(vec![]))],
+            foreign_members: None,
+        }
+        .split_collections();
+
+        assert_eq!(collection.features.len(), 5);
+        assert_eq!(collection.features[2].geometry, Some(point(2.0)));
+        assert_eq!(collection.features[3].property("name").unwrap(), "points");
+        assert_eq!(
+            collection.features[4].geometry,
+            Some(Geometry::new(Value::GeometryCollection(vec![])))
+        );
+    }
+}
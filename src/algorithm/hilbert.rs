@@ -0,0 +1,156 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{Feature, FeatureCollection};
+
+/// The number of cells along each side of the grid the Hilbert curve is drawn through
+const SIDE: u32 = 1 << 16;
+
+/// The distance along the Hilbert curve through a `SIDE` × `SIDE` grid of the cell `(x, y)`
+fn hilbert_index(mut x: u32, mut y: u32) -> u64 {
+    let mut index = 0;
+    let mut s = SIDE / 2;
+    while s > 0 {
+        let rx = u32::from(x & s > 0);
+        let ry = u32::from(y & s > 0);
+        index += u64::from(s) * u64::from(s) * u64::from((3 * rx) ^ ry);
+        // Rotate the quadrant, so that the curve through it starts and ends next to its
+        // neighbours
+        if ry == 0 {
+            if rx == 1 {
+                x = SIDE - 1 - x;
+                y = SIDE - 1 - y;
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        s /= 2;
+    }
+    index
+}
+
+/// The 2D center of the feature's `bbox` if it has one, or else of its geometry
+fn center(feature: &Feature) -> Option<(f64, f64)> {
+    let bbox = match &feature.bbox {
+        Some(bbox) if bbox.len() >= 4 => bbox.clone(),
+        _ => feature.compute_bbox()?,
+    };
+    let dimensions = bbox.len() / 2;
+    Some((
+        (bbox[0] + bbox[dimensions]) / 2.0,
+        (bbox[1] + bbox[dimensions + 1]) / 2.0,
+    ))
+}
+
+impl FeatureCollection {
+    /// Sort the features along a Hilbert curve through the centers of their bboxes, so that
+    /// features which are near each other are mostly near each other in the collection too.
+    ///
+    /// This improves the locality of tiles, chunks or spatial index nodes built from
+    /// consecutive features. The curve spans the extent of the centers on a 65536 × 65536
+    /// grid. The declared `bbox` of a feature is used if it has one, and otherwise the bbox is
+    /// computed from its geometry. Features without a geometry or `bbox` are moved to the
+    /// end, and features on the same cell of the grid keep their order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geojson::{position, Feature, FeatureCollection, Value};
+    ///
+    /// let point = |x: f64, y: f64| Feature::from(Value::Point(position![x, y]));
+    /// let mut collection = FeatureCollection {
+    ///     bbox: None,
+    ///     features: vec![point(0.0, 0.0), point(9.0, 9.0), point(1.0, 1.0), point(8.0, 8.0)],
+    ///     foreign_members: None,
+    /// };
+    ///
+    /// collection.sort_hilbert();
+    /// assert_eq!(collection.features[1], point(1.0, 1.0));
+    /// assert_eq!(collection.features[3], point(9.0, 9.0));
+    /// ```
+    pub fn sort_hilbert(&mut self) {
+        let centers: Vec<_> = self.features.iter().map(center).collect();
+        let (mut min_x, mut min_y) = (f64::INFINITY, f64::INFINITY);
+        let (mut max_x, mut max_y) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+        for &(x, y) in centers.iter().flatten() {
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+        }
+        let cell = |coordinate: f64, min: f64, max: f64| {
+            if max > min {
+                ((coordinate - min) / (max - min) * f64::from(SIDE - 1)) as u32
+            } else {
+                0
+            }
+        };
+        let mut keyed: Vec<_> = centers
+            .into_iter()
+            .map(|center| {
+                center.map_or(u64::MAX, |(x, y)| {
+                    hilbert_index(cell(x, min_x, max_x), cell(y, min_y, max_y))
+                })
+            })
+            .zip(self.features.drain(..))
+            .collect();
+        keyed.sort_by_key(|&(index, _)| index);
+        self.features
+            .extend(keyed.into_iter().map(|(_, feature)| feature));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hilbert_index, SIDE};
+    use crate::{Feature, FeatureCollection, Value};
+
+    #[test]
+    fn sort_hilbert() {
+        assert_eq!(hilbert_index(0, 0), 0);
+        assert_eq!(
+            hilbert_index(SIDE - 1, 0),
+            u64::from(SIDE) * u64::from(SIDE) - 1
+        );
+
+        // Every cell of an 8 × 8 grid, in an order far from the curve's
+        let mut features: Vec<_> = (0..64)
+            .map(|i| (i * 37) % 64)
+            .map(|i| Feature::from(Value::Point(position![(i % 8) as f64, (i / 8) as f64])))
+            .collect();
+        features.insert(10, Feature::from(Value::GeometryCollection(vec![])));
+        let mut collection = FeatureCollection {
+            bbox: None,
+            features,
+            foreign_members: None,
+        };
+        collection.sort_hilbert();
+
+        assert_eq!(
+            collection.features[64].geometry.as_ref().unwrap().value,
+            Value::GeometryCollection(vec![])
+        );
+        let centers: Vec<_> = collection.features[..64]
+            .iter()
+            .map(|feature| match &feature.geometry.as_ref().unwrap().value {
+                Value::Point(position) => (position[0], position[1]),
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(centers[0], (0.0, 0.0));
+        for pair in centers.windows(2) {
+            let distance = (pair[0].0 - pair[1].0).abs() + (pair[0].1 - pair[1].1).abs();
+            assert_eq!(distance, 1.0, "{:?}", pair);
+        }
+    }
+}
@@ -0,0 +1,378 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Planar intersection tests between rectangles and GeoJSON geometries
+
+use super::Rect;
+use crate::{Feature, FeatureCollection, Geometry, Position, Value};
+
+/// Does `value` share at least one point with `rect`?
+pub(crate) fn rect_intersects_value(rect: &Rect, value: &Value) -> bool {
+    match value {
+        Value::Point(position) => position_in_rect(rect, position),
+        Value::MultiPoint(positions) => positions.iter().any(|p| position_in_rect(rect, p)),
+        Value::LineString(line) => rect_intersects_line(rect, line),
+        Value::MultiLineString(lines) => lines.iter().any(|line| rect_intersects_line(rect, line)),
+        Value::Polygon(rings) => rect_intersects_polygon(rect, rings),
+        Value::MultiPolygon(polygons) => polygons
+            .iter()
+            .any(|rings| rect_intersects_polygon(rect, rings)),
+        Value::GeometryCollection(geometries) => geometries
+            .iter()
+            .any(|geometry| rect_intersects_value(rect, &geometry.value)),
+    }
+}
+
+pub(crate) fn xy(position: &Position) -> Option<(f64, f64)> {
+    match position.as_slice() {
+        [x, y, ..] => Some((*x, *y)),
+        _ => None,
+    }
+}
+
+fn position_in_rect(rect: &Rect, position: &Position) -> bool {
+    xy(position).is_some_and(|(x, y)| rect.contains(x, y))
+}
+
+fn rect_intersects_line(rect: &Rect, line: &[Position]) -> bool {
+    let points: Vec<(f64, f64)> = line.iter().filter_map(xy).collect();
+    match points.as_slice() {
+        [] => false,
+        [(x, y)] => rect.contains(*x, *y),
+        _ => points
+            .windows(2)
+            .any(|segment| rect_intersects_segment(rect, segment[0], segment[1])),
+    }
+}
+
+fn rect_intersects_polygon(rect: &Rect, rings: &[Vec<Position>]) -> bool {
+    // Any boundary crossing (or a polygon entirely within `rect`) is caught here
+    if rings.iter().any(|ring| rect_intersects_line(rect, ring)) {
+        return true;
+    }
+    // Otherwise `rect` is either wholly inside or wholly outside the polygon
+    let (x, y) = (rect.min_x, rect.min_y);
+    match rings.split_first() {
+        Some((exterior, interiors)) => {
+            point_in_ring(x, y, exterior) && !interiors.iter().any(|ring| point_in_ring(x, y, ring))
+        }
+        None => false,
+    }
+}
+
+/// Even-odd ray casting test of `(x, y)` against a ring of positions
+pub(crate) fn point_in_ring(x: f64, y: f64, ring: &[Position]) -> bool {
+    let points: Vec<(f64, f64)> = ring.iter().filter_map(xy).collect();
+    let mut inside = false;
+    for (i, &(xi, yi)) in points.iter().enumerate() {
+        let (xj, yj) = points[(i + points.len() - 1) % points.len()];
+        if (yi > y) != (yj > y) && x < (xj - xi) * (y - yi) / (yj - yi) + xi {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+fn rect_intersects_segment(rect: &Rect, a: (f64, f64), b: (f64, f64)) -> bool {
+    if rect.contains(a.0, a.1) || rect.contains(b.0, b.1) {
+        return true;
+    }
+    let corners = rect.corners();
+    (0..4).any(|i| segments_intersect(a, b, corners[i], corners[(i + 1) % 4]))
+}
+
+fn orientation(p: (f64, f64), q: (f64, f64), r: (f64, f64)) -> f64 {
+    (q.0 - p.0) * (r.1 - p.1) - (q.1 - p.1) * (r.0 - p.0)
+}
+
+fn on_segment(p: (f64, f64), q: (f64, f64), r: (f64, f64)) -> bool {
+    r.0 >= p.0.min(q.0) && r.0 <= p.0.max(q.0) && r.1 >= p.1.min(q.1) && r.1 <= p.1.max(q.1)
+}
+
+pub(crate) fn segments_intersect(
+    a: (f64, f64),
+    b: (f64, f64),
+    c: (f64, f64),
+    d: (f64, f64),
+) -> bool {
+    let d1 = orientation(c, d, a);
+    let d2 = orientation(c, d, b);
+    let d3 = orientation(a, b, c);
+    let d4 = orientation(a, b, d);
+    if ((d1 > 0.0 && d2 < 0.0) || (d1 < 0.0 && d2 > 0.0))
+        && ((d3 > 0.0 && d4 < 0.0) || (d3 < 0.0 && d4 > 0.0))
+    {
+        return true;
+    }
+    (d1 == 0.0 && on_segment(c, d, a))
+        || (d2 == 0.0 && on_segment(c, d, b))
+        || (d3 == 0.0 && on_segment(a, b, c))
+        || (d4 == 0.0 && on_segment(a, b, d))
+}
+
+/// The parts of a geometry which planar intersection tests look at
+#[derive(Default)]
+struct Parts<'a> {
+    /// Every segment of every line and ring, with points as segments of zero length
+    segments: Vec<((f64, f64), (f64, f64))>,
+    /// A position of every point, line and polygon
+    vertices: Vec<(f64, f64)>,
+    polygons: Vec<&'a [Vec<Position>]>,
+}
+
+impl<'a> Parts<'a> {
+    fn of(value: &'a Value) -> Self {
+        let mut parts = Parts::default();
+        parts.add(value);
+        parts
+    }
+
+    fn add_line(&mut self, line: &[Position]) {
+        let points: Vec<(f64, f64)> = line.iter().filter_map(xy).collect();
+        match points.as_slice() {
+            [] => {}
+            [point] => self.segments.push((*point, *point)),
+            _ => self
+                .segments
+                .extend(points.windows(2).map(|segment| (segment[0], segment[1]))),
+        }
+        self.vertices.extend(points.first());
+    }
+
+    fn add(&mut self, value: &'a Value) {
+        match value {
+            Value::Point(position) => self.add_line(std::slice::from_ref(position)),
+            Value::MultiPoint(positions) => {
+                for position in positions {
+                    self.add_line(std::slice::from_ref(position));
+                }
+            }
+            Value::LineString(line) => self.add_line(line),
+            Value::MultiLineString(lines) => lines.iter().for_each(|line| self.add_line(line)),
+            Value::Polygon(rings) => self.add_polygon(rings),
+            Value::MultiPolygon(polygons) => {
+                polygons.iter().for_each(|rings| self.add_polygon(rings))
+            }
+            Value::GeometryCollection(geometries) => geometries
+                .iter()
+                .for_each(|geometry| self.add(&geometry.value)),
+        }
+    }
+
+    fn add_polygon(&mut self, rings: &'a [Vec<Position>]) {
+        rings.iter().for_each(|ring| self.add_line(ring));
+        self.polygons.push(rings);
+    }
+
+    /// Is `(x, y)` within one of the polygons, not counting their boundaries?
+    fn polygons_contain(&self, (x, y): (f64, f64)) -> bool {
+        self.polygons.iter().any(|rings| match rings.split_first() {
+            Some((exterior, interiors)) => {
+                point_in_ring(x, y, exterior)
+                    && !interiors.iter().any(|ring| point_in_ring(x, y, ring))
+            }
+            None => false,
+        })
+    }
+}
+
+/// Do `a` and `b` share at least one point?
+pub(crate) fn values_intersect(a: &Value, b: &Value) -> bool {
+    let (a, b) = (Parts::of(a), Parts::of(b));
+    // Any crossing or touching of boundaries, lines and points is caught here
+    let crossing = a.segments.iter().any(|&(p, q)| {
+        b.segments
+            .iter()
+            .any(|&(r, s)| segments_intersect(p, q, r, s))
+    });
+    // Otherwise one can only intersect the other by lying wholly within one of its polygons
+    crossing
+        || a.vertices.iter().any(|&vertex| b.polygons_contain(vertex))
+        || b.vertices.iter().any(|&vertex| a.polygons_contain(vertex))
+}
+
+impl Value {
+    /// Whether this geometry shares at least one point with the rectangle
+    /// `[min_x, min_y, max_x, max_y]`, including its edges.
+    pub fn intersects_bbox(&self, bbox: [f64; 4]) -> bool {
+        rect_intersects_value(&Rect::from_bbox(bbox), self)
+    }
+}
+
+impl Geometry {
+    /// Whether this geometry shares at least one point with the rectangle
+    /// `[min_x, min_y, max_x, max_y]`. See
+    /// [`Value::intersects_bbox`](enum.Value.html#method.intersects_bbox).
+    pub fn intersects_bbox(&self, bbox: [f64; 4]) -> bool {
+        self.value.intersects_bbox(bbox)
+    }
+}
+
+impl Feature {
+    /// Whether this feature's geometry shares at least one point with the rectangle
+    /// `[min_x, min_y, max_x, max_y]`. Features without a geometry intersect nothing, and the
+    /// feature's `bbox` member is not taken into account.
+    pub fn intersects_bbox(&self, bbox: [f64; 4]) -> bool {
+        self.geometry
+            .as_ref()
+            .is_some_and(|geometry| geometry.intersects_bbox(bbox))
+    }
+}
+
+impl FeatureCollection {
+    /// The features whose geometry shares at least one point with the rectangle
+    /// `[min_x, min_y, max_x, max_y]`, as for a map's viewport.
+    ///
+    /// Each feature is tested against its geometry, not its `bbox` member: a feature whose
+    /// bounding box overlaps the rectangle, but whose geometry doesn't, is left out.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geojson::{position, Feature, FeatureCollection, Value};
+    ///
+    /// let collection = FeatureCollection {
+    ///     bbox: None,
+    ///     features: vec![
+    ///         // An L shape, whose bounding box covers the viewport but which doesn't
+    ///         Feature::from(Value::LineString(vec![
+    ///             position![0.0, 10.0],
+    ///             position![0.0, 0.0],
+    ///             position![10.0, 0.0],
+    ///         ])),
+    ///         Feature::from(Value::Point(position![5.0, 5.0])),
+    ///     ],
+    ///     foreign_members: None,
+    /// };
+    /// let visible: Vec<_> = collection.features_in_bbox([4.0, 4.0, 6.0, 6.0]).collect();
+    /// assert_eq!(visible, vec![&collection.features[1]]);
+    /// ```
+    pub fn features_in_bbox(&self, bbox: [f64; 4]) -> impl Iterator<Item = &Feature> {
+        let rect = Rect::from_bbox(bbox);
+        self.features.iter().filter(move |feature| {
+            feature
+                .geometry
+                .as_ref()
+                .is_some_and(|geometry| rect_intersects_value(&rect, &geometry.value))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{rect_intersects_value, values_intersect};
+    use crate::algorithm::Rect;
+    use crate::{Feature, FeatureCollection, Value};
+
+    fn rect() -> Rect {
+        Rect::from_bbox([0.0, 0.0, 10.0, 10.0])
+    }
+
+    #[test]
+    fn line_crossing_rect() {
+        let line = Value::LineString(vec![position![-5.0, 5.0], position![15.0, 5.0]]);
+        assert!(rect_intersects_value(&rect(), &line));
+        let line = Value::LineString(vec![position![-5.0, 15.0], position![15.0, 15.0]]);
+        assert!(!rect_intersects_value(&rect(), &line));
+    }
+
+    #[test]
+    fn polygon_containing_rect() {
+        let polygon = Value::Polygon(vec![vec![
+            position![-20.0, -20.0],
+            position![20.0, -20.0],
+            position![20.0, 20.0],
+            position![-20.0, 20.0],
+            position![-20.0, -20.0],
+        ]]);
+        assert!(rect_intersects_value(&rect(), &polygon));
+    }
+
+    #[test]
+    fn rect_inside_polygon_hole() {
+        let polygon = Value::Polygon(vec![
+            vec![
+                position![-20.0, -20.0],
+                position![20.0, -20.0],
+                position![20.0, 20.0],
+                position![-20.0, 20.0],
+                position![-20.0, -20.0],
+            ],
+            vec![
+                position![-15.0, -15.0],
+                position![-15.0, 15.0],
+                position![15.0, 15.0],
+                position![15.0, -15.0],
+                position![-15.0, -15.0],
+            ],
+        ]);
+        assert!(!rect_intersects_value(&rect(), &polygon));
+    }
+
+    #[test]
+    fn features_in_bbox() {
+        let mut without_geometry = Feature::from(Value::Point(position![5.0, 5.0]));
+        without_geometry.geometry = None;
+        without_geometry.bbox = Some(vec![0.0, 0.0, 10.0, 10.0]);
+        let collection = FeatureCollection {
+            bbox: None,
+            features: vec![
+                Feature::from(Value::Point(position![10.0, 10.0])),
+                Feature::from(Value::Point(position![11.0, 10.0])),
+                without_geometry,
+                Feature::from(Value::LineString(vec![
+                    position![-5.0, 5.0],
+                    position![15.0, 5.0],
+                ])),
+            ],
+            foreign_members: None,
+        };
+        let found: Vec<_> = collection
+            .features_in_bbox([0.0, 0.0, 10.0, 10.0])
+            .collect();
+        assert_eq!(
+            found,
+            vec![&collection.features[0], &collection.features[3]]
+        );
+        assert!(!collection.features[2].intersects_bbox([0.0, 0.0, 10.0, 10.0]));
+    }
+
+    #[test]
+    fn geometries_intersecting() {
+        let square = Value::Polygon(vec![Rect::from_bbox([0.0, 0.0, 10.0, 10.0]).to_ring()]);
+        let inside = Value::Point(position![5.0, 5.0]);
+        let on_edge = Value::Point(position![10.0, 5.0]);
+        let outside = Value::MultiPoint(vec![position![11.0, 5.0], position![-1.0, 5.0]]);
+        let crossing = Value::LineString(vec![
+            position![-5.0, 12.0],
+            position![5.0, 8.0],
+            position![5.0, 20.0],
+        ]);
+        let nested = Value::Polygon(vec![Rect::from_bbox([2.0, 2.0, 3.0, 3.0]).to_ring()]);
+        for value in [&inside, &on_edge, &crossing, &nested] {
+            assert!(values_intersect(&square, value), "{:?}", value);
+            assert!(values_intersect(value, &square), "{:?}", value);
+        }
+        assert!(!values_intersect(&square, &outside));
+        assert!(values_intersect(
+            &inside,
+            &Value::Point(position![5.0, 5.0])
+        ));
+        assert!(!values_intersect(&inside, &on_edge));
+        assert!(values_intersect(
+            &on_edge,
+            &Value::LineString(vec![position![10.0, 0.0], position![10.0, 10.0]])
+        ));
+    }
+}
@@ -0,0 +1,356 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::PositionsMut;
+use crate::{Feature, FeatureCollection, GeoJson, Geometry, Value};
+use std::convert::Infallible;
+
+/// Apply `f` to the `(x, y)` of every position of `target`, and clear its stale `bbox`es
+fn try_map<E>(
+    target: &mut impl PositionsMut,
+    f: &mut impl FnMut((f64, f64)) -> Result<(f64, f64), E>,
+) -> Result<(), E> {
+    target.for_each_bbox_mut(&mut |bbox| *bbox = None);
+    target.try_for_each_position_mut(&mut |position| {
+        if let [x, y, ..] = position.as_mut_slice() {
+            let (new_x, new_y) = f((*x, *y))?;
+            *x = new_x;
+            *y = new_y;
+        }
+        Ok(())
+    })
+}
+
+/// Adapt an infallible closure to the fallible one taken by the `try_` methods
+fn infallible(
+    mut f: impl FnMut((f64, f64)) -> (f64, f64),
+) -> impl FnMut((f64, f64)) -> Result<(f64, f64), Infallible> {
+    move |xy| Ok(f(xy))
+}
+
+impl Value {
+    /// Return a copy of this geometry with `f` applied to the `(x, y)` of every position.
+    ///
+    /// Any further elements of a position, such as an altitude, are kept unchanged, and
+    /// positions with fewer than two elements are skipped.
+    ///
+    /// With the `geo` feature, this shadows `geo::MapCoords::map_coords`, which can still be
+    /// called as `MapCoords::map_coords(&value, f)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geojson::{position, Value};
+    ///
+    /// let point = Value::Point(position![1.0, 2.0, 30.0]);
+    /// // Kilometres to metres
+    /// let point = point.map_coords(|(x, y)| (x * 1000.0, y * 1000.0));
+    /// assert_eq!(point, Value::Point(position![1000.0, 2000.0, 30.0]));
+    /// ```
+    pub fn map_coords(&self, f: impl FnMut((f64, f64)) -> (f64, f64)) -> Self {
+        let mut value = self.clone();
+        value.map_coords_in_place(f);
+        value
+    }
+
+    /// Apply `f` to the `(x, y)` of every position of this geometry, in place.
+    ///
+    /// See [`map_coords`](#method.map_coords).
+    pub fn map_coords_in_place(&mut self, f: impl FnMut((f64, f64)) -> (f64, f64)) {
+        let _ = self.try_map_coords_in_place(infallible(f));
+    }
+
+    /// Return a copy of this geometry with the fallible `f` applied to the `(x, y)` of every
+    /// position, or the first error `f` returns.
+    ///
+    /// See [`map_coords`](#method.map_coords).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geojson::{position, Value};
+    ///
+    /// let line = Value::LineString(vec![position![10.0, 50.0], position![10.0, 91.0]]);
+    /// let result = line.try_map_coords(|(x, y)| {
+    ///     if y.abs() <= 90.0 {
+    ///         Ok((x, y))
+    ///     } else {
+    ///         Err(format!("latitude {} is out of range", y))
+    ///     }
+    /// });
+    /// assert_eq!(result, Err("latitude 91 is out of range".to_string()));
+    /// ```
+    pub fn try_map_coords<E>(
+        &self,
+        f: impl FnMut((f64, f64)) -> Result<(f64, f64), E>,
+    ) -> Result<Self, E> {
+        let mut value = self.clone();
+        value.try_map_coords_in_place(f)?;
+        Ok(value)
+    }
+
+    /// Apply the fallible `f` to the `(x, y)` of every position of this geometry, in place,
+    /// stopping at the first error `f` returns.
+    ///
+    /// Positions before the one which failed will already have been changed.
+    pub fn try_map_coords_in_place<E>(
+        &mut self,
+        mut f: impl FnMut((f64, f64)) -> Result<(f64, f64), E>,
+    ) -> Result<(), E> {
+        try_map(self, &mut f)
+    }
+}
+
+impl Geometry {
+    /// Return a copy of this geometry with `f` applied to the `(x, y)` of every position.
+    ///
+    /// The copy has no `bbox`, which would otherwise be stale. See
+    /// [`Value::map_coords`](enum.Value.html#method.map_coords).
+    pub fn map_coords(&self, f: impl FnMut((f64, f64)) -> (f64, f64)) -> Self {
+        let mut geometry = self.clone();
+        geometry.map_coords_in_place(f);
+        geometry
+    }
+
+    /// Apply `f` to the `(x, y)` of every position of this geometry, in place, and clear its
+    /// `bbox`.
+    pub fn map_coords_in_place(&mut self, f: impl FnMut((f64, f64)) -> (f64, f64)) {
+        let _ = self.try_map_coords_in_place(infallible(f));
+    }
+
+    /// Return a copy of this geometry with the fallible `f` applied to the `(x, y)` of every
+    /// position, or the first error `f` returns.
+    ///
+    /// See [`Value::try_map_coords`](enum.Value.html#method.try_map_coords).
+    pub fn try_map_coords<E>(
+        &self,
+        f: impl FnMut((f64, f64)) -> Result<(f64, f64), E>,
+    ) -> Result<Self, E> {
+        let mut geometry = self.clone();
+        geometry.try_map_coords_in_place(f)?;
+        Ok(geometry)
+    }
+
+    /// Apply the fallible `f` to the `(x, y)` of every position of this geometry, in place,
+    /// stopping at the first error `f` returns, and clear its `bbox`.
+    pub fn try_map_coords_in_place<E>(
+        &mut self,
+        mut f: impl FnMut((f64, f64)) -> Result<(f64, f64), E>,
+    ) -> Result<(), E> {
+        try_map(self, &mut f)
+    }
+}
+
+impl Feature {
+    /// Return a copy of this feature with `f` applied to the `(x, y)` of every position of its
+    /// geometry.
+    ///
+    /// The copy has no `bbox`, which would otherwise be stale. See
+    /// [`Value::map_coords`](enum.Value.html#method.map_coords).
+    pub fn map_coords(&self, f: impl FnMut((f64, f64)) -> (f64, f64)) -> Self {
+        let mut feature = self.clone();
+        feature.map_coords_in_place(f);
+        feature
+    }
+
+    /// Apply `f` to the `(x, y)` of every position of this feature's geometry, in place, and
+    /// clear the `bbox` of the feature and its geometry.
+    pub fn map_coords_in_place(&mut self, f: impl FnMut((f64, f64)) -> (f64, f64)) {
+        let _ = self.try_map_coords_in_place(infallible(f));
+    }
+
+    /// Return a copy of this feature with the fallible `f` applied to the `(x, y)` of every
+    /// position of its geometry, or the first error `f` returns.
+    ///
+    /// See [`Value::try_map_coords`](enum.Value.html#method.try_map_coords).
+    pub fn try_map_coords<E>(
+        &self,
+        f: impl FnMut((f64, f64)) -> Result<(f64, f64), E>,
+    ) -> Result<Self, E> {
+        let mut feature = self.clone();
+        feature.try_map_coords_in_place(f)?;
+        Ok(feature)
+    }
+
+    /// Apply the fallible `f` to the `(x, y)` of every position of this feature's geometry, in
+    /// place, stopping at the first error `f` returns, and clear the `bbox` of the feature and
+    /// its geometry.
+    pub fn try_map_coords_in_place<E>(
+        &mut self,
+        mut f: impl FnMut((f64, f64)) -> Result<(f64, f64), E>,
+    ) -> Result<(), E> {
+        try_map(self, &mut f)
+    }
+}
+
+impl FeatureCollection {
+    /// Return a copy of this collection with `f` applied to the `(x, y)` of every position of
+    /// every feature.
+    ///
+    /// The copy and its features have no `bbox`, which would otherwise be stale. See
+    /// [`Value::map_coords`](enum.Value.html#method.map_coords).
+    pub fn map_coords(&self, f: impl FnMut((f64, f64)) -> (f64, f64)) -> Self {
+        let mut collection = self.clone();
+        collection.map_coords_in_place(f);
+        collection
+    }
+
+    /// Apply `f` to the `(x, y)` of every position of every feature, in place, and clear the
+    /// `bbox`es of the collection and its features.
+    pub fn map_coords_in_place(&mut self, f: impl FnMut((f64, f64)) -> (f64, f64)) {
+        let _ = self.try_map_coords_in_place(infallible(f));
+    }
+
+    /// Return a copy of this collection with the fallible `f` applied to the `(x, y)` of every
+    /// position of every feature, or the first error `f` returns.
+    ///
+    /// See [`Value::try_map_coords`](enum.Value.html#method.try_map_coords).
+    pub fn try_map_coords<E>(
+        &self,
+        f: impl FnMut((f64, f64)) -> Result<(f64, f64), E>,
+    ) -> Result<Self, E> {
+        let mut collection = self.clone();
+        collection.try_map_coords_in_place(f)?;
+        Ok(collection)
+    }
+
+    /// Apply the fallible `f` to the `(x, y)` of every position of every feature, in place,
+    /// stopping at the first error `f` returns, and clear the `bbox`es of the collection and
+    /// its features.
+    pub fn try_map_coords_in_place<E>(
+        &mut self,
+        mut f: impl FnMut((f64, f64)) -> Result<(f64, f64), E>,
+    ) -> Result<(), E> {
+        try_map(self, &mut f)
+    }
+}
+
+impl GeoJson {
+    /// Return a copy of this object with `f` applied to the `(x, y)` of every position.
+    ///
+    /// See [`Value::map_coords`](enum.Value.html#method.map_coords).
+    pub fn map_coords(&self, f: impl FnMut((f64, f64)) -> (f64, f64)) -> Self {
+        let mut geojson = self.clone();
+        geojson.map_coords_in_place(f);
+        geojson
+    }
+
+    /// Apply `f` to the `(x, y)` of every position of this object, in place, and clear any
+    /// `bbox`es made stale.
+    pub fn map_coords_in_place(&mut self, f: impl FnMut((f64, f64)) -> (f64, f64)) {
+        match self {
+            GeoJson::Geometry(geometry) => geometry.map_coords_in_place(f),
+            GeoJson::Feature(feature) => feature.map_coords_in_place(f),
+            GeoJson::FeatureCollection(collection) => collection.map_coords_in_place(f),
+        }
+    }
+
+    /// Return a copy of this object with the fallible `f` applied to the `(x, y)` of every
+    /// position, or the first error `f` returns.
+    ///
+    /// See [`Value::try_map_coords`](enum.Value.html#method.try_map_coords).
+    pub fn try_map_coords<E>(
+        &self,
+        f: impl FnMut((f64, f64)) -> Result<(f64, f64), E>,
+    ) -> Result<Self, E> {
+        let mut geojson = self.clone();
+        geojson.try_map_coords_in_place(f)?;
+        Ok(geojson)
+    }
+
+    /// Apply the fallible `f` to the `(x, y)` of every position of this object, in place,
+    /// stopping at the first error `f` returns, and clear any `bbox`es made stale.
+    pub fn try_map_coords_in_place<E>(
+        &mut self,
+        mut f: impl FnMut((f64, f64)) -> Result<(f64, f64), E>,
+    ) -> Result<(), E> {
+        try_map(self, &mut f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Feature, FeatureCollection, GeoJson, Geometry, Value};
+
+    #[test]
+    fn map_collection() {
+        let mut feature = Feature::from(Geometry::new(Value::GeometryCollection(vec![
+            Geometry::new(Value::Point(position![1.0, 2.0, 3.0])),
+            Geometry::new(Value::LineString(vec![
+                position![0.0, 0.0],
+                position![-1.0, 1.0],
+            ])),
+        ])));
+        feature.bbox = Some(vec![-1.0, 0.0, 1.0, 2.0]);
+        let mut geojson = GeoJson::from(FeatureCollection {
+            bbox: Some(vec![-1.0, 0.0, 1.0, 2.0]),
+            features: vec![feature],
+            foreign_members: None,
+        });
+
+        let mut calls = 0;
+        geojson.map_coords_in_place(|(x, y)| {
+            calls += 1;
+            (x + 10.0, -y)
+        });
+        assert_eq!(calls, 3);
+
+        let collection = match geojson {
+            GeoJson::FeatureCollection(collection) => collection,
+            _ => unreachable!(),
+        };
+        assert_eq!(collection.bbox, None);
+        assert_eq!(collection.features[0].bbox, None);
+        assert_eq!(
+            collection.features[0].geometry.as_ref().unwrap().value,
+            Value::GeometryCollection(vec![
+                Geometry::new(Value::Point(position![11.0, -2.0, 3.0])),
+                Geometry::new(Value::LineString(vec![
+                    position![10.0, 0.0],
+                    position![9.0, -1.0]
+                ])),
+            ])
+        );
+    }
+
+    #[test]
+    fn try_map_stops_at_first_error() {
+        let collection = FeatureCollection {
+            bbox: None,
+            features: vec![
+                Feature::from(Geometry::new(Value::Point(position![1.0, 1.0]))),
+                Feature::from(Geometry::new(Value::Point(position![200.0, 1.0]))),
+                Feature::from(Geometry::new(Value::Point(position![300.0, 1.0]))),
+            ],
+            foreign_members: None,
+        };
+        let mut calls = 0;
+        let result = collection.try_map_coords(|(x, y)| {
+            calls += 1;
+            if x > 180.0 {
+                Err(x)
+            } else {
+                Ok((x, y))
+            }
+        });
+        assert_eq!(result, Err(200.0));
+        assert_eq!(calls, 2);
+
+        let mapped = collection.try_map_coords(|(x, y)| Ok::<_, ()>((y, x)));
+        assert_eq!(
+            mapped.unwrap().features[2].geometry.as_ref().unwrap().value,
+            Value::Point(position![1.0, 300.0])
+        );
+    }
+}
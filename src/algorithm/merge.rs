@@ -0,0 +1,442 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::feature_collection::IdKey;
+use crate::json::JsonObject;
+use crate::{Bbox, Feature, FeatureCollection, Geometry, Value};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// What [`FeatureCollection::merge`](struct.FeatureCollection.html#method.merge) does with a
+/// feature of the second collection whose `id` is already taken by a feature of the first
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MergePolicy {
+    /// Keep the feature of the first collection, and drop the other
+    KeepFirst,
+    /// Replace the feature of the first collection with the other, in its place
+    KeepLast,
+    /// Keep the feature of the first collection, adding the properties of the other to it and
+    /// overwriting those with the same names
+    MergeProperties,
+}
+
+/// Merge the geometries of a group of features into a single geometry.
+///
+/// Points, lines and polygons become `MultiPoint`s, `MultiLineString`s and `MultiPolygon`s,
+/// while a mixture of kinds becomes a `GeometryCollection`.
+fn merge_geometries(geometries: Vec<Geometry>) -> Option<Geometry> {
+    let mut points = Vec::new();
+    let mut lines = Vec::new();
+    let mut polygons = Vec::new();
+    let mut kinds = [false; 4];
+    for geometry in &geometries {
+        match &geometry.value {
+            Value::Point(point) => points.push(point.clone()),
+            Value::MultiPoint(parts) => points.extend(parts.iter().cloned()),
+            Value::LineString(line) => lines.push(line.clone()),
+            Value::MultiLineString(parts) => lines.extend(parts.iter().cloned()),
+            Value::Polygon(polygon) => polygons.push(polygon.clone()),
+            Value::MultiPolygon(parts) => polygons.extend(parts.iter().cloned()),
+            Value::GeometryCollection(_) => {}
+        }
+        let kind = match &geometry.value {
+            Value::Point(_) | Value::MultiPoint(_) => 0,
+            Value::LineString(_) | Value::MultiLineString(_) => 1,
+            Value::Polygon(_) | Value::MultiPolygon(_) => 2,
+            Value::GeometryCollection(_) => 3,
+        };
+        kinds[kind] = true;
+    }
+    let value = match kinds {
+        [false, false, false, false] => return None,
+        [true, false, false, false] => Value::MultiPoint(points),
+        [false, true, false, false] => Value::MultiLineString(lines),
+        [false, false, true, false] => Value::MultiPolygon(polygons),
+        _ => Value::GeometryCollection(
+            geometries
+                .into_iter()
+                .map(|geometry| Geometry {
+                    bbox: None,
+                    ..geometry
+                })
+                .collect(),
+        ),
+    };
+    Some(Geometry::new(value))
+}
+
+impl FeatureCollection {
+    /// Merge the features which have the same `key` into one feature each, combining their
+    /// geometries into multi-part geometries.
+    ///
+    /// Points, lines and polygons are combined into `MultiPoint`s, `MultiLineString`s and
+    /// `MultiPolygon`s respectively, and a mixture of them into a `GeometryCollection`. Each
+    /// merged feature takes the place, `id`, `properties` and foreign members of the first
+    /// feature of its group, and has no `bbox`. Features for which `key` returns `None`, and
+    /// groups of one feature, are kept unchanged.
+    ///
+    /// This is the inverse of [`explode`](#method.explode).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geojson::{position, Feature, FeatureCollection, Value};
+    ///
+    /// let stop = |name: &str, x: f64| {
+    ///     let mut feature = Feature::from(Value::Point(position![x, 0.0]));
+    ///     feature.set_property("route", name);
+    ///     feature
+    /// };
+    /// let collection = FeatureCollection {
+    ///     bbox: None,
+    ///     features: vec![stop("A", 0.0), stop("B", 1.0), stop("A", 2.0)],
+    ///     foreign_members: None,
+    /// };
+    ///
+    /// let merged = collection.merge_by_property("route");
+    /// assert_eq!(merged.features.len(), 2);
+    /// assert_eq!(
+    ///     merged.features[0].geometry.as_ref().unwrap().value,
+    ///     Value::MultiPoint(vec![position![0.0, 0.0], position![2.0, 0.0]])
+    /// );
+    /// ```
+    pub fn merge_by<K: Eq + Hash>(self, mut key: impl FnMut(&Feature) -> Option<K>) -> Self {
+        let mut groups: Vec<Vec<Feature>> = Vec::new();
+        let mut group_indices: HashMap<K, usize> = HashMap::new();
+        for feature in self.features {
+            match key(&feature) {
+                Some(key) => match group_indices.get(&key) {
+                    Some(&index) => groups[index].push(feature),
+                    None => {
+                        group_indices.insert(key, groups.len());
+                        groups.push(vec![feature]);
+                    }
+                },
+                None => groups.push(vec![feature]),
+            }
+        }
+
+        let features = groups
+            .into_iter()
+            .map(|mut group| {
+                if group.len() == 1 {
+                    return group.remove(0);
+                }
+                let geometries = group.iter_mut().filter_map(|f| f.geometry.take()).collect();
+                let first = group.swap_remove(0);
+                Feature {
+                    bbox: None,
+                    geometry: merge_geometries(geometries),
+                    ..first
+                }
+            })
+            .collect();
+        FeatureCollection {
+            bbox: self.bbox,
+            features,
+            foreign_members: self.foreign_members,
+        }
+    }
+
+    /// Merge the features which have the same `id`.
+    ///
+    /// See [`merge_by`](#method.merge_by).
+    pub fn merge_by_id(self) -> Self {
+        self.merge_by(|feature| {
+            let id = feature.id.as_ref()?;
+            serde_json::to_string(id).ok()
+        })
+    }
+
+    /// Merge the features which have the same value of the property `name`. Features without
+    /// the property, or with a `null` value, are kept unchanged.
+    ///
+    /// See [`merge_by`](#method.merge_by).
+    pub fn merge_by_property(self, name: impl AsRef<str>) -> Self {
+        self.merge_by(|feature| match feature.property(name.as_ref()) {
+            None | Some(serde_json::Value::Null) => None,
+            Some(value) => Some(value.to_string()),
+        })
+    }
+}
+
+/// The union of two bboxes of the same dimensions
+fn union_bbox(a: Bbox, b: Bbox) -> Option<Bbox> {
+    if a.len() != b.len() || !matches!(a.len(), 4 | 6) {
+        return None;
+    }
+    let dimensions = a.len() / 2;
+    Some(
+        a.iter()
+            .zip(&b)
+            .enumerate()
+            .map(|(i, (a, b))| if i < dimensions { a.min(*b) } else { a.max(*b) })
+            .collect(),
+    )
+}
+
+impl FeatureCollection {
+    /// Combine the features of this collection and `other`, such as regional extracts of one
+    /// dataset, into one collection, resolving features of both with the same `id` by
+    /// `policy`.
+    ///
+    /// The features of `other` follow those of this collection, except those replaced or
+    /// dropped by `policy`. Features without an `id`, and features with the same `id` within one
+    /// collection, are all kept. The merged `bbox` is the union of both bboxes if both
+    /// collections have one, and otherwise `None`. The foreign members of both are kept, those
+    /// of `other` overwriting any with the same names unless `policy` is
+    /// [`KeepFirst`](enum.MergePolicy.html#variant.KeepFirst).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geojson::feature::Id;
+    /// use geojson::{position, Feature, FeatureCollection, MergePolicy, Value};
+    ///
+    /// let road = |id: &str, name: &str| {
+    ///     let mut feature = Feature::from(Value::Point(position![0.0, 0.0]));
+    ///     feature.id = Some(Id::String(id.to_string()));
+    ///     feature.set_property("name", name);
+    ///     feature
+    /// };
+    /// let north = FeatureCollection {
+    ///     bbox: Some(vec![0.0, 5.0, 10.0, 10.0]),
+    ///     features: vec![road("a", "Old Road"), road("b", "High Street")],
+    ///     foreign_members: None,
+    /// };
+    /// let south = FeatureCollection {
+    ///     bbox: Some(vec![0.0, 0.0, 10.0, 5.0]),
+    ///     features: vec![road("a", "New Road"), road("c", "Low Street")],
+    ///     foreign_members: None,
+    /// };
+    ///
+    /// let merged = north.merge(south, MergePolicy::KeepLast);
+    /// assert_eq!(merged.bbox, Some(vec![0.0, 0.0, 10.0, 10.0]));
+    /// let names: Vec<_> = merged.features.iter().map(|f| f.property("name").unwrap()).collect();
+    /// assert_eq!(names, ["New Road", "High Street", "Low Street"]);
+    /// ```
+    pub fn merge(self, other: FeatureCollection, policy: MergePolicy) -> FeatureCollection {
+        let mut features = self.features;
+        let mut indices: HashMap<IdKey, usize> = HashMap::new();
+        for (index, feature) in features.iter().enumerate() {
+            if let Some(id) = &feature.id {
+                indices.entry(IdKey::from(id)).or_insert(index);
+            }
+        }
+        for feature in other.features {
+            let index = feature
+                .id
+                .as_ref()
+                .and_then(|id| indices.get(&IdKey::from(id)).copied());
+            let existing = match index {
+                Some(index) => &mut features[index],
+                None => {
+                    features.push(feature);
+                    continue;
+                }
+            };
+            match policy {
+                MergePolicy::KeepFirst => {}
+                MergePolicy::KeepLast => *existing = feature,
+                MergePolicy::MergeProperties => {
+                    if let Some(properties) = feature.properties {
+                        existing
+                            .properties
+                            .get_or_insert_with(JsonObject::new)
+                            .extend(properties);
+                    }
+                }
+            }
+        }
+
+        let bbox = match (self.bbox, other.bbox) {
+            (Some(a), Some(b)) => union_bbox(a, b),
+            _ => None,
+        };
+        let foreign_members = match (self.foreign_members, other.foreign_members) {
+            (Some(mut first), Some(last)) => {
+                for (key, value) in last {
+                    if policy != MergePolicy::KeepFirst || !first.contains_key(&key) {
+                        first.insert(key, value);
+                    }
+                }
+                Some(first)
+            }
+            (first, last) => first.or(last),
+        };
+        FeatureCollection {
+            bbox,
+            features,
+            foreign_members,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MergePolicy;
+    use crate::feature::Id;
+    use crate::{Feature, FeatureCollection, Value};
+
+    fn feature(id: Option<&str>, value: Option<Value>) -> Feature {
+        let mut feature = Feature::from(Value::Point(position![]));
+        feature.geometry = value.map(Into::into);
+        feature.id = id.map(|id| Id::String(id.to_string()));
+        feature
+    }
+
+    #[test]
+    fn merge_by_id() {
+        let line = Value::LineString(vec![position![0.0, 0.0], position![1.0, 1.0]]);
+        let collection = FeatureCollection {
+            bbox: None,
+            features: vec![
+                feature(Some("a"), Some(line.clone())),
+                feature(None, Some(Value::Point(position![5.0, 5.0]))),
+                feature(Some("b"), Some(Value::Point(position![0.0, 0.0]))),
+                feature(Some("a"), None),
+                feature(Some("a"), Some(Value::MultiLineString(vec![vec![]]))),
+                feature(Some("b"), Some(line.clone())),
+            ],
+            foreign_members: None,
+        }
+        .merge_by_id();
+
+        let values: Vec<_> = collection
+            .features
+            .iter()
+            .map(|f| (f.id.clone(), f.geometry.as_ref().unwrap().value.clone()))
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                (
+                    Some(Id::String("a".to_string())),
+                    Value::MultiLineString(vec![
+                        vec![position![0.0, 0.0], position![1.0, 1.0]],
+                        vec![]
+                    ])
+                ),
+                (None, Value::Point(position![5.0, 5.0])),
+                (
+                    Some(Id::String("b".to_string())),
+                    Value::GeometryCollection(vec![
+                        Value::Point(position![0.0, 0.0]).into(),
+                        line.into()
+                    ])
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn explode_and_merge() {
+        let mut multi = Feature::from(Value::MultiPoint(vec![
+            position![0.0, 0.0],
+            position![1.0, 1.0],
+        ]));
+        multi.id = Some(Id::Number(1.into()));
+        let collection = FeatureCollection {
+            bbox: None,
+            features: vec![multi],
+            foreign_members: None,
+        };
+        assert_eq!(collection.clone().explode().merge_by_id(), collection);
+    }
+
+    #[test]
+    fn merge_collections() {
+        let with_name = |id: Option<&str>, name: &str, lanes: Option<u64>| {
+            let mut feature = feature(id, None);
+            feature.set_property("name", name);
+            if let Some(lanes) = lanes {
+                feature.set_property("lanes", lanes);
+            }
+            feature
+        };
+        let first = FeatureCollection {
+            bbox: Some(vec![0.0, 0.0, 1.0, 1.0]),
+            features: vec![
+                with_name(Some("a"), "first a", Some(2)),
+                with_name(None, "first", None),
+            ],
+            foreign_members: Some(
+                serde_json::json!({ "source": "north" })
+                    .as_object()
+                    .unwrap()
+                    .clone(),
+            ),
+        };
+        let last = FeatureCollection {
+            bbox: Some(vec![0.0, 0.0, 0.0, 2.0, 0.5, 0.0]),
+            features: vec![
+                with_name(Some("a"), "last a", None),
+                with_name(None, "last", None),
+                with_name(Some("b"), "last b", None),
+            ],
+            foreign_members: Some(
+                serde_json::json!({ "source": "south", "year": 2020 })
+                    .as_object()
+                    .unwrap()
+                    .clone(),
+            ),
+        };
+        let names = |collection: &FeatureCollection| -> Vec<_> {
+            collection
+                .features
+                .iter()
+                .map(|f| f.properties.clone().unwrap())
+                .map(serde_json::Value::Object)
+                .collect()
+        };
+
+        let kept = first.clone().merge(last.clone(), MergePolicy::KeepFirst);
+        assert_eq!(kept.bbox, None);
+        assert_eq!(
+            names(&kept),
+            vec![
+                serde_json::json!({ "name": "first a", "lanes": 2 }),
+                serde_json::json!({ "name": "first" }),
+                serde_json::json!({ "name": "last" }),
+                serde_json::json!({ "name": "last b" }),
+            ]
+        );
+        assert_eq!(
+            serde_json::Value::Object(kept.foreign_members.unwrap()),
+            serde_json::json!({ "source": "north", "year": 2020 })
+        );
+
+        let replaced = first.clone().merge(last.clone(), MergePolicy::KeepLast);
+        assert_eq!(names(&replaced)[0], serde_json::json!({ "name": "last a" }));
+        assert_eq!(replaced.features.len(), 4);
+        assert_eq!(replaced.foreign_members.unwrap()["source"], "south");
+
+        let merged = first.clone().merge(last, MergePolicy::MergeProperties);
+        assert_eq!(
+            names(&merged)[0],
+            serde_json::json!({ "name": "last a", "lanes": 2 })
+        );
+
+        let both = first.clone().merge(
+            FeatureCollection {
+                bbox: Some(vec![-1.0, 0.5, 0.5, 3.0]),
+                features: vec![],
+                foreign_members: None,
+            },
+            MergePolicy::KeepFirst,
+        );
+        assert_eq!(both.bbox, Some(vec![-1.0, 0.0, 1.0, 3.0]));
+        assert_eq!(both.foreign_members, first.foreign_members);
+    }
+}
@@ -0,0 +1,406 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Geometric operations which work directly on GeoJSON coordinates
+
+use crate::{Bbox, Feature, FeatureCollection, GeoJson, Geometry, Position, Value};
+
+pub(crate) mod affine;
+mod altitude;
+mod bbox;
+mod canonicalize;
+mod clip;
+mod coords_iter;
+mod dedup;
+mod densify;
+mod explode;
+mod fingerprint;
+mod flatten;
+mod hilbert;
+mod map_coords;
+pub(crate) mod merge;
+pub(crate) mod round;
+pub(crate) mod sample;
+mod simplify;
+pub(crate) mod stats;
+mod swap_xy;
+
+#[cfg(feature = "rstar")]
+pub(crate) mod distance;
+pub(crate) mod intersects;
+
+/// An axis-aligned 2D rectangle
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Rect {
+    pub min_x: f64,
+    pub min_y: f64,
+    pub max_x: f64,
+    pub max_y: f64,
+}
+
+impl Rect {
+    /// Create a `Rect` from a `[min_x, min_y, max_x, max_y]` array
+    pub fn from_bbox(bbox: [f64; 4]) -> Self {
+        Rect {
+            min_x: bbox[0],
+            min_y: bbox[1],
+            max_x: bbox[2],
+            max_y: bbox[3],
+        }
+    }
+
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.min_x && x <= self.max_x && y >= self.min_y && y <= self.max_y
+    }
+
+    /// The corners of this rectangle, counter-clockwise from `(min_x, min_y)`
+    pub fn corners(&self) -> [(f64, f64); 4] {
+        [
+            (self.min_x, self.min_y),
+            (self.max_x, self.min_y),
+            (self.max_x, self.max_y),
+            (self.min_x, self.max_y),
+        ]
+    }
+
+    /// A closed exterior ring tracing this rectangle counter-clockwise
+    pub fn to_ring(self) -> Vec<Position> {
+        let mut ring: Vec<Position> = self
+            .corners()
+            .iter()
+            .map(|&(x, y)| position![x, y])
+            .collect();
+        ring.push(position![self.min_x, self.min_y]);
+        ring
+    }
+}
+
+/// Call `f` on every position within `value`, descending into `GeometryCollection`s
+pub(crate) fn for_each_position<'a>(value: &'a Value, f: &mut impl FnMut(&'a Position)) {
+    match value {
+        Value::Point(position) => f(position),
+        Value::MultiPoint(positions) | Value::LineString(positions) => positions.iter().for_each(f),
+        Value::MultiLineString(lines) | Value::Polygon(lines) => lines.iter().flatten().for_each(f),
+        Value::MultiPolygon(polygons) => polygons.iter().flatten().flatten().for_each(f),
+        Value::GeometryCollection(geometries) => geometries
+            .iter()
+            .for_each(|geometry| for_each_position(&geometry.value, f)),
+    }
+}
+
+/// Call `f` on every position within `value`, descending into `GeometryCollection`s and
+/// stopping at the first error
+pub(crate) fn try_for_each_position_mut<E>(
+    value: &mut Value,
+    f: &mut impl FnMut(&mut Position) -> Result<(), E>,
+) -> Result<(), E> {
+    match value {
+        Value::Point(position) => f(position),
+        Value::MultiPoint(positions) | Value::LineString(positions) => {
+            positions.iter_mut().try_for_each(f)
+        }
+        Value::MultiLineString(lines) | Value::Polygon(lines) => {
+            lines.iter_mut().flatten().try_for_each(f)
+        }
+        Value::MultiPolygon(polygons) => polygons.iter_mut().flatten().flatten().try_for_each(f),
+        Value::GeometryCollection(geometries) => geometries
+            .iter_mut()
+            .try_for_each(|geometry| try_for_each_position_mut(&mut geometry.value, f)),
+    }
+}
+
+/// GeoJSON objects made up of geometries
+pub(crate) trait Values {
+    /// Call `f` on the value of every geometry of this object, without descending into
+    /// `GeometryCollection`s
+    fn for_each_value<'a>(&'a self, f: &mut impl FnMut(&'a Value));
+}
+
+impl Values for Value {
+    fn for_each_value<'a>(&'a self, f: &mut impl FnMut(&'a Value)) {
+        f(self)
+    }
+}
+
+impl Values for Geometry {
+    fn for_each_value<'a>(&'a self, f: &mut impl FnMut(&'a Value)) {
+        f(&self.value)
+    }
+}
+
+impl Values for Feature {
+    fn for_each_value<'a>(&'a self, f: &mut impl FnMut(&'a Value)) {
+        if let Some(geometry) = &self.geometry {
+            f(&geometry.value)
+        }
+    }
+}
+
+impl Values for FeatureCollection {
+    fn for_each_value<'a>(&'a self, f: &mut impl FnMut(&'a Value)) {
+        for feature in &self.features {
+            feature.for_each_value(f);
+        }
+    }
+}
+
+impl Values for GeoJson {
+    fn for_each_value<'a>(&'a self, f: &mut impl FnMut(&'a Value)) {
+        match self {
+            GeoJson::Geometry(geometry) => geometry.for_each_value(f),
+            GeoJson::Feature(feature) => feature.for_each_value(f),
+            GeoJson::FeatureCollection(collection) => collection.for_each_value(f),
+        }
+    }
+}
+
+/// GeoJSON objects whose positions and bounding boxes can be modified in place
+pub(crate) trait PositionsMut {
+    /// Call `f` on every position, descending into `GeometryCollection`s and stopping at the
+    /// first error
+    fn try_for_each_position_mut<E>(
+        &mut self,
+        f: &mut impl FnMut(&mut Position) -> Result<(), E>,
+    ) -> Result<(), E>;
+
+    /// Call `f` on the `bbox` of this object and of every object nested within it
+    fn for_each_bbox_mut(&mut self, f: &mut impl FnMut(&mut Option<Bbox>));
+
+    /// Call `f` on the value of every geometry of this object, without descending into
+    /// `GeometryCollection`s
+    fn for_each_value_mut(&mut self, f: &mut impl FnMut(&mut Value));
+}
+
+impl PositionsMut for Value {
+    fn try_for_each_position_mut<E>(
+        &mut self,
+        f: &mut impl FnMut(&mut Position) -> Result<(), E>,
+    ) -> Result<(), E> {
+        try_for_each_position_mut(self, f)
+    }
+
+    fn for_each_bbox_mut(&mut self, f: &mut impl FnMut(&mut Option<Bbox>)) {
+        if let Value::GeometryCollection(geometries) = self {
+            for geometry in geometries {
+                geometry.for_each_bbox_mut(f);
+            }
+        }
+    }
+
+    fn for_each_value_mut(&mut self, f: &mut impl FnMut(&mut Value)) {
+        f(self)
+    }
+}
+
+impl PositionsMut for Geometry {
+    fn try_for_each_position_mut<E>(
+        &mut self,
+        f: &mut impl FnMut(&mut Position) -> Result<(), E>,
+    ) -> Result<(), E> {
+        try_for_each_position_mut(&mut self.value, f)
+    }
+
+    fn for_each_bbox_mut(&mut self, f: &mut impl FnMut(&mut Option<Bbox>)) {
+        f(&mut self.bbox);
+        self.value.for_each_bbox_mut(f);
+    }
+
+    fn for_each_value_mut(&mut self, f: &mut impl FnMut(&mut Value)) {
+        f(&mut self.value)
+    }
+}
+
+impl PositionsMut for Feature {
+    fn try_for_each_position_mut<E>(
+        &mut self,
+        f: &mut impl FnMut(&mut Position) -> Result<(), E>,
+    ) -> Result<(), E> {
+        match &mut self.geometry {
+            Some(geometry) => geometry.try_for_each_position_mut(f),
+            None => Ok(()),
+        }
+    }
+
+    fn for_each_bbox_mut(&mut self, f: &mut impl FnMut(&mut Option<Bbox>)) {
+        f(&mut self.bbox);
+        if let Some(geometry) = &mut self.geometry {
+            geometry.for_each_bbox_mut(f);
+        }
+    }
+
+    fn for_each_value_mut(&mut self, f: &mut impl FnMut(&mut Value)) {
+        if let Some(geometry) = &mut self.geometry {
+            f(&mut geometry.value)
+        }
+    }
+}
+
+impl PositionsMut for FeatureCollection {
+    fn try_for_each_position_mut<E>(
+        &mut self,
+        f: &mut impl FnMut(&mut Position) -> Result<(), E>,
+    ) -> Result<(), E> {
+        self.features
+            .iter_mut()
+            .try_for_each(|feature| feature.try_for_each_position_mut(f))
+    }
+
+    fn for_each_bbox_mut(&mut self, f: &mut impl FnMut(&mut Option<Bbox>)) {
+        f(&mut self.bbox);
+        for feature in &mut self.features {
+            feature.for_each_bbox_mut(f);
+        }
+    }
+
+    fn for_each_value_mut(&mut self, f: &mut impl FnMut(&mut Value)) {
+        for feature in &mut self.features {
+            feature.for_each_value_mut(f);
+        }
+    }
+}
+
+impl PositionsMut for GeoJson {
+    fn try_for_each_position_mut<E>(
+        &mut self,
+        f: &mut impl FnMut(&mut Position) -> Result<(), E>,
+    ) -> Result<(), E> {
+        match self {
+            GeoJson::Geometry(geometry) => geometry.try_for_each_position_mut(f),
+            GeoJson::Feature(feature) => feature.try_for_each_position_mut(f),
+            GeoJson::FeatureCollection(collection) => collection.try_for_each_position_mut(f),
+        }
+    }
+
+    fn for_each_bbox_mut(&mut self, f: &mut impl FnMut(&mut Option<Bbox>)) {
+        match self {
+            GeoJson::Geometry(geometry) => geometry.for_each_bbox_mut(f),
+            GeoJson::Feature(feature) => feature.for_each_bbox_mut(f),
+            GeoJson::FeatureCollection(collection) => collection.for_each_bbox_mut(f),
+        }
+    }
+
+    fn for_each_value_mut(&mut self, f: &mut impl FnMut(&mut Value)) {
+        match self {
+            GeoJson::Geometry(geometry) => geometry.for_each_value_mut(f),
+            GeoJson::Feature(feature) => feature.for_each_value_mut(f),
+            GeoJson::FeatureCollection(collection) => collection.for_each_value_mut(f),
+        }
+    }
+}
+
+/// An iterator over every position within `value`, descending into `GeometryCollection`s.
+///
+/// With `exterior_only`, only the exterior rings of polygons are included.
+pub(crate) fn positions(
+    value: &Value,
+    exterior_only: bool,
+) -> Box<dyn Iterator<Item = &Position> + '_> {
+    match value {
+        Value::Point(position) => Box::new(std::iter::once(position)),
+        Value::MultiPoint(positions) | Value::LineString(positions) => Box::new(positions.iter()),
+        Value::MultiLineString(lines) => Box::new(lines.iter().flatten()),
+        Value::Polygon(rings) if exterior_only => Box::new(rings.iter().take(1).flatten()),
+        Value::Polygon(rings) => Box::new(rings.iter().flatten()),
+        Value::MultiPolygon(polygons) if exterior_only => Box::new(
+            polygons
+                .iter()
+                .flat_map(|rings| rings.iter().take(1))
+                .flatten(),
+        ),
+        Value::MultiPolygon(polygons) => Box::new(polygons.iter().flatten().flatten()),
+        Value::GeometryCollection(geometries) => Box::new(
+            geometries
+                .iter()
+                .flat_map(move |geometry| positions(&geometry.value, exterior_only)),
+        ),
+    }
+}
+
+/// Twice the signed area of a closed ring, which is positive for counter-clockwise rings.
+///
+/// Positions with fewer than two elements are ignored.
+pub(crate) fn signed_area(ring: &[Position]) -> f64 {
+    let positions = || ring.iter().filter(|position| position.len() >= 2);
+    positions()
+        .zip(positions().skip(1))
+        .map(|(a, b)| a[0] * b[1] - b[0] * a[1])
+        .sum()
+}
+
+/// The squared planar distance from `(x, y)` to the segment from `a` to `b`
+pub(crate) fn segment_distance_2(
+    (ax, ay): (f64, f64),
+    (bx, by): (f64, f64),
+    x: f64,
+    y: f64,
+) -> f64 {
+    let (dx, dy) = (bx - ax, by - ay);
+    let length_2 = dx * dx + dy * dy;
+    let t = if length_2 == 0.0 {
+        0.0
+    } else {
+        (((x - ax) * dx + (y - ay) * dy) / length_2).clamp(0.0, 1.0)
+    };
+    (ax + t * dx - x).powi(2) + (ay + t * dy - y).powi(2)
+}
+
+/// The 2D extent of `value`, or `None` if it contains no positions.
+///
+/// Positions with fewer than two elements are ignored.
+pub(crate) fn bounding_rect(value: &Value) -> Option<Rect> {
+    let mut rect: Option<Rect> = None;
+    for_each_position(value, &mut |position| {
+        if position.len() < 2 {
+            return;
+        }
+        let (x, y) = (position[0], position[1]);
+        rect = Some(match rect {
+            None => Rect {
+                min_x: x,
+                min_y: y,
+                max_x: x,
+                max_y: y,
+            },
+            Some(r) => Rect {
+                min_x: r.min_x.min(x),
+                min_y: r.min_y.min(y),
+                max_x: r.max_x.max(x),
+                max_y: r.max_y.max(y),
+            },
+        });
+    });
+    rect
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bounding_rect, Rect};
+    use crate::{Geometry, Value};
+
+    #[test]
+    fn bounding_rect_of_nested_collection() {
+        let value = Value::GeometryCollection(vec![
+            Geometry::new(Value::Point(position![1.0, 5.0])),
+            Geometry::new(Value::LineString(vec![
+                position![-2.0, 0.0],
+                position![3.0, 1.0, 9.0],
+            ])),
+        ]);
+        assert_eq!(
+            bounding_rect(&value),
+            Some(Rect::from_bbox([-2.0, 0.0, 3.0, 5.0]))
+        );
+        assert_eq!(bounding_rect(&Value::MultiPoint(vec![])), None);
+    }
+}
@@ -0,0 +1,104 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::PositionsMut;
+use crate::{Feature, FeatureCollection, GeoJson, Geometry, Value};
+use std::convert::Infallible;
+
+pub(crate) fn round(coordinate: &mut f64, factor: f64) {
+    let rounded = (*coordinate * factor).round() / factor;
+    // Scaling very large coordinates, or by a very large factor, overflows
+    if rounded.is_finite() {
+        *coordinate = rounded;
+    }
+}
+
+fn round_coords(target: &mut impl PositionsMut, decimals: u8) {
+    let factor = 10f64.powi(i32::from(decimals));
+    // Rounding is monotonic, so a rounded bbox still bounds the rounded positions
+    target.for_each_bbox_mut(&mut |bbox| {
+        bbox.iter_mut()
+            .flatten()
+            .for_each(|coordinate| round(coordinate, factor))
+    });
+    let _ = target.try_for_each_position_mut(&mut |position| {
+        position
+            .iter_mut()
+            .for_each(|coordinate| round(coordinate, factor));
+        Ok::<_, Infallible>(())
+    });
+}
+
+macro_rules! impl_round_coords {
+    ($($type:ty),*) => {
+        $(
+            impl $type {
+                /// Round every coordinate, including altitudes and any `bbox`, to `decimals`
+                /// decimal places, in place.
+                ///
+                /// Six decimal places of longitude or latitude is a precision of roughly ten
+                /// centimetres, and is usually enough to publish data at a fraction of its
+                /// size.
+                ///
+                /// ```
+                /// use geojson::{position, Value};
+                ///
+                /// let mut point = Value::Point(position![-0.127758123, 51.507351234, 11.04]);
+                /// point.round_coords(5);
+                /// assert_eq!(point, Value::Point(position![-0.12776, 51.50735, 11.04]));
+                /// ```
+                pub fn round_coords(&mut self, decimals: u8) {
+                    round_coords(self, decimals)
+                }
+            }
+        )*
+    };
+}
+
+impl_round_coords!(Value, Geometry, Feature, FeatureCollection, GeoJson);
+
+#[cfg(test)]
+mod tests {
+    use crate::{Feature, Geometry, Value};
+
+    #[test]
+    fn round_feature() {
+        let mut feature = Feature::from(Geometry::new(Value::GeometryCollection(vec![
+            Geometry {
+                bbox: Some(vec![1.23456, 2.5, 1.23456, 2.5]),
+                value: Value::Point(position![1.23456, 2.5]),
+                foreign_members: None,
+            },
+            Geometry::new(Value::LineString(vec![
+                position![-1.05, 1e300, 9.99999],
+                position![0.0, -0.004],
+            ])),
+        ])));
+        feature.bbox = Some(vec![-1.05, -0.004, 1.23456, 1e300]);
+        feature.round_coords(2);
+
+        assert_eq!(feature.bbox, Some(vec![-1.05, -0.0, 1.23, 1e300]));
+        match feature.geometry.unwrap().value {
+            Value::GeometryCollection(geometries) => {
+                assert_eq!(geometries[0].bbox, Some(vec![1.23, 2.5, 1.23, 2.5]));
+                assert_eq!(geometries[0].value, Value::Point(position![1.23, 2.5]));
+                assert_eq!(
+                    geometries[1].value,
+                    Value::LineString(vec![position![-1.05, 1e300, 10.0], position![0.0, 0.0]])
+                );
+            }
+            _ => unreachable!(),
+        }
+    }
+}
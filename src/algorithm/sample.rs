@@ -0,0 +1,225 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::FeatureCollection;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+/// A seed which differs between runs, from the random keys the standard library gives each
+/// `HashMap`
+fn random_seed() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(0);
+    hasher.finish()
+}
+
+/// A [SplitMix64](https://prng.di.unimi.it/splitmix64.c) pseudorandom number generator, which
+/// is fast and good enough to choose samples with
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`
+    fn below(&mut self, n: usize) -> usize {
+        ((u128::from(self.next_u64()) * n as u128) >> 64) as usize
+    }
+
+    /// A number in `0.0..1.0`
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// A uniform random sample of up to `size` of a stream of items, of unknown length, chosen by
+/// reservoir sampling
+pub(crate) struct Reservoir<T> {
+    rng: Rng,
+    size: usize,
+    seen: usize,
+    items: Vec<(usize, T)>,
+}
+
+impl<T> Reservoir<T> {
+    pub fn new(size: usize, seed: u64) -> Self {
+        Reservoir {
+            rng: Rng::new(seed),
+            size,
+            seen: 0,
+            items: Vec::with_capacity(size),
+        }
+    }
+
+    /// Count the next item of the stream, returning the slot of the sample to
+    /// [`insert`](#method.insert) it into if it is chosen, so items which aren't chosen
+    /// needn't be made at all
+    pub fn offer(&mut self) -> Option<usize> {
+        let index = self.seen;
+        self.seen += 1;
+        if index < self.size {
+            Some(index)
+        } else {
+            Some(self.rng.below(index + 1)).filter(|&slot| slot < self.size)
+        }
+    }
+
+    /// Put the item last offered into `slot`
+    pub fn insert(&mut self, slot: usize, item: T) {
+        let index = self.seen - 1;
+        if slot == self.items.len() {
+            self.items.push((index, item));
+        } else {
+            self.items[slot] = (index, item);
+        }
+    }
+
+    /// The sampled items, in the order of the stream
+    pub fn into_items(mut self) -> Vec<T> {
+        self.items.sort_unstable_by_key(|&(index, _)| index);
+        self.items.into_iter().map(|(_, item)| item).collect()
+    }
+}
+
+impl FeatureCollection {
+    /// A copy of this collection with `n` of its features chosen at random, or all of them if
+    /// it has no more than `n`, in their order in this collection.
+    ///
+    /// The sample differs each time. For a sample which is the same each time, use
+    /// [`sample_with_seed`](#method.sample_with_seed). The sample has the foreign members of
+    /// this collection, and no `bbox`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geojson::{position, Feature, FeatureCollection, Value};
+    ///
+    /// let collection = FeatureCollection {
+    ///     bbox: None,
+    ///     features: (0..1000)
+    ///         .map(|i| Feature::from(Value::Point(position![i as f64, 0.0])))
+    ///         .collect(),
+    ///     foreign_members: None,
+    /// };
+    ///
+    /// assert_eq!(collection.sample(10).features.len(), 10);
+    /// assert_eq!(
+    ///     collection.sample_with_seed(10, 7),
+    ///     collection.sample_with_seed(10, 7)
+    /// );
+    /// ```
+    pub fn sample(&self, n: usize) -> FeatureCollection {
+        self.sample_with_seed(n, random_seed())
+    }
+
+    /// A copy of this collection with `n` of its features chosen at random, which is the same
+    /// for the same `seed`. See [`sample`](#method.sample).
+    pub fn sample_with_seed(&self, n: usize, seed: u64) -> FeatureCollection {
+        let mut reservoir = Reservoir::new(n.min(self.features.len()), seed);
+        for feature in &self.features {
+            if let Some(slot) = reservoir.offer() {
+                reservoir.insert(slot, feature.clone());
+            }
+        }
+        FeatureCollection {
+            bbox: None,
+            features: reservoir.into_items(),
+            foreign_members: self.foreign_members.clone(),
+        }
+    }
+
+    /// A copy of this collection with each feature chosen with the probability `p`, which is
+    /// the same for the same `seed`, in their order in this collection.
+    ///
+    /// The number of features chosen varies around `p` times the number of features. See
+    /// [`sample`](#method.sample).
+    pub fn sample_fraction(&self, p: f64, seed: u64) -> FeatureCollection {
+        let mut rng = Rng::new(seed);
+        FeatureCollection {
+            bbox: None,
+            features: self
+                .features
+                .iter()
+                .filter(|_| rng.next_f64() < p)
+                .cloned()
+                .collect(),
+            foreign_members: self.foreign_members.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Reservoir;
+    use crate::{Feature, FeatureCollection, Value};
+
+    #[test]
+    fn samples() {
+        let collection = FeatureCollection {
+            bbox: Some(vec![0.0, 0.0, 99.0, 0.0]),
+            features: (0..100)
+                .map(|i| Feature::from(Value::Point(position![i as f64, 0.0])))
+                .collect(),
+            foreign_members: None,
+        };
+        let x = |feature: &Feature| match feature.geometry.as_ref().unwrap().value {
+            Value::Point(ref position) => position[0],
+            _ => unreachable!(),
+        };
+
+        let sample = collection.sample(10);
+        assert_eq!(sample.features.len(), 10);
+        assert_eq!(sample.bbox, None);
+        let xs: Vec<_> = sample.features.iter().map(x).collect();
+        assert!(xs.windows(2).all(|pair| pair[0] < pair[1]), "{:?}", xs);
+        assert_eq!(collection.sample(1000).features, collection.features);
+        assert_ne!(
+            collection.sample_with_seed(10, 1),
+            collection.sample_with_seed(10, 2)
+        );
+
+        assert!(collection.sample_fraction(0.0, 1).features.is_empty());
+        assert_eq!(collection.sample_fraction(1.0, 1).features.len(), 100);
+        let half = collection.sample_fraction(0.5, 1).features.len();
+        assert!((30..70).contains(&half), "{}", half);
+
+        // Each item of a stream is about as likely to be chosen as any other
+        let mut counts = [0; 10];
+        for seed in 0..10_000 {
+            let mut reservoir = Reservoir::new(3, seed);
+            for item in 0..10 {
+                if let Some(slot) = reservoir.offer() {
+                    reservoir.insert(slot, item);
+                }
+            }
+            for item in reservoir.into_items() {
+                counts[item] += 1;
+            }
+        }
+        assert!(
+            counts.iter().all(|&count| (2700..3300).contains(&count)),
+            "{:?}",
+            counts
+        );
+    }
+}
@@ -0,0 +1,302 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Line simplification which works directly on GeoJSON positions, so that altitudes and any
+//! further elements of the positions which are kept are preserved.
+
+use super::intersects::xy;
+use super::{segment_distance_2, PositionsMut};
+use crate::{Feature, FeatureCollection, GeoJson, Geometry, Position, Value};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+fn triangle_area(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> f64 {
+    ((b.0 - a.0) * (c.1 - a.1) - (c.0 - a.0) * (b.1 - a.1)).abs() / 2.0
+}
+
+/// Which of `points` the Ramer–Douglas–Peucker algorithm keeps
+fn douglas_peucker(points: &[(f64, f64)], epsilon: f64) -> Vec<bool> {
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+    let mut spans = vec![(0, points.len() - 1)];
+    while let Some((first, last)) = spans.pop() {
+        let farthest = (first + 1..last)
+            .map(|i| {
+                let (x, y) = points[i];
+                (i, segment_distance_2(points[first], points[last], x, y))
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((i, distance_2)) = farthest {
+            if distance_2.sqrt() > epsilon {
+                keep[i] = true;
+                spans.push((first, i));
+                spans.push((i, last));
+            }
+        }
+    }
+    keep
+}
+
+/// A point which Visvalingam–Whyatt may remove, ordered so that the smallest area is popped
+/// from a `BinaryHeap` first
+struct Candidate {
+    area: f64,
+    index: usize,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .area
+            .total_cmp(&self.area)
+            .then(other.index.cmp(&self.index))
+    }
+}
+
+/// Which of `points` the Visvalingam–Whyatt algorithm keeps, never keeping fewer than
+/// `min_len`
+fn visvalingam_whyatt(points: &[(f64, f64)], epsilon: f64, min_len: usize) -> Vec<bool> {
+    let len = points.len();
+    let mut keep = vec![true; len];
+    let mut previous: Vec<usize> = (0..len).map(|i| i.saturating_sub(1)).collect();
+    let mut next: Vec<usize> = (0..len).map(|i| i + 1).collect();
+    let area = |previous: usize, i: usize, next: usize| {
+        triangle_area(points[previous], points[i], points[next])
+    };
+    let mut areas: Vec<f64> = (0..len)
+        .map(|i| match i {
+            0 => f64::INFINITY,
+            i if i == len - 1 => f64::INFINITY,
+            i => area(i - 1, i, i + 1),
+        })
+        .collect();
+    let mut candidates: BinaryHeap<_> = (1..len - 1)
+        .map(|index| Candidate {
+            area: areas[index],
+            index,
+        })
+        .collect();
+
+    let mut remaining = len;
+    while let Some(Candidate {
+        area: smallest,
+        index,
+    }) = candidates.pop()
+    {
+        if !keep[index] || smallest != areas[index] {
+            // Superseded by a later entry for the same point
+            continue;
+        }
+        if smallest >= epsilon || remaining <= min_len {
+            break;
+        }
+        keep[index] = false;
+        remaining -= 1;
+        let (before, after) = (previous[index], next[index]);
+        next[before] = after;
+        previous[after] = before;
+        for neighbour in [before, after] {
+            if neighbour != 0 && neighbour != len - 1 {
+                areas[neighbour] = area(previous[neighbour], neighbour, next[neighbour]);
+                candidates.push(Candidate {
+                    area: areas[neighbour],
+                    index: neighbour,
+                });
+            }
+        }
+    }
+    keep
+}
+
+/// Simplify a line or ring with `algorithm`, keeping it unchanged if that would leave fewer than
+/// `min_len` positions, e.g. collapse a ring, or if any position is missing a coordinate
+fn simplify_positions(
+    positions: &mut Vec<Position>,
+    min_len: usize,
+    algorithm: &impl Fn(&[(f64, f64)], usize) -> Vec<bool>,
+) {
+    if positions.len() <= min_len.max(2) {
+        return;
+    }
+    let points: Option<Vec<_>> = positions.iter().map(xy).collect();
+    let keep = match points {
+        Some(points) => algorithm(&points, min_len),
+        None => return,
+    };
+    if keep.iter().filter(|keep| **keep).count() < min_len {
+        return;
+    }
+    let mut keep = keep.into_iter();
+    positions.retain(|_| keep.next().unwrap_or(true));
+}
+
+fn simplify_value(value: &mut Value, algorithm: &impl Fn(&[(f64, f64)], usize) -> Vec<bool>) {
+    match value {
+        Value::Point(_) | Value::MultiPoint(_) => {}
+        Value::LineString(line) => simplify_positions(line, 2, algorithm),
+        Value::MultiLineString(lines) => lines
+            .iter_mut()
+            .for_each(|line| simplify_positions(line, 2, algorithm)),
+        Value::Polygon(rings) => rings
+            .iter_mut()
+            .for_each(|ring| simplify_positions(ring, 4, algorithm)),
+        Value::MultiPolygon(polygons) => polygons
+            .iter_mut()
+            .flatten()
+            .for_each(|ring| simplify_positions(ring, 4, algorithm)),
+        Value::GeometryCollection(geometries) => geometries
+            .iter_mut()
+            .for_each(|geometry| simplify_value(&mut geometry.value, algorithm)),
+    }
+}
+
+fn simplify<T: PositionsMut + Clone>(
+    target: &T,
+    algorithm: impl Fn(&[(f64, f64)], usize) -> Vec<bool>,
+) -> T {
+    let mut target = target.clone();
+    target.for_each_bbox_mut(&mut |bbox| *bbox = None);
+    target.for_each_value_mut(&mut |value| simplify_value(value, &algorithm));
+    target
+}
+
+macro_rules! impl_simplify {
+    ($($type:ty),*) => {
+        $(
+            impl $type {
+                /// Return a copy simplified with the Ramer–Douglas–Peucker algorithm, removing
+                /// positions until every removed position is within `epsilon` of the simplified
+                /// line.
+                ///
+                /// Points are kept unchanged, as are lines and rings which would otherwise
+                /// collapse to fewer than two or four positions. `bbox`es are cleared. Distances
+                /// are planar and in the units of the coordinates, e.g. degrees.
+                ///
+                /// ```
+                /// use geojson::{position, Value};
+                ///
+                /// let line = Value::LineString(vec![
+                ///     position![0.0, 0.0, 10.0],
+                ///     position![5.0, 0.1, 20.0],
+                ///     position![10.0, 0.0, 30.0],
+                /// ]);
+                /// assert_eq!(
+                ///     line.simplify(0.5),
+                ///     Value::LineString(vec![position![0.0, 0.0, 10.0], position![10.0, 0.0, 30.0]])
+                /// );
+                /// ```
+                pub fn simplify(&self, epsilon: f64) -> Self {
+                    simplify(self, |points, _| douglas_peucker(points, epsilon))
+                }
+
+                /// Return a copy simplified with the Visvalingam–Whyatt algorithm, repeatedly
+                /// removing the position which forms the smallest triangle with its neighbours,
+                /// while that triangle's area is less than `epsilon`.
+                ///
+                /// Points are kept unchanged, as are lines and rings which would otherwise
+                /// collapse to fewer than two or four positions. `bbox`es are cleared. Areas are
+                /// planar and in the square units of the coordinates.
+                pub fn simplify_vw(&self, epsilon: f64) -> Self {
+                    simplify(self, |points, min_len| {
+                        visvalingam_whyatt(points, epsilon, min_len)
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_simplify!(Value, Geometry, Feature, FeatureCollection, GeoJson);
+
+#[cfg(test)]
+mod tests {
+    use crate::{Feature, Position, Value};
+
+    fn zigzag() -> Vec<Position> {
+        vec![
+            position![0.0, 0.0],
+            position![1.0, 0.1],
+            position![2.0, -0.1],
+            position![3.0, 5.0],
+            position![4.0, 6.0],
+            position![5.0, 7.0],
+            position![6.0, 8.1],
+            position![7.0, 9.0],
+            position![8.0, 9.0],
+            position![9.0, 9.0],
+            position![10.0, 10.0],
+        ]
+    }
+
+    #[test]
+    fn douglas_peucker() {
+        let mut feature = Feature::from(Value::LineString(zigzag()));
+        feature.bbox = Some(vec![0.0, -0.1, 10.0, 10.0]);
+        let simplified = feature.simplify(1.0);
+        assert_eq!(simplified.bbox, None);
+        assert_eq!(
+            simplified.geometry.unwrap().value,
+            Value::LineString(vec![
+                position![0.0, 0.0],
+                position![2.0, -0.1],
+                position![3.0, 5.0],
+                position![10.0, 10.0],
+            ])
+        );
+    }
+
+    #[test]
+    fn visvalingam_whyatt() {
+        let line = Value::LineString(zigzag());
+        assert_eq!(
+            line.simplify_vw(0.6),
+            Value::LineString(vec![
+                position![0.0, 0.0],
+                position![2.0, -0.1],
+                position![3.0, 5.0],
+                position![7.0, 9.0],
+                position![9.0, 9.0],
+                position![10.0, 10.0],
+            ])
+        );
+        assert_eq!(line.simplify_vw(0.0), line);
+    }
+
+    #[test]
+    fn rings_do_not_collapse() {
+        let triangle = Value::Polygon(vec![vec![
+            position![0.0, 0.0],
+            position![1.0, 0.0],
+            position![0.5, 0.1],
+            position![0.0, 0.0],
+        ]]);
+        assert_eq!(triangle.simplify(10.0), triangle);
+        assert_eq!(triangle.simplify_vw(10.0), triangle);
+    }
+}
@@ -0,0 +1,167 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::Values;
+use crate::{Feature, FeatureCollection, GeoJson, Geometry, Position, Value};
+
+/// Counts of the positions, geometries and polygon rings in a GeoJSON object, returned by
+/// `vertex_stats`.
+///
+/// Each member of a `GeometryCollection` counts as a geometry of its own, while the collection
+/// itself doesn't.
+///
+/// # Example
+///
+/// ```
+/// use geojson::{position, Value, VertexStats};
+///
+/// let polygon = Value::Polygon(vec![
+///     vec![position![0.0, 0.0], position![4.0, 0.0], position![4.0, 4.0], position![0.0, 0.0]],
+///     vec![position![1.0, 1.0], position![2.0, 1.0], position![2.0, 2.0], position![1.0, 2.0], position![1.0, 1.0]],
+/// ]);
+///
+/// assert_eq!(polygon.coord_count(), 9);
+/// assert_eq!(
+///     polygon.vertex_stats(),
+///     VertexStats {
+///         positions: 9,
+///         geometries: 1,
+///         rings: 2,
+///         max_geometry_positions: 9,
+///         max_ring_positions: 5,
+///     }
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct VertexStats {
+    /// The number of positions
+    pub positions: usize,
+    /// The number of geometries, not counting `GeometryCollection`s
+    pub geometries: usize,
+    /// The number of `Polygon` and `MultiPolygon` rings
+    pub rings: usize,
+    /// The most positions in a single geometry
+    pub max_geometry_positions: usize,
+    /// The most positions in a single ring
+    pub max_ring_positions: usize,
+}
+
+impl VertexStats {
+    fn add_ring(&mut self, ring: &[Position]) {
+        self.rings += 1;
+        self.max_ring_positions = self.max_ring_positions.max(ring.len());
+    }
+
+    fn add_value(&mut self, value: &Value) {
+        let positions = match value {
+            Value::Point(_) => 1,
+            Value::MultiPoint(positions) | Value::LineString(positions) => positions.len(),
+            Value::MultiLineString(lines) => lines.iter().map(Vec::len).sum(),
+            Value::Polygon(rings) => {
+                rings.iter().for_each(|ring| self.add_ring(ring));
+                rings.iter().map(Vec::len).sum()
+            }
+            Value::MultiPolygon(polygons) => {
+                polygons
+                    .iter()
+                    .flatten()
+                    .for_each(|ring| self.add_ring(ring));
+                polygons.iter().flatten().map(Vec::len).sum()
+            }
+            Value::GeometryCollection(geometries) => {
+                for geometry in geometries {
+                    self.add_value(&geometry.value);
+                }
+                return;
+            }
+        };
+        self.positions += positions;
+        self.geometries += 1;
+        self.max_geometry_positions = self.max_geometry_positions.max(positions);
+    }
+}
+
+fn vertex_stats(target: &impl Values) -> VertexStats {
+    let mut stats = VertexStats::default();
+    target.for_each_value(&mut |value| stats.add_value(value));
+    stats
+}
+
+macro_rules! impl_vertex_stats {
+    ($($type:ty),*) => {
+        $(
+            impl $type {
+                /// The number of positions, including those within `GeometryCollection`s.
+                pub fn coord_count(&self) -> usize {
+                    self.vertex_stats().positions
+                }
+
+                /// Count the positions, geometries and polygon rings.
+                ///
+                /// See [`VertexStats`](struct.VertexStats.html).
+                pub fn vertex_stats(&self) -> VertexStats {
+                    vertex_stats(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_vertex_stats!(Value, Geometry, Feature, FeatureCollection, GeoJson);
+
+#[cfg(test)]
+mod tests {
+    use super::VertexStats;
+    use crate::{Feature, FeatureCollection, Geometry, Value};
+
+    #[test]
+    fn collection_stats() {
+        let square = vec![
+            position![0.0, 0.0],
+            position![1.0, 0.0],
+            position![1.0, 1.0],
+            position![0.0, 1.0],
+            position![0.0, 0.0],
+        ];
+        let collection = FeatureCollection {
+            bbox: None,
+            features: vec![
+                Feature::from(Geometry::new(Value::GeometryCollection(vec![
+                    Geometry::new(Value::Point(position![0.0, 0.0])),
+                    Geometry::new(Value::LineString(vec![
+                        position![0.0, 0.0],
+                        position![1.0, 1.0],
+                    ])),
+                ]))),
+                Feature::from(Geometry::new(Value::MultiPolygon(vec![
+                    vec![square.clone()],
+                    vec![square],
+                ]))),
+            ],
+            foreign_members: None,
+        };
+        assert_eq!(
+            collection.vertex_stats(),
+            VertexStats {
+                positions: 13,
+                geometries: 3,
+                rings: 2,
+                max_geometry_positions: 10,
+                max_ring_positions: 5,
+            }
+        );
+        assert_eq!(collection.features[0].coord_count(), 3);
+        assert_eq!(Value::GeometryCollection(vec![]).coord_count(), 0);
+    }
+}
@@ -0,0 +1,94 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::PositionsMut;
+use crate::{Feature, FeatureCollection, GeoJson, Geometry, Value};
+use std::convert::Infallible;
+
+fn swap_xy(target: &mut impl PositionsMut) {
+    target.for_each_bbox_mut(&mut |bbox| {
+        if let Some(bbox) = bbox {
+            // Both corners of a bbox of any dimension start with their x and y
+            let dimensions = bbox.len() / 2;
+            if dimensions >= 2 {
+                bbox.swap(0, 1);
+                bbox.swap(dimensions, dimensions + 1);
+            }
+        }
+    });
+    let _ = target.try_for_each_position_mut(&mut |position| {
+        if position.len() >= 2 {
+            position.swap(0, 1);
+        }
+        Ok::<_, Infallible>(())
+    });
+}
+
+macro_rules! impl_swap_xy {
+    ($($type:ty),*) => {
+        $(
+            impl $type {
+                /// Swap the first two elements of every position, and of the corners of any
+                /// `bbox`, in place.
+                ///
+                /// This repairs data written in latitude, longitude order; GeoJSON positions
+                /// are always longitude, latitude. Swapping the axes also reverses the winding
+                /// order of polygon rings, which restores the winding of the original data.
+                ///
+                /// ```
+                /// use geojson::{position, Value};
+                ///
+                /// let mut point = Value::Point(position![51.5, -0.12, 11.0]);
+                /// point.swap_xy();
+                /// assert_eq!(point, Value::Point(position![-0.12, 51.5, 11.0]));
+                /// ```
+                pub fn swap_xy(&mut self) {
+                    swap_xy(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_swap_xy!(Value, Geometry, Feature, FeatureCollection, GeoJson);
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithm::signed_area;
+    use crate::{Feature, Geometry, Value};
+
+    #[test]
+    fn swap_polygon_feature() {
+        // Counter-clockwise in latitude, longitude order, so clockwise once swapped
+        let ring = vec![
+            position![0.0, 0.0, 5.0],
+            position![1.0, 0.0, 5.0],
+            position![1.0, 2.0, 5.0],
+            position![0.0, 0.0, 5.0],
+        ];
+        let mut feature = Feature::from(Geometry::new(Value::Polygon(vec![ring.clone()])));
+        feature.bbox = Some(vec![0.0, 0.0, 5.0, 1.0, 2.0, 5.0]);
+        feature.swap_xy();
+
+        assert_eq!(feature.bbox, Some(vec![0.0, 0.0, 5.0, 2.0, 1.0, 5.0]));
+        match feature.geometry.unwrap().value {
+            Value::Polygon(rings) => {
+                assert_eq!(rings[0][2], position![2.0, 1.0, 5.0]);
+                assert!(signed_area(&rings[0]) < 0.0);
+                assert!(signed_area(&ring) > 0.0);
+            }
+            _ => unreachable!(),
+        }
+    }
+}
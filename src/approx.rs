@@ -0,0 +1,186 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Coordinates, including those of `bbox`es, are compared with a tolerance, while every other
+//! member, such as `id` and `properties`, must be exactly equal.
+
+use crate::{Bbox, Feature, FeatureCollection, GeoJson, Geometry, Position, Value};
+use ::approx::{AbsDiffEq, RelativeEq};
+
+fn positions_eq(a: &[f64], b: &[f64], eq: &impl Fn(&f64, &f64) -> bool) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| eq(a, b))
+}
+
+fn slices_eq<T>(a: &[T], b: &[T], eq: impl Fn(&T, &T) -> bool) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| eq(a, b))
+}
+
+fn lines_eq(a: &[Position], b: &[Position], eq: &impl Fn(&f64, &f64) -> bool) -> bool {
+    slices_eq(a, b, |a, b| positions_eq(a, b, eq))
+}
+
+fn polygons_eq(a: &[Vec<Position>], b: &[Vec<Position>], eq: &impl Fn(&f64, &f64) -> bool) -> bool {
+    slices_eq(a, b, |a, b| lines_eq(a, b, eq))
+}
+
+fn bboxes_eq(a: &Option<Bbox>, b: &Option<Bbox>, eq: &impl Fn(&f64, &f64) -> bool) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => positions_eq(a, b, eq),
+        (a, b) => a.is_none() && b.is_none(),
+    }
+}
+
+fn value_eq(a: &Value, b: &Value, eq: &impl Fn(&f64, &f64) -> bool) -> bool {
+    match (a, b) {
+        (Value::Point(a), Value::Point(b)) => positions_eq(a, b, eq),
+        (Value::MultiPoint(a), Value::MultiPoint(b)) => lines_eq(a, b, eq),
+        (Value::LineString(a), Value::LineString(b)) => lines_eq(a, b, eq),
+        (Value::MultiLineString(a), Value::MultiLineString(b)) => polygons_eq(a, b, eq),
+        (Value::Polygon(a), Value::Polygon(b)) => polygons_eq(a, b, eq),
+        (Value::MultiPolygon(a), Value::MultiPolygon(b)) => {
+            slices_eq(a, b, |a, b| polygons_eq(a, b, eq))
+        }
+        (Value::GeometryCollection(a), Value::GeometryCollection(b)) => {
+            slices_eq(a, b, |a, b| geometry_eq(a, b, eq))
+        }
+        _ => false,
+    }
+}
+
+fn geometry_eq(a: &Geometry, b: &Geometry, eq: &impl Fn(&f64, &f64) -> bool) -> bool {
+    bboxes_eq(&a.bbox, &b.bbox, eq)
+        && value_eq(&a.value, &b.value, eq)
+        && a.foreign_members == b.foreign_members
+}
+
+fn feature_eq(a: &Feature, b: &Feature, eq: &impl Fn(&f64, &f64) -> bool) -> bool {
+    let geometries_eq = match (&a.geometry, &b.geometry) {
+        (Some(a), Some(b)) => geometry_eq(a, b, eq),
+        (a, b) => a.is_none() && b.is_none(),
+    };
+    geometries_eq
+        && bboxes_eq(&a.bbox, &b.bbox, eq)
+        && a.id == b.id
+        && a.properties == b.properties
+        && a.foreign_members == b.foreign_members
+}
+
+fn feature_collection_eq(
+    a: &FeatureCollection,
+    b: &FeatureCollection,
+    eq: &impl Fn(&f64, &f64) -> bool,
+) -> bool {
+    bboxes_eq(&a.bbox, &b.bbox, eq)
+        && slices_eq(&a.features, &b.features, |a, b| feature_eq(a, b, eq))
+        && a.foreign_members == b.foreign_members
+}
+
+fn geojson_eq(a: &GeoJson, b: &GeoJson, eq: &impl Fn(&f64, &f64) -> bool) -> bool {
+    match (a, b) {
+        (GeoJson::Geometry(a), GeoJson::Geometry(b)) => geometry_eq(a, b, eq),
+        (GeoJson::Feature(a), GeoJson::Feature(b)) => feature_eq(a, b, eq),
+        (GeoJson::FeatureCollection(a), GeoJson::FeatureCollection(b)) => {
+            feature_collection_eq(a, b, eq)
+        }
+        _ => false,
+    }
+}
+
+macro_rules! impl_approx {
+    ($($type:ty => $eq:ident),*) => {
+        $(
+            /// Compare the coordinates of two objects with an absolute tolerance.
+            ///
+            /// ```
+            /// use approx::assert_abs_diff_eq;
+            /// use geojson::{position, Value};
+            ///
+            /// let a = Value::Point(position![0.1 + 0.2, 1.0]);
+            /// let b = Value::Point(position![0.3, 1.0]);
+            /// assert_ne!(a, b);
+            /// assert_abs_diff_eq!(a, b);
+            /// ```
+            #[cfg_attr(docsrs, doc(cfg(feature = "approx")))]
+            impl AbsDiffEq for $type {
+                type Epsilon = f64;
+
+                fn default_epsilon() -> f64 {
+                    f64::default_epsilon()
+                }
+
+                fn abs_diff_eq(&self, other: &Self, epsilon: f64) -> bool {
+                    $eq(self, other, &|a, b| a.abs_diff_eq(b, epsilon))
+                }
+            }
+
+            /// Compare the coordinates of two objects with a tolerance relative to their
+            /// magnitude.
+            #[cfg_attr(docsrs, doc(cfg(feature = "approx")))]
+            impl RelativeEq for $type {
+                fn default_max_relative() -> f64 {
+                    f64::default_max_relative()
+                }
+
+                fn relative_eq(&self, other: &Self, epsilon: f64, max_relative: f64) -> bool {
+                    $eq(self, other, &|a, b| a.relative_eq(b, epsilon, max_relative))
+                }
+            }
+        )*
+    };
+}
+
+impl_approx!(
+    Value => value_eq,
+    Geometry => geometry_eq,
+    Feature => feature_eq,
+    FeatureCollection => feature_collection_eq,
+    GeoJson => geojson_eq
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::{Feature, Geometry, Value};
+    use ::approx::{assert_relative_eq, assert_relative_ne, AbsDiffEq};
+
+    #[test]
+    fn approximately_equal_features() {
+        let mut a = Feature::from(Geometry::new(Value::GeometryCollection(vec![
+            Geometry::new(Value::LineString(vec![
+                position![1.0, 2.0],
+                position![3.0, 4.0, 5.0],
+            ])),
+        ])));
+        a.bbox = Some(vec![1.0, 2.0, 3.0, 4.0]);
+        a.set_property("name", "a");
+        let mut b = a.clone();
+        b.bbox = Some(vec![1.0, 2.0, 3.0, 4.000001]);
+        assert!(a.abs_diff_eq(&b, 1e-5));
+        assert!(!a.abs_diff_eq(&b, 1e-7));
+        assert_relative_eq!(a, b, max_relative = 1e-5);
+
+        // Positions with a different number of elements are never equal
+        let mut c = a.clone();
+        c.geometry = Some(Geometry::new(Value::GeometryCollection(vec![
+            Geometry::new(Value::LineString(vec![
+                position![1.0, 2.0],
+                position![3.0, 4.0],
+            ])),
+        ])));
+        assert_relative_ne!(a, c, max_relative = 1.0);
+
+        let mut d = a.clone();
+        d.set_property("name", "d");
+        assert_relative_ne!(a, d);
+    }
+}
@@ -0,0 +1,220 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Implementations of [`arbitrary::Arbitrary`](https://docs.rs/arbitrary) which generate
+//! structurally valid GeoJSON, for fuzzing.
+//!
+//! Coordinates are finite, positions have two or three of them, `LineString`s have at least two
+//! positions, and polygon rings at least four, the last of which closes the ring. Foreign
+//! members never reuse the names of the members of their object and are `None` rather than
+//! empty, and `properties` are never `None`, which is written as `{}`, so every generated object
+//! serializes to GeoJSON which parses back to an equal object.
+
+use crate::feature::Id;
+use crate::json::{JsonObject, JsonValue};
+use crate::{Bbox, Feature, FeatureCollection, GeoJson, Geometry, Position, Value};
+use ::arbitrary::{Arbitrary, Result, Unstructured};
+
+/// The deepest nesting of `GeometryCollection`s, and of arrays and objects in properties
+const MAX_DEPTH: usize = 3;
+
+/// The most items in any one array or object
+const MAX_LEN: usize = 8;
+
+fn coordinate(u: &mut Unstructured) -> Result<f64> {
+    let x: f64 = u.arbitrary()?;
+    Ok(if x.is_finite() { x } else { 0.0 })
+}
+
+fn position(u: &mut Unstructured) -> Result<Position> {
+    let mut position = Position::new();
+    for _ in 0..u.int_in_range(2..=3)? {
+        position.push(coordinate(u)?);
+    }
+    Ok(position)
+}
+
+fn positions(u: &mut Unstructured, min: usize) -> Result<Vec<Position>> {
+    (0..u.int_in_range(min..=MAX_LEN)?)
+        .map(|_| position(u))
+        .collect()
+}
+
+fn ring(u: &mut Unstructured) -> Result<Vec<Position>> {
+    let mut ring = positions(u, 3)?;
+    ring.push(ring[0].clone());
+    Ok(ring)
+}
+
+fn polygon(u: &mut Unstructured) -> Result<Vec<Vec<Position>>> {
+    (0..u.int_in_range(1..=3)?).map(|_| ring(u)).collect()
+}
+
+fn list<T>(
+    u: &mut Unstructured,
+    mut item: impl FnMut(&mut Unstructured) -> Result<T>,
+) -> Result<Vec<T>> {
+    (0..u.int_in_range(0..=MAX_LEN)?).map(|_| item(u)).collect()
+}
+
+fn bbox(u: &mut Unstructured) -> Result<Option<Bbox>> {
+    if !u.arbitrary()? {
+        return Ok(None);
+    }
+    let len = if u.arbitrary()? { 6 } else { 4 };
+    (0..len)
+        .map(|_| coordinate(u))
+        .collect::<Result<_>>()
+        .map(Some)
+}
+
+fn json_value(u: &mut Unstructured, depth: usize) -> Result<JsonValue> {
+    let kinds = if depth == 0 { 4 } else { 6 };
+    Ok(match u.choose_index(kinds)? {
+        0 => JsonValue::Null,
+        1 => JsonValue::Bool(u.arbitrary()?),
+        2 => {
+            if u.arbitrary()? {
+                JsonValue::from(u.arbitrary::<i64>()?)
+            } else {
+                JsonValue::from(coordinate(u)?)
+            }
+        }
+        3 => JsonValue::String(u.arbitrary()?),
+        4 => JsonValue::Array(list(u, |u| json_value(u, depth - 1))?),
+        _ => JsonValue::Object(json_object(u, depth - 1, &[])?),
+    })
+}
+
+/// An object whose keys aren't any of `reserved`
+fn json_object(u: &mut Unstructured, depth: usize, reserved: &[&str]) -> Result<JsonObject> {
+    let mut object = JsonObject::new();
+    for _ in 0..u.int_in_range(0..=MAX_LEN)? {
+        let key: String = u.arbitrary()?;
+        if !reserved.contains(&key.as_str()) {
+            object.insert(key, json_value(u, depth)?);
+        }
+    }
+    Ok(object)
+}
+
+/// Foreign members whose names aren't any of `reserved`, or `None` rather than none
+fn foreign_members(u: &mut Unstructured, reserved: &[&str]) -> Result<Option<JsonObject>> {
+    let members = json_object(u, MAX_DEPTH, reserved)?;
+    Ok(if members.is_empty() {
+        None
+    } else {
+        Some(members)
+    })
+}
+
+fn value(u: &mut Unstructured, depth: usize) -> Result<Value> {
+    let kinds = if depth == 0 { 6 } else { 7 };
+    Ok(match u.choose_index(kinds)? {
+        0 => Value::Point(position(u)?),
+        1 => Value::MultiPoint(positions(u, 0)?),
+        2 => Value::LineString(positions(u, 2)?),
+        3 => Value::MultiLineString(list(u, |u| positions(u, 2))?),
+        4 => Value::Polygon(polygon(u)?),
+        5 => Value::MultiPolygon(list(u, polygon)?),
+        _ => Value::GeometryCollection(list(u, |u| geometry(u, depth - 1))?),
+    })
+}
+
+fn geometry(u: &mut Unstructured, depth: usize) -> Result<Geometry> {
+    Ok(Geometry {
+        value: value(u, depth)?,
+        bbox: bbox(u)?,
+        foreign_members: foreign_members(u, &["type", "coordinates", "geometries", "bbox"])?,
+    })
+}
+
+impl<'a> Arbitrary<'a> for Value {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        value(u, MAX_DEPTH)
+    }
+}
+
+impl<'a> Arbitrary<'a> for Geometry {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        geometry(u, MAX_DEPTH)
+    }
+}
+
+impl<'a> Arbitrary<'a> for Id {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(match u.choose_index(3)? {
+            0 => Id::String(u.arbitrary()?),
+            1 => Id::Number(u.arbitrary::<i64>()?.into()),
+            _ => Id::Number(serde_json::Number::from_f64(coordinate(u)?).unwrap()),
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Feature {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Feature {
+            bbox: bbox(u)?,
+            geometry: u.arbitrary()?,
+            id: u.arbitrary()?,
+            properties: Some(json_object(u, MAX_DEPTH, &[])?),
+            foreign_members: foreign_members(u, &["type", "geometry", "properties", "id", "bbox"])?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for FeatureCollection {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(FeatureCollection {
+            bbox: bbox(u)?,
+            features: list(u, |u| u.arbitrary())?,
+            foreign_members: foreign_members(u, &["type", "features", "bbox"])?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for GeoJson {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(match u.choose_index(3)? {
+            0 => GeoJson::Geometry(u.arbitrary()?),
+            1 => GeoJson::Feature(u.arbitrary()?),
+            _ => GeoJson::FeatureCollection(u.arbitrary()?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GeoJson;
+    use arbitrary::{Arbitrary, Unstructured};
+
+    #[test]
+    fn arbitrary_objects_round_trip() {
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        for _ in 0..500 {
+            let bytes: Vec<u8> = (0..1024)
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    state as u8
+                })
+                .collect();
+            let geojson = GeoJson::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+            let json = geojson.to_string();
+            let parsed: GeoJson = json.parse().unwrap();
+            assert_eq!(parsed, geojson, "{}", json);
+        }
+    }
+}
@@ -0,0 +1,853 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parsing into a [bumpalo](https://docs.rs/bumpalo) arena.
+//!
+//! Every string, coordinate and list of a parsed [`ArenaFeatureCollection`] is allocated in a
+//! `Bump` arena given by the caller, so allocating is a pointer bump, and the whole collection
+//! is freed at once when the arena is reset or dropped, rather than one allocation at a time.
+//! This suits short-lived collections, such as one parsed for each request a server handles.
+//!
+//! Geometries are stored in the layout of a [`FlatGeometry`](../flat/struct.FlatGeometry.html):
+//! the coordinates of all of their positions in one slice, and their structure as offsets into
+//! it. Properties and foreign members are slices of key and value pairs, in the order they were
+//! read.
+//!
+//! # Example
+//!
+//! ```
+//! use bumpalo::Bump;
+//! use geojson::arena::{self, ArenaJson};
+//!
+//! let json = r#"{
+//!     "type": "FeatureCollection",
+//!     "features": [{
+//!         "type": "Feature",
+//!         "geometry": { "type": "LineString", "coordinates": [[1, 2], [3, 4]] },
+//!         "properties": { "name": "a" }
+//!     }]
+//! }"#;
+//!
+//! let mut bump = Bump::new();
+//! {
+//!     let collection = arena::feature_collection_from_str(&bump, json).unwrap();
+//!     let feature = &collection.features[0];
+//!     assert_eq!(feature.property("name"), Some(&ArenaJson::String("a")));
+//!     assert_eq!(feature.geometry.as_ref().unwrap().coords(), &[1.0, 2.0, 3.0, 4.0]);
+//! }
+//! // Free the whole collection at once, and reuse the memory for the next
+//! bump.reset();
+//! ```
+//!
+//! [`ArenaFeatureCollection`]: struct.ArenaFeatureCollection.html
+
+use crate::errors::Error;
+use crate::flat::{FlatGeometry, FlatGeometryType};
+use crate::json::{Deserializer, JsonObject, JsonValue};
+use crate::{feature, Feature, FeatureCollection, Geometry, Value};
+use bumpalo::collections::Vec as BumpVec;
+use bumpalo::Bump;
+use serde::de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use std::fmt;
+
+/// The members of a JSON object, in the order they were read
+pub type ArenaObject<'b> = &'b [(&'b str, ArenaJson<'b>)];
+
+/// A JSON value allocated in an arena
+#[derive(Clone, Debug, PartialEq)]
+pub enum ArenaJson<'b> {
+    Null,
+    Bool(bool),
+    Number(serde_json::Number),
+    String(&'b str),
+    Array(&'b [ArenaJson<'b>]),
+    Object(ArenaObject<'b>),
+}
+
+impl ArenaJson<'_> {
+    /// Copy this value out of the arena
+    pub fn to_json_value(&self) -> JsonValue {
+        match self {
+            ArenaJson::Null => JsonValue::Null,
+            ArenaJson::Bool(bool) => JsonValue::Bool(*bool),
+            ArenaJson::Number(number) => JsonValue::Number(number.clone()),
+            ArenaJson::String(string) => JsonValue::String(string.to_string()),
+            ArenaJson::Array(values) => values.iter().map(ArenaJson::to_json_value).collect(),
+            ArenaJson::Object(object) => JsonValue::Object(object_to_json(object)),
+        }
+    }
+}
+
+fn object_to_json(object: ArenaObject<'_>) -> JsonObject {
+    object
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_json_value()))
+        .collect()
+}
+
+fn foreign_members_to_json(object: ArenaObject<'_>) -> Option<JsonObject> {
+    if object.is_empty() {
+        None
+    } else {
+        Some(object_to_json(object))
+    }
+}
+
+/// The coordinates of a geometry allocated in an arena, or the geometries of a
+/// `GeometryCollection`
+#[derive(Clone, Debug, PartialEq)]
+pub enum ArenaGeometryValue<'b> {
+    /// A geometry in the layout of a [`FlatGeometry`](../flat/struct.FlatGeometry.html)
+    Flat {
+        geometry_type: FlatGeometryType,
+        dimensions: usize,
+        coords: &'b [f64],
+        ring_offsets: &'b [usize],
+        part_offsets: &'b [usize],
+    },
+    GeometryCollection(&'b [ArenaGeometry<'b>]),
+}
+
+/// A [`Geometry`](../struct.Geometry.html) allocated in an arena
+#[derive(Clone, Debug, PartialEq)]
+pub struct ArenaGeometry<'b> {
+    pub bbox: Option<&'b [f64]>,
+    pub value: ArenaGeometryValue<'b>,
+    pub foreign_members: ArenaObject<'b>,
+}
+
+impl ArenaGeometry<'_> {
+    /// The coordinates of every position, one position after another, or nothing for a
+    /// `GeometryCollection`
+    pub fn coords(&self) -> &[f64] {
+        match self.value {
+            ArenaGeometryValue::Flat { coords, .. } => coords,
+            ArenaGeometryValue::GeometryCollection(_) => &[],
+        }
+    }
+
+    /// Copy this geometry out of the arena
+    pub fn to_geometry(&self) -> Geometry {
+        let value = match self.value {
+            ArenaGeometryValue::Flat {
+                geometry_type,
+                dimensions,
+                coords,
+                ring_offsets,
+                part_offsets,
+            } => Value::from(FlatGeometry::from_parts(
+                geometry_type,
+                dimensions,
+                coords.to_vec(),
+                ring_offsets.to_vec(),
+                part_offsets.to_vec(),
+            )),
+            ArenaGeometryValue::GeometryCollection(geometries) => Value::GeometryCollection(
+                geometries.iter().map(ArenaGeometry::to_geometry).collect(),
+            ),
+        };
+        Geometry {
+            bbox: self.bbox.map(<[f64]>::to_vec),
+            value,
+            foreign_members: foreign_members_to_json(self.foreign_members),
+        }
+    }
+}
+
+/// A [`Feature`](../struct.Feature.html) allocated in an arena
+#[derive(Clone, Debug, PartialEq)]
+pub struct ArenaFeature<'b> {
+    pub bbox: Option<&'b [f64]>,
+    pub geometry: Option<ArenaGeometry<'b>>,
+    /// A string or a number
+    pub id: Option<ArenaJson<'b>>,
+    pub properties: Option<ArenaObject<'b>>,
+    pub foreign_members: ArenaObject<'b>,
+}
+
+impl<'b> ArenaFeature<'b> {
+    /// The property `key`, if there is one
+    pub fn property(&self, key: &str) -> Option<&'b ArenaJson<'b>> {
+        self.properties?
+            .iter()
+            .find(|(name, _)| *name == key)
+            .map(|(_, value)| value)
+    }
+
+    /// Copy this feature out of the arena
+    pub fn to_feature(&self) -> Feature {
+        Feature {
+            bbox: self.bbox.map(<[f64]>::to_vec),
+            geometry: self.geometry.as_ref().map(ArenaGeometry::to_geometry),
+            id: self.id.as_ref().and_then(|id| match id {
+                ArenaJson::String(string) => Some(feature::Id::String(string.to_string())),
+                ArenaJson::Number(number) => Some(feature::Id::Number(number.clone())),
+                _ => None,
+            }),
+            properties: self.properties.map(object_to_json),
+            foreign_members: foreign_members_to_json(self.foreign_members),
+        }
+    }
+}
+
+/// A [`FeatureCollection`](../struct.FeatureCollection.html) allocated in an arena
+#[derive(Clone, Debug, PartialEq)]
+pub struct ArenaFeatureCollection<'b> {
+    pub bbox: Option<&'b [f64]>,
+    pub features: &'b [ArenaFeature<'b>],
+    pub foreign_members: ArenaObject<'b>,
+}
+
+impl ArenaFeatureCollection<'_> {
+    /// Copy this collection out of the arena
+    pub fn to_feature_collection(&self) -> FeatureCollection {
+        FeatureCollection {
+            bbox: self.bbox.map(<[f64]>::to_vec),
+            features: self.features.iter().map(ArenaFeature::to_feature).collect(),
+            foreign_members: foreign_members_to_json(self.foreign_members),
+        }
+    }
+}
+
+/// Parse a `FeatureCollection` from `json` into `bump`.
+pub fn feature_collection_from_str<'b>(
+    bump: &'b Bump,
+    json: &str,
+) -> Result<ArenaFeatureCollection<'b>, Error> {
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    let collection = FeatureCollectionSeed(bump)
+        .deserialize(&mut deserializer)
+        .map_err(Error::MalformedJson)?;
+    deserializer.end().map_err(Error::MalformedJson)?;
+    Ok(collection)
+}
+
+/// Parse a `Feature` from `json` into `bump`.
+pub fn feature_from_str<'b>(bump: &'b Bump, json: &str) -> Result<ArenaFeature<'b>, Error> {
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    let feature = FeatureSeed(bump)
+        .deserialize(&mut deserializer)
+        .map_err(Error::MalformedJson)?;
+    deserializer.end().map_err(Error::MalformedJson)?;
+    Ok(feature)
+}
+
+fn custom<E: de::Error>(error: Error) -> E {
+    E::custom(error)
+}
+
+fn missing<E: de::Error>(name: &str) -> E {
+    custom(Error::ExpectedProperty(name.to_string()))
+}
+
+/// Reads a string into the arena
+struct StrSeed<'b>(&'b Bump);
+
+impl<'de, 'b> DeserializeSeed<'de> for StrSeed<'b> {
+    type Value = &'b str;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<&'b str, D::Error> {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'de, 'b> Visitor<'de> for StrSeed<'b> {
+    type Value = &'b str;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a string")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<&'b str, E> {
+        Ok(self.0.alloc_str(value))
+    }
+}
+
+/// Reads any JSON value into the arena
+struct JsonSeed<'b>(&'b Bump);
+
+impl<'de, 'b> DeserializeSeed<'de> for JsonSeed<'b> {
+    type Value = ArenaJson<'b>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de, 'b> Visitor<'de> for JsonSeed<'b> {
+    type Value = ArenaJson<'b>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any JSON value")
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(ArenaJson::Null)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(ArenaJson::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.deserialize(deserializer)
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<Self::Value, E> {
+        Ok(ArenaJson::Bool(value))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+        Ok(ArenaJson::Number(value.into()))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+        Ok(ArenaJson::Number(value.into()))
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Self::Value, E> {
+        Ok(serde_json::Number::from_f64(value).map_or(ArenaJson::Null, ArenaJson::Number))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        Ok(ArenaJson::String(self.0.alloc_str(value)))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut values = BumpVec::new_in(self.0);
+        while let Some(value) = seq.next_element_seed(JsonSeed(self.0))? {
+            values.push(value);
+        }
+        Ok(ArenaJson::Array(values.into_bump_slice()))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut members = BumpVec::new_in(self.0);
+        while let Some(key) = map.next_key_seed(StrSeed(self.0))? {
+            members.push((key, map.next_value_seed(JsonSeed(self.0))?));
+        }
+        Ok(ArenaJson::Object(members.into_bump_slice()))
+    }
+}
+
+/// Reads an array of numbers, such as a `bbox`, into the arena
+struct F64sSeed<'b>(&'b Bump);
+
+impl<'de, 'b> DeserializeSeed<'de> for F64sSeed<'b> {
+    type Value = &'b [f64];
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, 'b> Visitor<'de> for F64sSeed<'b> {
+    type Value = &'b [f64];
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an array of numbers")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut numbers = BumpVec::new_in(self.0);
+        while let Some(number) = seq.next_element()? {
+            numbers.push(number);
+        }
+        Ok(numbers.into_bump_slice())
+    }
+}
+
+/// The coordinates and offsets of a geometry, as they are read
+struct FlatBuilder<'b> {
+    dimensions: Option<usize>,
+    coords: BumpVec<'b, f64>,
+    ring_offsets: BumpVec<'b, usize>,
+    part_offsets: BumpVec<'b, usize>,
+    positions: usize,
+}
+
+impl<'b> FlatBuilder<'b> {
+    fn new(bump: &'b Bump) -> Self {
+        let mut ring_offsets = BumpVec::new_in(bump);
+        ring_offsets.push(0);
+        let mut part_offsets = BumpVec::new_in(bump);
+        part_offsets.push(0);
+        FlatBuilder {
+            dimensions: None,
+            coords: BumpVec::new_in(bump),
+            ring_offsets,
+            part_offsets,
+            positions: 0,
+        }
+    }
+
+    fn end_ring(&mut self) {
+        self.ring_offsets.push(self.positions);
+    }
+
+    fn end_part(&mut self) {
+        self.part_offsets.push(self.ring_offsets.len() - 1);
+    }
+
+    fn finish(self, geometry_type: FlatGeometryType) -> ArenaGeometryValue<'b> {
+        ArenaGeometryValue::Flat {
+            geometry_type,
+            dimensions: self.dimensions.unwrap_or(0),
+            coords: self.coords.into_bump_slice(),
+            ring_offsets: self.ring_offsets.into_bump_slice(),
+            part_offsets: self.part_offsets.into_bump_slice(),
+        }
+    }
+}
+
+/// Reads `coordinates` nested `depth` arrays deep around each position into a `FlatBuilder`
+struct CoordinatesSeed<'a, 'b> {
+    builder: &'a mut FlatBuilder<'b>,
+    depth: usize,
+}
+
+impl<'de> DeserializeSeed<'de> for CoordinatesSeed<'_, '_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for CoordinatesSeed<'_, '_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "coordinates nested {} arrays deep",
+            self.depth + 1
+        )
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let builder = self.builder;
+        if self.depth == 0 {
+            let start = builder.coords.len();
+            while let Some(coordinate) = seq.next_element()? {
+                builder.coords.push(coordinate);
+            }
+            let dimensions = builder.coords.len() - start;
+            match builder.dimensions {
+                Some(expected) if expected != dimensions => {
+                    return Err(custom(Error::MixedDimensions(expected, dimensions)))
+                }
+                _ => builder.dimensions = Some(dimensions),
+            }
+            builder.positions += 1;
+            return Ok(());
+        }
+        while seq
+            .next_element_seed(CoordinatesSeed {
+                builder: &mut *builder,
+                depth: self.depth - 1,
+            })?
+            .is_some()
+        {}
+        match self.depth {
+            1 => builder.end_ring(),
+            2 => builder.end_part(),
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Read `coordinates` of `geometry_type` into the arena.
+fn read_coordinates<'de, 'b, D: Deserializer<'de>>(
+    bump: &'b Bump,
+    geometry_type: FlatGeometryType,
+    deserializer: D,
+) -> Result<ArenaGeometryValue<'b>, D::Error> {
+    let mut builder = FlatBuilder::new(bump);
+    let depth = match geometry_type {
+        FlatGeometryType::Point => 0,
+        FlatGeometryType::MultiPoint | FlatGeometryType::LineString => 1,
+        FlatGeometryType::MultiLineString | FlatGeometryType::Polygon => 2,
+        FlatGeometryType::MultiPolygon => 3,
+    };
+    CoordinatesSeed {
+        builder: &mut builder,
+        depth,
+    }
+    .deserialize(deserializer)?;
+    if depth == 0 {
+        builder.end_ring();
+    }
+    if depth <= 1 {
+        builder.end_part();
+    }
+    Ok(builder.finish(geometry_type))
+}
+
+fn flat_geometry_type(name: &str) -> Option<FlatGeometryType> {
+    Some(match name {
+        "Point" => FlatGeometryType::Point,
+        "MultiPoint" => FlatGeometryType::MultiPoint,
+        "LineString" => FlatGeometryType::LineString,
+        "MultiLineString" => FlatGeometryType::MultiLineString,
+        "Polygon" => FlatGeometryType::Polygon,
+        "MultiPolygon" => FlatGeometryType::MultiPolygon,
+        _ => return None,
+    })
+}
+
+/// Where the coordinates of a geometry are, once they have been read
+enum Coordinates<'b> {
+    Read(ArenaGeometryValue<'b>),
+    /// Coordinates read before the `type` of their geometry, and so before it was known how to
+    /// read them
+    Deferred(JsonValue),
+}
+
+struct GeometrySeed<'b>(&'b Bump);
+
+impl<'de, 'b> DeserializeSeed<'de> for GeometrySeed<'b> {
+    type Value = ArenaGeometry<'b>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, 'b> Visitor<'de> for GeometrySeed<'b> {
+    type Value = ArenaGeometry<'b>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a GeoJSON Geometry")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let bump = self.0;
+        let mut type_ = None;
+        let mut coordinates = None;
+        let mut geometries = None;
+        let mut bbox = None;
+        let mut foreign_members = BumpVec::new_in(bump);
+        while let Some(key) = map.next_key_seed(StrSeed(bump))? {
+            match key {
+                "type" => type_ = Some(map.next_value_seed(StrSeed(bump))?),
+                "coordinates" => {
+                    coordinates = Some(match type_.and_then(flat_geometry_type) {
+                        Some(geometry_type) => Coordinates::Read(
+                            map.next_value_seed(CoordinatesFor(bump, geometry_type))?,
+                        ),
+                        None => Coordinates::Deferred(map.next_value()?),
+                    })
+                }
+                "geometries" => geometries = Some(map.next_value_seed(GeometriesSeed(bump))?),
+                "bbox" => bbox = Some(map.next_value_seed(F64sSeed(bump))?),
+                _ => foreign_members.push((key, map.next_value_seed(JsonSeed(bump))?)),
+            }
+        }
+        let type_ = type_.ok_or_else(|| missing("type"))?;
+        let value = match (flat_geometry_type(type_), coordinates, geometries) {
+            (Some(_), Some(Coordinates::Read(value)), _) => value,
+            (Some(geometry_type), Some(Coordinates::Deferred(json)), _) => {
+                read_coordinates(bump, geometry_type, json).map_err(de::Error::custom)?
+            }
+            (Some(_), None, _) => return Err(missing("coordinates")),
+            (None, _, Some(geometries)) if type_ == "GeometryCollection" => {
+                ArenaGeometryValue::GeometryCollection(geometries)
+            }
+            (None, _, None) if type_ == "GeometryCollection" => return Err(missing("geometries")),
+            (None, _, _) => return Err(custom(Error::GeometryUnknownType(type_.to_string()))),
+        };
+        Ok(ArenaGeometry {
+            bbox,
+            value,
+            foreign_members: foreign_members.into_bump_slice(),
+        })
+    }
+}
+
+struct CoordinatesFor<'b>(&'b Bump, FlatGeometryType);
+
+impl<'de, 'b> DeserializeSeed<'de> for CoordinatesFor<'b> {
+    type Value = ArenaGeometryValue<'b>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        read_coordinates(self.0, self.1, deserializer)
+    }
+}
+
+struct GeometriesSeed<'b>(&'b Bump);
+
+impl<'de, 'b> DeserializeSeed<'de> for GeometriesSeed<'b> {
+    type Value = &'b [ArenaGeometry<'b>];
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, 'b> Visitor<'de> for GeometriesSeed<'b> {
+    type Value = &'b [ArenaGeometry<'b>];
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an array of GeoJSON Geometries")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut geometries = BumpVec::new_in(self.0);
+        while let Some(geometry) = seq.next_element_seed(GeometrySeed(self.0))? {
+            geometries.push(geometry);
+        }
+        Ok(geometries.into_bump_slice())
+    }
+}
+
+/// Reads a geometry, or `null`
+struct OptionalGeometrySeed<'b>(&'b Bump);
+
+impl<'de, 'b> DeserializeSeed<'de> for OptionalGeometrySeed<'b> {
+    type Value = Option<ArenaGeometry<'b>>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_option(self)
+    }
+}
+
+impl<'de, 'b> Visitor<'de> for OptionalGeometrySeed<'b> {
+    type Value = Option<ArenaGeometry<'b>>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a GeoJSON Geometry or null")
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        GeometrySeed(self.0).deserialize(deserializer).map(Some)
+    }
+}
+
+struct FeatureSeed<'b>(&'b Bump);
+
+impl<'de, 'b> DeserializeSeed<'de> for FeatureSeed<'b> {
+    type Value = ArenaFeature<'b>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, 'b> Visitor<'de> for FeatureSeed<'b> {
+    type Value = ArenaFeature<'b>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a GeoJSON Feature")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let bump = self.0;
+        let mut type_ = None;
+        let mut geometry = None;
+        let mut properties = None;
+        let mut id = None;
+        let mut bbox = None;
+        let mut foreign_members = BumpVec::new_in(bump);
+        while let Some(key) = map.next_key_seed(StrSeed(bump))? {
+            match key {
+                "type" => type_ = Some(map.next_value_seed(StrSeed(bump))?),
+                "geometry" => geometry = Some(map.next_value_seed(OptionalGeometrySeed(bump))?),
+                "properties" => {
+                    properties = Some(match map.next_value_seed(JsonSeed(bump))? {
+                        ArenaJson::Object(object) => Some(object),
+                        ArenaJson::Null => None,
+                        other => {
+                            return Err(custom(Error::PropertiesExpectedObjectOrNull(
+                                other.to_json_value(),
+                            )))
+                        }
+                    })
+                }
+                "id" => {
+                    id = Some(match map.next_value_seed(JsonSeed(bump))? {
+                        id @ ArenaJson::String(_) | id @ ArenaJson::Number(_) => id,
+                        other => {
+                            return Err(custom(Error::FeatureInvalidIdentifierType(
+                                other.to_json_value(),
+                            )))
+                        }
+                    })
+                }
+                "bbox" => bbox = Some(map.next_value_seed(F64sSeed(bump))?),
+                _ => foreign_members.push((key, map.next_value_seed(JsonSeed(bump))?)),
+            }
+        }
+        match type_ {
+            Some("Feature") => {}
+            Some(type_) => return Err(custom(Error::NotAFeature(type_.to_string()))),
+            None => return Err(missing("type")),
+        }
+        Ok(ArenaFeature {
+            bbox,
+            geometry: geometry.ok_or_else(|| missing("geometry"))?,
+            id,
+            properties: properties.ok_or_else(|| missing("properties"))?,
+            foreign_members: foreign_members.into_bump_slice(),
+        })
+    }
+}
+
+struct FeaturesSeed<'b>(&'b Bump);
+
+impl<'de, 'b> DeserializeSeed<'de> for FeaturesSeed<'b> {
+    type Value = &'b [ArenaFeature<'b>];
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, 'b> Visitor<'de> for FeaturesSeed<'b> {
+    type Value = &'b [ArenaFeature<'b>];
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an array of GeoJSON Features")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut features = BumpVec::new_in(self.0);
+        while let Some(feature) = seq.next_element_seed(FeatureSeed(self.0))? {
+            features.push(feature);
+        }
+        Ok(features.into_bump_slice())
+    }
+}
+
+struct FeatureCollectionSeed<'b>(&'b Bump);
+
+impl<'de, 'b> DeserializeSeed<'de> for FeatureCollectionSeed<'b> {
+    type Value = ArenaFeatureCollection<'b>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, 'b> Visitor<'de> for FeatureCollectionSeed<'b> {
+    type Value = ArenaFeatureCollection<'b>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a GeoJSON FeatureCollection")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let bump = self.0;
+        let mut type_ = None;
+        let mut features = None;
+        let mut bbox = None;
+        let mut foreign_members = BumpVec::new_in(bump);
+        while let Some(key) = map.next_key_seed(StrSeed(bump))? {
+            match key {
+                "type" => type_ = Some(map.next_value_seed(StrSeed(bump))?),
+                "features" => features = Some(map.next_value_seed(FeaturesSeed(bump))?),
+                "bbox" => bbox = Some(map.next_value_seed(F64sSeed(bump))?),
+                _ => foreign_members.push((key, map.next_value_seed(JsonSeed(bump))?)),
+            }
+        }
+        match type_ {
+            Some("FeatureCollection") => {}
+            Some(type_) => {
+                return Err(custom(Error::ExpectedType {
+                    expected: "FeatureCollection".to_owned(),
+                    actual: type_.to_string(),
+                }))
+            }
+            None => return Err(missing("type")),
+        }
+        Ok(ArenaFeatureCollection {
+            bbox,
+            features: features.ok_or_else(|| missing("features"))?,
+            foreign_members: foreign_members.into_bump_slice(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{feature_collection_from_str, feature_from_str, ArenaGeometryValue, ArenaJson};
+    use crate::{Feature, FeatureCollection};
+    use bumpalo::Bump;
+
+    #[test]
+    fn same_as_owned_parse() {
+        let bump = Bump::new();
+        let json = include_str!("../tests/fixtures/countries.geojson");
+        let collection = feature_collection_from_str(&bump, json).unwrap();
+        assert_eq!(
+            collection.to_feature_collection(),
+            serde_json::from_str::<FeatureCollection>(json).unwrap()
+        );
+
+        // Coordinates before the type, foreign members, and a geometry collection
+        let json = r#"{
+            "id": "a",
+            "geometry": {
+                "geometries": [
+                    { "coordinates": [[[0, 0], [1, 0], [1, 1], [0, 0]]], "type": "Polygon" },
+                    { "type": "Point", "coordinates": [1.5, 2, 3] }
+                ],
+                "type": "GeometryCollection",
+                "bbox": [0, 0, 1.5, 2]
+            },
+            "properties": { "nested": { "list": [1, "two", null, true] } },
+            "title": "foreign",
+            "type": "Feature"
+        }"#;
+        let feature = feature_from_str(&bump, json).unwrap();
+        assert_eq!(feature.id, Some(ArenaJson::String("a")));
+        assert_eq!(feature.foreign_members[0].0, "title");
+        match feature.geometry.as_ref().unwrap().value {
+            ArenaGeometryValue::GeometryCollection(geometries) => {
+                assert_eq!(geometries[0].coords().len(), 8);
+            }
+            ref value => panic!("unexpected {:?}", value),
+        }
+        assert_eq!(
+            feature.to_feature(),
+            serde_json::from_str::<Feature>(json).unwrap()
+        );
+    }
+
+    #[test]
+    fn invalid_objects() {
+        let bump = Bump::new();
+        for json in [
+            r#"{"type":"Feature","geometry":null}"#,
+            r#"{"type":"Feature","geometry":null,"properties":[]}"#,
+            r#"{"type":"Feature","geometry":null,"properties":null,"id":null}"#,
+            r#"{"type":"Feature","geometry":{"type":"Point","coordinates":[[1,2]]},"properties":null}"#,
+            r#"{"type":"Feature","geometry":{"type":"Circle","coordinates":[1,2]},"properties":null}"#,
+            r#"{"type":"Feature","geometry":{"type":"LineString","coordinates":[[1,2],[1,2,3]]},"properties":null}"#,
+            r#"{"type":"Point","geometry":null,"properties":null}"#,
+        ] {
+            assert!(feature_from_str(&bump, json).is_err(), "{}", json);
+        }
+        assert!(feature_collection_from_str(&bump, r#"{"type":"FeatureCollection"}"#).is_err());
+    }
+}
@@ -0,0 +1,195 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The `geojson` command, which validates, summarizes and reformats GeoJSON with this crate.
+//!
+//! Every command reads a file, or standard input if there is none or it is `-`, and writes to
+//! standard output. Run `geojson help` for the commands and their options.
+
+use geojson::geojsonl::LineDelimitedReader;
+use geojson::{GeoJson, WriterOptions};
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::process;
+
+const USAGE: &str = "\
+Usage: geojson <COMMAND> [OPTIONS] [FILE]
+
+Reads FILE, or standard input if it is missing or `-`, and writes to standard output.
+
+Commands:
+  validate     Check GeoJSON against RFC 7946 and for valid geometries, printing each
+               problem, and failing if there are any
+  stats        Count the features, geometries, rings and positions, and print the bbox
+  to-ndjson    Write the features of a FeatureCollection as line-delimited GeoJSON
+  from-ndjson  Collect line-delimited GeoJSON features into a FeatureCollection
+  format       Rewrite GeoJSON
+  help         Print this message
+
+Options of format:
+  --pretty <N>     Indent by N spaces per level
+  --precision <N>  Round coordinates to N decimal places
+  --canonical      Write canonical JSON, with sorted members and no whitespace
+
+Options of to-ndjson and from-ndjson:
+  --precision <N>  Round coordinates to N decimal places
+";
+
+/// A command line which can't be run, such as one with an unknown option
+#[derive(Debug)]
+struct UsageError(String);
+
+impl std::fmt::Display for UsageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}\n\n{}", self.0, USAGE)
+    }
+}
+
+impl Error for UsageError {}
+
+fn usage_error<T>(message: impl Into<String>) -> Result<T, Box<dyn Error>> {
+    Err(Box::new(UsageError(message.into())))
+}
+
+struct Args {
+    command: String,
+    file: Option<String>,
+    options: WriterOptions,
+}
+
+fn number<T: std::str::FromStr>(option: &str, value: Option<String>) -> Result<T, Box<dyn Error>> {
+    match value.map(|value| value.parse()) {
+        Some(Ok(value)) => Ok(value),
+        _ => usage_error(format!("{} expects a number", option)),
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, Box<dyn Error>> {
+    let command = match args.next() {
+        Some(command) => command,
+        None => return usage_error("Missing a command"),
+    };
+    let mut parsed = Args {
+        command,
+        file: None,
+        options: WriterOptions::new(),
+    };
+    let formats = parsed.command == "format";
+    let writes = formats || parsed.command == "to-ndjson" || parsed.command == "from-ndjson";
+    while let Some(arg) = args.next() {
+        parsed.options = match arg.as_str() {
+            "--pretty" if formats => parsed.options.pretty(number(&arg, args.next())?),
+            "--canonical" if formats => parsed.options.canonical(true),
+            "--precision" if writes => parsed.options.precision(number(&arg, args.next())?),
+            _ if arg.starts_with("--") => {
+                return usage_error(format!("Unknown option {} of {}", arg, parsed.command))
+            }
+            _ if parsed.file.is_none() => {
+                parsed.file = Some(arg);
+                continue;
+            }
+            _ => return usage_error(format!("Unexpected argument {}", arg)),
+        };
+    }
+    Ok(parsed)
+}
+
+fn input(file: Option<&str>) -> io::Result<Box<dyn Read>> {
+    Ok(match file {
+        None | Some("-") => Box::new(io::stdin()),
+        Some(path) => Box::new(io::BufReader::new(File::open(path)?)),
+    })
+}
+
+/// Run the command, returning whether it succeeded
+fn run(args: Args, output: &mut impl Write) -> Result<bool, Box<dyn Error>> {
+    if args.command == "help" {
+        write!(output, "{}", USAGE)?;
+        return Ok(true);
+    }
+    let input = input(args.file.as_deref())?;
+    match args.command.as_str() {
+        "validate" => {
+            let geojson = GeoJson::from_reader(input)?;
+            let invalid = geojson.explain_invalidity();
+            for invalidity in &invalid {
+                writeln!(output, "{}", invalidity)?;
+            }
+            return Ok(invalid.is_empty());
+        }
+        "stats" => {
+            let geojson = GeoJson::from_reader(input)?;
+            let stats = geojson.vertex_stats();
+            let features = match &geojson {
+                GeoJson::Geometry(_) => 0,
+                GeoJson::Feature(_) => 1,
+                GeoJson::FeatureCollection(collection) => collection.features.len(),
+            };
+            writeln!(output, "features: {}", features)?;
+            writeln!(output, "geometries: {}", stats.geometries)?;
+            writeln!(output, "rings: {}", stats.rings)?;
+            writeln!(output, "positions: {}", stats.positions)?;
+            writeln!(
+                output,
+                "max positions per geometry: {}",
+                stats.max_geometry_positions
+            )?;
+            if let Some(bbox) = geojson.compute_bbox() {
+                writeln!(output, "bbox: {}", serde_json::to_string(&bbox)?)?;
+            }
+        }
+        "to-ndjson" => {
+            let features = match GeoJson::from_reader(input)? {
+                GeoJson::FeatureCollection(collection) => collection.features,
+                GeoJson::Feature(feature) => vec![feature],
+                GeoJson::Geometry(geometry) => vec![geometry.into()],
+            };
+            for feature in features {
+                feature.to_writer_with(&mut *output, &args.options)?;
+                writeln!(output)?;
+            }
+        }
+        "from-ndjson" => {
+            let collection = LineDelimitedReader::new(input).read_feature_collection()?;
+            collection.to_writer_with(&mut *output, &args.options)?;
+            writeln!(output)?;
+        }
+        "format" => {
+            GeoJson::from_reader(input)?.to_writer_with(&mut *output, &args.options)?;
+            writeln!(output)?;
+        }
+        command => return usage_error(format!("Unknown command {}", command)),
+    }
+    Ok(true)
+}
+
+fn main() {
+    let stdout = io::stdout();
+    let mut output = BufWriter::new(stdout.lock());
+    let result = parse_args(std::env::args().skip(1)).and_then(|args| run(args, &mut output));
+    let flushed = output.flush();
+    match result {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(err) => {
+            eprintln!("geojson: {}", err);
+            process::exit(if err.is::<UsageError>() { 2 } else { 1 });
+        }
+    }
+    if let Err(err) = flushed {
+        eprintln!("geojson: {}", err);
+        process::exit(1);
+    }
+}
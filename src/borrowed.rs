@@ -0,0 +1,446 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Features whose properties borrow from the JSON they were parsed from.
+//!
+//! Parsing a [`Feature`](../struct.Feature.html) allocates a `String` for every property key
+//! and string value. A [`BorrowedFeature`] instead keeps them as `Cow<str>`s which borrow from
+//! the input wherever the JSON string has no escape sequences, so scanning the attributes of a
+//! large file allocates far less. Geometries are parsed as they are for a `Feature`.
+//!
+//! # Example
+//!
+//! ```
+//! use geojson::borrowed::{BorrowedFeatureCollection, PropertyValue};
+//! use std::borrow::Cow;
+//!
+//! let json = r#"{
+//!     "type": "FeatureCollection",
+//!     "features": [{
+//!         "type": "Feature",
+//!         "geometry": null,
+//!         "properties": { "name": "Null Island", "population": 0 }
+//!     }]
+//! }"#;
+//! let collection = BorrowedFeatureCollection::from_str(json).unwrap();
+//! let name = collection.features[0].property("name").unwrap();
+//! assert!(matches!(name, PropertyValue::String(Cow::Borrowed("Null Island"))));
+//! ```
+//!
+//! [`BorrowedFeature`]: struct.BorrowedFeature.html
+
+use crate::errors::Error;
+use crate::json::{Deserialize, Deserializer, JsonObject, JsonValue};
+use crate::{feature, util, Bbox, Feature, FeatureCollection, Geometry};
+use serde::de::{self, MapAccess, SeqAccess, Visitor};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// The properties of a [`BorrowedFeature`](struct.BorrowedFeature.html)
+pub type Properties<'a> = BTreeMap<Cow<'a, str>, PropertyValue<'a>>;
+
+/// A JSON value whose strings may borrow from the JSON it was parsed from
+#[derive(Clone, Debug, PartialEq)]
+pub enum PropertyValue<'a> {
+    Null,
+    Bool(bool),
+    Number(serde_json::Number),
+    String(Cow<'a, str>),
+    Array(Vec<PropertyValue<'a>>),
+    Object(Properties<'a>),
+}
+
+impl PropertyValue<'_> {
+    /// The string, if this is a string
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            PropertyValue::String(string) => Some(string),
+            _ => None,
+        }
+    }
+}
+
+impl From<PropertyValue<'_>> for JsonValue {
+    fn from(value: PropertyValue<'_>) -> Self {
+        match value {
+            PropertyValue::Null => JsonValue::Null,
+            PropertyValue::Bool(bool) => JsonValue::Bool(bool),
+            PropertyValue::Number(number) => JsonValue::Number(number),
+            PropertyValue::String(string) => JsonValue::String(string.into_owned()),
+            PropertyValue::Array(values) => values.into_iter().map(JsonValue::from).collect(),
+            PropertyValue::Object(properties) => JsonValue::Object(properties_to_json(properties)),
+        }
+    }
+}
+
+fn properties_to_json(properties: Properties<'_>) -> JsonObject {
+    properties
+        .into_iter()
+        .map(|(key, value)| (key.into_owned(), value.into()))
+        .collect()
+}
+
+/// A string which borrows from the input if it can
+struct CowStr<'a>(Cow<'a, str>);
+
+impl<'de> Deserialize<'de> for CowStr<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct CowStrVisitor;
+
+        impl<'de> Visitor<'de> for CowStrVisitor {
+            type Value = CowStr<'de>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a string")
+            }
+
+            fn visit_borrowed_str<E: de::Error>(self, value: &'de str) -> Result<Self::Value, E> {
+                Ok(CowStr(Cow::Borrowed(value)))
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                Ok(CowStr(Cow::Owned(value.to_string())))
+            }
+
+            fn visit_string<E: de::Error>(self, value: String) -> Result<Self::Value, E> {
+                Ok(CowStr(Cow::Owned(value)))
+            }
+        }
+
+        deserializer.deserialize_str(CowStrVisitor)
+    }
+}
+
+impl<'de> Deserialize<'de> for PropertyValue<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct PropertyValueVisitor;
+
+        impl<'de> Visitor<'de> for PropertyValueVisitor {
+            type Value = PropertyValue<'de>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("any JSON value")
+            }
+
+            fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+                Ok(PropertyValue::Null)
+            }
+
+            fn visit_bool<E: de::Error>(self, value: bool) -> Result<Self::Value, E> {
+                Ok(PropertyValue::Bool(value))
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+                Ok(PropertyValue::Number(value.into()))
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+                Ok(PropertyValue::Number(value.into()))
+            }
+
+            fn visit_f64<E: de::Error>(self, value: f64) -> Result<Self::Value, E> {
+                Ok(serde_json::Number::from_f64(value)
+                    .map_or(PropertyValue::Null, PropertyValue::Number))
+            }
+
+            fn visit_borrowed_str<E: de::Error>(self, value: &'de str) -> Result<Self::Value, E> {
+                Ok(PropertyValue::String(Cow::Borrowed(value)))
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                Ok(PropertyValue::String(Cow::Owned(value.to_string())))
+            }
+
+            fn visit_string<E: de::Error>(self, value: String) -> Result<Self::Value, E> {
+                Ok(PropertyValue::String(Cow::Owned(value)))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut values = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(value) = seq.next_element()? {
+                    values.push(value);
+                }
+                Ok(PropertyValue::Array(values))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                properties(map).map(PropertyValue::Object)
+            }
+        }
+
+        deserializer.deserialize_any(PropertyValueVisitor)
+    }
+}
+
+fn properties<'de, A: MapAccess<'de>>(mut map: A) -> Result<Properties<'de>, A::Error> {
+    let mut properties = Properties::new();
+    while let Some(CowStr(key)) = map.next_key()? {
+        properties.insert(key, map.next_value()?);
+    }
+    Ok(properties)
+}
+
+/// A [`Feature`](../struct.Feature.html) whose property keys and string values borrow from the
+/// JSON it was parsed from where they can
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorrowedFeature<'a> {
+    pub bbox: Option<Bbox>,
+    pub geometry: Option<Geometry>,
+    pub id: Option<feature::Id>,
+    pub properties: Option<Properties<'a>>,
+    pub foreign_members: Option<JsonObject>,
+}
+
+impl<'a> BorrowedFeature<'a> {
+    /// Parse a feature from `json`, which its properties borrow from.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(json: &'a str) -> Result<Self, Error> {
+        serde_json::from_str(json).map_err(Error::MalformedJson)
+    }
+
+    /// The property `key`, if there is one
+    pub fn property(&self, key: impl AsRef<str>) -> Option<&PropertyValue<'a>> {
+        self.properties.as_ref()?.get(key.as_ref())
+    }
+
+    /// Copy the borrowed strings, to get a `Feature` which no longer borrows from the input.
+    pub fn into_owned(self) -> Feature {
+        Feature {
+            bbox: self.bbox,
+            geometry: self.geometry,
+            id: self.id,
+            properties: self.properties.map(properties_to_json),
+            foreign_members: self.foreign_members,
+        }
+    }
+}
+
+impl<'a> From<BorrowedFeature<'a>> for Feature {
+    fn from(feature: BorrowedFeature<'a>) -> Self {
+        feature.into_owned()
+    }
+}
+
+impl<'de> Deserialize<'de> for BorrowedFeature<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FeatureVisitor;
+
+        impl<'de> Visitor<'de> for FeatureVisitor {
+            type Value = BorrowedFeature<'de>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a GeoJSON Feature")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut geometry = None;
+                let mut properties = None;
+                let mut object = JsonObject::new();
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "geometry" => geometry = Some(map.next_value::<Option<Geometry>>()?),
+                        "properties" => {
+                            properties = Some(map.next_value::<Option<PropertyValue>>()?)
+                        }
+                        _ => {
+                            object.insert(key, map.next_value()?);
+                        }
+                    }
+                }
+                let properties = match properties {
+                    Some(Some(PropertyValue::Object(properties))) => Some(properties),
+                    Some(None) => None,
+                    Some(Some(other)) => {
+                        return Err(de::Error::custom(Error::PropertiesExpectedObjectOrNull(
+                            other.into(),
+                        )))
+                    }
+                    None => return Err(de::Error::custom(missing("properties"))),
+                };
+                let geometry = geometry.ok_or_else(|| de::Error::custom(missing("geometry")))?;
+                feature(object, geometry, properties).map_err(de::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_map(FeatureVisitor)
+    }
+}
+
+fn missing(name: &str) -> Error {
+    Error::ExpectedProperty(name.to_string())
+}
+
+fn feature<'a>(
+    mut object: JsonObject,
+    geometry: Option<Geometry>,
+    properties: Option<Properties<'a>>,
+) -> Result<BorrowedFeature<'a>, Error> {
+    let type_ = util::expect_type(&mut object)?;
+    if type_ != "Feature" {
+        return Err(Error::NotAFeature(type_));
+    }
+    Ok(BorrowedFeature {
+        geometry,
+        properties,
+        id: util::get_id(&mut object)?,
+        bbox: util::get_bbox(&mut object)?,
+        foreign_members: util::get_foreign_members(object)?,
+    })
+}
+
+/// A [`FeatureCollection`](../struct.FeatureCollection.html) of
+/// [`BorrowedFeature`](struct.BorrowedFeature.html)s
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorrowedFeatureCollection<'a> {
+    pub bbox: Option<Bbox>,
+    pub features: Vec<BorrowedFeature<'a>>,
+    pub foreign_members: Option<JsonObject>,
+}
+
+impl<'a> BorrowedFeatureCollection<'a> {
+    /// Parse a feature collection from `json`, which the properties of its features borrow from.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(json: &'a str) -> Result<Self, Error> {
+        serde_json::from_str(json).map_err(Error::MalformedJson)
+    }
+
+    /// Copy the borrowed strings, to get a `FeatureCollection` which no longer borrows from the
+    /// input.
+    pub fn into_owned(self) -> FeatureCollection {
+        FeatureCollection {
+            bbox: self.bbox,
+            features: self
+                .features
+                .into_iter()
+                .map(BorrowedFeature::into_owned)
+                .collect(),
+            foreign_members: self.foreign_members,
+        }
+    }
+}
+
+impl<'a> From<BorrowedFeatureCollection<'a>> for FeatureCollection {
+    fn from(collection: BorrowedFeatureCollection<'a>) -> Self {
+        collection.into_owned()
+    }
+}
+
+impl<'de> Deserialize<'de> for BorrowedFeatureCollection<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FeatureCollectionVisitor;
+
+        impl<'de> Visitor<'de> for FeatureCollectionVisitor {
+            type Value = BorrowedFeatureCollection<'de>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a GeoJSON FeatureCollection")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut features = None;
+                let mut object = JsonObject::new();
+                while let Some(key) = map.next_key::<String>()? {
+                    if key == "features" {
+                        features = Some(map.next_value()?);
+                    } else {
+                        object.insert(key, map.next_value()?);
+                    }
+                }
+                let features = features.ok_or_else(|| de::Error::custom(missing("features")))?;
+                feature_collection(object, features).map_err(de::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_map(FeatureCollectionVisitor)
+    }
+}
+
+fn feature_collection(
+    mut object: JsonObject,
+    features: Vec<BorrowedFeature<'_>>,
+) -> Result<BorrowedFeatureCollection<'_>, Error> {
+    let type_ = util::expect_type(&mut object)?;
+    if type_ != "FeatureCollection" {
+        return Err(Error::ExpectedType {
+            expected: "FeatureCollection".to_owned(),
+            actual: type_,
+        });
+    }
+    Ok(BorrowedFeatureCollection {
+        bbox: util::get_bbox(&mut object)?,
+        features,
+        foreign_members: util::get_foreign_members(object)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BorrowedFeature, BorrowedFeatureCollection, PropertyValue};
+    use crate::FeatureCollection;
+    use std::borrow::Cow;
+
+    #[test]
+    fn borrows_unescaped_strings() {
+        let json = r#"{
+            "type": "Feature",
+            "id": 1,
+            "geometry": { "type": "Point", "coordinates": [1, 2] },
+            "properties": { "plain": "abc", "esc\"aped": "a\nb", "nested": [{ "k": "v" }] },
+            "title": "foreign"
+        }"#;
+        let feature = BorrowedFeature::from_str(json).unwrap();
+        let properties = feature.properties.as_ref().unwrap();
+        assert!(matches!(
+            properties.get_key_value("plain").unwrap(),
+            (
+                Cow::Borrowed(_),
+                PropertyValue::String(Cow::Borrowed("abc"))
+            )
+        ));
+        assert!(matches!(
+            properties.get_key_value("esc\"aped").unwrap(),
+            (Cow::Owned(_), PropertyValue::String(Cow::Owned(_)))
+        ));
+        match feature.property("nested") {
+            Some(PropertyValue::Array(values)) => match &values[0] {
+                PropertyValue::Object(object) => {
+                    assert_eq!(object["k"].as_str(), Some("v"))
+                }
+                value => panic!("unexpected {:?}", value),
+            },
+            value => panic!("unexpected {:?}", value),
+        }
+        assert_eq!(feature.into_owned(), serde_json::from_str(json).unwrap());
+    }
+
+    #[test]
+    fn same_as_feature_collection() {
+        let json = include_str!("../tests/fixtures/countries.geojson");
+        let collection = BorrowedFeatureCollection::from_str(json).unwrap();
+        assert_eq!(
+            FeatureCollection::from(collection),
+            serde_json::from_str::<FeatureCollection>(json).unwrap()
+        );
+
+        assert!(BorrowedFeature::from_str(r#"{"type":"Feature","geometry":null}"#).is_err());
+        assert!(
+            BorrowedFeature::from_str(r#"{"type":"Feature","geometry":null,"properties":1}"#)
+                .is_err()
+        );
+        assert!(
+            BorrowedFeatureCollection::from_str(r#"{"type":"Feature","features":[]}"#).is_err()
+        );
+    }
+}
@@ -0,0 +1,103 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! RFC 3339 datetime properties as [`chrono`](https://docs.rs/chrono) datetimes.
+
+use crate::errors::Error;
+use crate::Feature;
+use ::chrono::{DateTime, FixedOffset, TimeZone};
+use std::fmt::Display;
+
+impl Feature {
+    /// The property `key` parsed as an [RFC 3339](https://tools.ietf.org/html/rfc3339)
+    /// datetime, such as `"2024-05-01T12:30:00+02:00"`, keeping its offset, or `None` if it
+    /// isn't set or is `null`.
+    ///
+    /// # Errors
+    ///
+    /// If the property is set to anything other than `null` or an RFC 3339 string.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use chrono::{TimeZone, Utc};
+    /// use geojson::{position, Feature, Value};
+    ///
+    /// let mut feature = Feature::from(Value::Point(position![1.0, 2.0]));
+    /// feature.set_property("observed", "2024-05-01T12:30:00+02:00");
+    ///
+    /// let observed = feature.datetime_property("observed").unwrap().unwrap();
+    /// assert_eq!(observed, Utc.with_ymd_and_hms(2024, 5, 1, 10, 30, 0).unwrap());
+    /// assert_eq!(feature.datetime_property("updated").unwrap(), None);
+    /// ```
+    pub fn datetime_property(
+        &self,
+        key: impl AsRef<str>,
+    ) -> Result<Option<DateTime<FixedOffset>>, Error> {
+        let key = key.as_ref();
+        let value = match self.property(key) {
+            Some(value) if !value.is_null() => value,
+            _ => return Ok(None),
+        };
+        value
+            .as_str()
+            .and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+            .map(Some)
+            .ok_or_else(|| Error::InvalidDateTimeProperty(key.to_string(), value.clone()))
+    }
+
+    /// Set the property `key` to `datetime` as an
+    /// [RFC 3339](https://tools.ietf.org/html/rfc3339) string, with its offset, and with as many
+    /// digits of fractional seconds as it needs
+    pub fn set_datetime_property<Tz>(&mut self, key: impl Into<String>, datetime: &DateTime<Tz>)
+    where
+        Tz: TimeZone,
+        Tz::Offset: Display,
+    {
+        self.set_property(key, datetime.to_rfc3339());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Feature, Value};
+    use chrono::{FixedOffset, TimeZone, Utc};
+
+    #[test]
+    fn datetime_properties() {
+        let mut feature = Feature::from(Value::Point(position![0.0, 0.0]));
+        let offset = FixedOffset::west_opt(5 * 3600).unwrap();
+        let datetime = offset.with_ymd_and_hms(2020, 2, 29, 23, 59, 59).unwrap();
+        feature.set_datetime_property("a", &datetime);
+        assert_eq!(feature.property("a").unwrap(), "2020-02-29T23:59:59-05:00");
+        assert_eq!(feature.datetime_property("a").unwrap(), Some(datetime));
+
+        feature.set_datetime_property("b", &Utc.timestamp_opt(0, 1_500_000).unwrap());
+        assert_eq!(
+            feature.property("b").unwrap(),
+            "1970-01-01T00:00:00.001500+00:00"
+        );
+
+        feature.set_property("c", serde_json::Value::Null);
+        assert_eq!(feature.datetime_property("c").unwrap(), None);
+        for invalid in [
+            serde_json::json!("2020-02-30T00:00:00Z"),
+            serde_json::json!("yesterday"),
+            serde_json::json!(1_582_934_399),
+        ] {
+            feature.set_property("d", invalid);
+            assert!(feature.datetime_property("d").is_err());
+        }
+    }
+}
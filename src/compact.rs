@@ -0,0 +1,358 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The representation of GeoJSON objects in formats which are not human readable, such as
+//! bincode, CBOR and MessagePack, with the `compact` feature.
+//!
+//! JSON objects can only be deserialized from self-describing formats, so objects are written as
+//! structs and enums of their members instead, with coordinates as plain sequences of numbers.
+//! Properties and foreign members, which can hold any JSON, are written as strings of JSON.
+
+use crate::feature::Id;
+use crate::json::{Deserialize, Deserializer, JsonObject, Serialize, Serializer};
+use crate::{
+    Bbox, Feature, FeatureCollection, GeoJson, Geometry, LineStringType, PointType, PolygonType,
+    Value,
+};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+#[derive(Serialize)]
+enum ValueRef<'a> {
+    Point(&'a PointType),
+    MultiPoint(&'a [PointType]),
+    LineString(&'a LineStringType),
+    MultiLineString(&'a [LineStringType]),
+    Polygon(&'a PolygonType),
+    MultiPolygon(&'a [PolygonType]),
+    GeometryCollection(&'a [Geometry]),
+}
+
+impl<'a> From<&'a Value> for ValueRef<'a> {
+    fn from(value: &'a Value) -> Self {
+        match value {
+            Value::Point(point) => ValueRef::Point(point),
+            Value::MultiPoint(points) => ValueRef::MultiPoint(points),
+            Value::LineString(line_string) => ValueRef::LineString(line_string),
+            Value::MultiLineString(line_strings) => ValueRef::MultiLineString(line_strings),
+            Value::Polygon(polygon) => ValueRef::Polygon(polygon),
+            Value::MultiPolygon(polygons) => ValueRef::MultiPolygon(polygons),
+            Value::GeometryCollection(geometries) => ValueRef::GeometryCollection(geometries),
+        }
+    }
+}
+
+/// The same variants, in the same order, as `ValueRef`
+#[derive(Deserialize)]
+enum CompactValue {
+    Point(PointType),
+    MultiPoint(Vec<PointType>),
+    LineString(LineStringType),
+    MultiLineString(Vec<LineStringType>),
+    Polygon(PolygonType),
+    MultiPolygon(Vec<PolygonType>),
+    GeometryCollection(Vec<Geometry>),
+}
+
+impl From<CompactValue> for Value {
+    fn from(value: CompactValue) -> Self {
+        match value {
+            CompactValue::Point(point) => Value::Point(point),
+            CompactValue::MultiPoint(points) => Value::MultiPoint(points),
+            CompactValue::LineString(line_string) => Value::LineString(line_string),
+            CompactValue::MultiLineString(line_strings) => Value::MultiLineString(line_strings),
+            CompactValue::Polygon(polygon) => Value::Polygon(polygon),
+            CompactValue::MultiPolygon(polygons) => Value::MultiPolygon(polygons),
+            CompactValue::GeometryCollection(geometries) => Value::GeometryCollection(geometries),
+        }
+    }
+}
+
+/// An `Id`, with a number written as its JSON text so that no precision is lost
+#[derive(Serialize, Deserialize)]
+enum CompactId {
+    String(String),
+    Number(String),
+}
+
+#[derive(Serialize)]
+struct GeometryRef<'a> {
+    bbox: &'a Option<Bbox>,
+    value: ValueRef<'a>,
+    foreign_members: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CompactGeometry {
+    bbox: Option<Bbox>,
+    value: CompactValue,
+    foreign_members: Option<String>,
+}
+
+#[derive(Serialize)]
+struct FeatureRef<'a> {
+    bbox: Option<&'a Bbox>,
+    geometry: Option<&'a Geometry>,
+    id: Option<CompactId>,
+    properties: Option<String>,
+    foreign_members: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CompactFeature {
+    bbox: Option<Bbox>,
+    geometry: Option<Geometry>,
+    id: Option<CompactId>,
+    properties: Option<String>,
+    foreign_members: Option<String>,
+}
+
+#[derive(Serialize)]
+struct FeatureCollectionRef<'a, F> {
+    bbox: Option<&'a Bbox>,
+    features: F,
+    foreign_members: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CompactFeatureCollection {
+    bbox: Option<Bbox>,
+    features: Vec<Feature>,
+    foreign_members: Option<String>,
+}
+
+/// A GeoJSON object of any of the types which serialize as a `Geometry`, `Feature` or
+/// `FeatureCollection` respectively
+#[derive(Serialize)]
+pub(crate) enum GeoJsonRef<G, F, C> {
+    Geometry(G),
+    Feature(F),
+    FeatureCollection(C),
+}
+
+#[derive(Deserialize)]
+enum CompactGeoJson {
+    Geometry(Geometry),
+    Feature(Feature),
+    FeatureCollection(FeatureCollection),
+}
+
+fn to_json<S: Serializer>(object: Option<&JsonObject>) -> Result<Option<String>, S::Error> {
+    use serde::ser::Error;
+
+    object
+        .map(|object| serde_json::to_string(object).map_err(S::Error::custom))
+        .transpose()
+}
+
+fn from_json<'de, D: Deserializer<'de>>(
+    json: Option<String>,
+) -> Result<Option<JsonObject>, D::Error> {
+    use serde::de::Error;
+
+    json.map(|json| serde_json::from_str(&json).map_err(D::Error::custom))
+        .transpose()
+}
+
+pub(crate) fn serialize_value<S: Serializer>(
+    value: &Value,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    ValueRef::from(value).serialize(serializer)
+}
+
+pub(crate) fn serialize_geometry<S: Serializer>(
+    geometry: &Geometry,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    GeometryRef {
+        bbox: &geometry.bbox,
+        value: ValueRef::from(&geometry.value),
+        foreign_members: to_json::<S>(geometry.foreign_members.as_ref())?,
+    }
+    .serialize(serializer)
+}
+
+pub(crate) fn deserialize_geometry<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Geometry, D::Error> {
+    let geometry = CompactGeometry::deserialize(deserializer)?;
+    Ok(Geometry {
+        bbox: geometry.bbox,
+        value: geometry.value.into(),
+        foreign_members: from_json::<D>(geometry.foreign_members)?,
+    })
+}
+
+pub(crate) fn serialize_feature<S: Serializer>(
+    feature: &Feature,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serialize_feature_parts(
+        feature.bbox.as_ref(),
+        feature.geometry.as_ref(),
+        feature.id.as_ref(),
+        feature.properties.as_ref(),
+        feature.foreign_members.as_ref(),
+        serializer,
+    )
+}
+
+/// Serialize a feature from its members
+pub(crate) fn serialize_feature_parts<S: Serializer>(
+    bbox: Option<&Bbox>,
+    geometry: Option<&Geometry>,
+    id: Option<&Id>,
+    properties: Option<&JsonObject>,
+    foreign_members: Option<&JsonObject>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let id = id.map(|id| match id {
+        Id::String(string) => CompactId::String(string.clone()),
+        Id::Number(number) => CompactId::Number(number.to_string()),
+    });
+    FeatureRef {
+        bbox,
+        geometry,
+        id,
+        properties: to_json::<S>(properties)?,
+        foreign_members: to_json::<S>(foreign_members)?,
+    }
+    .serialize(serializer)
+}
+
+pub(crate) fn deserialize_feature<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Feature, D::Error> {
+    use serde::de::Error;
+
+    let feature = CompactFeature::deserialize(deserializer)?;
+    let id = match feature.id {
+        Some(CompactId::String(string)) => Some(Id::String(string)),
+        Some(CompactId::Number(number)) => Some(Id::Number(
+            serde_json::from_str(&number).map_err(D::Error::custom)?,
+        )),
+        None => None,
+    };
+    Ok(Feature {
+        bbox: feature.bbox,
+        geometry: feature.geometry,
+        id,
+        properties: from_json::<D>(feature.properties)?,
+        foreign_members: from_json::<D>(feature.foreign_members)?,
+    })
+}
+
+pub(crate) fn serialize_feature_collection<S: Serializer>(
+    collection: &FeatureCollection,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serialize_feature_collection_parts(
+        collection.bbox.as_ref(),
+        &collection.features,
+        collection.foreign_members.as_ref(),
+        serializer,
+    )
+}
+
+/// Serialize a feature collection from its members, with `features` serializing as a sequence
+/// of features
+pub(crate) fn serialize_feature_collection_parts<S: Serializer, F: Serialize>(
+    bbox: Option<&Bbox>,
+    features: F,
+    foreign_members: Option<&JsonObject>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    FeatureCollectionRef {
+        bbox,
+        features,
+        foreign_members: to_json::<S>(foreign_members)?,
+    }
+    .serialize(serializer)
+}
+
+pub(crate) fn deserialize_feature_collection<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<FeatureCollection, D::Error> {
+    let collection = CompactFeatureCollection::deserialize(deserializer)?;
+    Ok(FeatureCollection {
+        bbox: collection.bbox,
+        features: collection.features,
+        foreign_members: from_json::<D>(collection.foreign_members)?,
+    })
+}
+
+pub(crate) fn serialize_geojson<S: Serializer>(
+    geojson: &GeoJson,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match geojson {
+        GeoJson::Geometry(geometry) => GeoJsonRef::Geometry(geometry),
+        GeoJson::Feature(feature) => GeoJsonRef::Feature(feature),
+        GeoJson::FeatureCollection(collection) => GeoJsonRef::FeatureCollection(collection),
+    }
+    .serialize(serializer)
+}
+
+pub(crate) fn deserialize_geojson<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<GeoJson, D::Error> {
+    Ok(match CompactGeoJson::deserialize(deserializer)? {
+        CompactGeoJson::Geometry(geometry) => GeoJson::Geometry(geometry),
+        CompactGeoJson::Feature(feature) => GeoJson::Feature(feature),
+        CompactGeoJson::FeatureCollection(collection) => GeoJson::FeatureCollection(collection),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Feature, FeatureCollection, GeoJson, Geometry, Value};
+
+    #[test]
+    fn round_trip_through_cbor() {
+        let mut feature = Feature::from(Geometry {
+            bbox: Some(vec![0.0, 0.0, 1.0, 1.0]),
+            value: Value::GeometryCollection(vec![
+                Geometry::new(Value::Point(position![0.1 + 0.2, 1.0, -3.5])),
+                Geometry::new(Value::Polygon(vec![vec![
+                    position![0.0, 0.0],
+                    position![1.0, 0.0],
+                    position![1.0, 1.0],
+                    position![0.0, 0.0],
+                ]])),
+            ]),
+            foreign_members: serde_json::json!({ "title": "x" }).as_object().cloned(),
+        });
+        feature.id = Some(crate::feature::Id::Number(u64::MAX.into()));
+        feature.set_property("nested", serde_json::json!({ "a": [1, 2.5, null] }));
+        let mut empty = feature.clone();
+        empty.geometry = None;
+        empty.properties = None;
+        empty.id = Some(crate::feature::Id::String("a".to_string()));
+        let geojson = GeoJson::from(FeatureCollection {
+            bbox: None,
+            features: vec![feature, empty],
+            foreign_members: None,
+        });
+
+        let bytes = serde_cbor::to_vec(&geojson).unwrap();
+        assert!(bytes.len() < geojson.to_string().len());
+        let parsed: GeoJson = serde_cbor::from_slice(&bytes).unwrap();
+        assert_eq!(parsed, geojson);
+
+        // JSON is still written and read as GeoJSON
+        let parsed: GeoJson = serde_json::from_str(&geojson.to_string()).unwrap();
+        assert_eq!(parsed.to_string(), geojson.to_string());
+    }
+}
@@ -0,0 +1,231 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Serialization of `geo_types` geometries straight to GeoJSON, writing the same output as
+//! converting them to a `Value` first.
+
+use crate::ser::{AsGeoJson, SerializeGeometry};
+use crate::GeometryType;
+use geo_types::{Coord, CoordFloat, Geometry, LineString, Point, Polygon};
+use serde::ser::{SerializeMap, SerializeSeq};
+use serde::{Serialize, Serializer};
+
+struct Position<T: CoordFloat>(Coord<T>);
+
+impl<T: CoordFloat> Serialize for Position<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let x: f64 = self.0.x.to_f64().unwrap();
+        let y: f64 = self.0.y.to_f64().unwrap();
+        [x, y].serialize(serializer)
+    }
+}
+
+fn serialize_seq<S: Serializer, T: Serialize>(
+    serializer: S,
+    items: impl ExactSizeIterator<Item = T>,
+) -> Result<S::Ok, S::Error> {
+    let mut seq = serializer.serialize_seq(Some(items.len()))?;
+    for item in items {
+        seq.serialize_element(&item)?;
+    }
+    seq.end()
+}
+
+struct Points<'a, T: CoordFloat>(&'a [Point<T>]);
+
+impl<T: CoordFloat> Serialize for Points<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_seq(serializer, self.0.iter().map(|point| Position(point.0)))
+    }
+}
+
+struct Line<'a, T: CoordFloat>(&'a [Coord<T>]);
+
+impl<T: CoordFloat> Serialize for Line<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_seq(serializer, self.0.iter().map(|coord| Position(*coord)))
+    }
+}
+
+struct Lines<'a, T: CoordFloat>(&'a [LineString<T>]);
+
+impl<T: CoordFloat> Serialize for Lines<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_seq(serializer, self.0.iter().map(|line| Line(&line.0)))
+    }
+}
+
+struct Rings<'a, T: CoordFloat>(&'a Polygon<T>);
+
+impl<T: CoordFloat> Serialize for Rings<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let rings = std::iter::once(self.0.exterior()).chain(self.0.interiors());
+        let mut seq = serializer.serialize_seq(Some(1 + self.0.interiors().len()))?;
+        for ring in rings {
+            seq.serialize_element(&Line(&ring.0))?;
+        }
+        seq.end()
+    }
+}
+
+struct Polygons<'a, T: CoordFloat>(&'a [Polygon<T>]);
+
+impl<T: CoordFloat> Serialize for Polygons<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_seq(serializer, self.0.iter().map(Rings))
+    }
+}
+
+struct Geometries<'a, T: CoordFloat>(&'a [Geometry<T>]);
+
+impl<T: CoordFloat> Serialize for Geometries<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_seq(serializer, self.0.iter().map(AsGeoJson))
+    }
+}
+
+macro_rules! impl_serialize_geometry {
+    ($($type:ident => $geometry_type:ident, |$geometry:ident| $coordinates:expr;)*) => {
+        $(
+            #[cfg_attr(docsrs, doc(cfg(feature = "geo-types")))]
+            impl<T: CoordFloat> SerializeGeometry for geo_types::$type<T> {
+                fn geometry_type(&self) -> GeometryType {
+                    GeometryType::$geometry_type
+                }
+
+                fn serialize_members<M: SerializeMap>(&self, map: &mut M) -> Result<(), M::Error> {
+                    let $geometry = self;
+                    map.serialize_entry("coordinates", &$coordinates)
+                }
+            }
+        )*
+    };
+}
+
+impl_serialize_geometry! {
+    Point => Point, |point| Position(point.0);
+    MultiPoint => MultiPoint, |multi_point| Points(&multi_point.0);
+    LineString => LineString, |line_string| Line(&line_string.0);
+    Line => LineString, |line| Line(&[line.start, line.end]);
+    MultiLineString => MultiLineString, |multi_line_string| Lines(&multi_line_string.0);
+    Polygon => Polygon, |polygon| Rings(polygon);
+    Triangle => Polygon, |triangle| Rings(&triangle.to_polygon());
+    Rect => Polygon, |rect| Rings(&rect.to_polygon());
+    MultiPolygon => MultiPolygon, |multi_polygon| Polygons(&multi_polygon.0);
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "geo-types")))]
+impl<T: CoordFloat> SerializeGeometry for geo_types::GeometryCollection<T> {
+    fn geometry_type(&self) -> GeometryType {
+        GeometryType::GeometryCollection
+    }
+
+    fn serialize_members<M: SerializeMap>(&self, map: &mut M) -> Result<(), M::Error> {
+        map.serialize_entry("geometries", &Geometries(&self.0))
+    }
+}
+
+/// # Example
+///
+/// ```
+/// use geo_types::{line_string, Geometry};
+///
+/// let line: Geometry = line_string![(x: 1.0, y: 2.0), (x: 3.0, y: 4.0)].into();
+/// assert_eq!(
+///     geojson::geometry_to_string(&line).unwrap(),
+///     r#"{"coordinates":[[1.0,2.0],[3.0,4.0]],"type":"LineString"}"#
+/// );
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "geo-types")))]
+impl<T: CoordFloat> SerializeGeometry for Geometry<T> {
+    fn geometry_type(&self) -> GeometryType {
+        match self {
+            Geometry::Point(point) => point.geometry_type(),
+            Geometry::Line(line) => line.geometry_type(),
+            Geometry::LineString(line_string) => line_string.geometry_type(),
+            Geometry::Polygon(polygon) => polygon.geometry_type(),
+            Geometry::MultiPoint(multi_point) => multi_point.geometry_type(),
+            Geometry::MultiLineString(multi_line_string) => multi_line_string.geometry_type(),
+            Geometry::MultiPolygon(multi_polygon) => multi_polygon.geometry_type(),
+            Geometry::GeometryCollection(collection) => collection.geometry_type(),
+            Geometry::Rect(rect) => rect.geometry_type(),
+            Geometry::Triangle(triangle) => triangle.geometry_type(),
+        }
+    }
+
+    fn serialize_members<M: SerializeMap>(&self, map: &mut M) -> Result<(), M::Error> {
+        match self {
+            Geometry::Point(point) => point.serialize_members(map),
+            Geometry::Line(line) => line.serialize_members(map),
+            Geometry::LineString(line_string) => line_string.serialize_members(map),
+            Geometry::Polygon(polygon) => polygon.serialize_members(map),
+            Geometry::MultiPoint(multi_point) => multi_point.serialize_members(map),
+            Geometry::MultiLineString(multi_line_string) => {
+                multi_line_string.serialize_members(map)
+            }
+            Geometry::MultiPolygon(multi_polygon) => multi_polygon.serialize_members(map),
+            Geometry::GeometryCollection(collection) => collection.serialize_members(map),
+            Geometry::Rect(rect) => rect.serialize_members(map),
+            Geometry::Triangle(triangle) => triangle.serialize_members(map),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{geometry_to_string, Value};
+    use geo_types::{
+        coord, line_string, point, polygon, Geometry, GeometryCollection, Line, MultiLineString,
+        MultiPoint, MultiPolygon, Rect, Triangle,
+    };
+
+    #[test]
+    fn same_output_as_value() {
+        let polygon = polygon!(
+            exterior: [(x: 0.0, y: 0.0), (x: 4.0, y: 0.0), (x: 4.0, y: 4.0)],
+            interiors: [[(x: 1.0, y: 1.0), (x: 2.0, y: 1.0), (x: 2.0, y: 2.0)]],
+        );
+        let line_string = line_string![(x: 1.5, y: 2.0), (x: -3.0, y: 4.25)];
+        let geometries: Vec<Geometry<f64>> = vec![
+            point!(x: 1.0, y: 2.0).into(),
+            MultiPoint(vec![point!(x: 1.0, y: 2.0), point!(x: 3.0, y: 4.0)]).into(),
+            line_string.clone().into(),
+            Line::new(coord! { x: 0.0, y: 0.0 }, coord! { x: 1.0, y: 1.0 }).into(),
+            MultiLineString(vec![line_string.clone(), line_string]).into(),
+            polygon.clone().into(),
+            Triangle::new(
+                coord! { x: 0.0, y: 0.0 },
+                coord! { x: 1.0, y: 0.0 },
+                coord! { x: 0.0, y: 1.0 },
+            )
+            .into(),
+            Rect::new(coord! { x: 0.0, y: 0.0 }, coord! { x: 1.0, y: 2.0 }).into(),
+            MultiPolygon(vec![polygon.clone(), polygon]).into(),
+        ];
+        let collection = Geometry::GeometryCollection(GeometryCollection(vec![
+            geometries[0].clone(),
+            Geometry::GeometryCollection(GeometryCollection(geometries.clone())),
+        ]));
+
+        for geometry in geometries.iter().chain(std::iter::once(&collection)) {
+            let expected = crate::Geometry::new(Value::from(geometry)).to_string();
+            assert_eq!(geometry_to_string(geometry).unwrap(), expected);
+        }
+        let single = point!(x: 1.0f32, y: 2.5f32);
+        assert_eq!(
+            geometry_to_string(&single).unwrap(),
+            crate::Geometry::new(Value::from(&single)).to_string()
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::geo_types::{self, CoordFloat};
+use geo_types::CoordFloat;
 
 use crate::geometry;
 
@@ -36,7 +36,7 @@ where
             geometry::Value::MultiPoint(multi_point_type) => Ok(geo_types::MultiPoint(
                 multi_point_type
                     .iter()
-                    .map(|point_type| create_geo_point(&point_type))
+                    .map(|point_type| create_geo_point(point_type))
                     .collect(),
             )),
             _ => Err(GJError::InvalidGeometryConversion(value)),
@@ -148,7 +148,7 @@ where
                 Ok(geo_types::Geometry::MultiPoint(geo_types::MultiPoint(
                     multi_point_type
                         .iter()
-                        .map(|point_type| create_geo_point(&point_type))
+                        .map(|point_type| create_geo_point(point_type))
                         .collect(),
                 )))
             }
@@ -228,11 +228,11 @@ where
     }
 }
 
-fn create_geo_coordinate<T>(point_type: &PointType) -> geo_types::Coordinate<T>
+fn create_geo_coordinate<T>(point_type: &PointType) -> geo_types::Coord<T>
 where
     T: CoordFloat,
 {
-    geo_types::Coordinate {
+    geo_types::Coord {
         x: T::from(point_type[0]).unwrap(),
         y: T::from(point_type[1]).unwrap(),
     }
@@ -269,17 +269,17 @@ where
     geo_types::MultiLineString(
         multi_line_type
             .iter()
-            .map(|point_type| create_geo_line_string(&point_type))
+            .map(|point_type| create_geo_line_string(point_type))
             .collect(),
     )
 }
 
-fn create_geo_polygon<T>(polygon_type: &PolygonType) -> geo_types::Polygon<T>
+pub(crate) fn create_geo_polygon<T>(polygon_type: &PolygonType) -> geo_types::Polygon<T>
 where
     T: CoordFloat,
 {
     let exterior = polygon_type
-        .get(0)
+        .first()
         .map(|e| create_geo_line_string(e))
         .unwrap_or_else(|| create_geo_line_string(&vec![]));
 
@@ -302,7 +302,7 @@ where
     geo_types::MultiPolygon(
         multi_polygon_type
             .iter()
-            .map(|polygon_type| create_geo_polygon(&polygon_type))
+            .map(|polygon_type| create_geo_polygon(polygon_type))
             .collect(),
     )
 }
@@ -310,13 +310,12 @@ where
 #[cfg(test)]
 mod tests {
     use crate::{Geometry, Value};
-    use geo_types;
 
     use std::convert::TryInto;
 
     #[test]
     fn geojson_point_conversion_test() {
-        let coords = vec![100.0, 0.2];
+        let coords = position![100.0, 0.2];
         let geojson_point = Value::Point(coords.clone());
         let geo_point: geo_types::Point<f64> = geojson_point.try_into().unwrap();
 
@@ -326,8 +325,8 @@ mod tests {
 
     #[test]
     fn geojson_multi_point_conversion_test() {
-        let coord1 = vec![100.0, 0.2];
-        let coord2 = vec![101.0, 1.0];
+        let coord1 = position![100.0, 0.2];
+        let coord2 = position![101.0, 1.0];
         let geojson_multi_point = Value::MultiPoint(vec![coord1.clone(), coord2.clone()]);
         let geo_multi_point: geo_types::MultiPoint<f64> = geojson_multi_point.try_into().unwrap();
 
@@ -339,8 +338,8 @@ mod tests {
 
     #[test]
     fn geojson_line_string_conversion_test() {
-        let coord1 = vec![100.0, 0.2];
-        let coord2 = vec![101.0, 1.0];
+        let coord1 = position![100.0, 0.2];
+        let coord2 = position![101.0, 1.0];
         let geojson_line_string = Value::LineString(vec![coord1.clone(), coord2.clone()]);
         let geo_line_string: geo_types::LineString<f64> = geojson_line_string.try_into().unwrap();
 
@@ -352,9 +351,9 @@ mod tests {
 
     #[test]
     fn geojson_multi_line_string_conversion_test() {
-        let coord1 = vec![100.0, 0.2];
-        let coord2 = vec![101.0, 1.0];
-        let coord3 = vec![102.0, 0.8];
+        let coord1 = position![100.0, 0.2];
+        let coord2 = position![101.0, 1.0];
+        let coord3 = position![102.0, 0.8];
         let geojson_multi_line_string = Value::MultiLineString(vec![
             vec![coord1.clone(), coord2.clone()],
             vec![coord2.clone(), coord3.clone()],
@@ -362,13 +361,13 @@ mod tests {
         let geo_multi_line_string: geo_types::MultiLineString<f64> =
             geojson_multi_line_string.try_into().unwrap();
 
-        let ref geo_line_string1 = geo_multi_line_string.0[0];
+        let geo_line_string1 = &geo_multi_line_string.0[0];
         assert_almost_eq!(geo_line_string1.0[0].x, coord1[0], 1e-6);
         assert_almost_eq!(geo_line_string1.0[0].y, coord1[1], 1e-6);
         assert_almost_eq!(geo_line_string1.0[1].x, coord2[0], 1e-6);
         assert_almost_eq!(geo_line_string1.0[1].y, coord2[1], 1e-6);
 
-        let ref geo_line_string2 = geo_multi_line_string.0[1];
+        let geo_line_string2 = &geo_multi_line_string.0[1];
         assert_almost_eq!(geo_line_string2.0[0].x, coord2[0], 1e-6);
         assert_almost_eq!(geo_line_string2.0[0].y, coord2[1], 1e-6);
         assert_almost_eq!(geo_line_string2.0[1].x, coord3[0], 1e-6);
@@ -377,12 +376,12 @@ mod tests {
 
     #[test]
     fn geojson_polygon_conversion_test() {
-        let coord1 = vec![100.0, 0.0];
-        let coord2 = vec![101.0, 1.0];
-        let coord3 = vec![101.0, 1.0];
-        let coord4 = vec![104.0, 0.2];
-        let coord5 = vec![100.9, 0.2];
-        let coord6 = vec![100.9, 0.7];
+        let coord1 = position![100.0, 0.0];
+        let coord2 = position![101.0, 1.0];
+        let coord3 = position![101.0, 1.0];
+        let coord4 = position![104.0, 0.2];
+        let coord5 = position![100.9, 0.2];
+        let coord6 = position![100.9, 0.7];
 
         let geojson_multi_line_string_type1 = vec![
             vec![
@@ -401,7 +400,7 @@ mod tests {
         let geojson_polygon = Value::Polygon(geojson_multi_line_string_type1);
         let geo_polygon: geo_types::Polygon<f64> = geojson_polygon.try_into().unwrap();
 
-        let ref geo_line_string1 = geo_polygon.exterior();
+        let geo_line_string1 = geo_polygon.exterior();
         assert_almost_eq!(geo_line_string1.0[0].x, coord1[0], 1e-6);
         assert_almost_eq!(geo_line_string1.0[0].y, coord1[1], 1e-6);
         assert_almost_eq!(geo_line_string1.0[1].x, coord2[0], 1e-6);
@@ -411,7 +410,7 @@ mod tests {
         assert_almost_eq!(geo_line_string1.0[3].x, coord1[0], 1e-6);
         assert_almost_eq!(geo_line_string1.0[3].y, coord1[1], 1e-6);
 
-        let ref geo_line_string2 = geo_polygon.interiors()[0];
+        let geo_line_string2 = &geo_polygon.interiors()[0];
         assert_almost_eq!(geo_line_string2.0[0].x, coord4[0], 1e-6);
         assert_almost_eq!(geo_line_string2.0[0].y, coord4[1], 1e-6);
         assert_almost_eq!(geo_line_string2.0[1].x, coord5[0], 1e-6);
@@ -432,9 +431,9 @@ mod tests {
 
     #[test]
     fn geojson_polygon_without_interiors_conversion_test() {
-        let coord1 = vec![100.0, 0.0];
-        let coord2 = vec![101.0, 1.0];
-        let coord3 = vec![101.0, 1.0];
+        let coord1 = position![100.0, 0.0];
+        let coord2 = position![101.0, 1.0];
+        let coord3 = position![101.0, 1.0];
 
         let geojson_multi_line_string_type1 = vec![vec![
             coord1.clone(),
@@ -445,7 +444,7 @@ mod tests {
         let geojson_polygon = Value::Polygon(geojson_multi_line_string_type1);
         let geo_polygon: geo_types::Polygon<f64> = geojson_polygon.try_into().unwrap();
 
-        let ref geo_line_string1 = geo_polygon.exterior();
+        let geo_line_string1 = geo_polygon.exterior();
         assert_almost_eq!(geo_line_string1.0[0].x, coord1[0], 1e-6);
         assert_almost_eq!(geo_line_string1.0[0].y, coord1[1], 1e-6);
         assert_almost_eq!(geo_line_string1.0[1].x, coord2[0], 1e-6);
@@ -460,12 +459,12 @@ mod tests {
 
     #[test]
     fn geojson_multi_polygon_conversion_test() {
-        let coord1 = vec![100.0, 0.0];
-        let coord2 = vec![101.0, 1.0];
-        let coord3 = vec![101.0, 1.0];
-        let coord4 = vec![104.0, 0.2];
-        let coord5 = vec![100.9, 0.2];
-        let coord6 = vec![100.9, 0.7];
+        let coord1 = position![100.0, 0.0];
+        let coord2 = position![101.0, 1.0];
+        let coord3 = position![101.0, 1.0];
+        let coord4 = position![104.0, 0.2];
+        let coord5 = position![100.9, 0.2];
+        let coord6 = position![100.9, 0.7];
 
         let geojson_line_string_type1 = vec![
             coord1.clone(),
@@ -487,7 +486,7 @@ mod tests {
         let geo_multi_polygon: geo_types::MultiPolygon<f64> =
             geojson_multi_polygon.try_into().unwrap();
 
-        let ref geo_line_string1 = geo_multi_polygon.0[0].exterior();
+        let geo_line_string1 = geo_multi_polygon.0[0].exterior();
         assert_almost_eq!(geo_line_string1.0[0].x, coord1[0], 1e-6);
         assert_almost_eq!(geo_line_string1.0[0].y, coord1[1], 1e-6);
         assert_almost_eq!(geo_line_string1.0[1].x, coord2[0], 1e-6);
@@ -497,7 +496,7 @@ mod tests {
         assert_almost_eq!(geo_line_string1.0[3].x, coord1[0], 1e-6);
         assert_almost_eq!(geo_line_string1.0[3].y, coord1[1], 1e-6);
 
-        let ref geo_line_string2 = geo_multi_polygon.0[1].exterior();
+        let geo_line_string2 = geo_multi_polygon.0[1].exterior();
         assert_almost_eq!(geo_line_string2.0[0].x, coord4[0], 1e-6);
         assert_almost_eq!(geo_line_string2.0[0].y, coord4[1], 1e-6);
         assert_almost_eq!(geo_line_string2.0[1].x, coord5[0], 1e-6);
@@ -510,11 +509,11 @@ mod tests {
 
     #[test]
     fn geojson_geometry_collection_conversion_test() {
-        let coord1 = vec![100.0, 0.0];
-        let coord2 = vec![100.0, 1.0];
-        let coord3 = vec![101.0, 1.0];
-        let coord4 = vec![102.0, 0.0];
-        let coord5 = vec![101.0, 0.0];
+        let coord1 = position![100.0, 0.0];
+        let coord2 = position![100.0, 1.0];
+        let coord3 = position![101.0, 1.0];
+        let coord4 = position![102.0, 0.0];
+        let coord5 = position![101.0, 0.0];
 
         let geojson_multi_point = Value::MultiPoint(vec![coord1.clone(), coord2.clone()]);
         let geojson_multi_line_string = Value::MultiLineString(vec![
@@ -550,7 +549,7 @@ mod tests {
 
     #[test]
     fn geojson_geometry_conversion() {
-        let coords = vec![100.0, 0.2];
+        let coords = position![100.0, 0.2];
         let geojson_geometry = Geometry::from(Value::Point(coords.clone()));
         let geo_geometry: geo_types::Geometry<f64> = geojson_geometry
             .try_into()
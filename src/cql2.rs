@@ -0,0 +1,1081 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Evaluation of [OGC CQL2](https://docs.ogc.org/is/21-065r2/21-065r2.html) filters against
+//! features, as used by the `filter` parameter of OGC API – Features Part 3.
+//!
+//! Filters can be read from both the text and the JSON encoding of CQL2. Supported are the
+//! comparison operators, `AND`, `OR` and `NOT`, `IS NULL`, `LIKE`, `BETWEEN`, `IN` and
+//! `CASEI`, and the spatial functions `S_INTERSECTS`, `S_DISJOINT` and `S_EQUALS`, with
+//! geometry literals given as WKT or `BBOX(...)` in text, and as GeoJSON or `{"bbox": [...]}`
+//! in JSON. Temporal literals are compared as strings, which orders ISO 8601 instants
+//! correctly when they are written alike.
+//!
+//! Properties are looked up among a feature's properties. If it has no such property, `id`
+//! is the feature's id and `geometry` its geometry. Comparing a missing or `null` property,
+//! or values of different types, is neither true nor false but unknown, as in SQL, and a
+//! feature only matches a filter which is true for it.
+//!
+//! # Example
+//!
+//! ```
+//! use geojson::cql2::Expression;
+//! use geojson::{position, Feature, Value};
+//!
+//! let mut feature = Feature::from(Value::Point(position![5.0, 5.0]));
+//! feature.set_property("name", "Main Street");
+//! feature.set_property("lanes", 4);
+//!
+//! let filter = Expression::from_text(
+//!     "lanes >= 2 AND name LIKE 'Main%' AND S_INTERSECTS(geometry, BBOX(0, 0, 10, 10))",
+//! )
+//! .unwrap();
+//! assert!(filter.matches(&feature).unwrap());
+//!
+//! let filter = Expression::from_json(&serde_json::json!({
+//!     "op": "isNull",
+//!     "args": [{ "property": "speed" }]
+//! }))
+//! .unwrap();
+//! assert!(filter.matches(&feature).unwrap());
+//! ```
+
+use crate::algorithm::intersects::values_intersect;
+use crate::algorithm::Rect;
+use crate::json::{JsonObject, JsonValue};
+use crate::{feature, Error, Feature, Geometry, Position, Value};
+use std::borrow::Cow;
+use std::cmp::Ordering;
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::InvalidCql2(message.into())
+}
+
+/// A CQL2 operator or function
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Op {
+    And,
+    Or,
+    Not,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Like,
+    Between,
+    In,
+    IsNull,
+    Casei,
+    SIntersects,
+    SDisjoint,
+    SEquals,
+}
+
+const OPS: [Op; 17] = [
+    Op::And,
+    Op::Or,
+    Op::Not,
+    Op::Eq,
+    Op::Ne,
+    Op::Lt,
+    Op::Le,
+    Op::Gt,
+    Op::Ge,
+    Op::Like,
+    Op::Between,
+    Op::In,
+    Op::IsNull,
+    Op::Casei,
+    Op::SIntersects,
+    Op::SDisjoint,
+    Op::SEquals,
+];
+
+impl Op {
+    /// The name of this operator in CQL2 JSON, e.g. `"s_intersects"`
+    pub fn name(self) -> &'static str {
+        match self {
+            Op::And => "and",
+            Op::Or => "or",
+            Op::Not => "not",
+            Op::Eq => "=",
+            Op::Ne => "<>",
+            Op::Lt => "<",
+            Op::Le => "<=",
+            Op::Gt => ">",
+            Op::Ge => ">=",
+            Op::Like => "like",
+            Op::Between => "between",
+            Op::In => "in",
+            Op::IsNull => "isNull",
+            Op::Casei => "casei",
+            Op::SIntersects => "s_intersects",
+            Op::SDisjoint => "s_disjoint",
+            Op::SEquals => "s_equals",
+        }
+    }
+
+    /// The operator named `name`, ignoring case
+    pub fn from_name(name: &str) -> Option<Op> {
+        OPS.iter()
+            .copied()
+            .find(|op| op.name().eq_ignore_ascii_case(name))
+    }
+
+    fn arity_is_valid(self, arity: usize) -> bool {
+        match self {
+            Op::And | Op::Or => arity >= 2,
+            Op::Not | Op::IsNull | Op::Casei => arity == 1,
+            Op::Between => arity == 3,
+            _ => arity == 2,
+        }
+    }
+}
+
+/// A CQL2 expression, in the structure of its JSON encoding
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expression {
+    /// A string, number, boolean or `null`
+    Literal(JsonValue),
+    /// The value of a property of the feature
+    Property(String),
+    /// A geometry
+    Geometry(Value),
+    /// A bounding box of 4 or 6 numbers
+    Bbox(Vec<f64>),
+    /// A list of values, as the second argument of `in`
+    List(Vec<Expression>),
+    /// An operator or function applied to its arguments
+    Op(Op, Vec<Expression>),
+}
+
+impl Expression {
+    /// Parse the CQL2 text encoding of a filter, e.g. `"height > 10 AND roof = 'flat'"`.
+    pub fn from_text(text: &str) -> Result<Self, Error> {
+        let mut parser = Parser {
+            tokens: tokenize(text)?,
+            index: 0,
+        };
+        let expression = parser.or()?;
+        match parser.tokens.get(parser.index) {
+            None => Ok(expression),
+            Some(token) => Err(invalid(format!("unexpected {}", token))),
+        }
+    }
+
+    /// Parse the CQL2 JSON encoding of a filter, e.g.
+    /// `{"op": ">", "args": [{"property": "height"}, 10]}`.
+    pub fn from_json(value: &JsonValue) -> Result<Self, Error> {
+        let object = match value {
+            JsonValue::Array(values) => {
+                return values
+                    .iter()
+                    .map(Expression::from_json)
+                    .collect::<Result<_, _>>()
+                    .map(Expression::List)
+            }
+            JsonValue::Object(object) => object,
+            literal => return Ok(Expression::Literal(literal.clone())),
+        };
+        if let Some(op) = object.get("op") {
+            let op = op
+                .as_str()
+                .and_then(Op::from_name)
+                .ok_or_else(|| invalid(format!("unsupported operator `{}`", op)))?;
+            let args = match object.get("args") {
+                Some(JsonValue::Array(args)) => args
+                    .iter()
+                    .map(Expression::from_json)
+                    .collect::<Result<_, _>>()?,
+                Some(args) => vec![Expression::from_json(args)?],
+                None => vec![],
+            };
+            return operation(op, args);
+        }
+        if let Some(property) = object.get("property") {
+            return property
+                .as_str()
+                .map(|name| Expression::Property(name.to_owned()))
+                .ok_or_else(|| invalid(format!("expected a property name, got `{}`", property)));
+        }
+        if let Some(bbox) = object.get("bbox") {
+            return bbox
+                .as_array()
+                .and_then(|bbox| bbox.iter().map(JsonValue::as_f64).collect())
+                .map(Expression::Bbox)
+                .ok_or_else(|| invalid(format!("expected a bbox, got `{}`", bbox)));
+        }
+        for temporal in ["timestamp", "date"] {
+            if let Some(instant) = object.get(temporal) {
+                return Ok(Expression::Literal(instant.clone()));
+            }
+        }
+        if object.contains_key("type") {
+            return Geometry::from_json_value(value.clone())
+                .map(|geometry| Expression::Geometry(geometry.value));
+        }
+        Err(invalid(format!("unsupported expression `{}`", value)))
+    }
+
+    /// The CQL2 JSON encoding of this expression
+    pub fn to_json(&self) -> JsonValue {
+        let object = |key: &str, value: JsonValue| {
+            let mut object = JsonObject::new();
+            object.insert(key.to_owned(), value);
+            JsonValue::Object(object)
+        };
+        match self {
+            Expression::Literal(value) => value.clone(),
+            Expression::Property(name) => object("property", name.as_str().into()),
+            Expression::Geometry(value) => JsonValue::Object(JsonObject::from(value)),
+            Expression::Bbox(bbox) => object("bbox", bbox.as_slice().into()),
+            Expression::List(values) => values.iter().map(Expression::to_json).collect(),
+            Expression::Op(op, args) => {
+                let mut json = object("op", op.name().into());
+                json["args"] = args.iter().map(Expression::to_json).collect();
+                json
+            }
+        }
+    }
+
+    /// Whether this filter is true for `feature`.
+    ///
+    /// This is `false` when the filter is unknown, such as when it compares a property which
+    /// `feature` doesn't have. It is an error for the filter not to be a boolean, or to pass
+    /// an operator arguments of a type it can never take, such as `LIKE` a geometry.
+    pub fn matches(&self, feature: &Feature) -> Result<bool, Error> {
+        match self.evaluate(feature)? {
+            Operand::Bool(value) => Ok(value),
+            Operand::Null => Ok(false),
+            other => Err(invalid(format!("expected a boolean, got {}", other.kind()))),
+        }
+    }
+
+    fn evaluate<'a>(&'a self, feature: &'a Feature) -> Result<Operand<'a>, Error> {
+        match self {
+            Expression::Literal(value) => Ok(Operand::from_json(value)),
+            Expression::Property(name) => Ok(property(feature, name)),
+            Expression::Geometry(value) => Ok(Operand::Geometry(Cow::Borrowed(value))),
+            Expression::Bbox(bbox) => bbox_geometry(bbox),
+            Expression::List(values) => values
+                .iter()
+                .map(|value| value.evaluate(feature))
+                .collect::<Result<_, _>>()
+                .map(Operand::List),
+            Expression::Op(op, args) => {
+                let args = args
+                    .iter()
+                    .map(|arg| arg.evaluate(feature))
+                    .collect::<Result<Vec<_>, _>>()?;
+                apply(*op, args)
+            }
+        }
+    }
+}
+
+fn operation(op: Op, args: Vec<Expression>) -> Result<Expression, Error> {
+    if !op.arity_is_valid(args.len()) {
+        return Err(invalid(format!(
+            "`{}` can't take {} arguments",
+            op.name(),
+            args.len()
+        )));
+    }
+    if op == Op::In && !matches!(args[1], Expression::List(_)) {
+        return Err(invalid("`in` takes a list as its second argument"));
+    }
+    Ok(Expression::Op(op, args))
+}
+
+/// The value of an expression
+#[derive(Debug, PartialEq)]
+enum Operand<'a> {
+    /// `null`, missing, or unknown
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(Cow<'a, str>),
+    Geometry(Cow<'a, Value>),
+    List(Vec<Operand<'a>>),
+}
+
+impl<'a> Operand<'a> {
+    fn from_json(value: &'a JsonValue) -> Self {
+        match value {
+            JsonValue::Bool(value) => Operand::Bool(*value),
+            JsonValue::Number(number) => number.as_f64().map_or(Operand::Null, Operand::Number),
+            JsonValue::String(string) => Operand::String(Cow::Borrowed(string)),
+            JsonValue::Array(values) => {
+                Operand::List(values.iter().map(Operand::from_json).collect())
+            }
+            JsonValue::Null | JsonValue::Object(_) => Operand::Null,
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Operand::Null => "null",
+            Operand::Bool(_) => "a boolean",
+            Operand::Number(_) => "a number",
+            Operand::String(_) => "a string",
+            Operand::Geometry(_) => "a geometry",
+            Operand::List(_) => "a list",
+        }
+    }
+
+    /// `Some(value)` if this is a boolean, `None` if it is unknown
+    fn as_bool(&self, op: Op) -> Result<Option<bool>, Error> {
+        match self {
+            Operand::Bool(value) => Ok(Some(*value)),
+            Operand::Null => Ok(None),
+            other => Err(invalid(format!(
+                "`{}` expected a boolean, got {}",
+                op.name(),
+                other.kind()
+            ))),
+        }
+    }
+
+    /// `Some(value)` if this is a geometry, `None` if it is unknown
+    fn as_geometry(&self, op: Op) -> Result<Option<&Value>, Error> {
+        match self {
+            Operand::Geometry(value) => Ok(Some(value)),
+            Operand::Null => Ok(None),
+            other => Err(invalid(format!(
+                "`{}` expected a geometry, got {}",
+                op.name(),
+                other.kind()
+            ))),
+        }
+    }
+
+    /// The order of two values of the same type, or `None` if they can't be compared
+    fn compare(&self, other: &Operand) -> Option<Ordering> {
+        match (self, other) {
+            (Operand::Number(a), Operand::Number(b)) => a.partial_cmp(b),
+            (Operand::String(a), Operand::String(b)) => Some(a.cmp(b)),
+            (Operand::Bool(a), Operand::Bool(b)) => Some(a.cmp(b)),
+            (Operand::Geometry(a), Operand::Geometry(b)) if a == b => Some(Ordering::Equal),
+            _ => None,
+        }
+    }
+}
+
+fn property<'a>(feature: &'a Feature, name: &str) -> Operand<'a> {
+    if let Some(value) = feature.property(name) {
+        return Operand::from_json(value);
+    }
+    match name {
+        "id" => match &feature.id {
+            Some(feature::Id::String(id)) => Operand::String(Cow::Borrowed(id)),
+            Some(feature::Id::Number(id)) => id.as_f64().map_or(Operand::Null, Operand::Number),
+            None => Operand::Null,
+        },
+        "geometry" => feature.geometry.as_ref().map_or(Operand::Null, |geometry| {
+            Operand::Geometry(Cow::Borrowed(&geometry.value))
+        }),
+        _ => Operand::Null,
+    }
+}
+
+fn bbox_geometry(bbox: &[f64]) -> Result<Operand<'static>, Error> {
+    let rect = match *bbox {
+        [min_x, min_y, max_x, max_y] | [min_x, min_y, _, max_x, max_y, _] => {
+            Rect::from_bbox([min_x, min_y, max_x, max_y])
+        }
+        _ => return Err(invalid("a bbox has 4 or 6 numbers")),
+    };
+    Ok(Operand::Geometry(Cow::Owned(Value::Polygon(vec![
+        rect.to_ring()
+    ]))))
+}
+
+fn apply(op: Op, mut args: Vec<Operand>) -> Result<Operand, Error> {
+    let boolean = |value: Option<bool>| value.map_or(Operand::Null, Operand::Bool);
+    Ok(match op {
+        Op::And | Op::Or => {
+            // Either operand decides the result alone if it is false for `and`, or true for
+            // `or`; otherwise the result is unknown if any operand is
+            let decisive = op == Op::Or;
+            let mut unknown = false;
+            for arg in &args {
+                match arg.as_bool(op)? {
+                    Some(value) if value == decisive => return Ok(Operand::Bool(decisive)),
+                    Some(_) => {}
+                    None => unknown = true,
+                }
+            }
+            boolean(if unknown { None } else { Some(!decisive) })
+        }
+        Op::Not => boolean(args[0].as_bool(op)?.map(|value| !value)),
+        Op::Eq | Op::Ne | Op::Lt | Op::Le | Op::Gt | Op::Ge => {
+            boolean(args[0].compare(&args[1]).map(|ordering| match op {
+                Op::Eq => ordering == Ordering::Equal,
+                Op::Ne => ordering != Ordering::Equal,
+                Op::Lt => ordering == Ordering::Less,
+                Op::Le => ordering != Ordering::Greater,
+                Op::Gt => ordering == Ordering::Greater,
+                _ => ordering != Ordering::Less,
+            }))
+        }
+        Op::Like => match (&args[0], &args[1]) {
+            (Operand::String(value), Operand::String(pattern)) => {
+                Operand::Bool(like(value, pattern))
+            }
+            _ => Operand::Null,
+        },
+        Op::Between => boolean(
+            args[0]
+                .compare(&args[1])
+                .zip(args[0].compare(&args[2]))
+                .map(|(low, high)| low != Ordering::Less && high != Ordering::Greater),
+        ),
+        Op::In => match (&args[0], &args[1]) {
+            (Operand::Null, _) => Operand::Null,
+            (value, Operand::List(list)) => Operand::Bool(
+                list.iter()
+                    .any(|item| value.compare(item) == Some(Ordering::Equal)),
+            ),
+            _ => return Err(invalid("`in` takes a list as its second argument")),
+        },
+        Op::IsNull => Operand::Bool(args[0] == Operand::Null),
+        Op::Casei => match args.pop() {
+            Some(Operand::String(value)) => Operand::String(Cow::Owned(value.to_lowercase())),
+            Some(Operand::Null) => Operand::Null,
+            other => {
+                return Err(invalid(format!(
+                    "`casei` expected a string, got {}",
+                    other.map_or("nothing", |other| other.kind())
+                )))
+            }
+        },
+        Op::SIntersects | Op::SDisjoint | Op::SEquals => {
+            match (args[0].as_geometry(op)?, args[1].as_geometry(op)?) {
+                (Some(a), Some(b)) => Operand::Bool(match op {
+                    Op::SIntersects => values_intersect(a, b),
+                    Op::SDisjoint => !values_intersect(a, b),
+                    _ => a == b,
+                }),
+                _ => Operand::Null,
+            }
+        }
+    })
+}
+
+/// A `LIKE` pattern element
+#[derive(Clone, Copy, PartialEq)]
+enum Wildcard {
+    /// `%`, any number of characters
+    Any,
+    /// `_`, any one character
+    One,
+    Char(char),
+}
+
+/// Does `value` match the `LIKE` pattern `pattern`, where `\` escapes `%`, `_` and itself?
+fn like(value: &str, pattern: &str) -> bool {
+    let mut wildcards = vec![];
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        wildcards.push(match c {
+            '%' => Wildcard::Any,
+            '_' => Wildcard::One,
+            '\\' => Wildcard::Char(chars.next().unwrap_or('\\')),
+            c => Wildcard::Char(c),
+        });
+    }
+    let value: Vec<char> = value.chars().collect();
+    // Match greedily, going back to the last `%` to let it take one more character on failure
+    let (mut v, mut w) = (0, 0);
+    let mut backtrack = None;
+    while v < value.len() {
+        match wildcards.get(w) {
+            Some(Wildcard::Any) => {
+                backtrack = Some((v, w));
+                w += 1;
+            }
+            Some(Wildcard::One) => {
+                v += 1;
+                w += 1;
+            }
+            Some(Wildcard::Char(c)) if *c == value[v] => {
+                v += 1;
+                w += 1;
+            }
+            _ => match backtrack {
+                Some((last_v, last_w)) => {
+                    backtrack = Some((last_v + 1, last_w));
+                    v = last_v + 1;
+                    w = last_w + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    wildcards[w..]
+        .iter()
+        .all(|wildcard| *wildcard == Wildcard::Any)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Identifier(String),
+    QuotedIdentifier(String),
+    String(String),
+    Number(f64),
+    Punctuation(&'static str),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Token::Identifier(name) => write!(f, "`{}`", name),
+            Token::QuotedIdentifier(name) => write!(f, "`\"{}\"`", name),
+            Token::String(string) => write!(f, "`'{}'`", string),
+            Token::Number(number) => write!(f, "`{}`", number),
+            Token::Punctuation(punctuation) => write!(f, "`{}`", punctuation),
+        }
+    }
+}
+
+const PUNCTUATION: [&str; 9] = ["<>", "<=", ">=", "<", ">", "=", "(", ")", ","];
+
+/// Read the characters up to the closing `quote`, where a doubled quote stands for itself
+fn quoted(
+    chars: &mut std::iter::Peekable<std::str::CharIndices>,
+    quote: char,
+) -> Result<String, Error> {
+    let mut string = String::new();
+    loop {
+        match chars.next() {
+            Some((_, c)) if c == quote => {
+                if chars.peek().map(|&(_, c)| c) == Some(quote) {
+                    chars.next();
+                    string.push(quote);
+                } else {
+                    return Ok(string);
+                }
+            }
+            Some((_, c)) => string.push(c),
+            None => return Err(invalid(format!("missing closing {}", quote))),
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, Error> {
+    let mut tokens = vec![];
+    let mut chars = text.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        let next = text[start + c.len_utf8()..].chars().next();
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '\'' || c == '"' {
+            chars.next();
+            let string = quoted(&mut chars, c)?;
+            tokens.push(if c == '\'' {
+                Token::String(string)
+            } else {
+                Token::QuotedIdentifier(string)
+            });
+        } else if c.is_ascii_digit()
+            || (matches!(c, '-' | '+' | '.')
+                && next.is_some_and(|n| n.is_ascii_digit() || n == '.'))
+        {
+            let mut end = start + 1;
+            chars.next();
+            while let Some(&(index, c)) = chars.peek() {
+                let exponent_sign = matches!(c, '-' | '+')
+                    && matches!(text[..index].chars().last(), Some('e' | 'E'));
+                if !(c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E') || exponent_sign) {
+                    break;
+                }
+                end = index + 1;
+                chars.next();
+            }
+            let number = text[start..end]
+                .parse()
+                .map_err(|_| invalid(format!("invalid number `{}`", &text[start..end])))?;
+            tokens.push(Token::Number(number));
+        } else if c.is_alphabetic() || c == '_' {
+            let mut end = start;
+            while let Some(&(index, c)) = chars.peek() {
+                if !(c.is_alphanumeric() || matches!(c, '_' | '.' | ':')) {
+                    break;
+                }
+                end = index + c.len_utf8();
+                chars.next();
+            }
+            tokens.push(Token::Identifier(text[start..end].to_owned()));
+        } else {
+            let punctuation = PUNCTUATION
+                .iter()
+                .find(|punctuation| text[start..].starts_with(*punctuation))
+                .ok_or_else(|| invalid(format!("unexpected `{}`", c)))?;
+            for _ in 0..punctuation.len() {
+                chars.next();
+            }
+            tokens.push(Token::Punctuation(punctuation));
+        }
+    }
+    Ok(tokens)
+}
+
+/// A recursive descent parser of the CQL2 text encoding
+struct Parser {
+    tokens: Vec<Token>,
+    index: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index)
+    }
+
+    fn next(&mut self) -> Result<Token, Error> {
+        let token = self
+            .tokens
+            .get(self.index)
+            .cloned()
+            .ok_or_else(|| invalid("unexpected end of filter"))?;
+        self.index += 1;
+        Ok(token)
+    }
+
+    fn is_keyword_at(&self, index: usize, keyword: &str) -> bool {
+        matches!(self.tokens.get(index), Some(Token::Identifier(name)) if name.eq_ignore_ascii_case(keyword))
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.is_keyword_at(self.index, keyword);
+        if found {
+            self.index += 1;
+        }
+        found
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), Error> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.unexpected(keyword))
+        }
+    }
+
+    fn is_punctuation(&self, punctuation: &str) -> bool {
+        matches!(self.peek(), Some(Token::Punctuation(p)) if *p == punctuation)
+    }
+
+    fn eat_punctuation(&mut self, punctuation: &str) -> bool {
+        let found = self.is_punctuation(punctuation);
+        if found {
+            self.index += 1;
+        }
+        found
+    }
+
+    fn expect_punctuation(&mut self, punctuation: &str) -> Result<(), Error> {
+        if self.eat_punctuation(punctuation) {
+            Ok(())
+        } else {
+            Err(self.unexpected(punctuation))
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> Error {
+        match self.peek() {
+            Some(token) => invalid(format!("expected `{}`, got {}", expected, token)),
+            None => invalid(format!(
+                "expected `{}`, got the end of the filter",
+                expected
+            )),
+        }
+    }
+
+    /// Parse `item`s separated by commas, up to a closing parenthesis
+    fn list<T>(
+        &mut self,
+        mut item: impl FnMut(&mut Self) -> Result<T, Error>,
+    ) -> Result<Vec<T>, Error> {
+        let mut items = vec![item(self)?];
+        while self.eat_punctuation(",") {
+            items.push(item(self)?);
+        }
+        self.expect_punctuation(")")?;
+        Ok(items)
+    }
+
+    /// Parse operands joined by `keyword` into one `op`
+    fn joined(
+        &mut self,
+        keyword: &str,
+        op: Op,
+        operand: impl Fn(&mut Self) -> Result<Expression, Error>,
+    ) -> Result<Expression, Error> {
+        let mut operands = vec![operand(self)?];
+        while self.eat_keyword(keyword) {
+            operands.push(operand(self)?);
+        }
+        Ok(if operands.len() == 1 {
+            operands.remove(0)
+        } else {
+            Expression::Op(op, operands)
+        })
+    }
+
+    fn or(&mut self) -> Result<Expression, Error> {
+        self.joined("OR", Op::Or, Self::and)
+    }
+
+    fn and(&mut self) -> Result<Expression, Error> {
+        self.joined("AND", Op::And, Self::not)
+    }
+
+    fn not(&mut self) -> Result<Expression, Error> {
+        if self.eat_keyword("NOT") {
+            return Ok(Expression::Op(Op::Not, vec![self.not()?]));
+        }
+        if self.eat_punctuation("(") {
+            let expression = self.or()?;
+            self.expect_punctuation(")")?;
+            return Ok(expression);
+        }
+        self.predicate()
+    }
+
+    fn predicate(&mut self) -> Result<Expression, Error> {
+        let scalar = self.scalar()?;
+        if let Some(Token::Punctuation(punctuation)) = self.peek() {
+            if let Some(op) = Op::from_name(punctuation) {
+                self.index += 1;
+                return Ok(Expression::Op(op, vec![scalar, self.scalar()?]));
+            }
+        }
+        if self.eat_keyword("IS") {
+            let negated = self.eat_keyword("NOT");
+            self.expect_keyword("NULL")?;
+            return Ok(negate(negated, Expression::Op(Op::IsNull, vec![scalar])));
+        }
+        let negated = ["LIKE", "BETWEEN", "IN"]
+            .iter()
+            .any(|keyword| self.is_keyword_at(self.index + 1, keyword))
+            && self.eat_keyword("NOT");
+        let predicate = if self.eat_keyword("LIKE") {
+            Expression::Op(Op::Like, vec![scalar, self.scalar()?])
+        } else if self.eat_keyword("BETWEEN") {
+            let low = self.scalar()?;
+            self.expect_keyword("AND")?;
+            Expression::Op(Op::Between, vec![scalar, low, self.scalar()?])
+        } else if self.eat_keyword("IN") {
+            self.expect_punctuation("(")?;
+            let list = self.list(Self::scalar)?;
+            Expression::Op(Op::In, vec![scalar, Expression::List(list)])
+        } else {
+            // A boolean on its own, such as `TRUE` or `S_INTERSECTS(...)`
+            return Ok(scalar);
+        };
+        Ok(negate(negated, predicate))
+    }
+
+    fn scalar(&mut self) -> Result<Expression, Error> {
+        let name = match self.next()? {
+            Token::Number(number) => return Ok(Expression::Literal(number.into())),
+            Token::String(string) => return Ok(Expression::Literal(string.into())),
+            Token::QuotedIdentifier(name) => return Ok(Expression::Property(name)),
+            Token::Identifier(name) => name,
+            token => return Err(invalid(format!("unexpected {}", token))),
+        };
+        let keyword = name.to_ascii_uppercase();
+        match keyword.as_str() {
+            "TRUE" => return Ok(Expression::Literal(true.into())),
+            "FALSE" => return Ok(Expression::Literal(false.into())),
+            "NULL" => return Ok(Expression::Literal(JsonValue::Null)),
+            _ => {}
+        }
+        let is_call = self.is_punctuation("(") || self.is_keyword_at(self.index, "Z");
+        if !is_call {
+            return Ok(Expression::Property(name));
+        }
+        match keyword.as_str() {
+            "BBOX" => {
+                self.expect_punctuation("(")?;
+                Ok(Expression::Bbox(self.list(Self::number)?))
+            }
+            "TIMESTAMP" | "DATE" => {
+                self.expect_punctuation("(")?;
+                match self.next()? {
+                    Token::String(instant) => {
+                        self.expect_punctuation(")")?;
+                        Ok(Expression::Literal(instant.into()))
+                    }
+                    token => Err(invalid(format!("expected an instant, got {}", token))),
+                }
+            }
+            _ if is_geometry_keyword(&keyword) => self.geometry(&keyword).map(Expression::Geometry),
+            _ => {
+                let op = Op::from_name(&name)
+                    .ok_or_else(|| invalid(format!("unsupported function `{}`", name)))?;
+                self.expect_punctuation("(")?;
+                let args = self.list(Self::scalar)?;
+                operation(op, args)
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<f64, Error> {
+        match self.next()? {
+            Token::Number(number) => Ok(number),
+            token => Err(invalid(format!("expected a number, got {}", token))),
+        }
+    }
+
+    /// A WKT position, of numbers separated by whitespace
+    fn position(&mut self) -> Result<Position, Error> {
+        let mut position = Position::new();
+        while let Some(Token::Number(number)) = self.peek() {
+            position.push(*number);
+            self.index += 1;
+        }
+        if position.len() < 2 {
+            return Err(self.unexpected("coordinate"));
+        }
+        Ok(position)
+    }
+
+    fn positions(&mut self) -> Result<Vec<Position>, Error> {
+        self.expect_punctuation("(")?;
+        self.list(Self::position)
+    }
+
+    fn rings(&mut self) -> Result<Vec<Vec<Position>>, Error> {
+        self.expect_punctuation("(")?;
+        self.list(Self::positions)
+    }
+
+    /// A WKT geometry, after its keyword
+    fn geometry(&mut self, keyword: &str) -> Result<Value, Error> {
+        self.eat_keyword("Z");
+        self.expect_punctuation("(")?;
+        Ok(match keyword {
+            "POINT" => {
+                let position = self.position()?;
+                self.expect_punctuation(")")?;
+                Value::Point(position)
+            }
+            "LINESTRING" => Value::LineString(self.list(Self::position)?),
+            "POLYGON" => Value::Polygon(self.list(Self::positions)?),
+            // Points may be written with or without parentheses of their own
+            "MULTIPOINT" => Value::MultiPoint(self.list(|parser| {
+                if parser.eat_punctuation("(") {
+                    let position = parser.position()?;
+                    parser.expect_punctuation(")")?;
+                    Ok(position)
+                } else {
+                    parser.position()
+                }
+            })?),
+            "MULTILINESTRING" => Value::MultiLineString(self.list(Self::positions)?),
+            "MULTIPOLYGON" => Value::MultiPolygon(self.list(Self::rings)?),
+            _ => Value::GeometryCollection(self.list(|parser| {
+                match parser.next()? {
+                    Token::Identifier(name) if is_geometry_keyword(&name.to_ascii_uppercase()) => {
+                        parser
+                            .geometry(&name.to_ascii_uppercase())
+                            .map(Geometry::new)
+                    }
+                    token => Err(invalid(format!("expected a geometry, got {}", token))),
+                }
+            })?),
+        })
+    }
+}
+
+fn is_geometry_keyword(keyword: &str) -> bool {
+    matches!(
+        keyword,
+        "POINT"
+            | "LINESTRING"
+            | "POLYGON"
+            | "MULTIPOINT"
+            | "MULTILINESTRING"
+            | "MULTIPOLYGON"
+            | "GEOMETRYCOLLECTION"
+    )
+}
+
+fn negate(negated: bool, expression: Expression) -> Expression {
+    if negated {
+        Expression::Op(Op::Not, vec![expression])
+    } else {
+        expression
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{like, Expression, Op};
+    use crate::{feature, Feature, Value};
+    use serde_json::json;
+
+    fn feature() -> Feature {
+        let mut feature = Feature::from(Value::LineString(vec![
+            position![0.0, 0.0],
+            position![10.0, 10.0],
+        ]));
+        feature.id = Some(feature::Id::Number(7.into()));
+        feature.set_property("name", "Main Street");
+        feature.set_property("lanes", 4);
+        feature.set_property("oneway", false);
+        feature.set_property("opened", "2001-05-01T00:00:00Z");
+        feature.set_property("surface", serde_json::Value::Null);
+        feature
+    }
+
+    fn matches(text: &str) -> bool {
+        Expression::from_text(text)
+            .unwrap_or_else(|e| panic!("{}: {}", text, e))
+            .matches(&feature())
+            .unwrap_or_else(|e| panic!("{}: {}", text, e))
+    }
+
+    #[test]
+    fn text_filters() {
+        for text in [
+            "lanes = 4",
+            "lanes <> 3 AND lanes > 3.5 AND lanes <= 4",
+            "\"name\" = 'Main Street'",
+            "oneway = FALSE OR lanes < 0",
+            "NOT (lanes < 2 OR name IS NULL)",
+            "surface IS NULL AND missing IS NULL AND name IS NOT NULL",
+            "name LIKE 'Main%' AND name LIKE '_ain Str__t' AND name NOT LIKE '%Road'",
+            "CASEI(name) = casei('MAIN STREET')",
+            "lanes BETWEEN 2 AND 4 AND lanes NOT BETWEEN 5 AND 6",
+            "lanes IN (1, 2, 4) AND name NOT IN ('a', 'b')",
+            "id = 7",
+            "opened > TIMESTAMP('2000-01-01T00:00:00Z')",
+            "S_INTERSECTS(geometry, POINT(5 5))",
+            "s_intersects(geometry, BBOX(9, 9, 20, 20))",
+            "S_DISJOINT(geometry, POLYGON((20 20, 30 20, 30 30, 20 20)))",
+            "S_INTERSECTS(geometry, MULTIPOINT((1 2), 5 5))",
+            "S_INTERSECTS(geometry, GEOMETRYCOLLECTION(POINT Z(-1 -1 0), LINESTRING(0 10, 10 0)))",
+            "S_EQUALS(geometry, LINESTRING(0 0, 10 10))",
+        ] {
+            assert!(matches(text), "{}", text);
+        }
+        for text in [
+            "lanes = 3",
+            "missing = 1",
+            "NOT missing = 1",
+            "name = 4",
+            "surface > 1 OR missing < 1",
+            "name LIKE 'main%'",
+            "S_INTERSECTS(geometry, POINT(5 6))",
+            "S_INTERSECTS(missing, POINT(5 5))",
+        ] {
+            assert!(!matches(text), "{}", text);
+        }
+    }
+
+    #[test]
+    fn json_filters() {
+        let filter = json!({
+            "op": "and",
+            "args": [
+                { "op": ">=", "args": [{ "property": "lanes" }, 2] },
+                { "op": "in", "args": [{ "property": "name" }, ["Main Street", "High Street"]] },
+                {
+                    "op": "s_intersects",
+                    "args": [
+                        { "property": "geometry" },
+                        { "type": "Point", "coordinates": [5, 5] }
+                    ]
+                },
+                { "op": "not", "args": [{ "op": "s_intersects", "args": [{ "property": "geometry" }, { "bbox": [20, 20, 30, 30] }] }] },
+                { "op": "<", "args": [{ "property": "opened" }, { "timestamp": "2010-01-01T00:00:00Z" }] }
+            ]
+        });
+        let expression = Expression::from_json(&filter).unwrap();
+        assert!(expression.matches(&feature()).unwrap());
+        assert_eq!(
+            Expression::from_json(&expression.to_json()).unwrap(),
+            expression
+        );
+
+        let text = Expression::from_text(
+            "lanes >= 2 AND NOT S_INTERSECTS(geometry, BBOX(20, 20, 30, 30))",
+        )
+        .unwrap();
+        assert_eq!(
+            text.to_json(),
+            json!({
+                "op": "and",
+                "args": [
+                    { "op": ">=", "args": [{ "property": "lanes" }, 2.0] },
+                    { "op": "not", "args": [{ "op": "s_intersects", "args": [{ "property": "geometry" }, { "bbox": [20.0, 20.0, 30.0, 30.0] }] }] }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_filters() {
+        for text in [
+            "",
+            "lanes =",
+            "lanes = 4 4",
+            "(lanes = 4",
+            "name = 'unterminated",
+            "FOO(lanes)",
+            "lanes BETWEEN 1 OR 2",
+            "S_INTERSECTS(geometry, POINT(1))",
+            "lanes IN 1",
+        ] {
+            assert!(Expression::from_text(text).is_err(), "{}", text);
+        }
+        for json in [
+            json!({ "op": "unknown", "args": [] }),
+            json!({ "op": "not", "args": [true, false] }),
+            json!({ "op": "in", "args": [1, 2] }),
+            json!({ "property": 1 }),
+            json!({ "bbox": ["a"] }),
+            json!({}),
+        ] {
+            assert!(Expression::from_json(&json).is_err(), "{}", json);
+        }
+        for text in [
+            "lanes",
+            "name LIKE POINT(1 1) OR S_INTERSECTS(name, POINT(1 1))",
+            "NOT name",
+        ] {
+            let expression = Expression::from_text(text).unwrap();
+            assert!(expression.matches(&feature()).is_err(), "{}", text);
+        }
+        assert_eq!(Op::from_name("ISNULL"), Some(Op::IsNull));
+    }
+
+    #[test]
+    fn like_patterns() {
+        assert!(like("abc", "abc"));
+        assert!(like("abc", "%"));
+        assert!(like("", "%%"));
+        assert!(like("abcabd", "%ab_"));
+        assert!(like("a%c", "a\\%c"));
+        assert!(!like("abc", "a\\%c"));
+        assert!(!like("abc", "ab"));
+        assert!(!like("ab", "abc"));
+        assert!(like("aaa", "%a%a%a%"));
+        assert!(!like("aa", "%a%a%a%"));
+    }
+}
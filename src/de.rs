@@ -0,0 +1,609 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Deserialization of GeoJSON objects straight from the serde stream.
+//!
+//! The members which can be large, `coordinates`, `geometries`, `geometry` and `features`, are
+//! built directly, without first building a `JsonValue` of them, unless they come before the
+//! `type` of a `GeoJson`, when they might be foreign members. Every other member is small,
+//! and is collected into a `JsonObject` which is converted just as `from_json_object` does, so
+//! that both accept the same objects.
+
+use crate::errors::Error;
+use crate::json::{Deserialize, Deserializer, JsonObject, JsonValue};
+use crate::{util, Feature, FeatureCollection, GeoJson, Geometry, Position, Value};
+use alloc::borrow::ToOwned;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use serde::de::DeserializeOwned;
+use serde::de::{self, MapAccess, SeqAccess, Visitor};
+
+/// The kind of GeoJSON object being deserialized
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Geometry,
+    Feature,
+    FeatureCollection,
+}
+
+impl Kind {
+    fn from_type(type_: &str) -> Option<Kind> {
+        match type_ {
+            "Feature" => Some(Kind::Feature),
+            "FeatureCollection" => Some(Kind::FeatureCollection),
+            "Point" | "MultiPoint" | "LineString" | "MultiLineString" | "Polygon"
+            | "MultiPolygon" | "GeometryCollection" => Some(Kind::Geometry),
+            _ => None,
+        }
+    }
+}
+
+/// The value of a `coordinates` member, as deeply nested as it was written
+///
+/// Arrays whose depth can't be told from their first element, such as `[[], [[1, 2]]]`, and
+/// values which aren't arrays of numbers at all, are kept as `Other`.
+enum Coordinates {
+    Number(f64),
+    Position(Position),
+    Positions(Vec<Position>),
+    Rings(Vec<Vec<Position>>),
+    Polygons(Vec<Vec<Vec<Position>>>),
+    Other(JsonValue),
+}
+
+impl From<Coordinates> for JsonValue {
+    fn from(coordinates: Coordinates) -> Self {
+        match coordinates {
+            Coordinates::Number(number) => JsonValue::from(number),
+            Coordinates::Position(position) => position_to_json(&position),
+            Coordinates::Positions(positions) => positions_to_json(&positions),
+            Coordinates::Rings(rings) => rings_to_json(&rings),
+            Coordinates::Polygons(polygons) => {
+                polygons.iter().map(|rings| rings_to_json(rings)).collect()
+            }
+            Coordinates::Other(value) => value,
+        }
+    }
+}
+
+fn position_to_json(position: &Position) -> JsonValue {
+    position.iter().copied().collect()
+}
+
+fn positions_to_json(positions: &[Position]) -> JsonValue {
+    positions.iter().map(position_to_json).collect()
+}
+
+fn rings_to_json(rings: &[Vec<Position>]) -> JsonValue {
+    rings.iter().map(|ring| positions_to_json(ring)).collect()
+}
+
+impl Coordinates {
+    fn into_position(self) -> Result<Position, Error> {
+        match self {
+            Coordinates::Position(position) => Ok(position),
+            other => util::json_to_position(&other.into()),
+        }
+    }
+
+    fn into_positions(self) -> Result<Vec<Position>, Error> {
+        match self {
+            Coordinates::Positions(positions) => Ok(positions),
+            other => util::json_to_1d_positions(&other.into()),
+        }
+    }
+
+    fn into_rings(self) -> Result<Vec<Vec<Position>>, Error> {
+        match self {
+            Coordinates::Rings(rings) => Ok(rings),
+            other => util::json_to_2d_positions(&other.into()),
+        }
+    }
+
+    fn into_polygons(self) -> Result<Vec<Vec<Vec<Position>>>, Error> {
+        match self {
+            Coordinates::Polygons(polygons) => Ok(polygons),
+            other => util::json_to_3d_positions(&other.into()),
+        }
+    }
+}
+
+/// Read the rest of a sequence whose first element was `first`, as elements of the same type
+fn rest<'de, A: SeqAccess<'de>, T: Deserialize<'de>, C: Default + Extend<T>>(
+    mut seq: A,
+    first: T,
+) -> Result<C, A::Error> {
+    let mut elements = C::default();
+    elements.extend(Some(first));
+    while let Some(element) = seq.next_element()? {
+        elements.extend(Some(element));
+    }
+    Ok(elements)
+}
+
+impl<'de> Deserialize<'de> for Coordinates {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct CoordinatesVisitor;
+
+        impl<'de> Visitor<'de> for CoordinatesVisitor {
+            type Value = Coordinates;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("GeoJSON coordinates")
+            }
+
+            fn visit_f64<E: de::Error>(self, value: f64) -> Result<Coordinates, E> {
+                Ok(Coordinates::Number(value))
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<Coordinates, E> {
+                Ok(Coordinates::Number(value as f64))
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<Coordinates, E> {
+                Ok(Coordinates::Number(value as f64))
+            }
+
+            fn visit_bool<E: de::Error>(self, value: bool) -> Result<Coordinates, E> {
+                Ok(Coordinates::Other(JsonValue::Bool(value)))
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Coordinates, E> {
+                Ok(Coordinates::Other(JsonValue::from(value)))
+            }
+
+            fn visit_unit<E: de::Error>(self) -> Result<Coordinates, E> {
+                Ok(Coordinates::Other(JsonValue::Null))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Coordinates, A::Error> {
+                let object = JsonObject::deserialize(de::value::MapAccessDeserializer::new(map))?;
+                Ok(Coordinates::Other(JsonValue::Object(object)))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Coordinates, A::Error> {
+                let first = match seq.next_element()? {
+                    Some(first) => first,
+                    None => return Ok(Coordinates::Other(JsonValue::Array(vec![]))),
+                };
+                Ok(match first {
+                    Coordinates::Number(number) => Coordinates::Position(rest(seq, number)?),
+                    Coordinates::Position(position) => Coordinates::Positions(rest(seq, position)?),
+                    Coordinates::Positions(positions) => Coordinates::Rings(rest(seq, positions)?),
+                    Coordinates::Rings(rings) => Coordinates::Polygons(rest(seq, rings)?),
+                    first => {
+                        let mut elements = vec![JsonValue::from(first)];
+                        while let Some(element) = seq.next_element::<Coordinates>()? {
+                            elements.push(element.into());
+                        }
+                        Coordinates::Other(JsonValue::Array(elements))
+                    }
+                })
+            }
+        }
+
+        deserializer.deserialize_any(CoordinatesVisitor)
+    }
+}
+
+fn required<T>(member: Option<T>, name: &str) -> Result<T, Error> {
+    member.ok_or_else(|| Error::ExpectedProperty(name.to_string()))
+}
+
+fn geometries_to_json(geometries: &[Geometry]) -> JsonValue {
+    geometries
+        .iter()
+        .map(|geometry| JsonValue::Object(geometry.into()))
+        .collect()
+}
+
+/// The members of a GeoJSON object
+#[derive(Default)]
+struct Members {
+    coordinates: Option<Coordinates>,
+    geometries: Option<Vec<Geometry>>,
+    geometry: Option<Option<Geometry>>,
+    features: Option<Vec<Feature>>,
+    /// Every other member, including `type`
+    object: JsonObject,
+}
+
+impl Members {
+    /// Move the large members which aren't members of an object of `kind` to its foreign
+    /// members.
+    fn into_object(mut self, kind: Kind) -> (Self, JsonObject) {
+        let mut object = core::mem::take(&mut self.object);
+        if kind != Kind::Geometry {
+            if let Some(coordinates) = self.coordinates.take() {
+                object.insert("coordinates".to_string(), coordinates.into());
+            }
+            if let Some(geometries) = self.geometries.take() {
+                object.insert("geometries".to_string(), geometries_to_json(&geometries));
+            }
+        }
+        if kind != Kind::Feature {
+            if let Some(geometry) = self.geometry.take() {
+                let geometry = match geometry {
+                    Some(geometry) => JsonValue::Object((&geometry).into()),
+                    None => JsonValue::Null,
+                };
+                object.insert("geometry".to_string(), geometry);
+            }
+        }
+        if kind != Kind::FeatureCollection {
+            if let Some(features) = self.features.take() {
+                let features = features
+                    .iter()
+                    .map(|feature| JsonValue::Object(feature.into()))
+                    .collect();
+                object.insert("features".to_string(), JsonValue::Array(features));
+            }
+        }
+        (self, object)
+    }
+
+    /// Parse the large members of an object of `kind` which were kept in `object`, as they came
+    /// before its `type`
+    fn parse_kept(&mut self, kind: Option<Kind>) -> Result<(), serde_json::Error> {
+        match kind {
+            Some(Kind::Geometry) => {
+                if let Some(coordinates) = self.object.remove("coordinates") {
+                    self.coordinates = Some(Coordinates::deserialize(coordinates)?);
+                }
+                if let Some(geometries) = self.object.remove("geometries") {
+                    self.geometries = Some(Vec::deserialize(geometries)?);
+                }
+            }
+            Some(Kind::Feature) => {
+                if let Some(geometry) = self.object.remove("geometry") {
+                    self.geometry = Some(Option::deserialize(geometry)?);
+                }
+            }
+            Some(Kind::FeatureCollection) => {
+                if let Some(features) = self.object.remove("features") {
+                    self.features = Some(Vec::deserialize(features)?);
+                }
+            }
+            None => {}
+        }
+        Ok(())
+    }
+
+    fn into_geometry(self) -> Result<Geometry, Error> {
+        let (members, mut object) = self.into_object(Kind::Geometry);
+        let Members {
+            mut coordinates,
+            mut geometries,
+            ..
+        } = members;
+        let bbox = util::get_bbox(&mut object)?;
+        let type_ = util::expect_type(&mut object)?;
+        let value = match type_.as_str() {
+            "Point" => Value::Point(required(coordinates.take(), "coordinates")?.into_position()?),
+            "MultiPoint" => {
+                Value::MultiPoint(required(coordinates.take(), "coordinates")?.into_positions()?)
+            }
+            "LineString" => {
+                Value::LineString(required(coordinates.take(), "coordinates")?.into_positions()?)
+            }
+            "MultiLineString" => {
+                Value::MultiLineString(required(coordinates.take(), "coordinates")?.into_rings()?)
+            }
+            "Polygon" => Value::Polygon(required(coordinates.take(), "coordinates")?.into_rings()?),
+            "MultiPolygon" => {
+                Value::MultiPolygon(required(coordinates.take(), "coordinates")?.into_polygons()?)
+            }
+            "GeometryCollection" => {
+                Value::GeometryCollection(required(geometries.take(), "geometries")?)
+            }
+            _ => return Err(Error::GeometryUnknownType(type_)),
+        };
+        // A `coordinates` member of a GeometryCollection, or `geometries` of any other geometry
+        if let Some(coordinates) = coordinates {
+            object.insert("coordinates".to_string(), coordinates.into());
+        }
+        if let Some(geometries) = geometries {
+            object.insert("geometries".to_string(), geometries_to_json(&geometries));
+        }
+        Ok(Geometry {
+            bbox,
+            value,
+            foreign_members: util::get_foreign_members(object)?,
+        })
+    }
+
+    fn into_feature(self) -> Result<Feature, Error> {
+        let (members, mut object) = self.into_object(Kind::Feature);
+        let type_ = util::expect_type(&mut object)?;
+        if type_ != "Feature" {
+            return Err(Error::NotAFeature(type_));
+        }
+        Ok(Feature {
+            geometry: required(members.geometry, "geometry")?,
+            properties: util::get_properties(&mut object)?,
+            id: util::get_id(&mut object)?,
+            bbox: util::get_bbox(&mut object)?,
+            foreign_members: util::get_foreign_members(object)?,
+        })
+    }
+
+    fn into_feature_collection(self) -> Result<FeatureCollection, Error> {
+        let (members, mut object) = self.into_object(Kind::FeatureCollection);
+        let type_ = util::expect_type(&mut object)?;
+        if type_ != "FeatureCollection" {
+            return Err(Error::ExpectedType {
+                expected: "FeatureCollection".to_owned(),
+                actual: type_,
+            });
+        }
+        Ok(FeatureCollection {
+            bbox: util::get_bbox(&mut object)?,
+            features: required(members.features, "features")?,
+            foreign_members: util::get_foreign_members(object)?,
+        })
+    }
+
+    fn into_geojson(self) -> Result<GeoJson, Error> {
+        let kind = match self.object.get("type") {
+            Some(JsonValue::String(type_)) => Kind::from_type(type_),
+            _ => return Err(Error::GeometryUnknownType("type".to_owned())),
+        };
+        match kind.ok_or(Error::EmptyType)? {
+            Kind::Geometry => self.into_geometry().map(GeoJson::Geometry),
+            Kind::Feature => self.into_feature().map(GeoJson::Feature),
+            Kind::FeatureCollection => self
+                .into_feature_collection()
+                .map(GeoJson::FeatureCollection),
+        }
+    }
+}
+
+/// Collects the members of an object of `kind`, or of any kind if it is `None`
+struct MembersVisitor {
+    kind: Option<Kind>,
+}
+
+impl<'de> Visitor<'de> for MembersVisitor {
+    type Value = Members;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a GeoJSON object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Members, A::Error> {
+        let mut members = Members::default();
+        let mut kind = self.kind;
+        while let Some(key) = map.next_key::<String>()? {
+            // Until the `type` is known, a large member may be a foreign member, so it's kept as
+            // JSON with the small members, and only parsed once the kind of object is known.
+            let is = |member_kind| kind == Some(member_kind);
+            match key.as_str() {
+                "coordinates" if is(Kind::Geometry) => {
+                    members.coordinates = Some(map.next_value()?);
+                }
+                "geometries" if is(Kind::Geometry) => {
+                    members.geometries = Some(map.next_value()?);
+                }
+                "geometry" if is(Kind::Feature) => {
+                    members.geometry = Some(map.next_value()?);
+                }
+                "features" if is(Kind::FeatureCollection) => {
+                    members.features = Some(map.next_value()?);
+                }
+                _ => {
+                    let value: JsonValue = map.next_value()?;
+                    if key == "type" && kind.is_none() {
+                        kind = value.as_str().and_then(Kind::from_type);
+                    }
+                    members.object.insert(key, value);
+                }
+            }
+        }
+        if self.kind.is_none() {
+            members.parse_kept(kind).map_err(de::Error::custom)?;
+        }
+        Ok(members)
+    }
+}
+
+fn deserialize_members<'de, D: Deserializer<'de>, T>(
+    deserializer: D,
+    kind: Option<Kind>,
+    convert: impl FnOnce(Members) -> Result<T, Error>,
+) -> Result<T, D::Error> {
+    let members = deserializer.deserialize_map(MembersVisitor { kind })?;
+    convert(members).map_err(|e| de::Error::custom(e.to_string()))
+}
+
+pub(crate) fn deserialize_geometry<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Geometry, D::Error> {
+    deserialize_members(deserializer, Some(Kind::Geometry), Members::into_geometry)
+}
+
+pub(crate) fn deserialize_feature<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Feature, D::Error> {
+    deserialize_members(deserializer, Some(Kind::Feature), Members::into_feature)
+}
+
+pub(crate) fn deserialize_feature_collection<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<FeatureCollection, D::Error> {
+    deserialize_members(
+        deserializer,
+        Some(Kind::FeatureCollection),
+        Members::into_feature_collection,
+    )
+}
+
+pub(crate) fn deserialize_geojson<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<GeoJson, D::Error> {
+    deserialize_members(deserializer, None, Members::into_geojson)
+}
+
+/// Parse a [`GeoJson`](enum.GeoJson.html), [`Geometry`](struct.Geometry.html),
+/// [`Feature`](struct.Feature.html) or [`FeatureCollection`](struct.FeatureCollection.html)
+/// from a buffer of JSON.
+///
+/// With the `simd-json` feature, this parses with [simd-json](https://docs.rs/simd-json), which
+/// can be faster on CPUs with SIMD instructions, and uses `bytes` as scratch space, leaving it
+/// in an unspecified state. Otherwise, it parses with serde_json and leaves
+/// `bytes` unchanged.
+///
+/// # Example
+///
+/// ```
+/// use geojson::{position, FeatureCollection, Value};
+///
+/// let mut bytes = br#"{
+///     "type": "FeatureCollection",
+///     "features": [{
+///         "type": "Feature",
+///         "geometry": { "type": "Point", "coordinates": [1.0, 2.5] },
+///         "properties": { "name": "a" }
+///     }]
+/// }"#
+/// .to_vec();
+/// let collection: FeatureCollection = geojson::from_slice(&mut bytes).unwrap();
+/// assert_eq!(
+///     collection.features[0].geometry.as_ref().unwrap().value,
+///     Value::Point(position![1.0, 2.5])
+/// );
+/// ```
+pub fn from_slice<T: DeserializeOwned>(bytes: &mut [u8]) -> Result<T, Error> {
+    #[cfg(feature = "simd-json")]
+    {
+        simd_json::serde::from_slice(bytes)
+            .map_err(|e| Error::MalformedJson(de::Error::custom(e.to_string())))
+    }
+    #[cfg(not(feature = "simd-json"))]
+    {
+        serde_json::from_slice(bytes).map_err(Error::MalformedJson)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Feature, FeatureCollection, GeoJson, Geometry, Value};
+    use serde_json::json;
+
+    /// Deserializing must accept the same objects, with the same results, as `from_json_value`
+    #[test]
+    fn same_as_from_json_value() {
+        let values = vec![
+            json!({ "coordinates": [1, 2.5], "type": "Point" }),
+            json!({ "type": "Point", "coordinates": [] }),
+            json!({ "type": "MultiPoint", "coordinates": [[1, 2], [3, 4, 5]] }),
+            json!({ "type": "LineString", "coordinates": [], "bbox": [0, 0, 1, 1] }),
+            json!({ "type": "Polygon", "coordinates": [[], [[1, 2]]] }),
+            json!({ "type": "Polygon", "coordinates": [[[0, 0], [1, 0], [0, 1], [0, 0]]] }),
+            json!({ "type": "MultiPolygon", "coordinates": [[[[0, 0], [1, 1]]]], "title": 1 }),
+            json!({
+                "type": "GeometryCollection",
+                "coordinates": "foreign",
+                "geometries": [{ "type": "Point", "coordinates": [1, 2] }],
+            }),
+            json!({
+                "properties": { "coordinates": [1] },
+                "geometry": { "type": "MultiLineString", "coordinates": [[[1, 2]]] },
+                "coordinates": [[1.5, 2.5]],
+                "features": [],
+                "id": 3,
+                "type": "Feature",
+            }),
+            json!({ "type": "Feature", "geometry": null, "properties": null, "id": "a" }),
+            json!({
+                "features": [{ "type": "Feature", "geometry": null, "properties": {} }],
+                "geometry": null,
+                "type": "FeatureCollection",
+            }),
+            // Members named like those of another type of object, before the `type`
+            json!({ "geometry": "foo", "type": "FeatureCollection", "features": [] }),
+            json!({
+                "coordinates": [[1, 2]],
+                "geometries": {},
+                "type": "Feature",
+                "geometry": null,
+                "properties": null,
+            }),
+        ];
+        for value in values {
+            let expected = GeoJson::from_json_value(value.clone()).unwrap();
+            let parsed: GeoJson = serde_json::from_value(value.clone()).unwrap();
+            assert_eq!(parsed, expected);
+            let parsed: GeoJson = serde_json::from_str(&value.to_string()).unwrap();
+            assert_eq!(parsed, expected);
+            match expected {
+                GeoJson::Geometry(expected) => {
+                    assert_eq!(serde_json::from_value::<Geometry>(value).unwrap(), expected)
+                }
+                GeoJson::Feature(expected) => {
+                    assert_eq!(serde_json::from_value::<Feature>(value).unwrap(), expected)
+                }
+                GeoJson::FeatureCollection(expected) => assert_eq!(
+                    serde_json::from_value::<FeatureCollection>(value).unwrap(),
+                    expected
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn positions_are_inline() {
+        let geometry: Geometry =
+            serde_json::from_str(r#"{"type":"LineString","coordinates":[[1,2],[3,4,5]]}"#).unwrap();
+        match geometry.value {
+            Value::LineString(positions) => {
+                assert!(positions.iter().all(|position| !position.spilled()))
+            }
+            value => panic!("unexpected {:?}", value),
+        }
+    }
+
+    #[test]
+    fn from_slice_same_as_from_str() {
+        let json = include_str!("../tests/fixtures/countries.geojson");
+        let parsed: GeoJson = crate::from_slice(&mut json.as_bytes().to_vec()).unwrap();
+        assert_eq!(parsed, json.parse::<GeoJson>().unwrap());
+        assert!(matches!(
+            crate::from_slice::<GeoJson>(&mut b"{\"type\":".to_vec()),
+            Err(crate::Error::MalformedJson(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_objects() {
+        let values = vec![
+            json!({ "type": "Point", "coordinates": [[1, 2]] }),
+            json!({ "type": "LineString", "coordinates": [1, 2] }),
+            json!({ "type": "Point", "coordinates": [1, "2"] }),
+            json!({ "type": "Point" }),
+            json!({ "type": "Circle", "coordinates": [1, 2] }),
+            json!({ "coordinates": [1, 2] }),
+            json!({ "type": "Feature", "geometry": null }),
+            json!({ "type": "Feature", "geometry": 3, "properties": null }),
+            json!({ "type": "Feature", "geometry": null, "properties": null, "id": null }),
+            json!({ "type": "FeatureCollection", "features": [1] }),
+        ];
+        for value in values {
+            assert!(GeoJson::from_json_value(value.clone()).is_err());
+            assert!(serde_json::from_value::<GeoJson>(value).is_err());
+        }
+        assert!(serde_json::from_value::<Feature>(json!({ "type": "Point" })).is_err());
+    }
+}
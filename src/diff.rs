@@ -0,0 +1,327 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The differences between two versions of a feature collection.
+//!
+//! Features are matched by their `id`, and [`FeatureCollection::diff`] reports which were
+//! added, removed or modified, and for each modified feature, whether its geometry changed and
+//! which of its properties changed. Its `Display` implementation writes a changelog with a
+//! line per change.
+//!
+//! # Example
+//!
+//! ```
+//! use geojson::feature::Id;
+//! use geojson::{position, Feature, FeatureCollection, Value};
+//!
+//! let road = |id: u64, name: &str| {
+//!     let mut feature = Feature::from(Value::Point(position![0.0, 0.0]));
+//!     feature.id = Some(Id::Number(id.into()));
+//!     feature.set_property("name", name);
+//!     feature
+//! };
+//! let old = FeatureCollection {
+//!     bbox: None,
+//!     features: vec![road(1, "Old Road"), road(2, "High Street")],
+//!     foreign_members: None,
+//! };
+//! let new = FeatureCollection {
+//!     bbox: None,
+//!     features: vec![road(1, "New Road"), road(3, "Low Street")],
+//!     foreign_members: None,
+//! };
+//!
+//! let diff = old.diff(&new);
+//! assert_eq!(
+//!     diff.to_string(),
+//!     "added feature 3\nremoved feature 2\nmodified feature 1: name: \"Old Road\" -> \"New Road\"\n"
+//! );
+//! ```
+//!
+//! [`FeatureCollection::diff`]: ../struct.FeatureCollection.html#method.diff
+
+use crate::feature::Id;
+use crate::feature_collection::IdKey;
+use crate::json::JsonValue;
+use crate::{Feature, FeatureCollection};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+/// A property of a feature which was added, removed or changed
+#[derive(Clone, Debug, PartialEq)]
+pub struct PropertyChange<'a> {
+    pub key: &'a str,
+    /// The value before, or `None` if the property was added
+    pub old: Option<&'a JsonValue>,
+    /// The value after, or `None` if the property was removed
+    pub new: Option<&'a JsonValue>,
+}
+
+/// A feature with the same `id` in both collections, which differs between them
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeatureChange<'a> {
+    pub id: &'a Id,
+    pub old: &'a Feature,
+    pub new: &'a Feature,
+    /// Whether the geometry differs
+    pub geometry_changed: bool,
+    /// The properties which differ, by key
+    pub properties: Vec<PropertyChange<'a>>,
+    /// Whether the `bbox` or the foreign members of the feature differ
+    pub members_changed: bool,
+}
+
+/// The differences between two versions of a feature collection, in the order of their
+/// features
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FeatureCollectionDiff<'a> {
+    /// The features of the new collection which aren't in the old one
+    pub added: Vec<&'a Feature>,
+    /// The features of the old collection which aren't in the new one
+    pub removed: Vec<&'a Feature>,
+    /// The features which are in both, but differ
+    pub modified: Vec<FeatureChange<'a>>,
+}
+
+impl FeatureCollectionDiff<'_> {
+    /// Whether the collections have the same features
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+fn property_changes<'a>(old: &'a Feature, new: &'a Feature) -> Vec<PropertyChange<'a>> {
+    let keys: BTreeSet<&str> = old
+        .properties_iter()
+        .chain(new.properties_iter())
+        .map(|(key, _)| key.as_str())
+        .collect();
+    keys.into_iter()
+        .map(|key| PropertyChange {
+            key,
+            old: old.property(key),
+            new: new.property(key),
+        })
+        .filter(|change| change.old != change.new)
+        .collect()
+}
+
+/// The features of `collection` by id, and those without an id, or with the id of an earlier
+/// feature, in order
+fn by_id(collection: &FeatureCollection) -> (HashMap<IdKey, &Feature>, Vec<&Feature>) {
+    let mut features = HashMap::new();
+    let mut anonymous = vec![];
+    for feature in &collection.features {
+        match &feature.id {
+            Some(id) if !features.contains_key(&IdKey::from(id)) => {
+                features.insert(IdKey::from(id), feature);
+            }
+            _ => anonymous.push(feature),
+        }
+    }
+    (features, anonymous)
+}
+
+/// The features of `a` which aren't in `b`, counting duplicates
+fn missing<'a>(a: &[&'a Feature], b: &[&Feature]) -> Vec<&'a Feature> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for feature in b {
+        *counts.entry(feature.to_string()).or_insert(0) += 1;
+    }
+    a.iter()
+        .copied()
+        .filter(|feature| match counts.get_mut(&feature.to_string()) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                false
+            }
+            _ => true,
+        })
+        .collect()
+}
+
+impl FeatureCollection {
+    /// The differences from this collection to `new`, matching features by `id`.
+    ///
+    /// Features without an `id`, and features with the same `id` as an earlier feature of
+    /// their collection, can't be matched, and are reported as added or removed unless an
+    /// equal feature is in the other collection. The members of the collections themselves
+    /// aren't compared. See the [`diff`](diff/index.html) module.
+    pub fn diff<'a>(&'a self, new: &'a FeatureCollection) -> FeatureCollectionDiff<'a> {
+        let (old_features, old_anonymous) = by_id(self);
+        let (new_features, new_anonymous) = by_id(new);
+        let mut diff = FeatureCollectionDiff::default();
+        for feature in &new.features {
+            let id = match &feature.id {
+                Some(id) => id,
+                None => continue,
+            };
+            let key = IdKey::from(id);
+            if !std::ptr::eq(new_features[&key], feature) {
+                continue;
+            }
+            let old = match old_features.get(&key) {
+                Some(old) => old,
+                None => {
+                    diff.added.push(feature);
+                    continue;
+                }
+            };
+            let change = FeatureChange {
+                id,
+                old,
+                new: feature,
+                geometry_changed: old.geometry != feature.geometry,
+                properties: property_changes(old, feature),
+                members_changed: old.bbox != feature.bbox
+                    || old.foreign_members != feature.foreign_members,
+            };
+            if change.geometry_changed || !change.properties.is_empty() || change.members_changed {
+                diff.modified.push(change);
+            }
+        }
+        diff.removed = self
+            .features
+            .iter()
+            .filter(|feature| match &feature.id {
+                Some(id) => {
+                    let key = IdKey::from(id);
+                    std::ptr::eq(old_features[&key], *feature) && !new_features.contains_key(&key)
+                }
+                None => false,
+            })
+            .collect();
+        diff.added.extend(missing(&new_anonymous, &old_anonymous));
+        diff.removed.extend(missing(&old_anonymous, &new_anonymous));
+        diff
+    }
+}
+
+/// The id as JSON, e.g. `7` or `"a"`
+fn id_to_string(id: &Id) -> String {
+    match id {
+        Id::String(id) => JsonValue::from(id.as_str()).to_string(),
+        Id::Number(id) => id.to_string(),
+    }
+}
+
+fn write_feature(f: &mut fmt::Formatter, verb: &str, feature: &Feature) -> fmt::Result {
+    match &feature.id {
+        Some(id) => writeln!(f, "{} feature {}", verb, id_to_string(id)),
+        None => writeln!(f, "{} a feature without an id", verb),
+    }
+}
+
+impl fmt::Display for FeatureCollectionDiff<'_> {
+    /// Write a line per added, removed and modified feature, e.g.
+    /// `modified feature 5: geometry; name: "a" -> "b"; lanes: added 2`
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for feature in &self.added {
+            write_feature(f, "added", feature)?;
+        }
+        for feature in &self.removed {
+            write_feature(f, "removed", feature)?;
+        }
+        for change in &self.modified {
+            let mut changes = vec![];
+            if change.geometry_changed {
+                changes.push("geometry".to_string());
+            }
+            for property in &change.properties {
+                changes.push(match (property.old, property.new) {
+                    (Some(old), Some(new)) => format!("{}: {} -> {}", property.key, old, new),
+                    (None, Some(new)) => format!("{}: added {}", property.key, new),
+                    (Some(old), None) => format!("{}: removed {}", property.key, old),
+                    (None, None) => unreachable!("unchanged properties aren't reported"),
+                });
+            }
+            if change.members_changed {
+                changes.push("members".to_string());
+            }
+            writeln!(
+                f,
+                "modified feature {}: {}",
+                id_to_string(change.id),
+                changes.join("; ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::feature::Id;
+    use crate::{Feature, FeatureCollection, Value};
+    use serde_json::json;
+
+    fn collection(features: Vec<serde_json::Value>) -> FeatureCollection {
+        FeatureCollection {
+            bbox: None,
+            features: features
+                .into_iter()
+                .map(|feature| serde_json::from_value(feature).unwrap())
+                .collect(),
+            foreign_members: None,
+        }
+    }
+
+    #[test]
+    fn diff_collections() {
+        let point = |x: f64| json!({ "type": "Point", "coordinates": [x, 0.0] });
+        let old = collection(vec![
+            json!({ "type": "Feature", "id": 1, "geometry": point(0.0), "properties": { "name": "a", "lanes": 2 } }),
+            json!({ "type": "Feature", "id": "2", "geometry": point(0.0), "properties": null }),
+            json!({ "type": "Feature", "id": 3, "geometry": point(0.0), "properties": { "name": "c" } }),
+            json!({ "type": "Feature", "geometry": point(5.0), "properties": null }),
+            json!({ "type": "Feature", "geometry": point(6.0), "properties": null }),
+        ]);
+        let new = collection(vec![
+            json!({ "type": "Feature", "id": 4, "geometry": null, "properties": null }),
+            json!({ "type": "Feature", "geometry": point(6.0), "properties": null }),
+            json!({ "type": "Feature", "id": 3, "geometry": point(1.0), "properties": { "name": "c" }, "bbox": [1, 0, 1, 0] }),
+            json!({ "type": "Feature", "id": 1, "geometry": point(0.0), "properties": { "name": "b", "width": 7 } }),
+            json!({ "type": "Feature", "geometry": point(7.0), "properties": null }),
+        ]);
+
+        let diff = old.diff(&new);
+        let ids = |features: &[&Feature]| -> Vec<_> {
+            features.iter().map(|feature| feature.id.clone()).collect()
+        };
+        assert_eq!(ids(&diff.added), vec![Some(Id::Number(4.into())), None]);
+        assert_eq!(
+            diff.added[1].geometry.as_ref().unwrap().value,
+            Value::Point(position![7.0, 0.0])
+        );
+        assert_eq!(
+            ids(&diff.removed),
+            vec![Some(Id::String("2".to_string())), None]
+        );
+        assert_eq!(diff.modified.len(), 2);
+        assert!(diff.modified[0].geometry_changed && diff.modified[0].members_changed);
+        assert!(diff.modified[0].properties.is_empty());
+        assert!(!diff.modified[1].geometry_changed && !diff.modified[1].members_changed);
+        assert_eq!(
+            diff.to_string(),
+            "added feature 4\n\
+             added a feature without an id\n\
+             removed feature \"2\"\n\
+             removed a feature without an id\n\
+             modified feature 3: geometry; members\n\
+             modified feature 1: lanes: removed 2; name: \"a\" -> \"b\"; width: added 7\n"
+        );
+
+        assert!(old.diff(&old).is_empty());
+    }
+}
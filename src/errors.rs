@@ -1,6 +1,7 @@
 //! Module for all GeoJSON-related errors
 use crate::geometry::Value as GValue;
 use crate::Feature;
+use alloc::string::String;
 use serde_json::value::Value;
 use thiserror::Error;
 
@@ -49,4 +50,50 @@ pub enum Error {
     ExpectedArrayValue(String),
     #[error("Expected an owned Object, but got `{0}`")]
     ExpectedObjectValue(Value),
+    #[error("Invalid geohash precision `{0}`, expected a value between 1 and 12")]
+    GeohashInvalidPrecision(usize),
+    #[error("Encountered an invalid geohash: `{0}`")]
+    InvalidGeohash(String),
+    #[error("Coordinate ({0}, {1}) is outside the range of longitude and latitude")]
+    GeohashCoordinateOutOfRange(f64, f64),
+    #[error("Encountered an error converting to H3 cells: {0}")]
+    H3Conversion(String),
+    #[error("Encountered an error reading or writing a shapefile: {0}")]
+    Shapefile(String),
+    #[error("Encountered an error reprojecting coordinates: {0}")]
+    Reprojection(String),
+    #[error("Encountered an invalid JSON-FG `{0}` member: `{1}`")]
+    InvalidJsonFgMember(&'static str, Value),
+    #[error("Encountered an invalid OGC API `{0}` member: `{1}`")]
+    InvalidOgcApiMember(&'static str, Value),
+    #[error("Encountered invalid EsriJSON: {0}")]
+    InvalidEsriJson(String),
+    #[error("Encountered invalid Overpass JSON: {0}")]
+    InvalidOverpassJson(String),
+    #[error("Encountered an invalid GeoJSON-LD `@context`: `{0}`")]
+    InvalidJsonLdContext(Value),
+    #[error("Encountered positions of {0} and of {1} coordinates in one flat geometry")]
+    MixedDimensions(usize, usize),
+    #[error("Encountered an invalid CQL2 filter: {0}")]
+    InvalidCql2(String),
+    #[error("Invalid tile zoom level `{0}`, expected a value between 0 and 30")]
+    TileInvalidZoom(u8),
+    #[error("Encountered an invalid tile: `{0}`")]
+    InvalidTile(String),
+    #[error("Encountered an error converting to or from a JavaScript value: {0}")]
+    WasmConversion(String),
+    #[error("Encountered a property `{0}` which isn't an RFC 3339 datetime: `{1}`")]
+    InvalidDateTimeProperty(String, Value),
+    #[error("Encountered a datetime which can't be formatted as RFC 3339: {0}")]
+    UnformattableDateTime(String),
+    #[error("Encountered a feature id which isn't a UUID: `{0}`")]
+    IdNotUuid(String),
+    #[error("Encountered a property `{0}` which isn't a decimal: `{1}`")]
+    InvalidDecimalProperty(String, Value),
+    #[error("Encountered a decimal which can't be written exactly as a JSON number: {0}")]
+    InexactDecimal(String),
+    #[error("Encountered an unknown type `{0}`, did you mean `{1}`?")]
+    UnknownTypeDidYouMean(String, &'static str),
+    #[error("Expected a position of at least 2 coordinates, but got {0}")]
+    PositionTooShort(usize),
 }
@@ -0,0 +1,546 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversion between GeoJSON and the
+//! [EsriJSON](https://developers.arcgis.com/documentation/common-data-types/geometry-objects.htm)
+//! format of ArcGIS REST services.
+//!
+//! EsriJSON polygons list all of their rings together, with exterior rings wound clockwise
+//! and holes counter-clockwise. When reading, each hole is assigned to the exterior ring which
+//! contains it, and rings are reversed to follow the
+//! [RFC 7946](https://tools.ietf.org/html/rfc7946#section-3.1.6) right-hand rule; when
+//! writing, rings are wound as ArcGIS expects.
+//!
+//! Coordinates are not reprojected, so query services with `outSR=4326` to receive WGS 84
+//! coordinates. Written geometries and feature sets are given a `spatialReference` of
+//! `{"wkid": 4326}`.
+//!
+//! # Example
+//!
+//! ```
+//! use geojson::{esri, position, Value};
+//! use serde_json::json;
+//!
+//! let feature_set = json!({
+//!     "geometryType": "esriGeometryPoint",
+//!     "objectIdFieldName": "OBJECTID",
+//!     "spatialReference": { "wkid": 4326 },
+//!     "features": [{
+//!         "geometry": { "x": -118.15, "y": 33.80 },
+//!         "attributes": { "OBJECTID": 1, "NAME": "Long Beach" }
+//!     }]
+//! });
+//!
+//! let collection = esri::feature_collection_from_esri(&feature_set).unwrap();
+//! let feature = &collection.features[0];
+//! assert_eq!(feature.id, Some(geojson::feature::Id::Number(1.into())));
+//! assert_eq!(
+//!     feature.geometry.as_ref().unwrap().value,
+//!     Value::Point(position![-118.15, 33.80])
+//! );
+//!
+//! let back = esri::feature_collection_to_esri(&collection).unwrap();
+//! assert_eq!(back["geometryType"], "esriGeometryPoint");
+//! ```
+
+use crate::algorithm::intersects::point_in_ring;
+use crate::algorithm::{for_each_position, signed_area};
+use crate::feature::Id;
+use crate::json::{JsonObject, JsonValue};
+use crate::{Error, Feature, FeatureCollection, Geometry, Position, Value};
+use serde_json::json;
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::InvalidEsriJson(message.into())
+}
+
+fn esri_number(object: &JsonObject, key: &str) -> Option<f64> {
+    object.get(key).and_then(JsonValue::as_f64)
+}
+
+fn esri_flag(object: &JsonObject, key: &str) -> bool {
+    object
+        .get(key)
+        .and_then(JsonValue::as_bool)
+        .unwrap_or(false)
+}
+
+/// Read an EsriJSON coordinate array, which holds `[x, y]`, `[x, y, z]`, `[x, y, m]` or
+/// `[x, y, z, m]` depending on `hasZ` and `hasM`
+fn esri_position(value: &JsonValue, has_z: bool) -> Result<Position, Error> {
+    let coords = value
+        .as_array()
+        .ok_or_else(|| invalid(format!("expected a coordinate array, got `{}`", value)))?;
+    let number = |i: usize| coords.get(i).and_then(JsonValue::as_f64);
+    match (number(0), number(1), has_z) {
+        (Some(x), Some(y), true) => Ok(match number(2) {
+            Some(z) => position![x, y, z],
+            None => position![x, y],
+        }),
+        (Some(x), Some(y), false) => Ok(position![x, y]),
+        _ => Err(invalid(format!(
+            "expected a coordinate array, got `{}`",
+            value
+        ))),
+    }
+}
+
+fn esri_positions(value: &JsonValue, has_z: bool) -> Result<Vec<Position>, Error> {
+    value
+        .as_array()
+        .ok_or_else(|| invalid(format!("expected an array of coordinates, got `{}`", value)))?
+        .iter()
+        .map(|position| esri_position(position, has_z))
+        .collect()
+}
+
+fn esri_paths(value: &JsonValue, has_z: bool) -> Result<Vec<Vec<Position>>, Error> {
+    value
+        .as_array()
+        .ok_or_else(|| invalid(format!("expected an array of paths, got `{}`", value)))?
+        .iter()
+        .map(|path| esri_positions(path, has_z))
+        .collect()
+}
+
+/// Group EsriJSON rings into polygons, reversing them to GeoJSON winding
+fn group_rings(rings: Vec<Vec<Position>>) -> Value {
+    let (exteriors, holes): (Vec<_>, Vec<_>) = rings
+        .into_iter()
+        .filter(|ring| !ring.is_empty())
+        .partition(|ring| signed_area(ring) <= 0.0);
+    let mut polygons: Vec<Vec<Vec<Position>>> = exteriors
+        .into_iter()
+        .map(|mut ring| {
+            ring.reverse();
+            vec![ring]
+        })
+        .collect();
+    for mut hole in holes {
+        let (x, y) = (hole[0][0], hole[0][1]);
+        hole.reverse();
+        match polygons
+            .iter_mut()
+            .find(|polygon| point_in_ring(x, y, &polygon[0]))
+        {
+            Some(polygon) => polygon.push(hole),
+            // A hole outside of every exterior ring is most likely a wrongly wound exterior
+            None => {
+                hole.reverse();
+                polygons.push(vec![hole]);
+            }
+        }
+    }
+    if polygons.len() == 1 {
+        Value::Polygon(polygons.remove(0))
+    } else {
+        Value::MultiPolygon(polygons)
+    }
+}
+
+/// Convert an EsriJSON geometry object into a `Geometry`.
+///
+/// Points, multipoints, polylines, polygons and envelopes are supported. Empty geometries,
+/// such as a point with a `null` x coordinate, produce `None`. M values are dropped.
+pub fn geometry_from_esri(value: &JsonValue) -> Result<Option<Geometry>, Error> {
+    let object = match value {
+        JsonValue::Null => return Ok(None),
+        JsonValue::Object(object) => object,
+        _ => {
+            return Err(invalid(format!(
+                "expected a geometry object, got `{}`",
+                value
+            )))
+        }
+    };
+    let has_z = esri_flag(object, "hasZ");
+    let value = if object.contains_key("x") {
+        let (x, y) = match (esri_number(object, "x"), esri_number(object, "y")) {
+            (Some(x), Some(y)) => (x, y),
+            _ => return Ok(None),
+        };
+        match esri_number(object, "z") {
+            Some(z) => Value::Point(position![x, y, z]),
+            None => Value::Point(position![x, y]),
+        }
+    } else if let Some(points) = object.get("points") {
+        Value::MultiPoint(esri_positions(points, has_z)?)
+    } else if let Some(paths) = object.get("paths") {
+        let mut paths = esri_paths(paths, has_z)?;
+        match paths.len() {
+            0 => return Ok(None),
+            1 => Value::LineString(paths.remove(0)),
+            _ => Value::MultiLineString(paths),
+        }
+    } else if let Some(rings) = object.get("rings") {
+        match group_rings(esri_paths(rings, has_z)?) {
+            Value::MultiPolygon(polygons) if polygons.is_empty() => return Ok(None),
+            value => value,
+        }
+    } else if object.contains_key("xmin") {
+        let corners = ["xmin", "ymin", "xmax", "ymax"].map(|key| esri_number(object, key));
+        match corners {
+            [Some(min_x), Some(min_y), Some(max_x), Some(max_y)] => Value::Polygon(vec![vec![
+                position![min_x, min_y],
+                position![max_x, min_y],
+                position![max_x, max_y],
+                position![min_x, max_y],
+                position![min_x, min_y],
+            ]]),
+            _ => return Ok(None),
+        }
+    } else {
+        return Err(invalid(format!("unknown geometry `{}`", value)));
+    };
+    Ok(Some(Geometry::new(value)))
+}
+
+fn has_z<'a>(mut positions: impl Iterator<Item = &'a Position>) -> bool {
+    let mut any = false;
+    let all = positions.all(|position| {
+        any = true;
+        position.len() >= 3
+    });
+    any && all
+}
+
+fn esri_coords(position: &Position, has_z: bool) -> JsonValue {
+    let len = if has_z { 3 } else { 2 };
+    json!(position.iter().take(len).collect::<Vec<_>>())
+}
+
+fn esri_ring(ring: &[Position], clockwise: bool, has_z: bool) -> JsonValue {
+    let reverse = (signed_area(ring) > 0.0) == clockwise;
+    let coords: Vec<_> = ring.iter().map(|p| esri_coords(p, has_z)).collect();
+    if reverse {
+        coords.into_iter().rev().collect()
+    } else {
+        coords.into_iter().collect()
+    }
+}
+
+fn esri_rings<'a>(polygons: impl Iterator<Item = &'a Vec<Vec<Position>>> + Clone) -> JsonValue {
+    let has_z = has_z(polygons.clone().flatten().flatten());
+    let mut rings = vec![];
+    for polygon in polygons {
+        for (i, ring) in polygon.iter().enumerate() {
+            rings.push(esri_ring(ring, i == 0, has_z));
+        }
+    }
+    json!({ "rings": rings, "hasZ": has_z })
+}
+
+fn esri_paths_json(lines: &[Vec<Position>]) -> JsonValue {
+    let has_z = has_z(lines.iter().flatten());
+    let paths: Vec<Vec<_>> = lines
+        .iter()
+        .map(|line| line.iter().map(|p| esri_coords(p, has_z)).collect())
+        .collect();
+    json!({ "paths": paths, "hasZ": has_z })
+}
+
+/// The EsriJSON `geometryType` corresponding to `value`
+fn esri_geometry_type(value: &Value) -> Result<&'static str, Error> {
+    match value {
+        Value::Point(_) => Ok("esriGeometryPoint"),
+        Value::MultiPoint(_) => Ok("esriGeometryMultipoint"),
+        Value::LineString(_) | Value::MultiLineString(_) => Ok("esriGeometryPolyline"),
+        Value::Polygon(_) | Value::MultiPolygon(_) => Ok("esriGeometryPolygon"),
+        Value::GeometryCollection(_) => Err(geometry_collection_unsupported()),
+    }
+}
+
+fn geometry_collection_unsupported() -> Error {
+    invalid("GeometryCollections cannot be represented as EsriJSON")
+}
+
+fn geometry_to_esri_object(geometry: &Geometry) -> Result<JsonValue, Error> {
+    let mut short = None;
+    for_each_position(&geometry.value, &mut |position| {
+        if position.len() < 2 {
+            short.get_or_insert(position.len());
+        }
+    });
+    if let Some(len) = short {
+        return Err(Error::PositionTooShort(len));
+    }
+    let mut object = match &geometry.value {
+        Value::Point(position) => match position.as_slice() {
+            [x, y, z, ..] => json!({ "x": x, "y": y, "z": z }),
+            [x, y] => json!({ "x": x, "y": y }),
+            _ => return Err(Error::PositionTooShort(position.len())),
+        },
+        Value::MultiPoint(positions) => {
+            let has_z = has_z(positions.iter());
+            let points: Vec<_> = positions.iter().map(|p| esri_coords(p, has_z)).collect();
+            json!({ "points": points, "hasZ": has_z })
+        }
+        Value::LineString(line) => esri_paths_json(std::slice::from_ref(line)),
+        Value::MultiLineString(lines) => esri_paths_json(lines),
+        Value::Polygon(rings) => esri_rings(std::iter::once(rings)),
+        Value::MultiPolygon(polygons) => esri_rings(polygons.iter()),
+        Value::GeometryCollection(_) => return Err(geometry_collection_unsupported()),
+    };
+    if let Some(object) = object.as_object_mut() {
+        if object.get("hasZ") == Some(&JsonValue::Bool(false)) {
+            object.remove("hasZ");
+        }
+    }
+    Ok(object)
+}
+
+/// Convert a `Geometry` into an EsriJSON geometry object, with a WGS 84 `spatialReference`.
+///
+/// `GeometryCollection`s have no EsriJSON equivalent and produce an error. Z values are
+/// written, with `hasZ`, when every position has one.
+pub fn geometry_to_esri(geometry: &Geometry) -> Result<JsonValue, Error> {
+    let mut value = geometry_to_esri_object(geometry)?;
+    value["spatialReference"] = json!({ "wkid": 4326 });
+    Ok(value)
+}
+
+/// Convert an EsriJSON feature, with `geometry` and `attributes` members, into a `Feature`.
+///
+/// The attributes become the feature's properties.
+pub fn feature_from_esri(value: &JsonValue) -> Result<Feature, Error> {
+    let object = value
+        .as_object()
+        .ok_or_else(|| invalid(format!("expected a feature object, got `{}`", value)))?;
+    let geometry = match object.get("geometry") {
+        Some(geometry) => geometry_from_esri(geometry)?,
+        None => None,
+    };
+    let properties = match object.get("attributes") {
+        None | Some(JsonValue::Null) => None,
+        Some(JsonValue::Object(attributes)) => Some(attributes.clone()),
+        Some(other) => return Err(invalid(format!("expected attributes, got `{}`", other))),
+    };
+    Ok(Feature {
+        bbox: None,
+        geometry,
+        id: None,
+        properties,
+        foreign_members: None,
+    })
+}
+
+/// Convert a `Feature` into an EsriJSON feature, with its properties as `attributes`.
+///
+/// The geometry is written without a `spatialReference`, which EsriJSON features inherit
+/// from their feature set.
+pub fn feature_to_esri(feature: &Feature) -> Result<JsonValue, Error> {
+    let mut object = JsonObject::new();
+    if let Some(geometry) = &feature.geometry {
+        object.insert("geometry".to_string(), geometry_to_esri_object(geometry)?);
+    }
+    object.insert(
+        "attributes".to_string(),
+        JsonValue::Object(feature.properties.clone().unwrap_or_default()),
+    );
+    Ok(JsonValue::Object(object))
+}
+
+/// Convert an EsriJSON feature set, such as the response of a feature service query, into a
+/// `FeatureCollection`.
+///
+/// If the feature set names an `objectIdFieldName`, that attribute is used as each feature's
+/// `id`, and is also kept in its properties.
+pub fn feature_collection_from_esri(value: &JsonValue) -> Result<FeatureCollection, Error> {
+    let features = value
+        .get("features")
+        .and_then(JsonValue::as_array)
+        .ok_or_else(|| invalid("expected a feature set with a `features` array"))?;
+    let id_field = value.get("objectIdFieldName").and_then(JsonValue::as_str);
+    let features = features
+        .iter()
+        .map(|value| {
+            let mut feature = feature_from_esri(value)?;
+            feature.id = match id_field.and_then(|field| feature.property(field)) {
+                Some(JsonValue::Number(n)) => Some(Id::Number(n.clone())),
+                Some(JsonValue::String(s)) => Some(Id::String(s.clone())),
+                _ => None,
+            };
+            Ok(feature)
+        })
+        .collect::<Result<_, Error>>()?;
+    Ok(FeatureCollection {
+        bbox: None,
+        features,
+        foreign_members: None,
+    })
+}
+
+/// Convert a `FeatureCollection` into an EsriJSON feature set.
+///
+/// EsriJSON feature sets hold a single `geometryType`, so every feature with a geometry must
+/// map to the same type; `Polygon` and `MultiPolygon` both map to polygons, for example.
+pub fn feature_collection_to_esri(collection: &FeatureCollection) -> Result<JsonValue, Error> {
+    let mut geometry_type = None;
+    for geometry in collection
+        .features
+        .iter()
+        .filter_map(|feature| feature.geometry.as_ref())
+    {
+        let this_type = esri_geometry_type(&geometry.value)?;
+        match geometry_type {
+            Some(existing) if existing != this_type => {
+                return Err(invalid(format!(
+                    "features of both `{}` and `{}` cannot be written to one feature set",
+                    existing, this_type
+                )))
+            }
+            _ => geometry_type = Some(this_type),
+        }
+    }
+    let features = collection
+        .features
+        .iter()
+        .map(feature_to_esri)
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut object = json!({
+        "spatialReference": { "wkid": 4326 },
+        "features": features,
+    });
+    if let Some(geometry_type) = geometry_type {
+        object["geometryType"] = geometry_type.into();
+    }
+    Ok(object)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        feature_collection_from_esri, feature_collection_to_esri, geometry_from_esri,
+        geometry_to_esri, geometry_to_esri_object,
+    };
+    use crate::algorithm::signed_area;
+    use crate::feature::Id;
+    use crate::{Error, Feature, FeatureCollection, Geometry, Value};
+    use serde_json::json;
+
+    #[test]
+    fn polygon_with_hole_roundtrip() {
+        // Exterior clockwise, hole counter-clockwise, plus a second exterior
+        let esri = json!({
+            "rings": [
+                [[0.0, 0.0], [0.0, 10.0], [10.0, 10.0], [10.0, 0.0], [0.0, 0.0]],
+                [[20.0, 0.0], [20.0, 1.0], [21.0, 1.0], [20.0, 0.0]],
+                [[2.0, 2.0], [4.0, 2.0], [4.0, 4.0], [2.0, 2.0]]
+            ],
+            "spatialReference": { "wkid": 4326 }
+        });
+        let geometry = geometry_from_esri(&esri).unwrap().unwrap();
+        let polygons = match &geometry.value {
+            Value::MultiPolygon(polygons) => polygons.clone(),
+            _ => unreachable!(),
+        };
+        assert_eq!(polygons.len(), 2);
+        assert_eq!(polygons[0].len(), 2);
+        assert!(signed_area(&polygons[0][0]) > 0.0);
+        assert!(signed_area(&polygons[0][1]) < 0.0);
+
+        assert_eq!(geometry_to_esri(&geometry).unwrap(), {
+            let mut expected = esri.clone();
+            let rings = expected["rings"].as_array_mut().unwrap();
+            let hole = rings.remove(2);
+            rings.insert(1, hole);
+            expected
+        });
+    }
+
+    #[test]
+    fn points_and_lines() {
+        let point = json!({ "x": 1.0, "y": 2.0, "z": 3.0, "m": 4.0 });
+        assert_eq!(
+            geometry_from_esri(&point).unwrap().unwrap().value,
+            Value::Point(position![1.0, 2.0, 3.0])
+        );
+        let empty = json!({ "x": null, "y": null });
+        assert_eq!(geometry_from_esri(&empty).unwrap(), None);
+
+        let line = json!({
+            "hasM": true,
+            "paths": [[[0.0, 0.0, 7.0], [1.0, 1.0, 8.0]]]
+        });
+        let geometry = geometry_from_esri(&line).unwrap().unwrap();
+        assert_eq!(
+            geometry.value,
+            Value::LineString(vec![position![0.0, 0.0], position![1.0, 1.0]])
+        );
+        assert_eq!(
+            geometry_to_esri_object(&geometry).unwrap(),
+            json!({ "paths": [[[0.0, 0.0], [1.0, 1.0]]] })
+        );
+
+        let multipoint = Geometry::new(Value::MultiPoint(vec![position![0.0, 1.0, 2.0]]));
+        assert_eq!(
+            geometry_to_esri_object(&multipoint).unwrap(),
+            json!({ "points": [[0.0, 1.0, 2.0]], "hasZ": true })
+        );
+    }
+
+    #[test]
+    fn short_positions() {
+        let ring = vec![
+            position![0.0],
+            position![1.0],
+            position![2.0],
+            position![0.0],
+        ];
+        let polygon = Geometry::new(Value::Polygon(vec![ring]));
+        assert!(matches!(
+            geometry_to_esri(&polygon),
+            Err(Error::PositionTooShort(1))
+        ));
+        let point = Geometry::new(Value::Point(position![]));
+        assert!(matches!(
+            geometry_to_esri(&point),
+            Err(Error::PositionTooShort(0))
+        ));
+    }
+
+    #[test]
+    fn feature_sets() {
+        let collection = FeatureCollection {
+            bbox: None,
+            features: vec![
+                Feature::from(Value::Point(position![0.0, 0.0])),
+                Feature::from(Value::LineString(vec![
+                    position![0.0, 0.0],
+                    position![1.0, 1.0],
+                ])),
+            ],
+            foreign_members: None,
+        };
+        assert!(matches!(
+            feature_collection_to_esri(&collection),
+            Err(Error::InvalidEsriJson(_))
+        ));
+
+        let feature_set = json!({
+            "objectIdFieldName": "FID",
+            "features": [{ "geometry": null, "attributes": { "FID": "a" } }]
+        });
+        let collection = feature_collection_from_esri(&feature_set).unwrap();
+        assert_eq!(collection.features[0].id, Some(Id::String("a".to_string())));
+        assert_eq!(collection.features[0].geometry, None);
+        assert_eq!(
+            feature_collection_to_esri(&collection).unwrap(),
+            json!({
+                "spatialReference": { "wkid": 4326 },
+                "features": [{ "attributes": { "FID": "a" } }]
+            })
+        );
+    }
+}
@@ -0,0 +1,599 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parsing GeoJSON into a stream of [`Event`]s, without building its objects.
+//!
+//! [`from_reader`] and [`from_slice`] pass each part of a GeoJSON object to a handler as it is
+//! parsed, in the order it was written: the start and end of each feature collection, feature
+//! and geometry, each coordinate as a scalar, each property, and so on. Work which only needs
+//! one pass over the coordinates, such as encoding tiles or computing extents, then never holds
+//! more than a single property in memory.
+//!
+//! Members are reported where they are written, so the [`GeometryType`] of a geometry may come
+//! after its coordinates, as this crate itself writes `type` after `coordinates`. Only members
+//! which come before the member which tells the kind of an object, such as a `bbox` before the
+//! `features` of a collection, are held until then. Coordinates are nested in
+//! [`StartArray`] and [`EndArray`] events for each array of arrays within the `coordinates`,
+//! such as each ring of a polygon, but not for the `coordinates` themselves or for positions.
+//!
+//! # Example
+//!
+//! ```
+//! use geojson::events::{self, Event};
+//!
+//! let json = r#"{
+//!     "type": "FeatureCollection",
+//!     "features": [{
+//!         "type": "Feature",
+//!         "geometry": { "type": "LineString", "coordinates": [[1, 2], [3, -4]] },
+//!         "properties": { "name": "a" }
+//!     }]
+//! }"#;
+//!
+//! let mut min_y = f64::INFINITY;
+//! let mut names = vec![];
+//! events::from_slice(json.as_bytes(), |event| match event {
+//!     Event::Coordinate(_, y, _) => min_y = min_y.min(y),
+//!     Event::Property("name", name) => names.push(name.clone()),
+//!     _ => {}
+//! })
+//! .unwrap();
+//! assert_eq!(min_y, -4.0);
+//! assert_eq!(names, ["a"]);
+//! ```
+//!
+//! [`Event`]: enum.Event.html
+//! [`from_reader`]: fn.from_reader.html
+//! [`from_slice`]: fn.from_slice.html
+//! [`GeometryType`]: enum.Event.html#variant.GeometryType
+//! [`StartArray`]: enum.Event.html#variant.StartArray
+//! [`EndArray`]: enum.Event.html#variant.EndArray
+
+use crate::errors::Error;
+use crate::json::JsonValue;
+use serde::de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use std::fmt;
+use std::io::Read;
+
+/// A part of a GeoJSON object, in the order it was written
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event<'a> {
+    StartFeatureCollection,
+    EndFeatureCollection,
+    StartFeature,
+    EndFeature,
+    /// The start of a geometry, which is `null` if there is no `StartGeometry` between the
+    /// start and end of its feature
+    StartGeometry,
+    /// The `type` of the current geometry, such as `"Polygon"`
+    GeometryType(&'a str),
+    EndGeometry,
+    /// The start of the `coordinates` of the current geometry
+    StartCoordinates,
+    /// The start of an array of arrays within the `coordinates`, such as a ring of a polygon
+    StartArray,
+    /// A position, with its third coordinate if it has one. Any further coordinates are
+    /// skipped.
+    Coordinate(f64, f64, Option<f64>),
+    EndArray,
+    EndCoordinates,
+    /// The `id` of the current feature, a string or a number
+    Id(&'a JsonValue),
+    /// A property of the current feature
+    Property(&'a str, &'a JsonValue),
+    /// The `bbox` of the current object
+    Bbox(&'a [f64]),
+    /// A member of the current object which isn't part of GeoJSON
+    ForeignMember(&'a str, &'a JsonValue),
+}
+
+/// Parse the GeoJSON object read from `reader`, passing each part of it to `handler`
+pub fn from_reader<R: Read>(reader: R, handler: impl FnMut(Event)) -> Result<(), Error> {
+    parse(&mut serde_json::Deserializer::from_reader(reader), handler)
+}
+
+/// Parse the GeoJSON object in `bytes`, passing each part of it to `handler`
+pub fn from_slice(bytes: &[u8], handler: impl FnMut(Event)) -> Result<(), Error> {
+    parse(&mut serde_json::Deserializer::from_slice(bytes), handler)
+}
+
+fn parse<'de, R: serde_json::de::Read<'de>>(
+    deserializer: &mut serde_json::Deserializer<R>,
+    mut handler: impl FnMut(Event),
+) -> Result<(), Error> {
+    ObjectSeed {
+        handler: &mut handler,
+        expected: None,
+        nullable: false,
+    }
+    .deserialize(&mut *deserializer)
+    .and_then(|()| deserializer.end())
+    .map_err(Error::MalformedJson)
+}
+
+/// The kind of GeoJSON object being parsed
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Kind {
+    Geometry,
+    Feature,
+    FeatureCollection,
+}
+
+impl Kind {
+    fn from_type(type_: &str) -> Option<Kind> {
+        match type_ {
+            "Feature" => Some(Kind::Feature),
+            "FeatureCollection" => Some(Kind::FeatureCollection),
+            "Point" | "MultiPoint" | "LineString" | "MultiLineString" | "Polygon"
+            | "MultiPolygon" | "GeometryCollection" => Some(Kind::Geometry),
+            _ => None,
+        }
+    }
+
+    fn start(self) -> Event<'static> {
+        match self {
+            Kind::Geometry => Event::StartGeometry,
+            Kind::Feature => Event::StartFeature,
+            Kind::FeatureCollection => Event::StartFeatureCollection,
+        }
+    }
+
+    fn end(self) -> Event<'static> {
+        match self {
+            Kind::Geometry => Event::EndGeometry,
+            Kind::Feature => Event::EndFeature,
+            Kind::FeatureCollection => Event::EndFeatureCollection,
+        }
+    }
+}
+
+/// A GeoJSON object, of the `expected` kind if there is one, or `null` if it is `nullable`
+struct ObjectSeed<'h, H> {
+    handler: &'h mut H,
+    expected: Option<Kind>,
+    nullable: bool,
+}
+
+impl<H: FnMut(Event)> ObjectSeed<'_, H> {
+    /// Start an object of `kind`, unless it has already started, passing on the members read
+    /// before its kind was known
+    fn start<E: de::Error>(
+        &mut self,
+        kind: Kind,
+        started: &mut Option<Kind>,
+        pending: &mut Vec<(String, JsonValue)>,
+    ) -> Result<(), E> {
+        match *started {
+            Some(started) if started == kind => return Ok(()),
+            Some(started) => {
+                return Err(E::custom(format!(
+                    "encountered members of a {:?} and of a {:?} in one object",
+                    started, kind
+                )))
+            }
+            None => {}
+        }
+        if let Some(expected) = self.expected.filter(|expected| *expected != kind) {
+            return Err(E::custom(format!(
+                "expected a {:?}, but got a {:?}",
+                expected, kind
+            )));
+        }
+        *started = Some(kind);
+        (self.handler)(kind.start());
+        for (key, value) in pending.drain(..) {
+            self.member(kind, &key, &value)?;
+        }
+        Ok(())
+    }
+
+    fn member<E: de::Error>(&mut self, kind: Kind, key: &str, value: &JsonValue) -> Result<(), E> {
+        match key {
+            "bbox" => {
+                let bbox: Vec<f64> = serde_json::from_value(value.clone()).map_err(E::custom)?;
+                (self.handler)(Event::Bbox(&bbox));
+            }
+            "id" if kind == Kind::Feature => {
+                if !value.is_string() && !value.is_number() {
+                    return Err(E::custom("expected a string or number `id`"));
+                }
+                (self.handler)(Event::Id(value));
+            }
+            key => (self.handler)(Event::ForeignMember(key, value)),
+        }
+        Ok(())
+    }
+}
+
+impl<'de, H: FnMut(Event)> DeserializeSeed<'de> for ObjectSeed<'_, H> {
+    type Value = ();
+
+    fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de, H: FnMut(Event)> Visitor<'de> for ObjectSeed<'_, H> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a GeoJSON object")
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<(), E> {
+        if self.nullable {
+            Ok(())
+        } else {
+            Err(E::invalid_type(de::Unexpected::Unit, &self))
+        }
+    }
+
+    fn visit_map<A: MapAccess<'de>>(mut self, mut map: A) -> Result<(), A::Error> {
+        let mut started = None;
+        let mut pending = vec![];
+        let mut has_type = false;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "type" => {
+                    let type_: String = map.next_value()?;
+                    let kind = Kind::from_type(&type_).ok_or_else(|| {
+                        de::Error::custom(format!("encountered an unknown type `{}`", type_))
+                    })?;
+                    self.start(kind, &mut started, &mut pending)?;
+                    if kind == Kind::Geometry {
+                        (self.handler)(Event::GeometryType(&type_));
+                    }
+                    has_type = true;
+                }
+                "features" => {
+                    self.start(Kind::FeatureCollection, &mut started, &mut pending)?;
+                    map.next_value_seed(ArraySeed {
+                        handler: &mut *self.handler,
+                        kind: Kind::Feature,
+                    })?;
+                }
+                "geometry" => {
+                    self.start(Kind::Feature, &mut started, &mut pending)?;
+                    map.next_value_seed(ObjectSeed {
+                        handler: &mut *self.handler,
+                        expected: Some(Kind::Geometry),
+                        nullable: true,
+                    })?;
+                }
+                "properties" => {
+                    self.start(Kind::Feature, &mut started, &mut pending)?;
+                    map.next_value_seed(PropertiesSeed(&mut *self.handler))?;
+                }
+                "coordinates" => {
+                    self.start(Kind::Geometry, &mut started, &mut pending)?;
+                    (self.handler)(Event::StartCoordinates);
+                    map.next_value_seed(CoordinatesSeed {
+                        handler: &mut *self.handler,
+                        nested: false,
+                    })?;
+                    (self.handler)(Event::EndCoordinates);
+                }
+                "geometries" => {
+                    self.start(Kind::Geometry, &mut started, &mut pending)?;
+                    map.next_value_seed(ArraySeed {
+                        handler: &mut *self.handler,
+                        kind: Kind::Geometry,
+                    })?;
+                }
+                _ => {
+                    let value: JsonValue = map.next_value()?;
+                    match started {
+                        Some(kind) => self.member(kind, &key, &value)?,
+                        None => pending.push((key, value)),
+                    }
+                }
+            }
+        }
+        match started {
+            Some(kind) if has_type => {
+                (self.handler)(kind.end());
+                Ok(())
+            }
+            _ => Err(de::Error::missing_field("type")),
+        }
+    }
+}
+
+/// An array of GeoJSON objects of one `kind`
+struct ArraySeed<'h, H> {
+    handler: &'h mut H,
+    kind: Kind,
+}
+
+impl<'de, H: FnMut(Event)> DeserializeSeed<'de> for ArraySeed<'_, H> {
+    type Value = ();
+
+    fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, H: FnMut(Event)> Visitor<'de> for ArraySeed<'_, H> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "an array of {:?} objects", self.kind)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while seq
+            .next_element_seed(ObjectSeed {
+                handler: &mut *self.handler,
+                expected: Some(self.kind),
+                nullable: false,
+            })?
+            .is_some()
+        {}
+        Ok(())
+    }
+}
+
+/// The `properties` of a feature, or `null`
+struct PropertiesSeed<'h, H>(&'h mut H);
+
+impl<'de, H: FnMut(Event)> DeserializeSeed<'de> for PropertiesSeed<'_, H> {
+    type Value = ();
+
+    fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de, H: FnMut(Event)> Visitor<'de> for PropertiesSeed<'_, H> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an object or null")
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some((key, value)) = map.next_entry::<String, JsonValue>()? {
+            (self.0)(Event::Property(&key, &value));
+        }
+        Ok(())
+    }
+}
+
+/// An array within `coordinates`, or the `coordinates` themselves if it isn't `nested`
+struct CoordinatesSeed<'h, H> {
+    handler: &'h mut H,
+    nested: bool,
+}
+
+impl<'de, H: FnMut(Event)> DeserializeSeed<'de> for CoordinatesSeed<'_, H> {
+    type Value = ();
+
+    fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, H: FnMut(Event)> Visitor<'de> for CoordinatesSeed<'_, H> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an array of coordinates")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<(), A::Error> {
+        coordinates(seq, self.handler, self.nested)
+    }
+}
+
+/// The first element of an array within `coordinates`, which tells whether the array is a
+/// position or an array of arrays
+enum Element {
+    Number(f64),
+    Array,
+}
+
+struct ElementSeed<'h, H> {
+    handler: &'h mut H,
+    /// Whether the array holding this element is nested, and so starts with a `StartArray`
+    /// if it turns out to hold arrays
+    parent_nested: bool,
+}
+
+impl<'de, H: FnMut(Event)> DeserializeSeed<'de> for ElementSeed<'_, H> {
+    type Value = Element;
+
+    fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<Element, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de, H: FnMut(Event)> Visitor<'de> for ElementSeed<'_, H> {
+    type Value = Element;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a number or an array")
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Element, E> {
+        Ok(Element::Number(value))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Element, E> {
+        Ok(Element::Number(value as f64))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Element, E> {
+        Ok(Element::Number(value as f64))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Element, A::Error> {
+        if self.parent_nested {
+            (self.handler)(Event::StartArray);
+        }
+        coordinates(seq, self.handler, true)?;
+        Ok(Element::Array)
+    }
+}
+
+/// Pass on the positions of an array within `coordinates`, which is `nested` within them
+/// unless it is the `coordinates` themselves
+fn coordinates<'de, A: SeqAccess<'de>, H: FnMut(Event)>(
+    mut seq: A,
+    handler: &mut H,
+    nested: bool,
+) -> Result<(), A::Error> {
+    let element = seq.next_element_seed(ElementSeed {
+        handler: &mut *handler,
+        parent_nested: nested,
+    })?;
+    match element {
+        Some(Element::Number(x)) => {
+            let y = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::custom("expected a position of at least two numbers"))?;
+            let z = seq.next_element()?;
+            while seq.next_element::<f64>()?.is_some() {}
+            handler(Event::Coordinate(x, y, z));
+        }
+        Some(Element::Array) => {
+            while seq
+                .next_element_seed(CoordinatesSeed {
+                    handler: &mut *handler,
+                    nested: true,
+                })?
+                .is_some()
+            {}
+            if nested {
+                handler(Event::EndArray);
+            }
+        }
+        None if nested => {
+            handler(Event::StartArray);
+            handler(Event::EndArray);
+        }
+        None => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_reader, from_slice, Event};
+    use serde_json::json;
+
+    fn events(json: &str) -> Vec<String> {
+        let mut events = vec![];
+        from_slice(json.as_bytes(), |event| events.push(format!("{:?}", event))).unwrap();
+        events
+    }
+
+    #[test]
+    fn parse_events() {
+        let collection = json!({
+            "bbox": [0, 0, 2, 2],
+            "features": [{
+                "geometry": {
+                    "coordinates": [[[0, 0], [2, 0], [2, 2], [0, 0]], []],
+                    "type": "Polygon"
+                },
+                "id": 7,
+                "properties": { "name": "a" },
+                "type": "Feature"
+            }, {
+                "type": "Feature",
+                "geometry": {
+                    "type": "GeometryCollection",
+                    "geometries": [{ "type": "Point", "coordinates": [1, 2, 3, 4] }]
+                },
+                "properties": null,
+                "source": "survey"
+            }],
+            "type": "FeatureCollection"
+        });
+        assert_eq!(
+            events(&collection.to_string()),
+            [
+                "StartFeatureCollection",
+                "Bbox([0.0, 0.0, 2.0, 2.0])",
+                "StartFeature",
+                "StartGeometry",
+                "StartCoordinates",
+                "StartArray",
+                "Coordinate(0.0, 0.0, None)",
+                "Coordinate(2.0, 0.0, None)",
+                "Coordinate(2.0, 2.0, None)",
+                "Coordinate(0.0, 0.0, None)",
+                "EndArray",
+                "StartArray",
+                "EndArray",
+                "EndCoordinates",
+                "GeometryType(\"Polygon\")",
+                "EndGeometry",
+                "Id(Number(7))",
+                "Property(\"name\", String(\"a\"))",
+                "EndFeature",
+                "StartFeature",
+                "StartGeometry",
+                "StartGeometry",
+                "StartCoordinates",
+                "Coordinate(1.0, 2.0, Some(3.0))",
+                "EndCoordinates",
+                "GeometryType(\"Point\")",
+                "EndGeometry",
+                "GeometryType(\"GeometryCollection\")",
+                "EndGeometry",
+                "ForeignMember(\"source\", String(\"survey\"))",
+                "EndFeature",
+                "EndFeatureCollection",
+            ]
+        );
+
+        let multi_polygon =
+            r#"{"type":"MultiPolygon","coordinates":[[[[0,0],[1,0],[1,1],[0,0]]]]}"#;
+        let mut depth = 0;
+        let mut coordinates = 0;
+        from_reader(multi_polygon.as_bytes(), |event| match event {
+            Event::StartArray => depth += 1,
+            Event::Coordinate(..) => {
+                assert_eq!(depth, 2);
+                coordinates += 1;
+            }
+            Event::EndArray => depth -= 1,
+            _ => {}
+        })
+        .unwrap();
+        assert_eq!((depth, coordinates), (0, 4));
+
+        for invalid in [
+            r#"{"type":"Point","coordinates":[1]}"#,
+            r#"{"type":"Point","coordinates":[[1, 2], 3]}"#,
+            r#"{"type":"Feature","coordinates":[1, 2]}"#,
+            r#"{"type":"FeatureCollection","features":[{"type":"Point","coordinates":[1, 2]}]}"#,
+            r#"{"coordinates":[1, 2]}"#,
+            r#"{"type":"Circle"}"#,
+            r#"{"type":"Feature","id":true}"#,
+            "null",
+        ] {
+            assert!(
+                from_slice(invalid.as_bytes(), |_| {}).is_err(),
+                "{}",
+                invalid
+            );
+        }
+    }
+}
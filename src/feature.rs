@@ -12,12 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::convert::TryFrom;
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use core::convert::TryFrom;
 
 use crate::errors::Error;
 use crate::json::{Deserialize, Deserializer, JsonObject, JsonValue, Serialize, Serializer};
-use crate::serde_json::json;
-use crate::{util, Feature, Geometry, Value};
+use crate::{util, Feature, Geometry, GeometryType, Value};
+use serde_json::json;
 
 impl From<Geometry> for Feature {
     fn from(geom: Geometry) -> Feature {
@@ -86,6 +89,11 @@ impl Feature {
         Self::try_from(value)
     }
 
+    /// The type of this feature's geometry, or `None` if it has none
+    pub fn geometry_type(&self) -> Option<GeometryType> {
+        self.geometry.as_ref().map(Geometry::geometry_type)
+    }
+
     /// Return the value of this property, if it's set
     pub fn property(&self, key: impl AsRef<str>) -> Option<&JsonValue> {
         self.properties
@@ -131,10 +139,20 @@ impl Feature {
     /// Returns an iterator over all the properties
     pub fn properties_iter(&self) -> Box<dyn ExactSizeIterator<Item = (&String, &JsonValue)> + '_> {
         match self.properties.as_ref() {
-            None => Box::new(std::iter::empty()),
+            None => Box::new(core::iter::empty()),
             Some(props) => Box::new(props.iter()),
         }
     }
+
+    /// Move the geometry out of this feature, leaving `None` in its place
+    pub fn take_geometry(&mut self) -> Option<Geometry> {
+        self.geometry.take()
+    }
+
+    /// Move the properties out of this feature, leaving `None` in their place
+    pub fn take_properties(&mut self) -> Option<JsonObject> {
+        self.properties.take()
+    }
 }
 
 impl TryFrom<JsonObject> for Feature {
@@ -172,6 +190,10 @@ impl Serialize for Feature {
     where
         S: Serializer,
     {
+        #[cfg(feature = "compact")]
+        if !serializer.is_human_readable() {
+            return crate::compact::serialize_feature(self, serializer);
+        }
         JsonObject::from(self).serialize(serializer)
     }
 }
@@ -181,11 +203,11 @@ impl<'de> Deserialize<'de> for Feature {
     where
         D: Deserializer<'de>,
     {
-        use serde::de::Error as SerdeError;
-
-        let val = JsonObject::deserialize(deserializer)?;
-
-        Feature::from_json_object(val).map_err(|e| D::Error::custom(e.to_string()))
+        #[cfg(feature = "compact")]
+        if !deserializer.is_human_readable() {
+            return crate::compact::deserialize_feature(deserializer);
+        }
+        crate::de::deserialize_feature(deserializer)
     }
 }
 
@@ -210,9 +232,47 @@ impl Serialize for Id {
     }
 }
 
+/// A predicate which is true of features which have the property `key`, for
+/// [`FeatureCollection::filter_features`](../struct.FeatureCollection.html#method.filter_features)
+/// and [`retain_features`](../struct.FeatureCollection.html#method.retain_features)
+pub fn has_property(key: impl Into<String>) -> impl Fn(&Feature) -> bool {
+    let key = key.into();
+    move |feature| feature.contains_property(&key)
+}
+
+/// A predicate which is true of features whose property `key` is `value`, for
+/// [`FeatureCollection::filter_features`](../struct.FeatureCollection.html#method.filter_features)
+/// and [`retain_features`](../struct.FeatureCollection.html#method.retain_features)
+///
+/// ```
+/// use geojson::{feature, position, Feature, FeatureCollection, Value};
+///
+/// let mut a = Feature::from(Value::Point(position![0.0, 0.0]));
+/// a.set_property("kind", "park");
+/// let mut b = Feature::from(Value::Point(position![1.0, 1.0]));
+/// b.set_property("kind", "road");
+/// let mut collection = FeatureCollection {
+///     bbox: None,
+///     features: vec![a, b],
+///     foreign_members: None,
+/// };
+/// assert_eq!(collection.filter_features(feature::property_eq("kind", "road")).count(), 1);
+///
+/// collection.retain_features(feature::property_eq("kind", "park"));
+/// assert_eq!(collection.features.len(), 1);
+/// ```
+pub fn property_eq(
+    key: impl Into<String>,
+    value: impl Into<JsonValue>,
+) -> impl Fn(&Feature) -> bool {
+    let key = key.into();
+    let value = value.into();
+    move |feature| feature.property(&key) == Some(&value)
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{feature, Error, Feature, GeoJson, Geometry, Value};
+    use crate::{feature, Error, Feature, GeoJson, Geometry, GeometryType, Value};
 
     fn feature_json_str() -> &'static str {
         "{\"geometry\":{\"coordinates\":[1.1,2.1],\"type\":\"Point\"},\"properties\":{},\"type\":\
@@ -238,7 +298,7 @@ mod tests {
     }
 
     fn value() -> Value {
-        Value::Point(vec![1.1, 2.1])
+        Value::Point(position![1.1, 2.1])
     }
 
     fn geometry() -> Geometry {
@@ -333,7 +393,7 @@ mod tests {
         let feature_json_str = "{\"geometry\":{\"coordinates\":[1.1,2.1],\"type\":\"Point\"},\"id\":0,\"properties\":{},\"type\":\"Feature\"}";
         let feature = crate::Feature {
             geometry: Some(Geometry {
-                value: Value::Point(vec![1.1, 2.1]),
+                value: Value::Point(position![1.1, 2.1]),
                 bbox: None,
                 foreign_members: None,
             }),
@@ -359,7 +419,7 @@ mod tests {
         let feature_json_str = "{\"geometry\":{\"coordinates\":[1.1,2.1],\"type\":\"Point\"},\"id\":\"foo\",\"properties\":{},\"type\":\"Feature\"}";
         let feature = crate::Feature {
             geometry: Some(Geometry {
-                value: Value::Point(vec![1.1, 2.1]),
+                value: Value::Point(position![1.1, 2.1]),
                 bbox: None,
                 foreign_members: None,
             }),
@@ -388,7 +448,7 @@ mod tests {
             Ok(_) => false,
             _ => false,
         };
-        assert_eq!(result, true,)
+        assert!(result)
     }
 
     #[test]
@@ -399,7 +459,7 @@ mod tests {
             Ok(_) => false,
             _ => false,
         };
-        assert_eq!(result, true,)
+        assert!(result)
     }
 
     #[test]
@@ -414,7 +474,7 @@ mod tests {
         );
         let feature = crate::Feature {
             geometry: Some(Geometry {
-                value: Value::Point(vec![1.1, 2.1]),
+                value: Value::Point(position![1.1, 2.1]),
                 bbox: None,
                 foreign_members: None,
             }),
@@ -443,13 +503,13 @@ mod tests {
 
         assert_eq!(feature.len_properties(), 0);
         assert_eq!(feature.property("foo"), None);
-        assert_eq!(feature.contains_property("foo"), false);
+        assert!(!feature.contains_property("foo"));
         assert_eq!(feature.properties_iter().collect::<Vec<_>>(), vec![]);
 
         feature.set_property("foo", 12);
         assert_eq!(feature.property("foo"), Some(&json!(12)));
         assert_eq!(feature.len_properties(), 1);
-        assert_eq!(feature.contains_property("foo"), true);
+        assert!(feature.contains_property("foo"));
         assert_eq!(
             feature.properties_iter().collect::<Vec<_>>(),
             vec![(&"foo".to_string(), &json!(12))]
@@ -458,7 +518,38 @@ mod tests {
         assert_eq!(Some(json!(12)), feature.remove_property("foo"));
         assert_eq!(feature.property("foo"), None);
         assert_eq!(feature.len_properties(), 0);
-        assert_eq!(feature.contains_property("foo"), false);
+        assert!(!feature.contains_property("foo"));
         assert_eq!(feature.properties_iter().collect::<Vec<_>>(), vec![]);
     }
+
+    #[test]
+    fn take_members() {
+        let mut feature = feature();
+        let geometry = feature.take_geometry().unwrap();
+        assert_eq!(geometry.into_value(), Value::Point(position![1.1, 2.1]));
+        assert_eq!(feature.take_properties(), properties());
+        assert_eq!(feature.geometry, None);
+        assert_eq!(feature.properties, None);
+
+        let collection = crate::FeatureCollection {
+            bbox: None,
+            features: vec![feature.clone()],
+            foreign_members: None,
+        };
+        assert_eq!(collection.clone().into_features(), vec![feature.clone()]);
+        assert_eq!(collection.into_iter().collect::<Vec<_>>(), vec![feature]);
+    }
+
+    #[test]
+    fn geometry_type() {
+        let mut feature = feature();
+        assert_eq!(feature.geometry_type(), Some(GeometryType::Point));
+        feature.geometry = Some(Geometry::new(Value::GeometryCollection(vec![])));
+        assert_eq!(
+            feature.geometry_type(),
+            Some(GeometryType::GeometryCollection)
+        );
+        feature.geometry = None;
+        assert_eq!(feature.geometry_type(), None);
+    }
 }
@@ -12,12 +12,20 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::convert::TryFrom;
+use alloc::borrow::ToOwned;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+#[cfg(feature = "std")]
+use core::hash::Hash;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
 
 use crate::errors::Error;
 use crate::json::{Deserialize, Deserializer, JsonObject, JsonValue, Serialize, Serializer};
-use crate::serde_json::json;
-use crate::{util, Bbox, Feature};
+use crate::{feature, util, Bbox, Feature, GeometryType};
+use serde_json::json;
 
 /// Feature Collection Objects
 ///
@@ -91,6 +99,326 @@ impl FeatureCollection {
     pub fn from_json_value(value: JsonValue) -> Result<Self, Error> {
         Self::try_from(value)
     }
+
+    /// Count the features of this collection by the type of their geometry.
+    ///
+    /// Features without a geometry are counted under `None`, and the others under the
+    /// type of their geometry, e.g. `Some(GeometryType::MultiPolygon)`. This is useful to choose a
+    /// schema for a format which only holds one type of geometry per layer.
+    ///
+    /// ```
+    /// use geojson::{position, Feature, FeatureCollection, GeometryType, Value};
+    ///
+    /// let collection = FeatureCollection {
+    ///     bbox: None,
+    ///     features: vec![
+    ///         Feature::from(Value::Point(position![0.0, 0.0])),
+    ///         Feature::from(Value::Point(position![1.0, 1.0])),
+    ///         Feature::from(Value::LineString(vec![position![0.0, 0.0], position![1.0, 1.0]])),
+    ///     ],
+    ///     foreign_members: None,
+    /// };
+    ///
+    /// let counts = collection.geometry_type_counts();
+    /// assert_eq!(counts[&Some(GeometryType::Point)], 2);
+    /// assert_eq!(counts[&Some(GeometryType::LineString)], 1);
+    /// assert_eq!(counts.get(&None), None);
+    /// ```
+    pub fn geometry_type_counts(&self) -> BTreeMap<Option<GeometryType>, usize> {
+        let mut counts = BTreeMap::new();
+        for feature in &self.features {
+            *counts.entry(feature.geometry_type()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Consume this collection, returning its features
+    pub fn into_features(self) -> Vec<Feature> {
+        self.features
+    }
+
+    /// The features for which `predicate` is true, e.g.
+    /// [`feature::has_property`](feature/fn.has_property.html)
+    pub fn filter_features<'a>(
+        &'a self,
+        mut predicate: impl FnMut(&Feature) -> bool + 'a,
+    ) -> impl Iterator<Item = &'a Feature> + 'a {
+        self.features
+            .iter()
+            .filter(move |feature| predicate(feature))
+    }
+
+    /// Keep only the features for which `predicate` is true, e.g.
+    /// [`feature::property_eq`](feature/fn.property_eq.html), in place
+    pub fn retain_features(&mut self, predicate: impl FnMut(&Feature) -> bool) {
+        self.features.retain(predicate);
+    }
+
+    /// Split the features into one collection for each `key` of a feature, such as the value of
+    /// a property, keeping their order.
+    ///
+    /// Every collection has a copy of the foreign members of this one, and no `bbox`.
+    ///
+    /// ```
+    /// use geojson::{position, Feature, FeatureCollection, Value};
+    ///
+    /// let place = |level: u64| {
+    ///     let mut feature = Feature::from(Value::Point(position![0.0, 0.0]));
+    ///     feature.set_property("admin_level", level);
+    ///     feature
+    /// };
+    /// let collection = FeatureCollection {
+    ///     bbox: None,
+    ///     features: vec![place(2), place(4), place(4)],
+    ///     foreign_members: None,
+    /// };
+    ///
+    /// let levels = collection.partition_by(|feature| feature.property("admin_level").cloned());
+    /// assert_eq!(levels[&Some(4.into())].features.len(), 2);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn partition_by<K: Eq + Hash>(
+        self,
+        mut key: impl FnMut(&Feature) -> K,
+    ) -> HashMap<K, FeatureCollection> {
+        let mut partitions = HashMap::new();
+        let foreign_members = self.foreign_members;
+        for feature in self.features {
+            partitions
+                .entry(key(&feature))
+                .or_insert_with(|| FeatureCollection {
+                    bbox: None,
+                    features: vec![],
+                    foreign_members: foreign_members.clone(),
+                })
+                .features
+                .push(feature);
+        }
+        partitions
+    }
+
+    /// Split the features into collections of `n` features each, except the last, which may
+    /// have fewer, for pages of a response or batches of an upload.
+    ///
+    /// Every collection has a copy of the foreign members of this one. If this collection has a
+    /// `bbox`, each has the bbox of its own features, and otherwise none.
+    ///
+    /// # Panics
+    ///
+    /// If `n` is 0.
+    ///
+    /// ```
+    /// use geojson::{position, Feature, FeatureCollection, Value};
+    ///
+    /// let collection = FeatureCollection {
+    ///     bbox: None,
+    ///     features: vec![Feature::from(Value::Point(position![0.0, 0.0])); 25],
+    ///     foreign_members: None,
+    /// };
+    ///
+    /// let pages: Vec<_> = collection.chunks(10).map(|page| page.features.len()).collect();
+    /// assert_eq!(pages, [10, 10, 5]);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn chunks(&self, n: usize) -> impl Iterator<Item = FeatureCollection> + '_ {
+        self.features.chunks(n).map(move |features| {
+            let mut chunk = FeatureCollection {
+                bbox: None,
+                features: features.to_vec(),
+                foreign_members: self.foreign_members.clone(),
+            };
+            if self.bbox.is_some() {
+                chunk.bbox = chunk.compute_bbox();
+            }
+            chunk
+        })
+    }
+
+    /// The first feature whose id is `id`.
+    ///
+    /// This searches the features one by one. To look up many features, build an
+    /// [`IndexedFeatureCollection`](struct.IndexedFeatureCollection.html) with
+    /// [`index_by_id`](#method.index_by_id) instead.
+    pub fn get_by_id(&self, id: &feature::Id) -> Option<&Feature> {
+        self.features
+            .iter()
+            .find(|feature| feature.id.as_ref() == Some(id))
+    }
+
+    /// Remove and return the first feature whose id is `id`
+    pub fn remove_by_id(&mut self, id: &feature::Id) -> Option<Feature> {
+        let index = self
+            .features
+            .iter()
+            .position(|feature| feature.id.as_ref() == Some(id))?;
+        Some(self.features.remove(index))
+    }
+
+    /// Build a map from the ids of the features of this collection to their indices, to look
+    /// features up by id without searching the collection.
+    ///
+    /// ```
+    /// use geojson::feature::Id;
+    /// use geojson::{position, Feature, FeatureCollection, Value};
+    ///
+    /// let features = (0..1000)
+    ///     .map(|i| Feature {
+    ///         id: Some(Id::String(format!("road-{}", i))),
+    ///         ..Feature::from(Value::Point(position![i as f64, 0.0]))
+    ///     })
+    ///     .collect();
+    /// let collection = FeatureCollection {
+    ///     bbox: None,
+    ///     features,
+    ///     foreign_members: None,
+    /// };
+    ///
+    /// let mut indexed = collection.index_by_id();
+    /// let id = Id::String("road-500".to_string());
+    /// indexed.update_by_id(&id, |feature| feature.set_property("closed", true));
+    /// assert_eq!(indexed.get_by_id(&id).unwrap().property("closed"), Some(&true.into()));
+    /// assert!(indexed.remove_by_id(&id).is_some());
+    /// assert_eq!(indexed.get_by_id(&id), None);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn index_by_id(self) -> IndexedFeatureCollection {
+        IndexedFeatureCollection::new(self)
+    }
+}
+
+/// A feature id as a key of a `HashMap`, as `serde_json::Number` isn't `Hash` in every version
+/// this crate supports
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum IdKey {
+    String(String),
+    Number(String),
+}
+
+#[cfg(feature = "std")]
+impl From<&feature::Id> for IdKey {
+    fn from(id: &feature::Id) -> Self {
+        match id {
+            feature::Id::String(id) => IdKey::String(id.clone()),
+            feature::Id::Number(id) => IdKey::Number(id.to_string()),
+        }
+    }
+}
+
+/// A [`FeatureCollection`](struct.FeatureCollection.html) with a map from the ids of its
+/// features to their indices
+///
+/// Features are found by id in constant time. Where several features have the same id, the
+/// first of them is found. Removing a feature takes time linear in the size of the collection,
+/// as removing it from the middle of a `Vec` does.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct IndexedFeatureCollection {
+    collection: FeatureCollection,
+    indices: HashMap<IdKey, usize>,
+}
+
+#[cfg(feature = "std")]
+impl IndexedFeatureCollection {
+    /// Index the features of `collection` by their ids, in one pass over them
+    ///
+    /// Where several features have the same id, only the first of them is indexed. Features
+    /// without an id stay in the collection, but can't be found by id. String and number ids
+    /// are distinct, so `"1"` and `1` don't collide.
+    pub fn new(collection: FeatureCollection) -> Self {
+        let mut indexed = IndexedFeatureCollection {
+            collection,
+            indices: HashMap::new(),
+        };
+        indexed.reindex();
+        indexed
+    }
+
+    fn reindex(&mut self) {
+        self.indices.clear();
+        for (index, feature) in self.collection.features.iter().enumerate() {
+            if let Some(id) = &feature.id {
+                self.indices.entry(IdKey::from(id)).or_insert(index);
+            }
+        }
+    }
+
+    fn index_of(&self, id: &feature::Id) -> Option<usize> {
+        self.indices.get(&IdKey::from(id)).copied()
+    }
+
+    /// The collection, which can only be changed through this wrapper so that the map stays
+    /// up to date
+    pub fn collection(&self) -> &FeatureCollection {
+        &self.collection
+    }
+
+    /// Drop the map, returning the collection
+    pub fn into_inner(self) -> FeatureCollection {
+        self.collection
+    }
+
+    /// The first feature whose id is `id`
+    pub fn get_by_id(&self, id: &feature::Id) -> Option<&Feature> {
+        self.index_of(id)
+            .map(|index| &self.collection.features[index])
+    }
+
+    /// Change the first feature whose id is `id` with `update`, returning whether there is such
+    /// a feature. `update` may change the id of the feature too.
+    pub fn update_by_id(&mut self, id: &feature::Id, update: impl FnOnce(&mut Feature)) -> bool {
+        let index = match self.index_of(id) {
+            Some(index) => index,
+            None => return false,
+        };
+        let feature = &mut self.collection.features[index];
+        update(feature);
+        if feature.id.as_ref() != Some(id) {
+            self.reindex();
+        }
+        true
+    }
+
+    /// Remove and return the first feature whose id is `id`
+    pub fn remove_by_id(&mut self, id: &feature::Id) -> Option<Feature> {
+        let index = self.index_of(id)?;
+        let feature = self.collection.features.remove(index);
+        self.reindex();
+        Some(feature)
+    }
+
+    /// Add `feature` to the end of the collection
+    pub fn push(&mut self, feature: Feature) {
+        if let Some(id) = &feature.id {
+            self.indices
+                .entry(IdKey::from(id))
+                .or_insert(self.collection.features.len());
+        }
+        self.collection.features.push(feature);
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<FeatureCollection> for IndexedFeatureCollection {
+    fn from(collection: FeatureCollection) -> Self {
+        IndexedFeatureCollection::new(collection)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<IndexedFeatureCollection> for FeatureCollection {
+    fn from(indexed: IndexedFeatureCollection) -> Self {
+        indexed.into_inner()
+    }
+}
+
+impl IntoIterator for FeatureCollection {
+    type Item = Feature;
+    type IntoIter = alloc::vec::IntoIter<Feature>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.features.into_iter()
+    }
 }
 
 impl TryFrom<JsonObject> for FeatureCollection {
@@ -128,6 +456,10 @@ impl Serialize for FeatureCollection {
     where
         S: Serializer,
     {
+        #[cfg(feature = "compact")]
+        if !serializer.is_human_readable() {
+            return crate::compact::serialize_feature_collection(self, serializer);
+        }
         JsonObject::from(self).serialize(serializer)
     }
 }
@@ -137,10 +469,204 @@ impl<'de> Deserialize<'de> for FeatureCollection {
     where
         D: Deserializer<'de>,
     {
-        use serde::de::Error as SerdeError;
+        #[cfg(feature = "compact")]
+        if !deserializer.is_human_readable() {
+            return crate::compact::deserialize_feature_collection(deserializer);
+        }
+        crate::de::deserialize_feature_collection(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{feature, Feature, FeatureCollection, Geometry, GeometryType, Value};
+
+    #[test]
+    fn geometry_type_counts() {
+        let mut untyped = Feature::from(Value::Point(position![0.0, 0.0]));
+        untyped.geometry = None;
+        let collection = FeatureCollection {
+            bbox: None,
+            features: vec![
+                untyped,
+                Feature::from(Value::GeometryCollection(vec![Geometry::new(
+                    Value::Point(position![0.0, 0.0]),
+                )])),
+                Feature::from(Value::MultiPolygon(vec![])),
+            ],
+            foreign_members: None,
+        };
+        let counts: Vec<_> = collection.geometry_type_counts().into_iter().collect();
+        assert_eq!(
+            counts,
+            vec![
+                (None, 1),
+                (Some(GeometryType::MultiPolygon), 1),
+                (Some(GeometryType::GeometryCollection), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn filter_and_retain_features() {
+        let mut named = Feature::from(Value::Point(position![0.0, 0.0]));
+        named.set_property("name", "a");
+        named.set_property("lanes", 2);
+        let mut other = named.clone();
+        other.set_property("lanes", 4);
+        let unnamed = Feature::from(Value::Point(position![1.0, 1.0]));
+        let mut collection = FeatureCollection {
+            bbox: None,
+            features: vec![named.clone(), unnamed, other],
+            foreign_members: None,
+        };
+
+        assert_eq!(
+            collection
+                .filter_features(feature::has_property("name"))
+                .count(),
+            2
+        );
+        let wide: Vec<_> = collection
+            .filter_features(|feature| {
+                feature
+                    .property("lanes")
+                    .and_then(|lanes| lanes.as_u64())
+                    .is_some_and(|lanes| lanes > 2)
+            })
+            .collect();
+        assert_eq!(wide, vec![&collection.features[2]]);
+
+        collection.retain_features(feature::property_eq("lanes", 2));
+        assert_eq!(collection.features, vec![named]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn features_by_id() {
+        let feature = |id: feature::Id, name: &str| {
+            let mut feature = Feature::from(Value::Point(position![0.0, 0.0]));
+            feature.id = Some(id);
+            feature.set_property("name", name);
+            feature
+        };
+        let a = feature::Id::String("a".to_string());
+        let one = feature::Id::Number(1.into());
+        let missing = feature::Id::Number(2.into());
+        let mut collection = FeatureCollection {
+            bbox: None,
+            features: vec![
+                Feature::from(Value::Point(position![0.0, 0.0])),
+                feature(a.clone(), "first"),
+                feature(one.clone(), "one"),
+                feature(a.clone(), "second"),
+            ],
+            foreign_members: None,
+        };
+        let name = |feature: Option<&Feature>| feature.and_then(|f| f.property("name").cloned());
+
+        assert_eq!(name(collection.get_by_id(&a)), Some("first".into()));
+        assert_eq!(collection.get_by_id(&missing), None);
+        let mut indexed = collection.clone().index_by_id();
+        assert_eq!(name(indexed.get_by_id(&a)), Some("first".into()));
+        assert_eq!(name(indexed.get_by_id(&one)), Some("one".into()));
+        assert_eq!(indexed.get_by_id(&missing), None);
 
-        let val = JsonObject::deserialize(deserializer)?;
+        assert!(indexed.update_by_id(&one, |feature| feature.id = Some(missing.clone())));
+        assert!(!indexed.update_by_id(&one, |_| {}));
+        assert_eq!(name(indexed.get_by_id(&missing)), Some("one".into()));
 
-        FeatureCollection::from_json_object(val).map_err(|e| D::Error::custom(e.to_string()))
+        assert_eq!(
+            name(indexed.remove_by_id(&a).as_ref()),
+            Some("first".into())
+        );
+        assert_eq!(name(indexed.get_by_id(&a)), Some("second".into()));
+        indexed.push(feature(one.clone(), "pushed"));
+        assert_eq!(name(indexed.get_by_id(&one)), Some("pushed".into()));
+        assert_eq!(indexed.collection().features.len(), 4);
+
+        assert!(collection.remove_by_id(&a).is_some());
+        assert_eq!(name(collection.get_by_id(&a)), Some("second".into()));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn partition_by() {
+        use crate::json::JsonObject;
+        use std::iter::FromIterator;
+
+        let feature = |layer: &str, x: f64| {
+            let mut feature = Feature::from(Value::Point(position![x, 0.0]));
+            feature.set_property("layer", layer);
+            feature
+        };
+        let collection = FeatureCollection {
+            bbox: Some(vec![0.0, 0.0, 3.0, 0.0]),
+            features: vec![
+                feature("roads", 0.0),
+                feature("rivers", 1.0),
+                feature("roads", 2.0),
+                Feature::from(Value::Point(position![3.0, 0.0])),
+            ],
+            foreign_members: Some(JsonObject::from_iter([(
+                "source".to_string(),
+                "osm".into(),
+            )])),
+        };
+        let partitions = collection.clone().partition_by(|feature| {
+            feature
+                .property("layer")
+                .and_then(|layer| layer.as_str())
+                .map(str::to_owned)
+        });
+
+        assert_eq!(partitions.len(), 3);
+        let roads = &partitions[&Some("roads".to_string())];
+        assert_eq!(
+            roads.features,
+            vec![
+                collection.features[0].clone(),
+                collection.features[2].clone()
+            ]
+        );
+        assert_eq!(roads.bbox, None);
+        assert_eq!(roads.foreign_members, collection.foreign_members);
+        assert_eq!(
+            partitions[&None].features,
+            vec![collection.features[3].clone()]
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn chunks() {
+        use crate::json::JsonObject;
+        use std::iter::FromIterator;
+
+        let point = |x: f64| Feature::from(Value::Point(position![x, 0.0]));
+        let mut collection = FeatureCollection {
+            bbox: None,
+            features: (0..5).map(|x| point(x as f64)).collect(),
+            foreign_members: Some(JsonObject::from_iter([("page".to_string(), true.into())])),
+        };
+        let chunks: Vec<_> = collection.chunks(2).collect();
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[2].features, vec![point(4.0)]);
+        assert_eq!(chunks[0].bbox, None);
+        assert_eq!(chunks[1].foreign_members, collection.foreign_members);
+
+        collection.bbox = Some(vec![0.0, 0.0, 4.0, 0.0]);
+        let bboxes: Vec<_> = collection.chunks(3).map(|chunk| chunk.bbox).collect();
+        assert_eq!(
+            bboxes,
+            vec![
+                Some(vec![0.0, 0.0, 2.0, 0.0]),
+                Some(vec![3.0, 0.0, 4.0, 0.0])
+            ]
+        );
+        assert_eq!(
+            collection.chunks(10).next().unwrap().features,
+            collection.features
+        );
     }
 }
@@ -0,0 +1,273 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A C interface for parsing, validating and serializing GeoJSON.
+//!
+//! GeoJSON is parsed from a buffer of UTF-8 into an opaque `GeoJsonObject`, which is freed with
+//! [`geojson_free`]. Strings returned by these functions are NUL-terminated UTF-8 and freed with
+//! [`geojson_string_free`]. A function which fails returns `NULL`, and
+//! [`geojson_last_error`] then describes the failure. A panic within a function is caught
+//! rather than unwinding into the caller, and is reported as a failure.
+//!
+//! To build a C library, run
+//! `cargo rustc --release --features ffi --crate-type cdylib` (or `staticlib`), and generate a
+//! header with [cbindgen](https://github.com/mozilla/cbindgen), which reads the `cbindgen.toml`
+//! of this crate: `cbindgen --output geojson.h`.
+//!
+//! ```c
+//! const char *json = "{\"type\": \"Point\", \"coordinates\": [1, 2]}";
+//! GeoJsonObject *object = geojson_parse((const uint8_t *)json, strlen(json));
+//! if (object == NULL) {
+//!     fprintf(stderr, "%s\n", geojson_last_error());
+//! } else {
+//!     char *written = geojson_to_string(object, false);
+//!     puts(written);
+//!     geojson_string_free(written);
+//!     geojson_free(object);
+//! }
+//! ```
+//!
+//! [`geojson_free`]: fn.geojson_free.html
+//! [`geojson_string_free`]: fn.geojson_string_free.html
+//! [`geojson_last_error`]: fn.geojson_last_error.html
+
+use crate::{GeoJson, WriterOptions};
+use std::cell::RefCell;
+use std::ffi::CString;
+use std::os::raw::c_char;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: impl ToString) {
+    let message = CString::new(message.to_string().replace('\0', "\\0")).unwrap();
+    LAST_ERROR.with(|error| *error.borrow_mut() = Some(message));
+}
+
+/// The result of `f`, or `on_panic` if it panics, so that a panic never unwinds across the
+/// C interface, which would be undefined behaviour
+fn catch_panic<T>(on_panic: T, f: impl FnOnce() -> T) -> T {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        let message = match payload.downcast_ref::<&str>() {
+            Some(message) => message,
+            None => payload
+                .downcast_ref::<String>()
+                .map_or("unknown cause", String::as_str),
+        };
+        set_last_error(format!("panicked: {}", message));
+        on_panic
+    })
+}
+
+/// `string` as a C string, or `NULL` if it can't be one
+fn to_c_string(string: String) -> *mut c_char {
+    match CString::new(string) {
+        Ok(string) => string.into_raw(),
+        Err(err) => {
+            set_last_error(err);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// A parsed GeoJSON object
+pub struct GeoJsonObject(GeoJson);
+
+/// The type of a [`GeoJsonObject`](struct.GeoJsonObject.html)
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GeoJsonType {
+    Geometry,
+    Feature,
+    FeatureCollection,
+    /// The type couldn't be determined; see [`geojson_last_error`](fn.geojson_last_error.html)
+    Error,
+}
+
+/// Parse the `len` bytes of UTF-8 at `data` as GeoJSON, returning `NULL` if it isn't valid
+/// GeoJSON.
+///
+/// # Safety
+///
+/// `data` must point to `len` readable bytes, or may be `NULL` if `len` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn geojson_parse(data: *const u8, len: usize) -> *mut GeoJsonObject {
+    catch_panic(ptr::null_mut(), || {
+        let bytes = if len == 0 {
+            &[]
+        } else {
+            slice::from_raw_parts(data, len)
+        };
+        match serde_json::from_slice(bytes) {
+            Ok(geojson) => Box::into_raw(Box::new(GeoJsonObject(geojson))),
+            Err(err) => {
+                set_last_error(err);
+                ptr::null_mut()
+            }
+        }
+    })
+}
+
+/// Free an object from [`geojson_parse`](fn.geojson_parse.html). Freeing `NULL` does nothing.
+///
+/// # Safety
+///
+/// `object` must be `NULL` or an object from `geojson_parse` which hasn't been freed.
+#[no_mangle]
+pub unsafe extern "C" fn geojson_free(object: *mut GeoJsonObject) {
+    catch_panic((), || {
+        if !object.is_null() {
+            drop(Box::from_raw(object));
+        }
+    })
+}
+
+/// The type of `object`, or `Error` if it couldn't be determined
+///
+/// # Safety
+///
+/// `object` must be an object from [`geojson_parse`](fn.geojson_parse.html) which hasn't
+/// been freed.
+#[no_mangle]
+pub unsafe extern "C" fn geojson_type(object: *const GeoJsonObject) -> GeoJsonType {
+    catch_panic(GeoJsonType::Error, || match &(*object).0 {
+        GeoJson::Geometry(_) => GeoJsonType::Geometry,
+        GeoJson::Feature(_) => GeoJsonType::Feature,
+        GeoJson::FeatureCollection(_) => GeoJsonType::FeatureCollection,
+    })
+}
+
+/// Serialize `object` as GeoJSON, pretty-printed if `pretty` is set, or return `NULL` if it
+/// can't be. Non-finite coordinates, which JSON can't represent, are written as `null`.
+///
+/// # Safety
+///
+/// `object` must be an object from [`geojson_parse`](fn.geojson_parse.html) which hasn't
+/// been freed.
+#[no_mangle]
+pub unsafe extern "C" fn geojson_to_string(
+    object: *const GeoJsonObject,
+    pretty: bool,
+) -> *mut c_char {
+    catch_panic(ptr::null_mut(), || {
+        let geojson = &(*object).0;
+        let written = if pretty {
+            geojson.to_string_with(&WriterOptions::new().pretty(2))
+        } else {
+            serde_json::to_string(geojson)
+        };
+        match written {
+            Ok(written) => to_c_string(written),
+            Err(err) => {
+                set_last_error(err);
+                ptr::null_mut()
+            }
+        }
+    })
+}
+
+/// The reasons `object` isn't valid, a line each such as
+/// `/features/0/geometry/coordinates/0: ring intersects itself`, or an empty string if it is
+/// valid, or `NULL` if it couldn't be checked.
+///
+/// See [`Geometry::explain_invalidity`](../struct.Geometry.html#method.explain_invalidity).
+///
+/// # Safety
+///
+/// `object` must be an object from [`geojson_parse`](fn.geojson_parse.html) which hasn't
+/// been freed.
+#[no_mangle]
+pub unsafe extern "C" fn geojson_explain_invalidity(object: *const GeoJsonObject) -> *mut c_char {
+    catch_panic(ptr::null_mut(), || {
+        let mut explanation = String::new();
+        for invalidity in (*object).0.explain_invalidity() {
+            explanation.push_str(&invalidity.to_string());
+            explanation.push('\n');
+        }
+        to_c_string(explanation)
+    })
+}
+
+/// Free a string returned by one of these functions. Freeing `NULL` does nothing.
+///
+/// # Safety
+///
+/// `string` must be `NULL` or a string from one of these functions which hasn't been freed.
+#[no_mangle]
+pub unsafe extern "C" fn geojson_string_free(string: *mut c_char) {
+    catch_panic((), || {
+        if !string.is_null() {
+            drop(CString::from_raw(string));
+        }
+    })
+}
+
+/// The message of the last error of these functions on this thread, or `NULL` if there hasn't
+/// been one. It is valid until the next error on this thread.
+#[no_mangle]
+pub extern "C" fn geojson_last_error() -> *const c_char {
+    catch_panic(ptr::null(), || {
+        LAST_ERROR.with(|error| match &*error.borrow() {
+            Some(message) => message.as_ptr(),
+            None => ptr::null(),
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+
+    #[test]
+    fn parse_validate_and_write() {
+        let json = br#"{"type":"Feature","geometry":{"type":"LineString","coordinates":[[0,0]]},"properties":null}"#;
+        unsafe {
+            let object = geojson_parse(json.as_ptr(), json.len());
+            assert!(!object.is_null());
+            assert_eq!(geojson_type(object), GeoJsonType::Feature);
+
+            let explanation = geojson_explain_invalidity(object);
+            assert_eq!(
+                CStr::from_ptr(explanation).to_str().unwrap(),
+                "/geometry/coordinates: fewer than 2 positions\n"
+            );
+            geojson_string_free(explanation);
+
+            let written = geojson_to_string(object, false);
+            assert_eq!(
+                CStr::from_ptr(written).to_str().unwrap(),
+                r#"{"geometry":{"coordinates":[[0.0,0.0]],"type":"LineString"},"properties":{},"type":"Feature"}"#
+            );
+            geojson_string_free(written);
+            geojson_free(object);
+
+            assert!(geojson_parse(b"{}".as_ptr(), 2).is_null());
+            assert!(!geojson_last_error().is_null());
+            assert!(geojson_parse(ptr::null(), 0).is_null());
+        }
+    }
+
+    #[test]
+    fn panics_are_caught() {
+        let written: *mut c_char = catch_panic(ptr::null_mut(), || panic!("unexpected"));
+        assert!(written.is_null());
+        let error = unsafe { CStr::from_ptr(geojson_last_error()) };
+        assert_eq!(error.to_str().unwrap(), "panicked: unexpected");
+    }
+}
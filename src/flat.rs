@@ -0,0 +1,329 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Geometries with all of their coordinates in one flat buffer.
+//!
+//! A [`FlatGeometry`] stores the coordinates of every position one after another in a single
+//! `Vec<f64>`, and the structure of the geometry as offsets into it, as
+//! [GeoArrow](https://geoarrow.org) does. Code which reads or transforms every coordinate can
+//! then work through contiguous memory, rather than following a pointer to each position.
+//!
+//! Every geometry is stored as a list of parts, each a list of rings, each a list of positions:
+//!
+//! | Geometry          | Parts          | Rings of each part   | Positions of each ring |
+//! |-------------------|----------------|----------------------|------------------------|
+//! | `Point`           | 1              | 1                    | 1                      |
+//! | `MultiPoint`      | 1              | 1                    | every point            |
+//! | `LineString`      | 1              | 1                    | every position         |
+//! | `MultiLineString` | 1              | every line string    | every position         |
+//! | `Polygon`         | 1              | every ring           | every position         |
+//! | `MultiPolygon`    | every polygon  | every ring           | every position         |
+//!
+//! Part `i` is made of the rings `part_offsets[i]..part_offsets[i + 1]`, and ring `j` of the
+//! positions `ring_offsets[j]..ring_offsets[j + 1]`.
+//!
+//! # Example
+//!
+//! ```
+//! use geojson::flat::FlatGeometry;
+//! use geojson::{position, Value};
+//! use std::convert::TryFrom;
+//!
+//! let value = Value::MultiLineString(vec![
+//!     vec![position![0.0, 0.0], position![1.0, 1.0]],
+//!     vec![position![2.0, 2.0], position![3.0, 3.0], position![4.0, 4.0]],
+//! ]);
+//! let flat = FlatGeometry::try_from(&value).unwrap();
+//! assert_eq!(flat.coords(), &[0.0, 0.0, 1.0, 1.0, 2.0, 2.0, 3.0, 3.0, 4.0, 4.0]);
+//! assert_eq!(flat.ring_offsets(), &[0, 2, 5]);
+//! assert_eq!(Value::from(flat), value);
+//! ```
+//!
+//! [`FlatGeometry`]: struct.FlatGeometry.html
+
+use crate::errors::Error;
+use crate::{Position, Value};
+use std::convert::TryFrom;
+
+/// The type of a [`FlatGeometry`](struct.FlatGeometry.html)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FlatGeometryType {
+    Point,
+    MultiPoint,
+    LineString,
+    MultiLineString,
+    Polygon,
+    MultiPolygon,
+}
+
+/// A geometry with the coordinates of all of its positions in one `Vec<f64>`
+///
+/// See the [module documentation](index.html) for its layout.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FlatGeometry {
+    geometry_type: FlatGeometryType,
+    dimensions: usize,
+    coords: Vec<f64>,
+    ring_offsets: Vec<usize>,
+    part_offsets: Vec<usize>,
+}
+
+impl FlatGeometry {
+    fn new(geometry_type: FlatGeometryType) -> Self {
+        FlatGeometry {
+            geometry_type,
+            dimensions: 0,
+            coords: Vec::new(),
+            ring_offsets: vec![0],
+            part_offsets: vec![0],
+        }
+    }
+
+    #[cfg(feature = "bumpalo")]
+    pub(crate) fn from_parts(
+        geometry_type: FlatGeometryType,
+        dimensions: usize,
+        coords: Vec<f64>,
+        ring_offsets: Vec<usize>,
+        part_offsets: Vec<usize>,
+    ) -> Self {
+        FlatGeometry {
+            geometry_type,
+            dimensions,
+            coords,
+            ring_offsets,
+            part_offsets,
+        }
+    }
+
+    /// The type of the geometry
+    pub fn geometry_type(&self) -> FlatGeometryType {
+        self.geometry_type
+    }
+
+    /// The number of coordinates of every position, or 0 if there are no positions
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    /// The coordinates of every position, one position after another
+    pub fn coords(&self) -> &[f64] {
+        &self.coords
+    }
+
+    /// The coordinates of every position, to be changed in place
+    pub fn coords_mut(&mut self) -> &mut [f64] {
+        &mut self.coords
+    }
+
+    /// The index of the first position of every ring, followed by the number of positions
+    pub fn ring_offsets(&self) -> &[usize] {
+        &self.ring_offsets
+    }
+
+    /// The index of the first ring of every part, followed by the number of rings
+    pub fn part_offsets(&self) -> &[usize] {
+        &self.part_offsets
+    }
+
+    /// The number of positions
+    pub fn num_positions(&self) -> usize {
+        self.ring_offsets[self.ring_offsets.len() - 1]
+    }
+
+    /// The coordinates of the position `index`
+    ///
+    /// # Panics
+    ///
+    /// If `index` isn't less than the number of positions.
+    pub fn position(&self, index: usize) -> &[f64] {
+        assert!(index < self.num_positions(), "position index out of range");
+        &self.coords[index * self.dimensions..(index + 1) * self.dimensions]
+    }
+
+    /// The coordinates of every position
+    pub fn positions(&self) -> impl Iterator<Item = &[f64]> {
+        (0..self.num_positions()).map(move |index| self.position(index))
+    }
+
+    fn push_ring(&mut self, positions: &[Position]) -> Result<(), Error> {
+        let start = self.num_positions();
+        for (index, position) in positions.iter().enumerate() {
+            if start + index == 0 {
+                self.dimensions = position.len();
+            } else if position.len() != self.dimensions {
+                return Err(Error::MixedDimensions(self.dimensions, position.len()));
+            }
+            self.coords.extend_from_slice(position);
+        }
+        self.ring_offsets.push(start + positions.len());
+        Ok(())
+    }
+
+    fn push_part(&mut self, rings: &[Vec<Position>]) -> Result<(), Error> {
+        for ring in rings {
+            self.push_ring(ring)?;
+        }
+        self.part_offsets.push(self.ring_offsets.len() - 1);
+        Ok(())
+    }
+
+    fn ring(&self, ring: usize) -> Vec<Position> {
+        (self.ring_offsets[ring]..self.ring_offsets[ring + 1])
+            .map(|index| Position::from_slice(self.position(index)))
+            .collect()
+    }
+
+    fn part(&self, part: usize) -> Vec<Vec<Position>> {
+        (self.part_offsets[part]..self.part_offsets[part + 1])
+            .map(|ring| self.ring(ring))
+            .collect()
+    }
+}
+
+impl TryFrom<&Value> for FlatGeometry {
+    type Error = Error;
+
+    /// Flatten `value`, which must not be a `GeometryCollection`, and whose positions must all
+    /// have the same number of coordinates.
+    fn try_from(value: &Value) -> Result<Self, Error> {
+        let geometry_type = match value {
+            Value::Point(_) => FlatGeometryType::Point,
+            Value::MultiPoint(_) => FlatGeometryType::MultiPoint,
+            Value::LineString(_) => FlatGeometryType::LineString,
+            Value::MultiLineString(_) => FlatGeometryType::MultiLineString,
+            Value::Polygon(_) => FlatGeometryType::Polygon,
+            Value::MultiPolygon(_) => FlatGeometryType::MultiPolygon,
+            Value::GeometryCollection(_) => {
+                return Err(Error::InvalidGeometryConversion(value.clone()))
+            }
+        };
+        let mut flat = FlatGeometry::new(geometry_type);
+        match value {
+            Value::Point(position) => flat.push_part(&[vec![position.clone()]])?,
+            Value::MultiPoint(positions) | Value::LineString(positions) => {
+                flat.push_ring(positions)?;
+                flat.part_offsets.push(1);
+            }
+            Value::MultiLineString(rings) | Value::Polygon(rings) => flat.push_part(rings)?,
+            Value::MultiPolygon(polygons) => {
+                for polygon in polygons {
+                    flat.push_part(polygon)?;
+                }
+            }
+            Value::GeometryCollection(_) => unreachable!(),
+        }
+        Ok(flat)
+    }
+}
+
+impl TryFrom<Value> for FlatGeometry {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Error> {
+        FlatGeometry::try_from(&value)
+    }
+}
+
+impl From<&FlatGeometry> for Value {
+    fn from(flat: &FlatGeometry) -> Self {
+        let positions = || flat.positions().map(Position::from_slice).collect();
+        let parts = || (0..flat.part_offsets.len() - 1).map(|part| flat.part(part));
+        match flat.geometry_type {
+            FlatGeometryType::Point => Value::Point(Position::from_slice(flat.position(0))),
+            FlatGeometryType::MultiPoint => Value::MultiPoint(positions()),
+            FlatGeometryType::LineString => Value::LineString(positions()),
+            FlatGeometryType::MultiLineString => {
+                Value::MultiLineString(parts().flatten().collect())
+            }
+            FlatGeometryType::Polygon => Value::Polygon(parts().flatten().collect()),
+            FlatGeometryType::MultiPolygon => Value::MultiPolygon(parts().collect()),
+        }
+    }
+}
+
+impl From<FlatGeometry> for Value {
+    fn from(flat: FlatGeometry) -> Self {
+        Value::from(&flat)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FlatGeometry, FlatGeometryType};
+    use crate::{Geometry, Value};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn round_trip() {
+        let square = vec![
+            position![0.0, 0.0],
+            position![1.0, 0.0],
+            position![1.0, 1.0],
+            position![0.0, 0.0],
+        ];
+        let values = vec![
+            Value::Point(position![1.0, 2.0, 3.0]),
+            Value::MultiPoint(vec![position![1.0, 2.0], position![3.0, 4.0]]),
+            Value::MultiPoint(vec![]),
+            Value::LineString(square.clone()),
+            Value::MultiLineString(vec![square.clone(), vec![]]),
+            Value::Polygon(vec![square.clone(), square.clone()]),
+            Value::MultiPolygon(vec![vec![square.clone()], vec![], vec![square.clone()]]),
+            Value::MultiPolygon(vec![]),
+        ];
+        for value in values {
+            let flat = FlatGeometry::try_from(&value).unwrap();
+            assert_eq!(Value::from(&flat), value);
+        }
+
+        let flat = FlatGeometry::try_from(Value::MultiPolygon(vec![
+            vec![square.clone(), square.clone()],
+            vec![square],
+        ]))
+        .unwrap();
+        assert_eq!(flat.geometry_type(), FlatGeometryType::MultiPolygon);
+        assert_eq!(flat.dimensions(), 2);
+        assert_eq!(flat.coords().len(), 24);
+        assert_eq!(flat.ring_offsets(), &[0, 4, 8, 12]);
+        assert_eq!(flat.part_offsets(), &[0, 2, 3]);
+        assert_eq!(flat.position(5), &[1.0, 0.0]);
+    }
+
+    #[test]
+    fn coords_mut() {
+        let value = Value::LineString(vec![position![1.0, 2.0], position![3.0, 4.0]]);
+        let mut flat = FlatGeometry::try_from(&value).unwrap();
+        flat.coords_mut().iter_mut().for_each(|coord| *coord *= 2.0);
+        assert_eq!(
+            Value::from(flat),
+            Value::LineString(vec![position![2.0, 4.0], position![6.0, 8.0]])
+        );
+    }
+
+    #[test]
+    fn invalid_geometries() {
+        assert!(FlatGeometry::try_from(Value::LineString(vec![
+            position![1.0, 2.0],
+            position![3.0, 4.0, 5.0],
+        ]))
+        .is_err());
+        assert!(
+            FlatGeometry::try_from(Value::GeometryCollection(vec![Geometry::new(
+                Value::Point(position![1.0, 2.0])
+            )]))
+            .is_err()
+        );
+    }
+}
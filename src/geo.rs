@@ -0,0 +1,872 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Implementations of [`geo`](https://docs.rs/geo) algorithm traits for GeoJSON types.
+//!
+//! Positions are treated as `Coord`s of their first two elements; any further elements are
+//! carried through `MapCoords` unchanged, and positions with fewer than two elements are
+//! skipped. Mapping the coordinates of a `Geometry` or `Feature` clears its `bbox`, which
+//! would otherwise be stale.
+//!
+//! The inherent `coords_iter`, `map_coords` and `try_map_coords` methods of these types, which
+//! work with GeoJSON positions and `(x, y)` tuples, shadow those of `CoordsIter`, `MapCoords`
+//! and `MapCoordsInPlace`; call the trait methods with fully qualified syntax, e.g.
+//! `MapCoords::map_coords(&feature, f)`.
+//!
+//! `centroid_feature` and `point_on_surface_feature` build on these to derive `Point` features,
+//! such as label points, from geometries and features.
+//!
+//! `is_valid` and `explain_invalidity` check geometries and features against the rules of
+//! RFC 7946 and then, where those hold, against those of `geo::Validation`, such as that
+//! polygon rings don't intersect themselves, with each problem at a path into the GeoJSON.
+
+use crate::algorithm::{bounding_rect, positions, try_for_each_position_mut};
+use crate::{Bbox, Feature, FeatureCollection, GeoJson, Geometry, Position, Value};
+use ::geo::validation::{
+    InvalidGeometry, InvalidLineString, InvalidMultiLineString, InvalidMultiPoint,
+    InvalidMultiPolygon, InvalidPolygon, RingRole,
+};
+use ::geo::{
+    BoundingRect, Centroid, CoordsIter, InteriorPoint, MapCoords, MapCoordsInPlace, Validation,
+};
+use geo_types::{Coord, Point, Rect};
+use std::convert::{Infallible, TryFrom};
+use std::fmt;
+
+fn coord(position: &Position) -> Option<Coord> {
+    match position.as_slice() {
+        [x, y, ..] => Some(Coord { x: *x, y: *y }),
+        _ => None,
+    }
+}
+
+fn try_map_position<E>(
+    position: &mut Position,
+    func: &impl Fn(Coord) -> Result<Coord, E>,
+) -> Result<(), E> {
+    if let Some(c) = coord(position) {
+        let mapped = func(c)?;
+        position[0] = mapped.x;
+        position[1] = mapped.y;
+    }
+    Ok(())
+}
+
+fn to_geo(value: &Value) -> Option<geo_types::Geometry> {
+    match value {
+        Value::GeometryCollection(_) => geo_types::GeometryCollection::try_from(value.clone())
+            .ok()
+            .map(geo_types::Geometry::GeometryCollection),
+        _ => geo_types::Geometry::try_from(value.clone()).ok(),
+    }
+}
+
+// Value
+
+#[cfg_attr(docsrs, doc(cfg(feature = "geo")))]
+impl BoundingRect<f64> for Value {
+    type Output = Option<Rect>;
+
+    fn bounding_rect(&self) -> Self::Output {
+        bounding_rect(self).map(|rect| {
+            Rect::new(
+                Coord {
+                    x: rect.min_x,
+                    y: rect.min_y,
+                },
+                Coord {
+                    x: rect.max_x,
+                    y: rect.max_y,
+                },
+            )
+        })
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "geo")))]
+impl CoordsIter for Value {
+    type Iter<'a> = Box<dyn Iterator<Item = Coord> + 'a>;
+    type ExteriorIter<'a> = Box<dyn Iterator<Item = Coord> + 'a>;
+    type Scalar = f64;
+
+    fn coords_iter(&self) -> Self::Iter<'_> {
+        Box::new(positions(self, false).filter_map(coord))
+    }
+
+    fn coords_count(&self) -> usize {
+        CoordsIter::coords_iter(self).count()
+    }
+
+    fn exterior_coords_iter(&self) -> Self::ExteriorIter<'_> {
+        Box::new(positions(self, true).filter_map(coord))
+    }
+}
+
+/// The centroid is computed by `geo` after converting to `geo_types`, and is `None` for
+/// empty geometries.
+#[cfg_attr(docsrs, doc(cfg(feature = "geo")))]
+impl Centroid for Value {
+    type Output = Option<Point>;
+
+    fn centroid(&self) -> Self::Output {
+        to_geo(self)?.centroid()
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "geo")))]
+impl MapCoords<f64, f64> for Value {
+    type Output = Value;
+
+    fn map_coords(&self, func: impl Fn(Coord) -> Coord + Copy) -> Self::Output {
+        let mut value = self.clone();
+        MapCoordsInPlace::map_coords_in_place(&mut value, func);
+        value
+    }
+
+    fn try_map_coords<E>(
+        &self,
+        func: impl Fn(Coord) -> Result<Coord, E> + Copy,
+    ) -> Result<Self::Output, E> {
+        let mut value = self.clone();
+        MapCoordsInPlace::try_map_coords_in_place(&mut value, func)?;
+        Ok(value)
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "geo")))]
+impl MapCoordsInPlace<f64> for Value {
+    fn map_coords_in_place(&mut self, func: impl Fn(Coord) -> Coord + Copy) {
+        let _ = MapCoordsInPlace::try_map_coords_in_place(self, |c| Ok::<_, Infallible>(func(c)));
+    }
+
+    fn try_map_coords_in_place<E>(
+        &mut self,
+        func: impl Fn(Coord) -> Result<Coord, E>,
+    ) -> Result<(), E> {
+        try_for_each_position_mut(self, &mut |position| try_map_position(position, &func))
+    }
+}
+
+// Geometry
+
+#[cfg_attr(docsrs, doc(cfg(feature = "geo")))]
+impl BoundingRect<f64> for Geometry {
+    type Output = Option<Rect>;
+
+    fn bounding_rect(&self) -> Self::Output {
+        self.value.bounding_rect()
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "geo")))]
+impl CoordsIter for Geometry {
+    type Iter<'a> = Box<dyn Iterator<Item = Coord> + 'a>;
+    type ExteriorIter<'a> = Box<dyn Iterator<Item = Coord> + 'a>;
+    type Scalar = f64;
+
+    fn coords_iter(&self) -> Self::Iter<'_> {
+        CoordsIter::coords_iter(&self.value)
+    }
+
+    fn coords_count(&self) -> usize {
+        self.value.coords_count()
+    }
+
+    fn exterior_coords_iter(&self) -> Self::ExteriorIter<'_> {
+        self.value.exterior_coords_iter()
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "geo")))]
+impl Centroid for Geometry {
+    type Output = Option<Point>;
+
+    fn centroid(&self) -> Self::Output {
+        self.value.centroid()
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "geo")))]
+impl MapCoords<f64, f64> for Geometry {
+    type Output = Geometry;
+
+    fn map_coords(&self, func: impl Fn(Coord) -> Coord + Copy) -> Self::Output {
+        Geometry {
+            bbox: None,
+            value: MapCoords::map_coords(&self.value, func),
+            foreign_members: self.foreign_members.clone(),
+        }
+    }
+
+    fn try_map_coords<E>(
+        &self,
+        func: impl Fn(Coord) -> Result<Coord, E> + Copy,
+    ) -> Result<Self::Output, E> {
+        Ok(Geometry {
+            bbox: None,
+            value: MapCoords::try_map_coords(&self.value, func)?,
+            foreign_members: self.foreign_members.clone(),
+        })
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "geo")))]
+impl MapCoordsInPlace<f64> for Geometry {
+    fn map_coords_in_place(&mut self, func: impl Fn(Coord) -> Coord + Copy) {
+        self.bbox = None;
+        MapCoordsInPlace::map_coords_in_place(&mut self.value, func)
+    }
+
+    fn try_map_coords_in_place<E>(
+        &mut self,
+        func: impl Fn(Coord) -> Result<Coord, E>,
+    ) -> Result<(), E> {
+        self.bbox = None;
+        MapCoordsInPlace::try_map_coords_in_place(&mut self.value, func)
+    }
+}
+
+// Feature
+
+/// Features without a geometry have no bounding rectangle.
+#[cfg_attr(docsrs, doc(cfg(feature = "geo")))]
+impl BoundingRect<f64> for Feature {
+    type Output = Option<Rect>;
+
+    fn bounding_rect(&self) -> Self::Output {
+        self.geometry.as_ref()?.bounding_rect()
+    }
+}
+
+/// Features without a geometry have no coordinates.
+#[cfg_attr(docsrs, doc(cfg(feature = "geo")))]
+impl CoordsIter for Feature {
+    type Iter<'a> = Box<dyn Iterator<Item = Coord> + 'a>;
+    type ExteriorIter<'a> = Box<dyn Iterator<Item = Coord> + 'a>;
+    type Scalar = f64;
+
+    fn coords_iter(&self) -> Self::Iter<'_> {
+        match &self.geometry {
+            Some(geometry) => CoordsIter::coords_iter(geometry),
+            None => Box::new(std::iter::empty()),
+        }
+    }
+
+    fn coords_count(&self) -> usize {
+        self.geometry.as_ref().map_or(0, CoordsIter::coords_count)
+    }
+
+    fn exterior_coords_iter(&self) -> Self::ExteriorIter<'_> {
+        match &self.geometry {
+            Some(geometry) => geometry.exterior_coords_iter(),
+            None => Box::new(std::iter::empty()),
+        }
+    }
+}
+
+/// Features without a geometry have no centroid.
+#[cfg_attr(docsrs, doc(cfg(feature = "geo")))]
+impl Centroid for Feature {
+    type Output = Option<Point>;
+
+    fn centroid(&self) -> Self::Output {
+        self.geometry.as_ref()?.centroid()
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "geo")))]
+impl MapCoords<f64, f64> for Feature {
+    type Output = Feature;
+
+    fn map_coords(&self, func: impl Fn(Coord) -> Coord + Copy) -> Self::Output {
+        Feature {
+            bbox: None,
+            geometry: self
+                .geometry
+                .as_ref()
+                .map(|geometry| MapCoords::map_coords(geometry, func)),
+            id: self.id.clone(),
+            properties: self.properties.clone(),
+            foreign_members: self.foreign_members.clone(),
+        }
+    }
+
+    fn try_map_coords<E>(
+        &self,
+        func: impl Fn(Coord) -> Result<Coord, E> + Copy,
+    ) -> Result<Self::Output, E> {
+        Ok(Feature {
+            bbox: None,
+            geometry: self
+                .geometry
+                .as_ref()
+                .map(|geometry| MapCoords::try_map_coords(geometry, func))
+                .transpose()?,
+            id: self.id.clone(),
+            properties: self.properties.clone(),
+            foreign_members: self.foreign_members.clone(),
+        })
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "geo")))]
+impl MapCoordsInPlace<f64> for Feature {
+    fn map_coords_in_place(&mut self, func: impl Fn(Coord) -> Coord + Copy) {
+        self.bbox = None;
+        if let Some(geometry) = &mut self.geometry {
+            MapCoordsInPlace::map_coords_in_place(geometry, func)
+        }
+    }
+
+    fn try_map_coords_in_place<E>(
+        &mut self,
+        func: impl Fn(Coord) -> Result<Coord, E>,
+    ) -> Result<(), E> {
+        self.bbox = None;
+        match &mut self.geometry {
+            Some(geometry) => MapCoordsInPlace::try_map_coords_in_place(geometry, func),
+            None => Ok(()),
+        }
+    }
+}
+
+// Label points
+
+fn point_feature(point: Point, source: Option<&Feature>) -> Feature {
+    Feature {
+        bbox: None,
+        geometry: Some(Geometry::new(Value::from(&point))),
+        id: source.and_then(|feature| feature.id.clone()),
+        properties: source.and_then(|feature| feature.properties.clone()),
+        foreign_members: None,
+    }
+}
+
+fn point_on_surface(value: &Value) -> Option<Point> {
+    to_geo(value)?.interior_point()
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "geo")))]
+impl Geometry {
+    /// A `Point` feature at the centroid of this geometry, or `None` if it is empty.
+    ///
+    /// The centroid of a concave polygon can be outside of it; see
+    /// [`point_on_surface_feature`](#method.point_on_surface_feature).
+    pub fn centroid_feature(&self) -> Option<Feature> {
+        Centroid::centroid(self).map(|point| point_feature(point, None))
+    }
+
+    /// A `Point` feature at a point on this geometry, which is within it if it is a polygon,
+    /// or `None` if it is empty.
+    ///
+    /// The point is computed by `geo::InteriorPoint`, and is near the centroid where possible.
+    pub fn point_on_surface_feature(&self) -> Option<Feature> {
+        point_on_surface(&self.value).map(|point| point_feature(point, None))
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "geo")))]
+impl Feature {
+    /// A `Point` feature at the centroid of this feature's geometry, with this feature's `id`
+    /// and `properties`, or `None` if it has no geometry or an empty one.
+    ///
+    /// This is not called `centroid`, which would shadow `geo::Centroid::centroid`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geojson::{position, Feature, Value};
+    ///
+    /// let mut feature = Feature::from(Value::LineString(vec![position![0.0, 0.0], position![4.0, 2.0]]));
+    /// feature.set_property("name", "Main Street");
+    ///
+    /// let label = feature.centroid_feature().unwrap();
+    /// assert_eq!(label.property("name").unwrap(), "Main Street");
+    /// assert_eq!(label.geometry.unwrap().value, Value::Point(position![2.0, 1.0]));
+    /// ```
+    pub fn centroid_feature(&self) -> Option<Feature> {
+        Centroid::centroid(self).map(|point| point_feature(point, Some(self)))
+    }
+
+    /// A `Point` feature at a point on this feature's geometry, which is within it if it is a
+    /// polygon, with this feature's `id` and `properties`, or `None` if it has no geometry or
+    /// an empty one.
+    ///
+    /// See [`Geometry::point_on_surface_feature`](struct.Geometry.html#method.point_on_surface_feature).
+    pub fn point_on_surface_feature(&self) -> Option<Feature> {
+        let geometry = self.geometry.as_ref()?;
+        point_on_surface(&geometry.value).map(|point| point_feature(point, Some(self)))
+    }
+}
+
+/// A reason a geometry isn't valid, from
+/// [`Geometry::explain_invalidity`](struct.Geometry.html#method.explain_invalidity) or
+/// [`Feature::explain_invalidity`](struct.Feature.html#method.explain_invalidity)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Invalidity {
+    /// A [JSON pointer](https://tools.ietf.org/html/rfc6901) to the invalid member of the
+    /// GeoJSON, such as `/coordinates/1` for the first hole of a polygon
+    pub path: String,
+    pub reason: String,
+}
+
+impl fmt::Display for Invalidity {
+    /// Write the path and the reason, e.g. `/coordinates/0: ring intersects itself`
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.reason)
+    }
+}
+
+fn invalidity(path: impl Into<String>, reason: impl Into<String>) -> Invalidity {
+    Invalidity {
+        path: path.into(),
+        reason: reason.into(),
+    }
+}
+
+fn check_bbox(bbox: &Option<Bbox>, path: &str, invalid: &mut Vec<Invalidity>) {
+    if let Some(bbox) = bbox {
+        if bbox.len() != 4 && bbox.len() != 6 {
+            invalid.push(invalidity(path, "bbox has neither 4 nor 6 values"));
+        }
+    }
+}
+
+fn check_position(position: &Position, path: String, invalid: &mut Vec<Invalidity>) {
+    if position.len() < 2 {
+        invalid.push(invalidity(path, "position has fewer than two coordinates"));
+    }
+}
+
+fn check_line(line: &[Position], min: usize, path: &str, invalid: &mut Vec<Invalidity>) {
+    for (i, position) in line.iter().enumerate() {
+        check_position(position, format!("{}/{}", path, i), invalid);
+    }
+    if line.len() < min {
+        let reason = format!("fewer than {} positions", min);
+        invalid.push(invalidity(path, reason));
+    }
+}
+
+fn check_polygon(polygon: &[Vec<Position>], path: &str, invalid: &mut Vec<Invalidity>) {
+    for (i, ring) in polygon.iter().enumerate() {
+        let path = format!("{}/{}", path, i);
+        check_line(ring, 4, &path, invalid);
+        if ring.len() >= 4 && ring.first() != ring.last() {
+            invalid.push(invalidity(path, "ring isn't closed"));
+        }
+    }
+}
+
+/// The index of a ring in the `coordinates` of its polygon
+fn ring_index(role: RingRole) -> usize {
+    match role {
+        RingRole::Exterior => 0,
+        RingRole::Interior(i) => i + 1,
+    }
+}
+
+fn line_string_invalidity(error: InvalidLineString, path: &str) -> Invalidity {
+    match error {
+        InvalidLineString::TooFewPoints => invalidity(path, "fewer than two distinct positions"),
+        InvalidLineString::NonFiniteCoord(i) => {
+            invalidity(format!("{}/{}", path, i.0), "non-finite coordinate")
+        }
+    }
+}
+
+fn polygon_invalidity(error: InvalidPolygon, path: &str) -> Invalidity {
+    let ring = |role| format!("{}/{}", path, ring_index(role));
+    match error {
+        InvalidPolygon::TooFewPointsInRing(role) => {
+            invalidity(ring(role), "ring has fewer than three distinct positions")
+        }
+        InvalidPolygon::SelfIntersection(role) => invalidity(ring(role), "ring intersects itself"),
+        InvalidPolygon::NonFiniteCoord(role, i) => {
+            invalidity(format!("{}/{}", ring(role), i.0), "non-finite coordinate")
+        }
+        InvalidPolygon::InteriorRingNotContainedInExteriorRing(role) => {
+            invalidity(ring(role), "hole isn't within the exterior ring")
+        }
+        InvalidPolygon::IntersectingRingsOnALine(a, b) => invalidity(
+            ring(a),
+            format!("ring touches ring {} along a line", ring_index(b)),
+        ),
+        InvalidPolygon::IntersectingRingsOnAnArea(a, b) => {
+            invalidity(ring(a), format!("ring overlaps ring {}", ring_index(b)))
+        }
+    }
+}
+
+fn geo_invalidity(error: InvalidGeometry, path: &str) -> Invalidity {
+    match error {
+        InvalidGeometry::InvalidPoint(_) => invalidity(path, "non-finite coordinate"),
+        InvalidGeometry::InvalidLineString(error) => line_string_invalidity(error, path),
+        InvalidGeometry::InvalidPolygon(error) => polygon_invalidity(error, path),
+        InvalidGeometry::InvalidMultiPoint(error) => {
+            let InvalidMultiPoint::InvalidPoint(i, _) = error;
+            invalidity(format!("{}/{}", path, i.0), "non-finite coordinate")
+        }
+        InvalidGeometry::InvalidMultiLineString(error) => {
+            let InvalidMultiLineString::InvalidLineString(i, error) = error;
+            line_string_invalidity(error, &format!("{}/{}", path, i.0))
+        }
+        InvalidGeometry::InvalidMultiPolygon(error) => match error {
+            InvalidMultiPolygon::InvalidPolygon(i, error) => {
+                polygon_invalidity(error, &format!("{}/{}", path, i.0))
+            }
+            InvalidMultiPolygon::ElementsOverlaps(i, j) => invalidity(
+                format!("{}/{}", path, i.0),
+                format!("polygon overlaps polygon {}", j.0),
+            ),
+            InvalidMultiPolygon::ElementsTouchOnALine(i, j) => invalidity(
+                format!("{}/{}", path, i.0),
+                format!("polygon touches polygon {} along a line", j.0),
+            ),
+        },
+        // Geometry collections are checked member by member, and GeoJSON has no lines,
+        // rectangles or triangles
+        error => invalidity(path, error.to_string()),
+    }
+}
+
+/// Check `geometry`, whose GeoJSON is at `path`
+fn check_geometry(geometry: &Geometry, path: &str, invalid: &mut Vec<Invalidity>) {
+    check_bbox(&geometry.bbox, &format!("{}/bbox", path), invalid);
+    let coordinates = format!("{}/coordinates", path);
+    let found = invalid.len();
+    match &geometry.value {
+        Value::Point(position) => check_position(position, coordinates.clone(), invalid),
+        Value::MultiPoint(positions) => check_line(positions, 0, &coordinates, invalid),
+        Value::LineString(line) => check_line(line, 2, &coordinates, invalid),
+        Value::MultiLineString(lines) => {
+            for (i, line) in lines.iter().enumerate() {
+                check_line(line, 2, &format!("{}/{}", coordinates, i), invalid);
+            }
+        }
+        Value::Polygon(polygon) => check_polygon(polygon, &coordinates, invalid),
+        Value::MultiPolygon(polygons) => {
+            for (i, polygon) in polygons.iter().enumerate() {
+                check_polygon(polygon, &format!("{}/{}", coordinates, i), invalid);
+            }
+        }
+        Value::GeometryCollection(geometries) => {
+            for (i, geometry) in geometries.iter().enumerate() {
+                check_geometry(geometry, &format!("{}/geometries/{}", path, i), invalid);
+            }
+            return;
+        }
+    }
+    // Only GeoJSON which follows RFC 7946 can be converted for `geo` to check
+    if invalid.len() == found {
+        if let Some(geometry) = to_geo(&geometry.value) {
+            let errors = geometry.validation_errors();
+            invalid.extend(
+                errors
+                    .into_iter()
+                    .map(|error| geo_invalidity(error, &coordinates)),
+            );
+        }
+    }
+}
+
+/// Check `feature`, whose GeoJSON is at `path`
+fn check_feature(feature: &Feature, path: &str, invalid: &mut Vec<Invalidity>) {
+    check_bbox(&feature.bbox, &format!("{}/bbox", path), invalid);
+    if let Some(geometry) = &feature.geometry {
+        check_geometry(geometry, &format!("{}/geometry", path), invalid);
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "geo")))]
+impl Geometry {
+    /// Whether this geometry is valid; see
+    /// [`explain_invalidity`](#method.explain_invalidity)
+    pub fn is_valid(&self) -> bool {
+        self.explain_invalidity().is_empty()
+    }
+
+    /// The reasons this geometry isn't valid, if any.
+    ///
+    /// These are first the rules of [RFC 7946](https://tools.ietf.org/html/rfc7946): a
+    /// `bbox` has 4 or 6 values, a position at least two coordinates, a `LineString` at least
+    /// two positions, and a polygon ring at least four, the last of which closes it. Each
+    /// geometry which follows them, or each member of a `GeometryCollection`, is then checked
+    /// with `geo::Validation`, which requires finite coordinates, rings which don't intersect
+    /// themselves or each other, holes within their exterior ring, and so on.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geojson::{position, Geometry, Value};
+    ///
+    /// let bowtie = Geometry::new(Value::Polygon(vec![vec![
+    ///     position![0.0, 0.0],
+    ///     position![2.0, 2.0],
+    ///     position![2.0, 0.0],
+    ///     position![0.0, 2.0],
+    ///     position![0.0, 0.0],
+    /// ]]));
+    /// assert!(!bowtie.is_valid());
+    /// assert_eq!(
+    ///     bowtie.explain_invalidity()[0].to_string(),
+    ///     "/coordinates/0: ring intersects itself"
+    /// );
+    /// ```
+    pub fn explain_invalidity(&self) -> Vec<Invalidity> {
+        let mut invalid = vec![];
+        check_geometry(self, "", &mut invalid);
+        invalid
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "geo")))]
+impl Feature {
+    /// Whether this feature is valid; see
+    /// [`explain_invalidity`](#method.explain_invalidity)
+    pub fn is_valid(&self) -> bool {
+        self.explain_invalidity().is_empty()
+    }
+
+    /// The reasons this feature's `bbox` or geometry aren't valid, if any, with paths such as
+    /// `/geometry/coordinates/0`. A feature without a geometry is valid.
+    ///
+    /// See [`Geometry::explain_invalidity`](struct.Geometry.html#method.explain_invalidity).
+    pub fn explain_invalidity(&self) -> Vec<Invalidity> {
+        let mut invalid = vec![];
+        check_feature(self, "", &mut invalid);
+        invalid
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "geo")))]
+impl FeatureCollection {
+    /// Whether this collection is valid; see
+    /// [`explain_invalidity`](#method.explain_invalidity)
+    pub fn is_valid(&self) -> bool {
+        self.explain_invalidity().is_empty()
+    }
+
+    /// The reasons this collection's `bbox` or features aren't valid, if any, with paths such
+    /// as `/features/3/geometry/coordinates/0`.
+    ///
+    /// See [`Feature::explain_invalidity`](struct.Feature.html#method.explain_invalidity).
+    pub fn explain_invalidity(&self) -> Vec<Invalidity> {
+        let mut invalid = vec![];
+        check_bbox(&self.bbox, "/bbox", &mut invalid);
+        for (i, feature) in self.features.iter().enumerate() {
+            check_feature(feature, &format!("/features/{}", i), &mut invalid);
+        }
+        invalid
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "geo")))]
+impl GeoJson {
+    /// Whether this object is valid; see
+    /// [`Geometry::explain_invalidity`](struct.Geometry.html#method.explain_invalidity)
+    pub fn is_valid(&self) -> bool {
+        self.explain_invalidity().is_empty()
+    }
+
+    /// The reasons this object isn't valid, if any.
+    ///
+    /// See [`Geometry::explain_invalidity`](struct.Geometry.html#method.explain_invalidity).
+    pub fn explain_invalidity(&self) -> Vec<Invalidity> {
+        match self {
+            GeoJson::Geometry(geometry) => geometry.explain_invalidity(),
+            GeoJson::Feature(feature) => feature.explain_invalidity(),
+            GeoJson::FeatureCollection(collection) => collection.explain_invalidity(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Feature, FeatureCollection, GeoJson, Geometry, Value};
+    use geo::{BoundingRect, Centroid, CoordsIter, MapCoords, MapCoordsInPlace};
+    use geo_types::{coord, point, Rect};
+
+    fn square() -> Value {
+        Value::Polygon(vec![
+            vec![
+                position![0.0, 0.0, 5.0],
+                position![4.0, 0.0, 5.0],
+                position![4.0, 4.0, 5.0],
+                position![0.0, 4.0, 5.0],
+                position![0.0, 0.0, 5.0],
+            ],
+            vec![
+                position![1.0, 1.0],
+                position![1.0, 2.0],
+                position![2.0, 2.0],
+                position![1.0, 1.0],
+            ],
+        ])
+    }
+
+    #[test]
+    fn bounding_rect_and_centroid() {
+        let feature = Feature::from(square());
+        assert_eq!(
+            feature.bounding_rect(),
+            Some(Rect::new(coord! { x: 0., y: 0. }, coord! { x: 4., y: 4. }))
+        );
+        let centroid = Value::MultiPoint(vec![position![0.0, 0.0], position![2.0, 4.0]]).centroid();
+        assert_eq!(centroid, Some(point! { x: 1., y: 2. }));
+
+        let collection =
+            Value::GeometryCollection(vec![Geometry::new(Value::Point(position![3.0, 3.0]))]);
+        assert_eq!(collection.centroid(), Some(point! { x: 3., y: 3. }));
+        assert_eq!(Value::MultiPoint(vec![]).centroid(), None);
+    }
+
+    #[test]
+    fn coords_iter() {
+        let value = square();
+        assert_eq!(value.coords_count(), 9);
+        assert_eq!(value.exterior_coords_iter().count(), 5);
+        assert_eq!(
+            CoordsIter::coords_iter(&value).nth(1),
+            Some(coord! { x: 4., y: 0. })
+        );
+    }
+
+    #[test]
+    fn map_coords_keeps_extra_dimensions() {
+        let mut feature = Feature::from(square());
+        feature.bbox = Some(vec![0.0, 0.0, 4.0, 4.0]);
+        let mapped = MapCoords::map_coords(&feature, |c| coord! { x: c.x + 10., y: c.y });
+        assert_eq!(mapped.bbox, None);
+        match mapped.geometry.unwrap().value {
+            Value::Polygon(rings) => {
+                assert_eq!(rings[0][1], position![14.0, 0.0, 5.0]);
+                assert_eq!(rings[1][0], position![11.0, 1.0]);
+            }
+            _ => unreachable!(),
+        }
+
+        let mut value = square();
+        let result = MapCoordsInPlace::try_map_coords_in_place(&mut value, |c| {
+            if c.x > 3.0 {
+                Err("too far east")
+            } else {
+                Ok(c)
+            }
+        });
+        assert_eq!(result, Err("too far east"));
+    }
+
+    #[test]
+    fn label_points() {
+        // A U shape, whose centroid is in the gap between its arms
+        let u = Value::Polygon(vec![vec![
+            position![0.0, 0.0],
+            position![3.0, 0.0],
+            position![3.0, 3.0],
+            position![2.0, 3.0],
+            position![2.0, 1.0],
+            position![1.0, 1.0],
+            position![1.0, 3.0],
+            position![0.0, 3.0],
+            position![0.0, 0.0],
+        ]]);
+        let mut feature = Feature::from(u);
+        feature.id = Some(crate::feature::Id::Number(1.into()));
+        feature.set_property("name", "U");
+
+        let centroid = feature.centroid_feature().unwrap();
+        assert_eq!(centroid.id, feature.id);
+        assert_eq!(centroid.properties, feature.properties);
+        let centroid = point_xy(&centroid);
+        assert!(centroid.0 > 1.0 && centroid.0 < 2.0 && centroid.1 > 1.0);
+
+        let (x, y) = point_xy(&feature.point_on_surface_feature().unwrap());
+        assert!(!(x > 1.0 && x < 2.0 && y > 1.0), "{:?}", (x, y));
+        assert!((0.0..=3.0).contains(&x) && (0.0..=3.0).contains(&y));
+
+        let empty = Geometry::new(Value::MultiPolygon(vec![]));
+        assert_eq!(empty.centroid_feature(), None);
+        assert_eq!(empty.point_on_surface_feature(), None);
+    }
+
+    fn point_xy(feature: &Feature) -> (f64, f64) {
+        match &feature.geometry.as_ref().unwrap().value {
+            Value::Point(position) => (position[0], position[1]),
+            value => panic!("expected a point, got {:?}", value),
+        }
+    }
+
+    #[test]
+    fn validity() {
+        let geometry = |json: serde_json::Value| serde_json::from_value::<Geometry>(json).unwrap();
+        let reasons = |geometry: &Geometry| -> Vec<String> {
+            geometry
+                .explain_invalidity()
+                .iter()
+                .map(ToString::to_string)
+                .collect()
+        };
+        assert!(Geometry::new(square()).is_valid());
+
+        let invalid = geometry(serde_json::json!({
+            "type": "GeometryCollection",
+            "geometries": [
+                { "type": "LineString", "coordinates": [[0, 0]] },
+                { "type": "Polygon", "coordinates": [[[0, 0], [1, 0], [1, 1], [0, 1]]], "bbox": [0, 0, 1] },
+                { "type": "MultiPoint", "coordinates": [[0, 0], [1]] },
+                { "type": "Polygon", "coordinates": [
+                    [[0, 0], [4, 0], [4, 4], [0, 4], [0, 0]],
+                    [[5, 5], [6, 5], [6, 6], [5, 5]]
+                ] },
+                { "type": "MultiPolygon", "coordinates": [
+                    [[[0, 0], [2, 0], [2, 2], [0, 2], [0, 0]]],
+                    [[[1, 1], [3, 1], [3, 3], [1, 3], [1, 1]]]
+                ] },
+                { "type": "LineString", "coordinates": [[1, 1], [1, 1]] }
+            ]
+        }));
+        assert!(!invalid.is_valid());
+        assert_eq!(
+            reasons(&invalid),
+            [
+                "/geometries/0/coordinates: fewer than 2 positions",
+                "/geometries/1/bbox: bbox has neither 4 nor 6 values",
+                "/geometries/1/coordinates/0: ring isn't closed",
+                "/geometries/2/coordinates/1: position has fewer than two coordinates",
+                "/geometries/3/coordinates/1: hole isn't within the exterior ring",
+                "/geometries/4/coordinates/0: polygon overlaps polygon 1",
+                "/geometries/5/coordinates: fewer than two distinct positions",
+            ]
+        );
+
+        let mut feature = Feature::from(Value::Point(position![f64::NAN, 0.0]));
+        assert_eq!(
+            feature.explain_invalidity()[0].to_string(),
+            "/geometry/coordinates: non-finite coordinate"
+        );
+        feature.geometry = None;
+        assert!(feature.is_valid());
+
+        let collection = FeatureCollection {
+            bbox: None,
+            features: vec![feature, Feature::from(Value::LineString(vec![]))],
+            foreign_members: None,
+        };
+        assert_eq!(
+            GeoJson::from(collection).explain_invalidity()[0].to_string(),
+            "/features/1/geometry/coordinates: fewer than 2 positions"
+        );
+    }
+}
@@ -0,0 +1,304 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Encoding and decoding of [geohashes](https://en.wikipedia.org/wiki/Geohash)
+//!
+//! Coordinates are interpreted as WGS 84 longitude and latitude, in that order, as
+//! required by [GeoJSON Format Specification § 4](https://tools.ietf.org/html/rfc7946#section-4).
+//!
+//! # Example
+//!
+//! ```
+//! use geojson::{geohash, position, Geometry, Value};
+//!
+//! let point = Geometry::new(Value::Point(position![-5.6, 42.6]));
+//! assert_eq!(point.geohash(5).unwrap(), "ezs42");
+//!
+//! let cell = geohash::decode("ezs42").unwrap();
+//! assert_eq!(cell.property("geohash"), Some(&"ezs42".into()));
+//! ```
+
+use crate::algorithm::intersects::rect_intersects_value;
+use crate::algorithm::{bounding_rect, Rect};
+use crate::errors::Error;
+use crate::{Bbox, Feature, Geometry, Value};
+
+const BASE32: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// The longest supported geohash, which resolves to cells of a few centimetres
+pub const MAX_PRECISION: usize = 12;
+
+fn check_precision(precision: usize) -> Result<(), Error> {
+    if precision == 0 || precision > MAX_PRECISION {
+        Err(Error::GeohashInvalidPrecision(precision))
+    } else {
+        Ok(())
+    }
+}
+
+/// The number of longitude and latitude bits in a geohash of `precision` characters
+fn cell_bits(precision: usize) -> (u32, u32) {
+    let bits = 5 * precision as u32;
+    (bits.div_ceil(2), bits / 2)
+}
+
+/// Encode a longitude/latitude pair as a geohash of `precision` characters
+pub fn encode(x: f64, y: f64, precision: usize) -> Result<String, Error> {
+    check_precision(precision)?;
+    if !(-180.0..=180.0).contains(&x) || !(-90.0..=90.0).contains(&y) {
+        return Err(Error::GeohashCoordinateOutOfRange(x, y));
+    }
+    let (mut lon, mut lat) = ((-180.0, 180.0), (-90.0, 90.0));
+    let mut hash = String::with_capacity(precision);
+    let mut even = true;
+    for _ in 0..precision {
+        let mut index = 0;
+        for _ in 0..5 {
+            let (range, value) = if even { (&mut lon, x) } else { (&mut lat, y) };
+            let mid = (range.0 + range.1) / 2.0;
+            index <<= 1;
+            if value >= mid {
+                index |= 1;
+                range.0 = mid;
+            } else {
+                range.1 = mid;
+            }
+            even = !even;
+        }
+        hash.push(BASE32[index] as char);
+    }
+    Ok(hash)
+}
+
+/// Decode a geohash into the `[min_x, min_y, max_x, max_y]` extent of its cell
+pub fn decode_bbox(hash: &str) -> Result<Bbox, Error> {
+    check_precision(hash.len())?;
+    let (mut lon, mut lat) = ((-180.0, 180.0), (-90.0, 90.0));
+    let mut even = true;
+    for c in hash.chars() {
+        let index = BASE32
+            .iter()
+            .position(|&b| b as char == c.to_ascii_lowercase())
+            .ok_or_else(|| Error::InvalidGeohash(hash.to_string()))?;
+        for bit in (0..5).rev() {
+            let range: &mut (f64, f64) = if even { &mut lon } else { &mut lat };
+            let mid = (range.0 + range.1) / 2.0;
+            if (index >> bit) & 1 == 1 {
+                range.0 = mid;
+            } else {
+                range.1 = mid;
+            }
+            even = !even;
+        }
+    }
+    Ok(vec![lon.0, lat.0, lon.1, lat.1])
+}
+
+/// Decode a geohash into a `Feature` whose `Polygon` geometry traces the extent of its cell.
+///
+/// The geohash itself is stored in the `"geohash"` property, and the cell extent as the `bbox`.
+pub fn decode(hash: &str) -> Result<Feature, Error> {
+    let bbox = decode_bbox(hash)?;
+    let rect = Rect::from_bbox([bbox[0], bbox[1], bbox[2], bbox[3]]);
+    let mut feature = Feature::from(Value::Polygon(vec![rect.to_ring()]));
+    feature.bbox = Some(bbox);
+    feature.set_property("geohash", hash);
+    Ok(feature)
+}
+
+/// All geohashes of `precision` characters whose cells intersect
+/// the `[min_x, min_y, max_x, max_y]` rectangle, in lexicographic order.
+///
+/// A rectangle whose `min_x` is greater than its `max_x` crosses the antimeridian, as
+/// described in [GeoJSON Format Specification § 5.2](https://tools.ietf.org/html/rfc7946#section-5.2),
+/// and is covered on both sides of it.
+///
+/// The number of cells grows by a factor of 32 with each additional character, so
+/// large extents should be covered with a low precision.
+pub fn cover_bbox(bbox: [f64; 4], precision: usize) -> Result<Vec<String>, Error> {
+    let [min_x, min_y, max_x, max_y] = bbox;
+    if min_x <= max_x {
+        return cover(Rect::from_bbox(bbox), precision, |_| true);
+    }
+    let mut hashes = cover(
+        Rect::from_bbox([min_x, min_y, 180.0, max_y]),
+        precision,
+        |_| true,
+    )?;
+    hashes.extend(cover(
+        Rect::from_bbox([-180.0, min_y, max_x, max_y]),
+        precision,
+        |_| true,
+    )?);
+    hashes.sort();
+    hashes.dedup();
+    Ok(hashes)
+}
+
+fn cover(
+    rect: Rect,
+    precision: usize,
+    mut filter: impl FnMut(&Rect) -> bool,
+) -> Result<Vec<String>, Error> {
+    check_precision(precision)?;
+    let (lon_bits, lat_bits) = cell_bits(precision);
+    let (columns, rows) = (1u64 << lon_bits, 1u64 << lat_bits);
+    let (width, height) = (360.0 / columns as f64, 180.0 / rows as f64);
+    let column = |x: f64| (((x + 180.0) / width).floor().max(0.0) as u64).min(columns - 1);
+    let row = |y: f64| (((y + 90.0) / height).floor().max(0.0) as u64).min(rows - 1);
+
+    let mut hashes = vec![];
+    for i in column(rect.min_x)..=column(rect.max_x) {
+        for j in row(rect.min_y)..=row(rect.max_y) {
+            let cell = Rect {
+                min_x: -180.0 + i as f64 * width,
+                min_y: -90.0 + j as f64 * height,
+                max_x: -180.0 + (i + 1) as f64 * width,
+                max_y: -90.0 + (j + 1) as f64 * height,
+            };
+            if filter(&cell) {
+                let (x, y) = (
+                    (cell.min_x + cell.max_x) / 2.0,
+                    (cell.min_y + cell.max_y) / 2.0,
+                );
+                hashes.push(encode(x, y, precision)?);
+            }
+        }
+    }
+    hashes.sort();
+    Ok(hashes)
+}
+
+impl Value {
+    /// The geohash of a `Point`, with `precision` characters.
+    ///
+    /// Returns an error for any other geometry type.
+    pub fn geohash(&self, precision: usize) -> Result<String, Error> {
+        match self {
+            Value::Point(position) if position.len() >= 2 => {
+                encode(position[0], position[1], precision)
+            }
+            Value::Point(position) => Err(Error::PositionTooShort(position.len())),
+            _ => Err(Error::ExpectedType {
+                expected: "Point".to_string(),
+                actual: self.type_str().to_string(),
+            }),
+        }
+    }
+
+    /// All geohashes of `precision` characters whose cells intersect this geometry,
+    /// in lexicographic order.
+    pub fn geohash_cover(&self, precision: usize) -> Result<Vec<String>, Error> {
+        match bounding_rect(self) {
+            Some(rect) => cover(rect, precision, |cell| rect_intersects_value(cell, self)),
+            None => check_precision(precision).map(|_| vec![]),
+        }
+    }
+}
+
+impl Geometry {
+    /// The geohash of a `Point` geometry. See [`Value::geohash`](enum.Value.html#method.geohash).
+    pub fn geohash(&self, precision: usize) -> Result<String, Error> {
+        self.value.geohash(precision)
+    }
+
+    /// All geohashes covering this geometry.
+    /// See [`Value::geohash_cover`](enum.Value.html#method.geohash_cover).
+    pub fn geohash_cover(&self, precision: usize) -> Result<Vec<String>, Error> {
+        self.value.geohash_cover(precision)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cover_bbox, decode, decode_bbox, encode};
+    use crate::{Error, Value};
+
+    #[test]
+    fn encode_point() {
+        assert_eq!(encode(-5.6, 42.6, 5).unwrap(), "ezs42");
+        assert_eq!(encode(10.40744, 57.64911, 11).unwrap(), "u4pruydqqvj");
+        assert!(matches!(
+            encode(0.0, 0.0, 13),
+            Err(Error::GeohashInvalidPrecision(13))
+        ));
+        assert!(matches!(
+            encode(200.0, 0.0, 5),
+            Err(Error::GeohashCoordinateOutOfRange(..))
+        ));
+    }
+
+    #[test]
+    fn decode_cell() {
+        let bbox = decode_bbox("ezs42").unwrap();
+        assert!((bbox[0] - -5.625).abs() < 1e-9);
+        assert!((bbox[1] - 42.583_007_812_5).abs() < 1e-9);
+        assert!((bbox[2] - -5.581_054_687_5).abs() < 1e-9);
+        assert!((bbox[3] - 42.626_953_125).abs() < 1e-9);
+
+        let feature = decode("ezs42").unwrap();
+        assert_eq!(feature.bbox, Some(bbox));
+        match feature.geometry.unwrap().value {
+            Value::Polygon(rings) => assert_eq!(rings[0].len(), 5),
+            _ => unreachable!(),
+        }
+        assert!(matches!(decode("ezs4a"), Err(Error::InvalidGeohash(_))));
+    }
+
+    #[test]
+    fn cover_bbox_cells() {
+        let hashes = cover_bbox([-5.6, 42.6, -5.6, 42.6], 5).unwrap();
+        assert_eq!(hashes, vec!["ezs42".to_string()]);
+        let hashes = cover_bbox([-180.0, -90.0, 180.0, 90.0], 1).unwrap();
+        assert_eq!(hashes.len(), 32);
+
+        // Across the antimeridian, from 170°E to 170°W
+        let hashes = cover_bbox([170.0, 1.0, -170.0, 2.0], 1).unwrap();
+        assert_eq!(
+            hashes,
+            vec![
+                encode(-175.0, 1.0, 1).unwrap(),
+                encode(175.0, 1.0, 1).unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    fn cover_polygon_skips_outside_cells() {
+        // A diagonal triangle whose bbox spans 4 cells, but which misses the south-east one
+        let polygon = Value::Polygon(vec![vec![
+            position![-44.0, 1.0],
+            position![40.0, 89.0],
+            position![-44.0, 89.0],
+            position![-44.0, 1.0],
+        ]]);
+        let bbox_hashes = cover_bbox([-44.0, 1.0, 40.0, 89.0], 1).unwrap();
+        assert_eq!(bbox_hashes.len(), 4);
+        let south_east = encode(20.0, 20.0, 1).unwrap();
+        let expected: Vec<String> = bbox_hashes
+            .into_iter()
+            .filter(|hash| *hash != south_east)
+            .collect();
+        assert_eq!(polygon.geohash_cover(1).unwrap(), expected);
+
+        assert!(matches!(
+            polygon.geohash(5),
+            Err(Error::ExpectedType { .. })
+        ));
+        assert!(matches!(
+            Value::Point(position![1.0]).geohash(5),
+            Err(Error::PositionTooShort(1))
+        ));
+    }
+}
@@ -14,12 +14,14 @@
 
 use crate::errors::Error;
 use crate::json::{self, Deserialize, Deserializer, JsonObject, JsonValue, Serialize, Serializer};
-use crate::serde;
-use crate::{Feature, FeatureCollection, Geometry};
-use std::convert::TryFrom;
-use std::fmt;
-use std::iter::FromIterator;
-use std::str::FromStr;
+use crate::{Feature, FeatureCollection, Geometry, Position, Value};
+use alloc::borrow::ToOwned;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+use core::iter::FromIterator;
+use core::str::FromStr;
 
 /// GeoJSON Objects
 ///
@@ -158,7 +160,7 @@ impl GeoJson {
     /// # Example
     /// ```
     /// use std::convert::TryInto;
-    /// use geojson::{Feature, GeoJson, Geometry, Value};
+    /// use geojson::{position, Feature, GeoJson, Geometry, Value};
     /// use serde_json::json;
     ///
     /// let json_value = json!({
@@ -178,7 +180,7 @@ impl GeoJson {
     ///     geojson,
     ///     GeoJson::Feature(Feature {
     ///         bbox: None,
-    ///         geometry: Some(Geometry::new(Value::Point(vec![102.0, 0.5]))),
+    ///         geometry: Some(Geometry::new(Value::Point(position![102.0, 0.5]))),
     ///         id: None,
     ///         properties: None,
     ///         foreign_members: None,
@@ -221,12 +223,209 @@ impl GeoJson {
     }
 
     // Deserialize a GeoJson object from an IO stream of JSON
+    #[cfg(feature = "std")]
     pub fn from_reader<R>(rdr: R) -> Result<Self, serde_json::Error>
     where
         R: std::io::Read,
     {
         serde_json::from_reader(rdr)
     }
+
+    /// Parse a GeoJSON object from `s`, accepting what `options` allow as well as GeoJSON.
+    ///
+    /// See [`ReaderOptions`](struct.ReaderOptions.html).
+    pub fn from_str_with(s: &str, options: &ReaderOptions) -> Result<Self, Error> {
+        let value = ::serde_json::from_str(s).map_err(Error::MalformedJson)?;
+        GeoJson::from_json_value_with(value, options)
+    }
+
+    /// Convert a JSON value into a GeoJSON object, accepting what `options` allow as well as
+    /// GeoJSON.
+    ///
+    /// See [`ReaderOptions`](struct.ReaderOptions.html).
+    pub fn from_json_value_with(value: JsonValue, options: &ReaderOptions) -> Result<Self, Error> {
+        match value {
+            JsonValue::Object(mut object) => {
+                if options.lenient_types {
+                    normalize_types(&mut object, Error::GeometryUnknownType)?;
+                }
+                GeoJson::from_json_object(object)
+            }
+            JsonValue::Array(mut array) => {
+                if options.bare_coordinates {
+                    if let Some(value) = bare_coordinates(&array) {
+                        return Ok(GeoJson::from(value));
+                    }
+                }
+                if options.feature_arrays && array.iter().all(JsonValue::is_object) {
+                    if options.lenient_types {
+                        for feature in array.iter_mut().filter_map(JsonValue::as_object_mut) {
+                            normalize_types(feature, Error::NotAFeature)?;
+                        }
+                    }
+                    let features = array
+                        .into_iter()
+                        .map(Feature::try_from)
+                        .collect::<Result<_, _>>()?;
+                    return Ok(GeoJson::FeatureCollection(FeatureCollection {
+                        bbox: None,
+                        features,
+                        foreign_members: None,
+                    }));
+                }
+                Err(Error::ExpectedObjectValue(JsonValue::Array(array)))
+            }
+            other => Err(Error::ExpectedObjectValue(other)),
+        }
+    }
+}
+
+/// Options for parsing input which isn't strictly GeoJSON, with
+/// [`GeoJson::from_str_with`](enum.GeoJson.html#method.from_str_with)
+///
+/// # Example
+///
+/// ```
+/// use geojson::{position, GeoJson, ReaderOptions, Value};
+///
+/// let options = ReaderOptions::new().bare_coordinates(true);
+/// assert_eq!(
+///     GeoJson::from_str_with("[-0.1276, 51.5072]", &options).unwrap(),
+///     GeoJson::from(Value::Point(position![-0.1276, 51.5072]))
+/// );
+/// assert!(GeoJson::from_str_with("[-0.1276, 51.5072]", &ReaderOptions::new()).is_err());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ReaderOptions {
+    bare_coordinates: bool,
+    feature_arrays: bool,
+    lenient_types: bool,
+}
+
+impl ReaderOptions {
+    /// Options which accept the same GeoJSON as `from_str`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept a bare position, such as `[lon, lat]`, as a Point geometry, and a bare array of
+    /// two or more positions as a LineString geometry, as some APIs write in place of a
+    /// geometry.
+    pub fn bare_coordinates(mut self, bare_coordinates: bool) -> Self {
+        self.bare_coordinates = bare_coordinates;
+        self
+    }
+
+    /// Accept a bare array of Feature objects, as written by tools which collect features
+    /// with no FeatureCollection around them, as a FeatureCollection of those features.
+    pub fn feature_arrays(mut self, feature_arrays: bool) -> Self {
+        self.feature_arrays = feature_arrays;
+        self
+    }
+
+    /// Accept `type` members which name a GeoJSON type in another case, such as `"point"` or
+    /// `"FEATURE"`, and fail on a `type` which is a few letters from a GeoJSON type with an
+    /// [`Error::UnknownTypeDidYouMean`](enum.Error.html#variant.UnknownTypeDidYouMean)
+    /// suggesting it. Any other unknown `type` fails with
+    /// [`Error::GeometryUnknownType`](enum.Error.html#variant.GeometryUnknownType), or
+    /// [`Error::NotAFeature`](enum.Error.html#variant.NotAFeature) in place of a feature.
+    pub fn lenient_types(mut self, lenient_types: bool) -> Self {
+        self.lenient_types = lenient_types;
+        self
+    }
+}
+
+/// The GeoJSON types, as written in `type` members
+const TYPES: [&str; 9] = [
+    "Point",
+    "MultiPoint",
+    "LineString",
+    "MultiLineString",
+    "Polygon",
+    "MultiPolygon",
+    "GeometryCollection",
+    "Feature",
+    "FeatureCollection",
+];
+
+/// Replace the `type` of `object`, and of the GeoJSON objects nested in it, with the GeoJSON
+/// type it names in another case, or fail with the GeoJSON type it is most likely a misspelling
+/// of, or with `unknown` if it is like none of them
+fn normalize_types(object: &mut JsonObject, unknown: fn(String) -> Error) -> Result<(), Error> {
+    if let Some(JsonValue::String(type_)) = object.get_mut("type") {
+        if !type_.is_empty() && !TYPES.contains(&type_.as_str()) {
+            if let Some(known) = TYPES.iter().find(|known| known.eq_ignore_ascii_case(type_)) {
+                *type_ = known.to_string();
+            } else if let Some(suggestion) = suggest_type(type_) {
+                return Err(Error::UnknownTypeDidYouMean(type_.clone(), suggestion));
+            } else {
+                return Err(unknown(type_.clone()));
+            }
+        }
+    }
+    if let Some(JsonValue::Object(geometry)) = object.get_mut("geometry") {
+        normalize_types(geometry, Error::GeometryUnknownType)?;
+    }
+    for (key, unknown) in [
+        ("features", Error::NotAFeature as fn(String) -> Error),
+        ("geometries", Error::GeometryUnknownType),
+    ] {
+        if let Some(JsonValue::Array(members)) = object.get_mut(key) {
+            for member in members.iter_mut().filter_map(JsonValue::as_object_mut) {
+                normalize_types(member, unknown)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The GeoJSON type closest to `type_` by edit distance, ignoring case, if it is within a third
+/// of the length of that type
+fn suggest_type(type_: &str) -> Option<&'static str> {
+    TYPES
+        .iter()
+        .map(|known| (edit_distance(type_, known), *known))
+        .filter(|&(distance, known)| distance <= known.len() / 3)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, known)| known)
+}
+
+/// The Levenshtein distance between `a` and `b`, ignoring ASCII case
+fn edit_distance(a: &str, b: &str) -> usize {
+    let mut row: Vec<usize> = (0..=b.chars().count()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b) in b.chars().enumerate() {
+            let substitution = diagonal + usize::from(!a.eq_ignore_ascii_case(&b));
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[row.len() - 1]
+}
+
+/// A position of two or more numbers
+fn bare_position(values: &[JsonValue]) -> Option<Position> {
+    if values.len() < 2 {
+        return None;
+    }
+    values.iter().map(JsonValue::as_f64).collect()
+}
+
+/// The Point of a bare position, or the LineString of a bare array of positions
+fn bare_coordinates(values: &[JsonValue]) -> Option<Value> {
+    if let Some(position) = bare_position(values) {
+        return Some(Value::Point(position));
+    }
+    if values.len() < 2 {
+        return None;
+    }
+    values
+        .iter()
+        .map(|value| bare_position(value.as_array()?))
+        .collect::<Option<Vec<_>>>()
+        .map(Value::LineString)
 }
 
 impl TryFrom<JsonObject> for GeoJson {
@@ -295,6 +494,10 @@ impl Serialize for GeoJson {
     where
         S: Serializer,
     {
+        #[cfg(feature = "compact")]
+        if !serializer.is_human_readable() {
+            return crate::compact::serialize_geojson(self, serializer);
+        }
         JsonObject::from(self).serialize(serializer)
     }
 }
@@ -304,11 +507,11 @@ impl<'de> Deserialize<'de> for GeoJson {
     where
         D: Deserializer<'de>,
     {
-        use serde::de::Error as SerdeError;
-
-        let val = JsonObject::deserialize(deserializer)?;
-
-        GeoJson::from_json_object(val).map_err(|e| D::Error::custom(e.to_string()))
+        #[cfg(feature = "compact")]
+        if !deserializer.is_human_readable() {
+            return crate::compact::deserialize_geojson(deserializer);
+        }
+        crate::de::deserialize_geojson(deserializer)
     }
 }
 
@@ -393,11 +596,12 @@ impl fmt::Display for FeatureCollection {
 
 #[cfg(test)]
 mod tests {
-    use crate::{Error, Feature, GeoJson, Geometry, Value};
+    use crate::{Error, Feature, GeoJson, Geometry, ReaderOptions, Value};
     use serde_json::json;
     use std::convert::TryInto;
     use std::str::FromStr;
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_geojson_from_reader() {
         let json_str = r#"{
@@ -444,7 +648,7 @@ mod tests {
             geojson,
             GeoJson::Feature(Feature {
                 bbox: None,
-                geometry: Some(Geometry::new(Value::Point(vec![102.0, 0.5]))),
+                geometry: Some(Geometry::new(Value::Point(position![102.0, 0.5]))),
                 id: None,
                 properties: None,
                 foreign_members: None,
@@ -475,4 +679,109 @@ mod tests {
             Err(Error::MalformedJson(_))
         ))
     }
+
+    #[test]
+    fn bare_coordinates() {
+        let options = ReaderOptions::new().bare_coordinates(true);
+        let parse = |s: &str| GeoJson::from_str_with(s, &options);
+        assert_eq!(
+            parse("[1, 2, 3]").unwrap(),
+            GeoJson::from(Value::Point(position![1.0, 2.0, 3.0]))
+        );
+        assert_eq!(
+            parse("[[1, 2], [3, 4]]").unwrap(),
+            GeoJson::from(Value::LineString(vec![
+                position![1.0, 2.0],
+                position![3.0, 4.0]
+            ]))
+        );
+        assert_eq!(
+            parse(r#"{"type":"Point","coordinates":[1, 2]}"#).unwrap(),
+            GeoJson::from(Value::Point(position![1.0, 2.0]))
+        );
+        for invalid in ["[]", "[1]", "[[1, 2]]", "[[1, 2], 3]", r#"[1, "2"]"#, "1"] {
+            assert!(
+                matches!(parse(invalid), Err(Error::ExpectedObjectValue(_))),
+                "{}",
+                invalid
+            );
+        }
+        assert!(GeoJson::from_str_with("[1, 2]", &ReaderOptions::new()).is_err());
+    }
+
+    #[test]
+    fn feature_arrays() {
+        let options = ReaderOptions::new().feature_arrays(true);
+        let parse = |s: &str| GeoJson::from_str_with(s, &options);
+        let collection = match parse(
+            r#"[
+                {"type":"Feature","geometry":{"type":"Point","coordinates":[1, 2]},"properties":null},
+                {"type":"Feature","geometry":null,"properties":{"a":1}}
+            ]"#,
+        ) {
+            Ok(GeoJson::FeatureCollection(collection)) => collection,
+            other => panic!("expected a FeatureCollection, got {:?}", other),
+        };
+        assert_eq!(collection.features.len(), 2);
+        assert_eq!(collection.features[1].property("a"), Some(&json!(1)));
+        assert_eq!(
+            parse("[]").unwrap().to_string(),
+            r#"{"features":[],"type":"FeatureCollection"}"#
+        );
+
+        assert!(matches!(
+            parse(r#"[{"type":"Point","coordinates":[1, 2]}]"#),
+            Err(Error::NotAFeature(_))
+        ));
+        assert!(matches!(
+            parse("[1, 2]"),
+            Err(Error::ExpectedObjectValue(_))
+        ));
+        assert!(GeoJson::from_str_with("[]", &ReaderOptions::new()).is_err());
+    }
+
+    #[test]
+    fn lenient_types() {
+        let options = ReaderOptions::new()
+            .lenient_types(true)
+            .feature_arrays(true);
+        let parse = |s: &str| GeoJson::from_str_with(s, &options);
+        let json = r#"{"type":"FEATURECOLLECTION","features":[
+            {"type":"feature","geometry":{"type":"geometrycollection","geometries":[
+                {"type":"point","coordinates":[1, 2]}
+            ]},"properties":{"type":"other"}}
+        ]}"#;
+        let expected = GeoJson::from_str(
+            &json
+                .replace("FEATURECOLLECTION", "FeatureCollection")
+                .replace("feature\"", "Feature\"")
+                .replace("geometrycollection", "GeometryCollection")
+                .replace("point", "Point"),
+        )
+        .unwrap();
+        assert_eq!(parse(json).unwrap(), expected);
+        assert!(GeoJson::from_str(json).is_err());
+
+        let error = parse(r#"[{"type":"Feature","geometry":{"type":"Polgyon","coordinates":[]},"properties":null}]"#)
+            .unwrap_err();
+        assert!(
+            matches!(&error, Error::UnknownTypeDidYouMean(type_, "Polygon") if type_ == "Polgyon")
+        );
+        assert_eq!(
+            error.to_string(),
+            "Encountered an unknown type `Polgyon`, did you mean `Polygon`?"
+        );
+        assert!(matches!(
+            parse(r#"{"type":"MultiPointy","coordinates":[]}"#),
+            Err(Error::UnknownTypeDidYouMean(_, "MultiPoint"))
+        ));
+        assert!(matches!(
+            parse(r#"{"type":"Circle","coordinates":[]}"#),
+            Err(Error::GeometryUnknownType(type_)) if type_ == "Circle"
+        ));
+        assert!(matches!(
+            parse(r#"{"type":"FeatureCollection","features":[{"type":"Row"}]}"#),
+            Err(Error::NotAFeature(type_)) if type_ == "Row"
+        ));
+    }
 }
@@ -0,0 +1,778 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Reading and writing of line-delimited GeoJSON, with one GeoJSON object per line.
+//!
+//! Loaders differ in the framing they accept: newline-delimited GeoJSON as read by BigQuery or
+//! `jq -c` uses `\n`, some Windows tools expect `\r\n`, and GeoJSON text sequences
+//! ([RFC 8142](https://tools.ietf.org/html/rfc8142)), as written by tippecanoe and `ogr2ogr`,
+//! prefix every object with an ASCII record separator. [`LineDelimitedWriter`] can write each,
+//! and [`LineDelimitedReader`] reads any of them.
+//!
+//! # Example
+//!
+//! ```
+//! use geojson::geojsonl::{LineDelimitedReader, LineDelimitedWriter, LineEnding};
+//! use geojson::{position, Feature, Value};
+//!
+//! let mut writer = LineDelimitedWriter::new(Vec::new())
+//!     .line_ending(LineEnding::CrLf)
+//!     .trailing_newline(false);
+//! writer.write(&Feature::from(Value::Point(position![1.0, 2.0]))).unwrap();
+//! writer.write(&Feature::from(Value::Point(position![3.0, 4.0]))).unwrap();
+//! let written = String::from_utf8(writer.into_inner()).unwrap();
+//! assert_eq!(written.lines().count(), 2);
+//! assert!(written.contains("}\r\n{") && written.ends_with('}'));
+//!
+//! let mut reader = LineDelimitedReader::new(written.as_bytes());
+//! let features: Vec<Feature> = reader.features().collect::<Result<_, _>>().unwrap();
+//! assert_eq!(features.len(), 2);
+//! ```
+//!
+//! [`LineDelimitedReader`]: struct.LineDelimitedReader.html
+//! [`LineDelimitedWriter`]: struct.LineDelimitedWriter.html
+
+use crate::algorithm::sample::Reservoir;
+use crate::errors::Error;
+use crate::json::Serialize;
+use crate::{Feature, FeatureCollection, Geometry};
+use serde::de::DeserializeOwned;
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::io::{Read, Write};
+
+/// The ASCII record separator which starts every object of a GeoJSON text sequence
+const RECORD_SEPARATOR: u8 = 0x1e;
+
+/// The line ending written after each object
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum LineEnding {
+    /// `\n`
+    #[default]
+    Lf,
+    /// `\r\n`
+    CrLf,
+}
+
+impl LineEnding {
+    fn as_bytes(self) -> &'static [u8] {
+        match self {
+            LineEnding::Lf => b"\n",
+            LineEnding::CrLf => b"\r\n",
+        }
+    }
+}
+
+/// Writes GeoJSON objects to a writer, each on its own line
+///
+/// By default, every object is followed by `\n`, without record separators.
+pub struct LineDelimitedWriter<W: Write> {
+    writer: W,
+    line_ending: LineEnding,
+    record_separator: bool,
+    trailing_newline: bool,
+    started: bool,
+}
+
+impl<W: Write> LineDelimitedWriter<W> {
+    /// A writer of newline-delimited GeoJSON to `writer`
+    pub fn new(writer: W) -> Self {
+        LineDelimitedWriter {
+            writer,
+            line_ending: LineEnding::default(),
+            record_separator: false,
+            trailing_newline: true,
+            started: false,
+        }
+    }
+
+    /// A writer of GeoJSON text sequences, as specified by RFC 8142: a record separator before,
+    /// and `\n` after, every object.
+    pub fn text_sequence(writer: W) -> Self {
+        Self::new(writer).record_separator(true)
+    }
+
+    /// Separate objects with `line_ending`.
+    pub fn line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = line_ending;
+        self
+    }
+
+    /// Write an ASCII record separator (`0x1E`) before every object.
+    pub fn record_separator(mut self, record_separator: bool) -> Self {
+        self.record_separator = record_separator;
+        self
+    }
+
+    /// Whether to end the last object with a line ending, as every other object is.
+    ///
+    /// If this is unset, line endings are only written between objects, and an empty output
+    /// contains nothing at all.
+    pub fn trailing_newline(mut self, trailing_newline: bool) -> Self {
+        self.trailing_newline = trailing_newline;
+        self
+    }
+
+    /// Write `object`, such as a [`Feature`](../struct.Feature.html), on a line of its own.
+    pub fn write(&mut self, object: &impl Serialize) -> Result<(), serde_json::Error> {
+        let line_ending = self.line_ending.as_bytes();
+        if self.started && !self.trailing_newline {
+            self.writer
+                .write_all(line_ending)
+                .map_err(serde_json::Error::io)?;
+        }
+        if self.record_separator {
+            self.writer
+                .write_all(&[RECORD_SEPARATOR])
+                .map_err(serde_json::Error::io)?;
+        }
+        serde_json::to_writer(&mut self.writer, object)?;
+        if self.trailing_newline {
+            self.writer
+                .write_all(line_ending)
+                .map_err(serde_json::Error::io)?;
+        }
+        self.started = true;
+        Ok(())
+    }
+
+    /// Write every object of `objects`.
+    pub fn write_all<'a, T: Serialize + 'a>(
+        &mut self,
+        objects: impl IntoIterator<Item = &'a T>,
+    ) -> Result<(), serde_json::Error> {
+        objects
+            .into_iter()
+            .try_for_each(|object| self.write(object))
+    }
+
+    /// Flush the underlying writer.
+    pub fn flush(&mut self) -> Result<(), serde_json::Error> {
+        self.writer.flush().map_err(serde_json::Error::io)
+    }
+
+    /// The underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// The size of the buffer a [`LineDelimitedReader`](struct.LineDelimitedReader.html) starts with
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Reads GeoJSON objects from a reader, each on its own line
+///
+/// Objects may be separated by `\n` or `\r\n`, and may start with an ASCII record separator, so
+/// this reads newline-delimited GeoJSON and GeoJSON text sequences alike. Empty lines are
+/// skipped. The ends of the objects are found with [memchr](https://docs.rs/memchr), so only
+/// the JSON parser looks at every byte.
+pub struct LineDelimitedReader<R: Read> {
+    reader: R,
+    buffer: Vec<u8>,
+    start: usize,
+    end: usize,
+    eof: bool,
+    capacity_hint: usize,
+}
+
+impl<R: Read> LineDelimitedReader<R> {
+    /// A reader of line-delimited GeoJSON from `reader`, which needn't be buffered
+    pub fn new(reader: R) -> Self {
+        Self::with_buffer_size(reader, READ_BUFFER_SIZE)
+    }
+
+    fn with_buffer_size(reader: R, buffer_size: usize) -> Self {
+        LineDelimitedReader {
+            reader,
+            buffer: vec![0; buffer_size],
+            start: 0,
+            end: 0,
+            eof: false,
+            capacity_hint: 0,
+        }
+    }
+
+    /// The number of objects expected, as known from an index, a `Content-Length` or a first
+    /// pass over the input, for which [`read_all`](#method.read_all) and
+    /// [`read_feature_collection`](#method.read_feature_collection) reserve room up front,
+    /// rather than growing their `Vec` as objects are read.
+    ///
+    /// This is only a hint: more or fewer objects may be read.
+    pub fn with_capacity_hint(mut self, objects: usize) -> Self {
+        self.capacity_hint = objects;
+        self
+    }
+
+    /// Read the next object, such as a [`Feature`](../struct.Feature.html), or `None` at the
+    /// end of the input.
+    pub fn read<T: DeserializeOwned>(&mut self) -> Option<Result<T, Error>> {
+        loop {
+            let record = match self.next_record() {
+                Ok(Some(record)) => record,
+                Ok(None) => return None,
+                Err(error) => return Some(Err(Error::MalformedJson(serde_json::Error::io(error)))),
+            };
+            if !record.iter().all(u8::is_ascii_whitespace) {
+                return Some(serde_json::from_slice(record).map_err(Error::MalformedJson));
+            }
+        }
+    }
+
+    /// Read every remaining object as a `Feature`.
+    pub fn features(&mut self) -> impl Iterator<Item = Result<Feature, Error>> + '_ {
+        std::iter::from_fn(move || self.read())
+    }
+
+    /// Read every remaining object as a `Feature` of a [`FeatureStream`], to transform them as
+    /// they are read.
+    ///
+    /// [`FeatureStream`]: struct.FeatureStream.html
+    pub fn into_stream(mut self) -> FeatureStream<impl Iterator<Item = Result<Feature, Error>>> {
+        FeatureStream::new(std::iter::from_fn(move || self.read()))
+    }
+
+    /// Read the remaining features, transformed by `f`. See
+    /// [`FeatureStream::map`](struct.FeatureStream.html#method.map).
+    pub fn map(
+        self,
+        f: impl FnMut(Feature) -> Feature,
+    ) -> FeatureStream<impl Iterator<Item = Result<Feature, Error>>> {
+        self.into_stream().map(f)
+    }
+
+    /// Read the remaining features for which `predicate` holds. See
+    /// [`FeatureStream::filter`](struct.FeatureStream.html#method.filter).
+    pub fn filter(
+        self,
+        predicate: impl FnMut(&Feature) -> bool,
+    ) -> FeatureStream<impl Iterator<Item = Result<Feature, Error>>> {
+        self.into_stream().filter(predicate)
+    }
+
+    /// Read the remaining features until one for which `predicate` doesn't hold. See
+    /// [`FeatureStream::take_while`](struct.FeatureStream.html#method.take_while).
+    pub fn take_while(
+        self,
+        predicate: impl FnMut(&Feature) -> bool,
+    ) -> FeatureStream<impl Iterator<Item = Result<Feature, Error>>> {
+        self.into_stream().take_while(predicate)
+    }
+
+    /// Read the remaining features, with their geometries transformed by `f`. See
+    /// [`FeatureStream::map_geometry`](struct.FeatureStream.html#method.map_geometry).
+    pub fn map_geometry(
+        self,
+        f: impl FnMut(Geometry) -> Geometry,
+    ) -> FeatureStream<impl Iterator<Item = Result<Feature, Error>>> {
+        self.into_stream().map_geometry(f)
+    }
+
+    /// Read every remaining object, stopping at the first which can't be parsed.
+    pub fn read_all<T: DeserializeOwned>(&mut self) -> Result<Vec<T>, Error> {
+        let mut objects = Vec::with_capacity(self.capacity_hint);
+        while let Some(object) = self.read() {
+            objects.push(object?);
+        }
+        Ok(objects)
+    }
+
+    /// Read every remaining object as a `Feature` of one `FeatureCollection`.
+    pub fn read_feature_collection(&mut self) -> Result<FeatureCollection, Error> {
+        Ok(FeatureCollection {
+            bbox: None,
+            features: self.read_all()?,
+            foreign_members: None,
+        })
+    }
+
+    /// Read `n` of the remaining objects, chosen at random by reservoir sampling, or all of
+    /// them if there are no more than `n`, in their order in the input. The sample is the same
+    /// for the same `seed`.
+    ///
+    /// This reads the whole input but holds no more than `n` objects at a time, so it can
+    /// sample inputs too large to read into memory. Only the objects chosen at some point are
+    /// parsed, so others which can't be parsed may go unnoticed.
+    ///
+    /// ```
+    /// use geojson::geojsonl::LineDelimitedReader;
+    /// use geojson::Feature;
+    ///
+    /// let input = r#"{"type":"Feature","geometry":null,"properties":{"n":1}}
+    /// {"type":"Feature","geometry":null,"properties":{"n":2}}
+    /// {"type":"Feature","geometry":null,"properties":{"n":3}}
+    /// "#;
+    /// let sample: Vec<Feature> = LineDelimitedReader::new(input.as_bytes())
+    ///     .sample(2, 42)
+    ///     .unwrap();
+    /// assert_eq!(sample.len(), 2);
+    /// ```
+    pub fn sample<T: DeserializeOwned>(&mut self, n: usize, seed: u64) -> Result<Vec<T>, Error> {
+        let mut reservoir = Reservoir::new(n, seed);
+        loop {
+            let record = match self.next_record() {
+                Ok(Some(record)) => record,
+                Ok(None) => return Ok(reservoir.into_items()),
+                Err(error) => return Err(Error::MalformedJson(serde_json::Error::io(error))),
+            };
+            if record.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+            if let Some(slot) = reservoir.offer() {
+                let object = serde_json::from_slice(record).map_err(Error::MalformedJson)?;
+                reservoir.insert(slot, object);
+            }
+        }
+    }
+
+    /// The underlying reader.
+    ///
+    /// Any input which has been read into the buffer of this reader, but not yet parsed, is
+    /// lost.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// The bytes up to the next line ending or record separator
+    fn next_record(&mut self) -> std::io::Result<Option<&[u8]>> {
+        let mut searched = self.start;
+        loop {
+            let unsearched = &self.buffer[searched..self.end];
+            if let Some(offset) = memchr::memchr2(b'\n', RECORD_SEPARATOR, unsearched) {
+                let record = self.start..searched + offset;
+                self.start = record.end + 1;
+                return Ok(Some(&self.buffer[record]));
+            }
+            searched = self.end;
+            if self.eof {
+                if self.start == self.end {
+                    return Ok(None);
+                }
+                let record = self.start..self.end;
+                self.start = self.end;
+                return Ok(Some(&self.buffer[record]));
+            }
+            // Make room for more input, by moving the unfinished record to the front of the
+            // buffer or, if it fills the whole buffer, growing the buffer
+            if self.start > 0 {
+                self.buffer.copy_within(self.start..self.end, 0);
+                searched -= self.start;
+                self.end -= self.start;
+                self.start = 0;
+            } else if self.end == self.buffer.len() {
+                self.buffer.resize(self.buffer.len() * 2, 0);
+            }
+            let read = loop {
+                match self.reader.read(&mut self.buffer[self.end..]) {
+                    Err(error) if error.kind() == std::io::ErrorKind::Interrupted => continue,
+                    result => break result?,
+                }
+            };
+            self.end += read;
+            self.eof = read == 0;
+        }
+    }
+}
+
+/// An iterator of features as they are read, with adapters which transform each feature
+/// before it is yielded, so that a pipeline from a reader to a writer holds one feature at a
+/// time.
+///
+/// The adapters only see the features which were read, and pass on the errors of those which
+/// couldn't be, so these are yielded in their place in the stream. They shadow the adapters of
+/// the same names of `Iterator`, which see the `Result`s themselves.
+///
+/// ```
+/// use geojson::geojsonl::{LineDelimitedReader, LineDelimitedWriter};
+///
+/// let input = r#"{"type":"Feature","geometry":{"type":"Point","coordinates":[1,2]},"properties":{"n":1}}
+/// {"type":"Feature","geometry":{"type":"Point","coordinates":[3,4]},"properties":{"n":2}}
+/// {"type":"Feature","geometry":null,"properties":{"n":3}}
+/// "#;
+/// let features = LineDelimitedReader::new(input.as_bytes())
+///     .filter(|feature| feature.geometry.is_some())
+///     .map_geometry(|mut geometry| {
+///         geometry.swap_xy();
+///         geometry
+///     })
+///     .map(|mut feature| {
+///         feature.remove_property("n");
+///         feature
+///     });
+///
+/// let mut writer = LineDelimitedWriter::new(Vec::new());
+/// for feature in features {
+///     writer.write(&feature.unwrap()).unwrap();
+/// }
+/// let output = String::from_utf8(writer.into_inner()).unwrap();
+/// assert_eq!(output.lines().count(), 2);
+/// assert!(output.contains("[2.0,1.0]") && !output.contains("\"n\""));
+/// ```
+pub struct FeatureStream<I> {
+    features: I,
+}
+
+impl<I: Iterator<Item = Result<Feature, Error>>> FeatureStream<I> {
+    /// A stream of the features of `features`, such as those of
+    /// [`concat`](fn.concat.html) or [`merge_by_key`](fn.merge_by_key.html)
+    pub fn new(features: I) -> Self {
+        FeatureStream { features }
+    }
+
+    /// Transform each feature with `f`
+    pub fn map(
+        self,
+        mut f: impl FnMut(Feature) -> Feature,
+    ) -> FeatureStream<impl Iterator<Item = Result<Feature, Error>>> {
+        FeatureStream::new(self.features.map(move |feature| feature.map(&mut f)))
+    }
+
+    /// Leave out the features for which `predicate` doesn't hold
+    pub fn filter(
+        self,
+        mut predicate: impl FnMut(&Feature) -> bool,
+    ) -> FeatureStream<impl Iterator<Item = Result<Feature, Error>>> {
+        FeatureStream::new(self.features.filter(move |feature| match feature {
+            Ok(feature) => predicate(feature),
+            Err(_) => true,
+        }))
+    }
+
+    /// End the stream before the first feature for which `predicate` doesn't hold
+    pub fn take_while(
+        self,
+        mut predicate: impl FnMut(&Feature) -> bool,
+    ) -> FeatureStream<impl Iterator<Item = Result<Feature, Error>>> {
+        FeatureStream::new(self.features.take_while(move |feature| match feature {
+            Ok(feature) => predicate(feature),
+            Err(_) => true,
+        }))
+    }
+
+    /// Transform the geometry of each feature which has one with `f`
+    pub fn map_geometry(
+        self,
+        mut f: impl FnMut(Geometry) -> Geometry,
+    ) -> FeatureStream<impl Iterator<Item = Result<Feature, Error>>> {
+        self.map(move |mut feature| {
+            feature.geometry = feature.geometry.map(&mut f);
+            feature
+        })
+    }
+}
+
+impl<I: Iterator<Item = Result<Feature, Error>>> Iterator for FeatureStream<I> {
+    type Item = Result<Feature, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.features.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.features.size_hint()
+    }
+}
+
+/// Read the features of each of `readers` in turn, as one stream, such as the shards of a
+/// dataset split across several files.
+///
+/// ```
+/// use geojson::geojsonl::{self, LineDelimitedReader};
+///
+/// let shards = [
+///     "{\"type\":\"Feature\",\"geometry\":null,\"properties\":{\"n\":1}}\n",
+///     "{\"type\":\"Feature\",\"geometry\":null,\"properties\":{\"n\":2}}\n",
+/// ];
+/// let readers = shards.iter().map(|shard| LineDelimitedReader::new(shard.as_bytes()));
+/// assert_eq!(geojsonl::concat(readers).count(), 2);
+/// ```
+pub fn concat<R: Read>(
+    readers: impl IntoIterator<Item = LineDelimitedReader<R>>,
+) -> impl Iterator<Item = Result<Feature, Error>> {
+    readers
+        .into_iter()
+        .flat_map(|mut reader| std::iter::from_fn(move || reader.read()))
+}
+
+/// The next feature of one of the readers of a [`merge_by_key`](fn.merge_by_key.html)
+struct Head<K> {
+    key: K,
+    reader: usize,
+    feature: Feature,
+}
+
+impl<K: Ord> Ord for Head<K> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.key, self.reader).cmp(&(&other.key, other.reader))
+    }
+}
+
+impl<K: Ord> PartialOrd for Head<K> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: Ord> PartialEq for Head<K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<K: Ord> Eq for Head<K> {}
+
+struct MergeByKey<R: Read, K, F> {
+    readers: Vec<LineDelimitedReader<R>>,
+    heads: BinaryHeap<Reverse<Head<K>>>,
+    /// The readers whose next feature is yet to be read
+    unread: Vec<usize>,
+    key: F,
+}
+
+impl<R: Read, K: Ord, F: FnMut(&Feature) -> K> Iterator for MergeByKey<R, K, F> {
+    type Item = Result<Feature, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(reader) = self.unread.pop() {
+            match self.readers[reader].read::<Feature>() {
+                Some(Ok(feature)) => self.heads.push(Reverse(Head {
+                    key: (self.key)(&feature),
+                    reader,
+                    feature,
+                })),
+                Some(Err(error)) => {
+                    self.unread.push(reader);
+                    return Some(Err(error));
+                }
+                None => {}
+            }
+        }
+        let Reverse(head) = self.heads.pop()?;
+        self.unread.push(head.reader);
+        Some(Ok(head.feature))
+    }
+}
+
+/// Read the features of `readers` as one stream, ordered by `key`, by a k-way merge of
+/// readers whose features are each ordered by `key`, such as sorted shards of a dataset.
+///
+/// Features with equal keys are read in the order of their readers. This holds only one
+/// feature per reader at a time, and the stream is only ordered if each reader is. A feature
+/// which can't be parsed is read as an error, and the merge goes on from the next line of its
+/// reader.
+///
+/// ```
+/// use geojson::geojsonl::{self, LineDelimitedReader};
+///
+/// let feature = |n: u64| {
+///     format!("{{\"type\":\"Feature\",\"geometry\":null,\"properties\":{{\"n\":{}}}}}\n", n)
+/// };
+/// let shards = [feature(1) + &feature(4), feature(2) + &feature(3)];
+/// let readers = shards.iter().map(|shard| LineDelimitedReader::new(shard.as_bytes()));
+///
+/// let merged: Vec<u64> = geojsonl::merge_by_key(readers, |feature| {
+///     feature.property("n").and_then(|n| n.as_u64())
+/// })
+/// .map(|feature| feature.unwrap().property("n").unwrap().as_u64().unwrap())
+/// .collect();
+/// assert_eq!(merged, [1, 2, 3, 4]);
+/// ```
+pub fn merge_by_key<R: Read, K: Ord>(
+    readers: impl IntoIterator<Item = LineDelimitedReader<R>>,
+    key: impl FnMut(&Feature) -> K,
+) -> impl Iterator<Item = Result<Feature, Error>> {
+    let readers: Vec<_> = readers.into_iter().collect();
+    MergeByKey {
+        heads: BinaryHeap::with_capacity(readers.len()),
+        unread: (0..readers.len()).rev().collect(),
+        readers,
+        key,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{merge_by_key, LineDelimitedReader, LineDelimitedWriter, LineEnding};
+    use crate::{Feature, Geometry, Value};
+
+    fn write(mut writer: LineDelimitedWriter<Vec<u8>>, count: usize) -> String {
+        let features = vec![Feature::from(Value::Point(position![1.0, 2.0])); count];
+        writer.write_all(&features).unwrap();
+        String::from_utf8(writer.into_inner()).unwrap()
+    }
+
+    #[test]
+    fn framing() {
+        let feature = Feature::from(Value::Point(position![1.0, 2.0])).to_string();
+
+        assert_eq!(
+            write(LineDelimitedWriter::new(Vec::new()), 2),
+            format!("{}\n{}\n", feature, feature)
+        );
+        assert_eq!(
+            write(
+                LineDelimitedWriter::new(Vec::new())
+                    .line_ending(LineEnding::CrLf)
+                    .trailing_newline(false),
+                2
+            ),
+            format!("{}\r\n{}", feature, feature)
+        );
+        assert_eq!(
+            write(LineDelimitedWriter::text_sequence(Vec::new()), 2),
+            format!("\u{1e}{}\n\u{1e}{}\n", feature, feature)
+        );
+        assert_eq!(
+            write(
+                LineDelimitedWriter::text_sequence(Vec::new()).trailing_newline(false),
+                0
+            ),
+            ""
+        );
+    }
+
+    #[test]
+    fn read_framings() {
+        let json = Feature::from(Value::Point(position![1.0, 2.0])).to_string();
+        let feature: Feature = serde_json::from_str(&json).unwrap();
+        let inputs = vec![
+            format!("{}\n{}\n", json, json),
+            format!("{}\r\n\r\n{}", json, json),
+            format!("\u{1e}{}\n\u{1e}{}\n", json, json),
+            format!("\n\u{1e}{}\u{1e}{}\n\n", json, json),
+        ];
+        for input in inputs {
+            for buffer_size in [1, 7, 64 * 1024] {
+                let mut reader =
+                    LineDelimitedReader::with_buffer_size(input.as_bytes(), buffer_size);
+                let features: Vec<_> = reader.features().map(Result::unwrap).collect();
+                assert_eq!(
+                    features,
+                    vec![feature.clone(), feature.clone()],
+                    "{:?}",
+                    input
+                );
+                assert!(reader.read::<Feature>().is_none());
+            }
+        }
+
+        let truncated = format!("{}\n{{\n", json);
+        let mut reader = LineDelimitedReader::new(truncated.as_bytes());
+        assert!(reader.read::<Feature>().unwrap().is_ok());
+        assert!(reader.read::<Feature>().unwrap().is_err());
+        assert!(reader.read::<Feature>().is_none());
+    }
+
+    #[test]
+    fn read_with_capacity_hint() {
+        let json = Feature::from(Value::Point(position![1.0, 2.0])).to_string();
+        let input = format!("{}\n{}\n{}\n", json, json, json);
+
+        let mut reader = LineDelimitedReader::new(input.as_bytes()).with_capacity_hint(100);
+        let features: Vec<Feature> = reader.read_all().unwrap();
+        assert_eq!(features.len(), 3);
+        assert!(features.capacity() >= 100);
+
+        let mut reader = LineDelimitedReader::new(input.as_bytes()).with_capacity_hint(1);
+        let collection = reader.read_feature_collection().unwrap();
+        assert_eq!(collection.features, features);
+
+        let invalid = format!("{}\n[\n", json);
+        let mut reader = LineDelimitedReader::new(invalid.as_bytes());
+        assert!(reader.read_all::<Feature>().is_err());
+    }
+
+    #[test]
+    fn merge_readers() {
+        let feature = |n: i64, shard: usize| {
+            let mut feature = Feature::from(Value::Point(position![1.0, 2.0]));
+            feature.set_property("n", n);
+            feature.set_property("shard", shard);
+            feature.to_string()
+        };
+        let shards = [
+            [feature(1, 0), feature(5, 0), feature(6, 0)].join("\n"),
+            String::new(),
+            [feature(2, 2), "[".to_string(), feature(5, 2), feature(7, 2)].join("\n"),
+            [feature(0, 3), feature(3, 3)].join("\n"),
+        ];
+        let readers = shards
+            .iter()
+            .map(|shard| LineDelimitedReader::with_buffer_size(shard.as_bytes(), 7));
+        let merged: Vec<_> = merge_by_key(readers, |feature| {
+            feature.property("n").and_then(|n| n.as_i64())
+        })
+        .collect();
+
+        assert!(merged[3].is_err());
+        let merged: Vec<_> = merged
+            .into_iter()
+            .filter_map(Result::ok)
+            .map(|feature| {
+                let property = |key| feature.property(key).unwrap().as_i64().unwrap();
+                (property("n"), property("shard"))
+            })
+            .collect();
+        assert_eq!(
+            merged,
+            [
+                (0, 3),
+                (1, 0),
+                (2, 2),
+                (3, 3),
+                (5, 0),
+                (5, 2),
+                (6, 0),
+                (7, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn stream_adapters() {
+        let feature = |n: i64| {
+            let mut feature = Feature::from(Value::Point(position![n as f64, 0.0]));
+            feature.set_property("n", n);
+            feature.to_string()
+        };
+        let input = [
+            feature(1),
+            feature(2),
+            "[".to_string(),
+            feature(3),
+            feature(4),
+        ]
+        .join("\n");
+        let n = |feature: &Feature| feature.property("n").unwrap().as_i64().unwrap();
+
+        let features: Vec<_> = LineDelimitedReader::new(input.as_bytes())
+            .filter(|feature| n(feature) != 2)
+            .take_while(|feature| n(feature) < 4)
+            .map_geometry(|_| Geometry::new(Value::Point(position![0.0, 0.0])))
+            .map(|mut feature| {
+                feature.set_property("n", n(&feature) * 10);
+                feature
+            })
+            .collect();
+        assert_eq!(features.len(), 3);
+        assert!(features[1].is_err());
+        for (feature, expected) in [&features[0], &features[2]].iter().zip([10, 30]) {
+            let feature = feature.as_ref().unwrap();
+            assert_eq!(n(feature), expected);
+            assert_eq!(
+                feature.geometry.as_ref().unwrap().value,
+                Value::Point(position![0.0, 0.0])
+            );
+        }
+    }
+}
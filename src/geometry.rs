@@ -12,11 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{convert::TryFrom, fmt};
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::{convert::TryFrom, fmt};
 
 use crate::errors::Error;
 use crate::json::{Deserialize, Deserializer, JsonObject, JsonValue, Serialize, Serializer};
-use crate::serde;
 use crate::{util, Bbox, LineStringType, PointType, PolygonType};
 
 /// The underlying value for a `Geometry`.
@@ -27,17 +29,19 @@ use crate::{util, Bbox, LineStringType, PointType, PolygonType};
 /// primitives AND `geo_types::Geometry` enum members:
 ///
 /// ```rust
+/// use geojson::position;
+///
 /// # #[cfg(feature = "geo-types")]
 /// # fn test() {
 /// let point = geo_types::Point::new(2., 9.);
 /// let genum = geo_types::Geometry::from(point);
 /// assert_eq!(
 ///     geojson::Value::from(&point),
-///     geojson::Value::Point(vec![2., 9.]),
+///     geojson::Value::Point(position![2., 9.]),
 /// );
 /// assert_eq!(
 ///     geojson::Value::from(&genum),
-///     geojson::Value::Point(vec![2., 9.]),
+///     geojson::Value::Point(position![2., 9.]),
 /// );
 /// # }
 /// # #[cfg(not(feature = "geo-types"))]
@@ -85,15 +89,7 @@ pub enum Value {
 impl<'a> From<&'a Value> for JsonObject {
     fn from(value: &'a Value) -> JsonObject {
         let mut map = JsonObject::new();
-        let ty = String::from(match value {
-            Value::Point(..) => "Point",
-            Value::MultiPoint(..) => "MultiPoint",
-            Value::LineString(..) => "LineString",
-            Value::MultiLineString(..) => "MultiLineString",
-            Value::Polygon(..) => "Polygon",
-            Value::MultiPolygon(..) => "MultiPolygon",
-            Value::GeometryCollection(..) => "GeometryCollection",
-        });
+        let ty = String::from(value.type_str());
 
         map.insert(String::from("type"), ::serde_json::to_value(&ty).unwrap());
 
@@ -102,13 +98,72 @@ impl<'a> From<&'a Value> for JsonObject {
                 Value::GeometryCollection(..) => "geometries",
                 _ => "coordinates",
             }),
-            ::serde_json::to_value(&value).unwrap(),
+            ::serde_json::to_value(value).unwrap(),
         );
         map
     }
 }
 
+/// The type of a [`Value`](enum.Value.html), without its coordinates
+///
+/// ```
+/// use geojson::{position, GeometryType, Value};
+///
+/// let point = Value::Point(position![1.0, 2.0]);
+/// assert_eq!(point.geometry_type(), GeometryType::Point);
+/// assert_eq!(point.geometry_type().to_string(), "Point");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum GeometryType {
+    Point,
+    MultiPoint,
+    LineString,
+    MultiLineString,
+    Polygon,
+    MultiPolygon,
+    GeometryCollection,
+}
+
+impl GeometryType {
+    /// The GeoJSON `type` member for geometries of this type, e.g. `"Point"`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            GeometryType::Point => "Point",
+            GeometryType::MultiPoint => "MultiPoint",
+            GeometryType::LineString => "LineString",
+            GeometryType::MultiLineString => "MultiLineString",
+            GeometryType::Polygon => "Polygon",
+            GeometryType::MultiPolygon => "MultiPolygon",
+            GeometryType::GeometryCollection => "GeometryCollection",
+        }
+    }
+}
+
+impl fmt::Display for GeometryType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 impl Value {
+    /// The type of this geometry
+    pub fn geometry_type(&self) -> GeometryType {
+        match self {
+            Value::Point(..) => GeometryType::Point,
+            Value::MultiPoint(..) => GeometryType::MultiPoint,
+            Value::LineString(..) => GeometryType::LineString,
+            Value::MultiLineString(..) => GeometryType::MultiLineString,
+            Value::Polygon(..) => GeometryType::Polygon,
+            Value::MultiPolygon(..) => GeometryType::MultiPolygon,
+            Value::GeometryCollection(..) => GeometryType::GeometryCollection,
+        }
+    }
+
+    /// The GeoJSON `type` member for this geometry, e.g. `"Point"`
+    pub(crate) fn type_str(&self) -> &'static str {
+        self.geometry_type().as_str()
+    }
+
     pub fn from_json_object(object: JsonObject) -> Result<Self, Error> {
         Self::try_from(object)
     }
@@ -166,6 +221,10 @@ impl Serialize for Value {
     where
         S: Serializer,
     {
+        #[cfg(feature = "compact")]
+        if !serializer.is_human_readable() {
+            return crate::compact::serialize_value(self, serializer);
+        }
         JsonValue::from(self).serialize(serializer)
     }
 }
@@ -179,24 +238,26 @@ impl Serialize for Value {
 /// Constructing a `Geometry`:
 ///
 /// ```
-/// use geojson::{Geometry, Value};
+/// use geojson::{position, Geometry, Value};
 ///
-/// let geometry = Geometry::new(Value::Point(vec![7.428959, 1.513394]));
+/// let geometry = Geometry::new(Value::Point(position![7.428959, 1.513394]));
 /// ```
 ///
 /// Geometries can be created from `Value`s.
 /// ```
+/// use geojson::position;
+///
 /// # use geojson::{Geometry, Value};
-/// let geometry1: Geometry = Value::Point(vec![7.428959, 1.513394]).into();
+/// let geometry1: Geometry = Value::Point(position![7.428959, 1.513394]).into();
 /// ```
 ///
 /// Serializing a `Geometry` to a GeoJSON string:
 ///
 /// ```
-/// use geojson::{GeoJson, Geometry, Value};
+/// use geojson::{position, GeoJson, Geometry, Value};
 /// use serde_json;
 ///
-/// let geometry = Geometry::new(Value::Point(vec![7.428959, 1.513394]));
+/// let geometry = Geometry::new(Value::Point(position![7.428959, 1.513394]));
 ///
 /// let geojson_string = geometry.to_string();
 ///
@@ -209,7 +270,7 @@ impl Serialize for Value {
 /// Deserializing a GeoJSON string into a `Geometry`:
 ///
 /// ```
-/// use geojson::{GeoJson, Geometry, Value};
+/// use geojson::{position, GeoJson, Geometry, Value};
 ///
 /// let geojson_str = "{\"coordinates\":[7.428959,1.513394],\"type\":\"Point\"}";
 ///
@@ -219,7 +280,7 @@ impl Serialize for Value {
 /// };
 ///
 /// assert_eq!(
-///     Geometry::new(Value::Point(vec![7.428959, 1.513394]),),
+///     Geometry::new(Value::Point(position![7.428959, 1.513394]),),
 ///     geometry,
 /// );
 /// ```
@@ -246,6 +307,16 @@ impl Geometry {
             foreign_members: None,
         }
     }
+
+    /// Consume this geometry, returning its value
+    pub fn into_value(self) -> Value {
+        self.value
+    }
+
+    /// The type of this geometry's value
+    pub fn geometry_type(&self) -> GeometryType {
+        self.value.geometry_type()
+    }
 }
 
 impl<'a> From<&'a Geometry> for JsonObject {
@@ -306,6 +377,10 @@ impl Serialize for Geometry {
     where
         S: Serializer,
     {
+        #[cfg(feature = "compact")]
+        if !serializer.is_human_readable() {
+            return crate::compact::serialize_geometry(self, serializer);
+        }
         JsonObject::from(self).serialize(serializer)
     }
 }
@@ -315,11 +390,11 @@ impl<'de> Deserialize<'de> for Geometry {
     where
         D: Deserializer<'de>,
     {
-        use serde::de::Error as SerdeError;
-
-        let val = JsonObject::deserialize(deserializer)?;
-
-        Geometry::from_json_object(val).map_err(|e| D::Error::custom(e.to_string()))
+        #[cfg(feature = "compact")]
+        if !deserializer.is_human_readable() {
+            return crate::compact::deserialize_geometry(deserializer);
+        }
+        crate::de::deserialize_geometry(deserializer)
     }
 }
 
@@ -349,7 +424,7 @@ mod tests {
     fn encode_decode_geometry() {
         let geometry_json_str = "{\"coordinates\":[1.1,2.1],\"type\":\"Point\"}";
         let geometry = Geometry {
-            value: Value::Point(vec![1.1, 2.1]),
+            value: Value::Point(position![1.1, 2.1]),
             bbox: None,
             foreign_members: None,
         };
@@ -383,7 +458,7 @@ mod tests {
         assert_eq!(
             geometry,
             Geometry {
-                value: Value::Point(vec![0.0, 0.1]),
+                value: Value::Point(position![0.0, 0.1]),
                 bbox: None,
                 foreign_members: None,
             }
@@ -392,7 +467,11 @@ mod tests {
 
     #[test]
     fn test_geometry_display() {
-        let v = Value::LineString(vec![vec![0.0, 0.1], vec![0.1, 0.2], vec![0.2, 0.3]]);
+        let v = Value::LineString(vec![
+            position![0.0, 0.1],
+            position![0.1, 0.2],
+            position![0.2, 0.3],
+        ]);
         let geometry = Geometry::new(v);
         assert_eq!(
             "{\"coordinates\":[[0.0,0.1],[0.1,0.2],[0.2,0.3]],\"type\":\"LineString\"}",
@@ -402,7 +481,11 @@ mod tests {
 
     #[test]
     fn test_value_display() {
-        let v = Value::LineString(vec![vec![0.0, 0.1], vec![0.1, 0.2], vec![0.2, 0.3]]);
+        let v = Value::LineString(vec![
+            position![0.0, 0.1],
+            position![0.1, 0.2],
+            position![0.2, 0.3],
+        ]);
         assert_eq!(
             "{\"coordinates\":[[0.0,0.1],[0.1,0.2],[0.2,0.3]],\"type\":\"LineString\"}",
             v.to_string()
@@ -419,7 +502,7 @@ mod tests {
             serde_json::to_value(true).unwrap(),
         );
         let geometry = Geometry {
-            value: Value::Point(vec![1.1, 2.1]),
+            value: Value::Point(position![1.1, 2.1]),
             bbox: None,
             foreign_members: Some(foreign_members),
         };
@@ -443,12 +526,12 @@ mod tests {
             value: Value::GeometryCollection(vec![
                 Geometry {
                     bbox: None,
-                    value: Value::Point(vec![100.0, 0.0]),
+                    value: Value::Point(position![100.0, 0.0]),
                     foreign_members: None,
                 },
                 Geometry {
                     bbox: None,
-                    value: Value::LineString(vec![vec![101.0, 0.0], vec![102.0, 1.0]]),
+                    value: Value::LineString(vec![position![101.0, 0.0], position![102.0, 1.0]]),
                     foreign_members: None,
                 },
             ]),
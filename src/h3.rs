@@ -0,0 +1,226 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversion between [H3](https://h3geo.org) cells and GeoJSON, using the
+//! [`h3o`](https://docs.rs/h3o) crate.
+//!
+//! # Example
+//!
+//! ```
+//! use geojson::{h3, position, Value};
+//! use h3o::{geom::ContainmentMode, Resolution};
+//!
+//! let polygon = Value::Polygon(vec![vec![
+//!     position![2.0, 48.0],
+//!     position![2.5, 48.0],
+//!     position![2.5, 48.5],
+//!     position![2.0, 48.5],
+//!     position![2.0, 48.0],
+//! ]]);
+//! let cells = polygon
+//!     .to_h3_cells(Resolution::Five, ContainmentMode::ContainsCentroid)
+//!     .unwrap();
+//!
+//! // One hexagon Feature per cell, ready to be written out for visualization
+//! let collection = h3::cells_to_feature_collection(cells);
+//! assert!(!collection.features.is_empty());
+//! ```
+
+use crate::conversion::to_geo_types::create_geo_polygon;
+use crate::errors::Error;
+use crate::{Feature, FeatureCollection, Geometry, Position, Value};
+use h3o::geom::{ContainmentMode, TilerBuilder};
+use h3o::{CellIndex, LatLng, Resolution};
+
+/// The property under which a cell's index is stored by [`cell_to_feature`](fn.cell_to_feature.html)
+pub const H3_PROPERTY: &str = "h3";
+
+/// Convert an H3 cell into a `Feature` with a `Polygon` geometry tracing the cell boundary.
+///
+/// The cell index is stored in the `"h3"` property, in its hexadecimal string form.
+pub fn cell_to_feature(cell: CellIndex) -> Feature {
+    let mut ring: Vec<_> = cell
+        .boundary()
+        .iter()
+        .map(|vertex| position![vertex.lng(), vertex.lat()])
+        .collect();
+    if let Some(first) = ring.first().cloned() {
+        ring.push(first);
+    }
+    let mut feature = Feature::from(Value::Polygon(vec![ring]));
+    feature.set_property(H3_PROPERTY, cell.to_string());
+    feature
+}
+
+/// Convert H3 cells into a `FeatureCollection` of hexagon (or pentagon) `Polygon` Features.
+///
+/// See [`cell_to_feature`](fn.cell_to_feature.html).
+pub fn cells_to_feature_collection(
+    cells: impl IntoIterator<Item = CellIndex>,
+) -> FeatureCollection {
+    FeatureCollection {
+        bbox: None,
+        features: cells.into_iter().map(cell_to_feature).collect(),
+        foreign_members: None,
+    }
+}
+
+fn collect_cells(
+    value: &Value,
+    resolution: Resolution,
+    containment: ContainmentMode,
+    cells: &mut Vec<CellIndex>,
+) -> Result<(), Error> {
+    let point_cell = |position: &Position| match position.as_slice() {
+        [x, y, ..] => LatLng::new(*y, *x)
+            .map(|ll| ll.to_cell(resolution))
+            .map_err(|e| Error::H3Conversion(e.to_string())),
+        _ => Err(Error::ExpectedF64Value),
+    };
+    let mut tiler = TilerBuilder::new(resolution)
+        .containment_mode(containment)
+        .build();
+    match value {
+        Value::Point(position) => cells.push(point_cell(position)?),
+        Value::MultiPoint(positions) => {
+            for position in positions {
+                cells.push(point_cell(position)?);
+            }
+        }
+        Value::Polygon(polygon) => {
+            tiler
+                .add(create_geo_polygon(polygon))
+                .map_err(|e| Error::H3Conversion(e.to_string()))?;
+            cells.extend(tiler.into_coverage());
+        }
+        Value::MultiPolygon(polygons) => {
+            tiler
+                .add_batch(polygons.iter().map(create_geo_polygon))
+                .map_err(|e| Error::H3Conversion(e.to_string()))?;
+            cells.extend(tiler.into_coverage());
+        }
+        Value::GeometryCollection(geometries) => {
+            for geometry in geometries {
+                collect_cells(&geometry.value, resolution, containment, cells)?;
+            }
+        }
+        Value::LineString(_) | Value::MultiLineString(_) => {
+            return Err(Error::ExpectedType {
+                expected: "Point, MultiPoint, Polygon, or MultiPolygon".to_string(),
+                actual: value.type_str().to_string(),
+            })
+        }
+    }
+    Ok(())
+}
+
+impl Value {
+    /// The H3 cells covering this geometry at `resolution`, sorted and without duplicates.
+    ///
+    /// Polygonal geometries are polyfilled according to `containment`; each `Point` maps to
+    /// the cell containing it. `LineString`s are not supported and return an error.
+    #[cfg_attr(docsrs, doc(cfg(feature = "h3")))]
+    pub fn to_h3_cells(
+        &self,
+        resolution: Resolution,
+        containment: ContainmentMode,
+    ) -> Result<Vec<CellIndex>, Error> {
+        let mut cells = vec![];
+        collect_cells(self, resolution, containment, &mut cells)?;
+        cells.sort_unstable();
+        cells.dedup();
+        Ok(cells)
+    }
+}
+
+impl Geometry {
+    /// The H3 cells covering this geometry. See [`Value::to_h3_cells`](enum.Value.html#method.to_h3_cells).
+    #[cfg_attr(docsrs, doc(cfg(feature = "h3")))]
+    pub fn to_h3_cells(
+        &self,
+        resolution: Resolution,
+        containment: ContainmentMode,
+    ) -> Result<Vec<CellIndex>, Error> {
+        self.value.to_h3_cells(resolution, containment)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cell_to_feature, H3_PROPERTY};
+    use crate::{Error, Value};
+    use h3o::geom::ContainmentMode;
+    use h3o::{CellIndex, LatLng, Resolution};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn cell_feature() {
+        let cell = CellIndex::try_from(0x8a1fb46622dffff).unwrap();
+        let feature = cell_to_feature(cell);
+        assert_eq!(
+            feature.property(H3_PROPERTY),
+            Some(&"8a1fb46622dffff".into())
+        );
+        match feature.geometry.unwrap().value {
+            Value::Polygon(rings) => {
+                assert_eq!(rings[0].len(), 7);
+                assert_eq!(rings[0].first(), rings[0].last());
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn point_cell() {
+        let point = Value::Point(position![2.349014, 48.864716]);
+        let expected = LatLng::new(48.864716, 2.349014)
+            .unwrap()
+            .to_cell(Resolution::Nine);
+        assert_eq!(
+            point
+                .to_h3_cells(Resolution::Nine, ContainmentMode::ContainsCentroid)
+                .unwrap(),
+            vec![expected]
+        );
+    }
+
+    #[test]
+    fn polyfill_contains_centroids() {
+        let polygon = Value::Polygon(vec![vec![
+            position![2.0, 48.0],
+            position![2.5, 48.0],
+            position![2.5, 48.5],
+            position![2.0, 48.5],
+            position![2.0, 48.0],
+        ]]);
+        let cells = polygon
+            .to_h3_cells(Resolution::Six, ContainmentMode::ContainsCentroid)
+            .unwrap();
+        assert!(!cells.is_empty());
+        for cell in cells {
+            let centroid = LatLng::from(cell);
+            assert!((2.0..=2.5).contains(&centroid.lng()));
+            assert!((48.0..=48.5).contains(&centroid.lat()));
+        }
+    }
+
+    #[test]
+    fn line_string_unsupported() {
+        let line = Value::LineString(vec![position![0.0, 0.0], position![1.0, 1.0]]);
+        assert!(matches!(
+            line.to_h3_cells(Resolution::Five, ContainmentMode::Covers),
+            Err(Error::ExpectedType { .. })
+        ));
+    }
+}
@@ -0,0 +1,270 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `Hash` and `Eq` for geometries, to use them as the keys of a `HashMap` or `HashSet`.
+//!
+//! [`Value`](../enum.Value.html) and [`Geometry`](../struct.Geometry.html) are only
+//! `PartialEq`, as their coordinates are floats. Wrapping them in one of these keys chooses how
+//! coordinates are compared instead:
+//!
+//! - [`Bitwise`] compares coordinates by their bit patterns, so `0.0` and `-0.0` differ and
+//!   a `NaN` equals a `NaN` with the same bits. Geometries which are parsed from, or computed
+//!   in the same way from, the same GeoJSON are equal.
+//! - [`Quantized`] compares coordinates rounded to `DECIMALS` decimal places, so geometries
+//!   whose coordinates differ by less than the precision of their data are equal. Coordinates
+//!   either side of a rounding boundary still differ however close they are, and `NaN` rounds
+//!   to `0.0`.
+//!
+//! Either way, geometries are equal when they have the same type and structure, and the same
+//! number of coordinates in each position. A `Geometry` also compares its `bbox`, in the same
+//! way, and its foreign members. Both keys can wrap an owned geometry or a reference to one.
+//!
+//! # Example
+//!
+//! ```
+//! use geojson::hash::{Bitwise, Quantized};
+//! use geojson::{position, Value};
+//! use std::collections::HashSet;
+//!
+//! let points = [
+//!     Value::Point(position![1.0, 2.0]),
+//!     Value::Point(position![1.0, 2.0]),
+//!     Value::Point(position![1.000_000_01, 2.0]),
+//! ];
+//!
+//! let exact: HashSet<_> = points.iter().map(Bitwise).collect();
+//! assert_eq!(exact.len(), 2);
+//! let rounded: HashSet<Quantized<_, 7>> = points.iter().map(Quantized).collect();
+//! assert_eq!(rounded.len(), 1);
+//! ```
+//!
+//! [`Bitwise`]: struct.Bitwise.html
+//! [`Quantized`]: struct.Quantized.html
+
+use crate::{Geometry, Position, Value};
+use std::hash::{Hash, Hasher};
+use std::mem;
+
+/// A geometry whose coordinates can be compared and hashed by a key of each coordinate,
+/// implemented for [`Value`](../enum.Value.html), [`Geometry`](../struct.Geometry.html) and
+/// references to them
+pub trait HashCoordinates {
+    /// Feed this geometry to `state`, with `key` of each coordinate
+    fn hash_coordinates<H: Hasher>(&self, key: &dyn Fn(f64) -> u64, state: &mut H);
+
+    /// Whether this geometry and `other` are equal, with coordinates compared by `key`
+    fn eq_coordinates(&self, other: &Self, key: &dyn Fn(f64) -> u64) -> bool;
+}
+
+fn hash_all<T, H: Hasher>(items: &[T], state: &mut H, mut hash: impl FnMut(&T, &mut H)) {
+    items.len().hash(state);
+    for item in items {
+        hash(item, state);
+    }
+}
+
+fn all_eq<T>(a: &[T], b: &[T], eq: impl Fn(&T, &T) -> bool) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| eq(a, b))
+}
+
+fn hash_position<H: Hasher>(position: &[f64], key: &dyn Fn(f64) -> u64, state: &mut H) {
+    hash_all(position, state, |&x, state| key(x).hash(state));
+}
+
+fn position_eq(a: &[f64], b: &[f64], key: &dyn Fn(f64) -> u64) -> bool {
+    all_eq(a, b, |&a, &b| key(a) == key(b))
+}
+
+fn hash_line<H: Hasher>(line: &[Position], key: &dyn Fn(f64) -> u64, state: &mut H) {
+    hash_all(line, state, |position, state| {
+        hash_position(position, key, state)
+    });
+}
+
+fn line_eq(a: &[Position], b: &[Position], key: &dyn Fn(f64) -> u64) -> bool {
+    all_eq(a, b, |a, b| position_eq(a, b, key))
+}
+
+fn hash_polygon<H: Hasher>(polygon: &[Vec<Position>], key: &dyn Fn(f64) -> u64, state: &mut H) {
+    hash_all(polygon, state, |ring, state| hash_line(ring, key, state));
+}
+
+fn polygon_eq(a: &[Vec<Position>], b: &[Vec<Position>], key: &dyn Fn(f64) -> u64) -> bool {
+    all_eq(a, b, |a, b| line_eq(a, b, key))
+}
+
+impl HashCoordinates for Value {
+    fn hash_coordinates<H: Hasher>(&self, key: &dyn Fn(f64) -> u64, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        match self {
+            Value::Point(position) => hash_position(position, key, state),
+            Value::MultiPoint(line) | Value::LineString(line) => hash_line(line, key, state),
+            Value::MultiLineString(polygon) | Value::Polygon(polygon) => {
+                hash_polygon(polygon, key, state)
+            }
+            Value::MultiPolygon(polygons) => hash_all(polygons, state, |polygon, state| {
+                hash_polygon(polygon, key, state)
+            }),
+            Value::GeometryCollection(geometries) => {
+                hash_all(geometries, state, |geometry, state| {
+                    geometry.hash_coordinates(key, state)
+                })
+            }
+        }
+    }
+
+    fn eq_coordinates(&self, other: &Self, key: &dyn Fn(f64) -> u64) -> bool {
+        match (self, other) {
+            (Value::Point(a), Value::Point(b)) => position_eq(a, b, key),
+            (Value::MultiPoint(a), Value::MultiPoint(b))
+            | (Value::LineString(a), Value::LineString(b)) => line_eq(a, b, key),
+            (Value::MultiLineString(a), Value::MultiLineString(b))
+            | (Value::Polygon(a), Value::Polygon(b)) => polygon_eq(a, b, key),
+            (Value::MultiPolygon(a), Value::MultiPolygon(b)) => {
+                all_eq(a, b, |a, b| polygon_eq(a, b, key))
+            }
+            (Value::GeometryCollection(a), Value::GeometryCollection(b)) => {
+                all_eq(a, b, |a, b| a.eq_coordinates(b, key))
+            }
+            _ => false,
+        }
+    }
+}
+
+impl HashCoordinates for Geometry {
+    fn hash_coordinates<H: Hasher>(&self, key: &dyn Fn(f64) -> u64, state: &mut H) {
+        self.value.hash_coordinates(key, state);
+        self.bbox.is_some().hash(state);
+        if let Some(bbox) = &self.bbox {
+            hash_position(bbox, key, state);
+        }
+        self.foreign_members.hash(state);
+    }
+
+    fn eq_coordinates(&self, other: &Self, key: &dyn Fn(f64) -> u64) -> bool {
+        let bbox_eq = match (&self.bbox, &other.bbox) {
+            (Some(a), Some(b)) => position_eq(a, b, key),
+            (a, b) => a.is_none() && b.is_none(),
+        };
+        bbox_eq
+            && self.foreign_members == other.foreign_members
+            && self.value.eq_coordinates(&other.value, key)
+    }
+}
+
+impl<T: HashCoordinates> HashCoordinates for &T {
+    fn hash_coordinates<H: Hasher>(&self, key: &dyn Fn(f64) -> u64, state: &mut H) {
+        (**self).hash_coordinates(key, state)
+    }
+
+    fn eq_coordinates(&self, other: &Self, key: &dyn Fn(f64) -> u64) -> bool {
+        (**self).eq_coordinates(*other, key)
+    }
+}
+
+/// A geometry which is `Hash` and `Eq`, comparing coordinates by their bit patterns
+///
+/// See the [module documentation](index.html).
+#[derive(Clone, Copy, Debug)]
+pub struct Bitwise<T>(pub T);
+
+fn bits(x: f64) -> u64 {
+    x.to_bits()
+}
+
+impl<T: HashCoordinates> PartialEq for Bitwise<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq_coordinates(&other.0, &bits)
+    }
+}
+
+impl<T: HashCoordinates> Eq for Bitwise<T> {}
+
+impl<T: HashCoordinates> Hash for Bitwise<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash_coordinates(&bits, state)
+    }
+}
+
+/// A geometry which is `Hash` and `Eq`, comparing coordinates rounded to `DECIMALS` decimal
+/// places, e.g. 7 for about a centimetre in longitude and latitude
+///
+/// See the [module documentation](index.html).
+#[derive(Clone, Copy, Debug)]
+pub struct Quantized<T, const DECIMALS: u32>(pub T);
+
+impl<T, const DECIMALS: u32> Quantized<T, DECIMALS> {
+    fn key(x: f64) -> u64 {
+        (x * 10f64.powi(DECIMALS as i32)).round() as i64 as u64
+    }
+}
+
+impl<T: HashCoordinates, const DECIMALS: u32> PartialEq for Quantized<T, DECIMALS> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq_coordinates(&other.0, &Self::key)
+    }
+}
+
+impl<T: HashCoordinates, const DECIMALS: u32> Eq for Quantized<T, DECIMALS> {}
+
+impl<T: HashCoordinates, const DECIMALS: u32> Hash for Quantized<T, DECIMALS> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash_coordinates(&Self::key, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Bitwise, Quantized};
+    use crate::{Geometry, Value};
+    use std::collections::HashMap;
+
+    #[test]
+    fn geometry_keys() {
+        let geometry = |json: &str| serde_json::from_str::<Geometry>(json).unwrap();
+        let geometries = [
+            geometry(r#"{"type":"LineString","coordinates":[[0,0],[1,1]]}"#),
+            geometry(r#"{"type":"LineString","coordinates":[[0,0],[1,1]]}"#),
+            geometry(r#"{"type":"LineString","coordinates":[[0,0],[1,1.0000000001]]}"#),
+            geometry(r#"{"type":"LineString","coordinates":[[0,0],[1,1]],"bbox":[0,0,1,1]}"#),
+            geometry(r#"{"type":"MultiPoint","coordinates":[[0,0],[1,1]]}"#),
+            geometry(r#"{"type":"LineString","coordinates":[[0,0],[1,1,0]]}"#),
+            geometry(
+                r#"{"type":"GeometryCollection","geometries":[{"type":"Point","coordinates":[-0.0,0]}]}"#,
+            ),
+            geometry(
+                r#"{"type":"GeometryCollection","geometries":[{"type":"Point","coordinates":[0,0]}]}"#,
+            ),
+        ];
+
+        let mut counts = HashMap::new();
+        for geometry in &geometries {
+            *counts.entry(Bitwise(geometry)).or_insert(0) += 1;
+        }
+        assert_eq!(counts.len(), 7);
+        assert_eq!(counts[&Bitwise(&geometries[0])], 2);
+
+        let mut counts = HashMap::new();
+        for geometry in geometries.iter().cloned() {
+            *counts.entry(Quantized::<_, 7>(geometry)).or_insert(0) += 1;
+        }
+        assert_eq!(counts.len(), 5);
+        assert_eq!(counts[&Quantized(geometries[0].clone())], 3);
+        assert_eq!(counts[&Quantized(geometries[6].clone())], 2);
+
+        let nan = Value::Point(position![f64::NAN, 0.0]);
+        assert!(Bitwise(&nan) == Bitwise(&nan));
+        assert!(Quantized::<_, 3>(&nan) == Quantized(&Value::Point(position![0.0, 0.0])));
+    }
+}
@@ -0,0 +1,321 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Features whose property keys are shared between features.
+//!
+//! Every [`Feature`](../struct.Feature.html) of a collection owns its own copy of each of its
+//! property keys, even though the features of most collections all have the same few keys. An
+//! [`InternedFeature`] keeps its keys as `Arc<str>`s handed out by a [`KeyInterner`], so each
+//! distinct key is stored once, however many features it appears in.
+//!
+//! # Example
+//!
+//! ```
+//! use geojson::interned::InternedFeatureCollection;
+//! use std::sync::Arc;
+//!
+//! let json = r#"{
+//!     "type": "FeatureCollection",
+//!     "features": [
+//!         { "type": "Feature", "geometry": null, "properties": { "name": "a" } },
+//!         { "type": "Feature", "geometry": null, "properties": { "name": "b" } }
+//!     ]
+//! }"#;
+//! let collection = InternedFeatureCollection::from_str(json).unwrap();
+//! let key = |index: usize| {
+//!     let properties = collection.features[index].properties.as_ref().unwrap();
+//!     properties.keys().next().unwrap().clone()
+//! };
+//! assert!(Arc::ptr_eq(&key(0), &key(1)));
+//! ```
+//!
+//! [`InternedFeature`]: struct.InternedFeature.html
+//! [`KeyInterner`]: struct.KeyInterner.html
+
+use crate::errors::Error;
+use crate::json::{Deserializer, JsonObject, JsonValue};
+use crate::{feature, util, Bbox, Feature, FeatureCollection, Geometry};
+use serde::de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+
+/// The properties of an [`InternedFeature`](struct.InternedFeature.html)
+pub type InternedProperties = BTreeMap<Arc<str>, JsonValue>;
+
+/// A set of strings, which hands out a shared `Arc<str>` for every copy of the same string
+#[derive(Clone, Debug, Default)]
+pub struct KeyInterner {
+    keys: HashSet<Arc<str>>,
+}
+
+impl KeyInterner {
+    pub fn new() -> Self {
+        KeyInterner::default()
+    }
+
+    /// The shared copy of `key`, which is added to the interner if it isn't already there
+    pub fn intern(&mut self, key: &str) -> Arc<str> {
+        if let Some(interned) = self.keys.get(key) {
+            return interned.clone();
+        }
+        let interned: Arc<str> = Arc::from(key);
+        self.keys.insert(interned.clone());
+        interned
+    }
+
+    /// The number of distinct strings interned
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+/// A [`Feature`](../struct.Feature.html) whose property keys are interned
+#[derive(Clone, Debug, PartialEq)]
+pub struct InternedFeature {
+    pub bbox: Option<Bbox>,
+    pub geometry: Option<Geometry>,
+    pub id: Option<feature::Id>,
+    pub properties: Option<InternedProperties>,
+    pub foreign_members: Option<JsonObject>,
+}
+
+impl InternedFeature {
+    /// Move the members of `feature` into an `InternedFeature`, interning its property keys in
+    /// `interner`.
+    pub fn from_feature(feature: Feature, interner: &mut KeyInterner) -> Self {
+        InternedFeature {
+            bbox: feature.bbox,
+            geometry: feature.geometry,
+            id: feature.id,
+            properties: feature.properties.map(|properties| {
+                properties
+                    .into_iter()
+                    .map(|(key, value)| (interner.intern(&key), value))
+                    .collect()
+            }),
+            foreign_members: feature.foreign_members,
+        }
+    }
+
+    /// The value of the property `key`, if the feature has it
+    pub fn property(&self, key: impl AsRef<str>) -> Option<&JsonValue> {
+        self.properties.as_ref()?.get(key.as_ref())
+    }
+
+    /// Copy the property keys, to get a `Feature` with keys of its own
+    pub fn into_feature(self) -> Feature {
+        Feature {
+            bbox: self.bbox,
+            geometry: self.geometry,
+            id: self.id,
+            properties: self.properties.map(|properties| {
+                properties
+                    .into_iter()
+                    .map(|(key, value)| (key.to_string(), value))
+                    .collect()
+            }),
+            foreign_members: self.foreign_members,
+        }
+    }
+}
+
+impl From<InternedFeature> for Feature {
+    fn from(feature: InternedFeature) -> Self {
+        feature.into_feature()
+    }
+}
+
+/// A [`FeatureCollection`](../struct.FeatureCollection.html) of
+/// [`InternedFeature`](struct.InternedFeature.html)s
+#[derive(Clone, Debug, PartialEq)]
+pub struct InternedFeatureCollection {
+    pub bbox: Option<Bbox>,
+    pub features: Vec<InternedFeature>,
+    pub foreign_members: Option<JsonObject>,
+}
+
+impl InternedFeatureCollection {
+    /// Parse a feature collection from `json`, interning the property keys of its features.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(json: &str) -> Result<Self, Error> {
+        InternedFeatureCollection::from_str_with_interner(json, &mut KeyInterner::new())
+    }
+
+    /// Parse a feature collection from `json`, interning the property keys of its features in
+    /// `interner`, so that they are shared with those of collections parsed before.
+    ///
+    /// Each feature's keys are interned as soon as the feature is parsed, so only one feature at
+    /// a time has keys of its own.
+    pub fn from_str_with_interner(json: &str, interner: &mut KeyInterner) -> Result<Self, Error> {
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+        let collection = FeatureCollectionSeed(interner)
+            .deserialize(&mut deserializer)
+            .map_err(Error::MalformedJson)?;
+        deserializer.end().map_err(Error::MalformedJson)?;
+        Ok(collection)
+    }
+
+    /// Copy the property keys, to get a `FeatureCollection` whose features have keys of their
+    /// own
+    pub fn into_feature_collection(self) -> FeatureCollection {
+        FeatureCollection {
+            bbox: self.bbox,
+            features: self
+                .features
+                .into_iter()
+                .map(InternedFeature::into_feature)
+                .collect(),
+            foreign_members: self.foreign_members,
+        }
+    }
+}
+
+impl From<InternedFeatureCollection> for FeatureCollection {
+    fn from(collection: InternedFeatureCollection) -> Self {
+        collection.into_feature_collection()
+    }
+}
+
+struct FeaturesSeed<'i>(&'i mut KeyInterner);
+
+impl<'de> DeserializeSeed<'de> for FeaturesSeed<'_> {
+    type Value = Vec<InternedFeature>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for FeaturesSeed<'_> {
+    type Value = Vec<InternedFeature>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an array of GeoJSON Features")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut features = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(feature) = seq.next_element::<Feature>()? {
+            features.push(InternedFeature::from_feature(feature, self.0));
+        }
+        Ok(features)
+    }
+}
+
+struct FeatureCollectionSeed<'i>(&'i mut KeyInterner);
+
+impl<'de> DeserializeSeed<'de> for FeatureCollectionSeed<'_> {
+    type Value = InternedFeatureCollection;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for FeatureCollectionSeed<'_> {
+    type Value = InternedFeatureCollection;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a GeoJSON FeatureCollection")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut features = None;
+        let mut object = JsonObject::new();
+        while let Some(key) = map.next_key::<String>()? {
+            if key == "features" {
+                features = Some(map.next_value_seed(FeaturesSeed(&mut *self.0))?);
+            } else {
+                object.insert(key, map.next_value()?);
+            }
+        }
+        let features = features
+            .ok_or_else(|| de::Error::custom(Error::ExpectedProperty("features".to_string())))?;
+        feature_collection(object, features).map_err(de::Error::custom)
+    }
+}
+
+fn feature_collection(
+    mut object: JsonObject,
+    features: Vec<InternedFeature>,
+) -> Result<InternedFeatureCollection, Error> {
+    let type_ = util::expect_type(&mut object)?;
+    if type_ != "FeatureCollection" {
+        return Err(Error::ExpectedType {
+            expected: "FeatureCollection".to_owned(),
+            actual: type_,
+        });
+    }
+    Ok(InternedFeatureCollection {
+        bbox: util::get_bbox(&mut object)?,
+        features,
+        foreign_members: util::get_foreign_members(object)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InternedFeatureCollection, KeyInterner};
+    use crate::FeatureCollection;
+    use std::sync::Arc;
+
+    #[test]
+    fn shares_keys_between_features() {
+        let json = r#"{
+            "type": "FeatureCollection",
+            "bbox": [0, 0, 1, 1],
+            "features": [
+                { "type": "Feature", "geometry": null, "properties": { "a": 1, "b": { "c": 2 } } },
+                { "type": "Feature", "id": "x", "geometry": { "type": "Point", "coordinates": [1, 1] }, "properties": { "a": 3 } },
+                { "type": "Feature", "geometry": null, "properties": null }
+            ],
+            "title": "foreign"
+        }"#;
+        let mut interner = KeyInterner::new();
+        let collection =
+            InternedFeatureCollection::from_str_with_interner(json, &mut interner).unwrap();
+        assert_eq!(interner.len(), 2);
+        let key = |index: usize| {
+            let properties = collection.features[index].properties.as_ref().unwrap();
+            properties.get_key_value("a").unwrap().0.clone()
+        };
+        assert!(Arc::ptr_eq(&key(0), &key(1)));
+        assert_eq!(collection.features[1].property("a"), Some(&3.into()));
+        assert_eq!(collection.features[2].property("a"), None);
+
+        let expected: FeatureCollection = serde_json::from_str(json).unwrap();
+        assert_eq!(collection.into_feature_collection(), expected);
+    }
+
+    #[test]
+    fn invalid_collections() {
+        for json in [
+            r#"{ "type": "FeatureCollection" }"#,
+            r#"{ "type": "Feature", "features": [] }"#,
+            r#"{ "type": "FeatureCollection", "features": [{ "type": "Point" }] }"#,
+            r#"{ "type": "FeatureCollection", "features": [] } x"#,
+        ] {
+            assert!(
+                InternedFeatureCollection::from_str(json).is_err(),
+                "{}",
+                json
+            );
+        }
+    }
+}
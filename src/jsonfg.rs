@@ -0,0 +1,341 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Typed access to the [OGC Features and Geometries JSON](https://docs.ogc.org/DRAFTS/21-045.html)
+//! (JSON-FG) members of Features and FeatureCollections.
+//!
+//! JSON-FG extends GeoJSON with members which are parsed into, and serialized from, the
+//! `foreign_members` of a `Feature` or `FeatureCollection`, so documents round trip unchanged.
+//! The accessors in this module read and write those members:
+//!
+//! * `place`: a geometry in a coordinate reference system other than WGS 84
+//! * `time`: an instant or interval associated with a feature
+//! * `coordRefSys`: the coordinate reference system of `place`
+//!
+//! # Example
+//!
+//! ```
+//! use geojson::jsonfg::{CoordRefSys, Time};
+//! use geojson::Feature;
+//!
+//! let feature: Feature = serde_json::from_str(
+//!     r#"{
+//!         "type": "Feature",
+//!         "geometry": null,
+//!         "properties": null,
+//!         "time": { "interval": ["2014-04-24T10:50:18Z", ".."] },
+//!         "coordRefSys": "http://www.opengis.net/def/crs/EPSG/0/3857",
+//!         "place": { "type": "Point", "coordinates": [-13617000.0, 6044000.0] }
+//!     }"#,
+//! )
+//! .unwrap();
+//!
+//! let time = feature.time().unwrap().unwrap();
+//! assert_eq!(time.interval.unwrap()[1], "..");
+//! assert_eq!(
+//!     feature.coord_ref_sys().unwrap(),
+//!     Some(CoordRefSys::Uri("http://www.opengis.net/def/crs/EPSG/0/3857".to_string()))
+//! );
+//! assert!(feature.place().unwrap().is_some());
+//! ```
+
+use crate::json::{JsonObject, JsonValue};
+use crate::util::{foreign_member, set_foreign_member};
+use crate::{Error, Feature, FeatureCollection, Geometry};
+use serde_json::json;
+use std::convert::TryFrom;
+
+const PLACE: &str = "place";
+const TIME: &str = "time";
+const COORD_REF_SYS: &str = "coordRefSys";
+
+/// The JSON-FG `time` member of a feature.
+///
+/// Instants are kept as the strings they were written as: `date` holds an RFC 3339 full-date
+/// and `timestamp` an RFC 3339 date-time in UTC. The bounds of an `interval` are either of
+/// these, or `".."` for an unbounded end.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Time {
+    pub date: Option<String>,
+    pub timestamp: Option<String>,
+    pub interval: Option<[String; 2]>,
+}
+
+impl Time {
+    /// A `Time` of a single date, such as `"2021-04-22"`
+    pub fn date(date: impl Into<String>) -> Self {
+        Time {
+            date: Some(date.into()),
+            ..Time::default()
+        }
+    }
+
+    /// A `Time` of a single timestamp, such as `"2021-04-22T10:05:23Z"`
+    pub fn timestamp(timestamp: impl Into<String>) -> Self {
+        Time {
+            timestamp: Some(timestamp.into()),
+            ..Time::default()
+        }
+    }
+
+    /// A `Time` of an interval, where `".."` stands for an unbounded start or end
+    pub fn interval(start: impl Into<String>, end: impl Into<String>) -> Self {
+        Time {
+            interval: Some([start.into(), end.into()]),
+            ..Time::default()
+        }
+    }
+}
+
+impl TryFrom<&JsonValue> for Time {
+    type Error = Error;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let invalid = || Error::InvalidJsonFgMember(TIME, value.clone());
+        let object = value.as_object().ok_or_else(invalid)?;
+        let string = |key: &str| match object.get(key) {
+            None | Some(JsonValue::Null) => Ok(None),
+            Some(JsonValue::String(s)) => Ok(Some(s.clone())),
+            Some(_) => Err(invalid()),
+        };
+        let interval = match object.get("interval") {
+            None | Some(JsonValue::Null) => None,
+            Some(JsonValue::Array(bounds)) => match bounds.as_slice() {
+                [JsonValue::String(start), JsonValue::String(end)] => {
+                    Some([start.clone(), end.clone()])
+                }
+                _ => return Err(invalid()),
+            },
+            Some(_) => return Err(invalid()),
+        };
+        Ok(Time {
+            date: string("date")?,
+            timestamp: string("timestamp")?,
+            interval,
+        })
+    }
+}
+
+impl From<&Time> for JsonValue {
+    fn from(time: &Time) -> Self {
+        let mut object = JsonObject::new();
+        if let Some(date) = &time.date {
+            object.insert("date".to_string(), json!(date));
+        }
+        if let Some(timestamp) = &time.timestamp {
+            object.insert("timestamp".to_string(), json!(timestamp));
+        }
+        if let Some(interval) = &time.interval {
+            object.insert("interval".to_string(), json!(interval));
+        }
+        JsonValue::Object(object)
+    }
+}
+
+/// The JSON-FG `coordRefSys` member of a feature or feature collection
+#[derive(Clone, Debug, PartialEq)]
+pub enum CoordRefSys {
+    /// A URI or safe CURIE, such as `"http://www.opengis.net/def/crs/EPSG/0/3857"` or
+    /// `"[EPSG:3857]"`
+    Uri(String),
+    /// A reference by `href`, with an optional coordinate epoch for dynamic reference systems
+    Reference { href: String, epoch: Option<f64> },
+    /// A compound reference system, such as a horizontal and a vertical one
+    Compound(Vec<CoordRefSys>),
+}
+
+impl TryFrom<&JsonValue> for CoordRefSys {
+    type Error = Error;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let invalid = || Error::InvalidJsonFgMember(COORD_REF_SYS, value.clone());
+        match value {
+            JsonValue::String(uri) => Ok(CoordRefSys::Uri(uri.clone())),
+            JsonValue::Array(members) => members
+                .iter()
+                .map(CoordRefSys::try_from)
+                .collect::<Result<_, _>>()
+                .map(CoordRefSys::Compound),
+            JsonValue::Object(object) => {
+                if object.get("type").and_then(JsonValue::as_str) != Some("Reference") {
+                    return Err(invalid());
+                }
+                let href = object
+                    .get("href")
+                    .and_then(JsonValue::as_str)
+                    .ok_or_else(invalid)?;
+                let epoch = match object.get("epoch") {
+                    None | Some(JsonValue::Null) => None,
+                    Some(epoch) => Some(epoch.as_f64().ok_or_else(invalid)?),
+                };
+                Ok(CoordRefSys::Reference {
+                    href: href.to_string(),
+                    epoch,
+                })
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl From<&CoordRefSys> for JsonValue {
+    fn from(crs: &CoordRefSys) -> Self {
+        match crs {
+            CoordRefSys::Uri(uri) => json!(uri),
+            CoordRefSys::Reference { href, epoch: None } => {
+                json!({ "type": "Reference", "href": href })
+            }
+            CoordRefSys::Reference {
+                href,
+                epoch: Some(epoch),
+            } => json!({ "type": "Reference", "href": href, "epoch": epoch }),
+            CoordRefSys::Compound(members) => members.iter().map(JsonValue::from).collect(),
+        }
+    }
+}
+
+impl Feature {
+    /// The JSON-FG `place` of this feature, if it is present and not `null`.
+    ///
+    /// JSON-FG also allows solid geometries, such as `Polyhedron`, in `place`; these produce an
+    /// `Error::GeometryUnknownType`.
+    pub fn place(&self) -> Result<Option<Geometry>, Error> {
+        foreign_member(&self.foreign_members, PLACE)
+            .map(|value| Geometry::from_json_value(value.clone()))
+            .transpose()
+    }
+
+    /// Set the JSON-FG `place` of this feature, or remove it with `None`
+    pub fn set_place(&mut self, place: Option<&Geometry>) {
+        set_foreign_member(
+            &mut self.foreign_members,
+            PLACE,
+            place.map(|geometry| JsonObject::from(geometry).into()),
+        )
+    }
+
+    /// The JSON-FG `time` of this feature, if it is present and not `null`
+    pub fn time(&self) -> Result<Option<Time>, Error> {
+        foreign_member(&self.foreign_members, TIME)
+            .map(Time::try_from)
+            .transpose()
+    }
+
+    /// Set the JSON-FG `time` of this feature, or remove it with `None`
+    pub fn set_time(&mut self, time: Option<&Time>) {
+        set_foreign_member(&mut self.foreign_members, TIME, time.map(JsonValue::from))
+    }
+
+    /// The JSON-FG `coordRefSys` of this feature, if it is present and not `null`
+    pub fn coord_ref_sys(&self) -> Result<Option<CoordRefSys>, Error> {
+        foreign_member(&self.foreign_members, COORD_REF_SYS)
+            .map(CoordRefSys::try_from)
+            .transpose()
+    }
+
+    /// Set the JSON-FG `coordRefSys` of this feature, or remove it with `None`
+    pub fn set_coord_ref_sys(&mut self, crs: Option<&CoordRefSys>) {
+        set_foreign_member(
+            &mut self.foreign_members,
+            COORD_REF_SYS,
+            crs.map(JsonValue::from),
+        )
+    }
+}
+
+impl FeatureCollection {
+    /// The JSON-FG `coordRefSys` of this collection, which applies to each feature without
+    /// its own, if it is present and not `null`
+    pub fn coord_ref_sys(&self) -> Result<Option<CoordRefSys>, Error> {
+        foreign_member(&self.foreign_members, COORD_REF_SYS)
+            .map(CoordRefSys::try_from)
+            .transpose()
+    }
+
+    /// Set the JSON-FG `coordRefSys` of this collection, or remove it with `None`
+    pub fn set_coord_ref_sys(&mut self, crs: Option<&CoordRefSys>) {
+        set_foreign_member(
+            &mut self.foreign_members,
+            COORD_REF_SYS,
+            crs.map(JsonValue::from),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CoordRefSys, Time};
+    use crate::{Error, Feature, FeatureCollection, Geometry, Value};
+    use serde_json::json;
+
+    #[test]
+    fn set_and_roundtrip() {
+        let mut feature = Feature::from(Value::Point(position![1.0, 2.0]));
+        let place = Geometry::new(Value::Point(position![111319.0, 222684.0]));
+        let crs = CoordRefSys::Compound(vec![
+            CoordRefSys::Reference {
+                href: "http://www.opengis.net/def/crs/EPSG/0/3857".to_string(),
+                epoch: Some(2017.23),
+            },
+            CoordRefSys::Uri("[EPSG:5703]".to_string()),
+        ]);
+        feature.set_place(Some(&place));
+        feature.set_time(Some(&Time::date("2021-04-22")));
+        feature.set_coord_ref_sys(Some(&crs));
+
+        let parsed: Feature = serde_json::from_str(&feature.to_string()).unwrap();
+        assert_eq!(parsed.place().unwrap(), Some(place));
+        assert_eq!(parsed.time().unwrap(), Some(Time::date("2021-04-22")));
+        assert_eq!(parsed.coord_ref_sys().unwrap(), Some(crs));
+        assert_eq!(
+            parsed.foreign_members.as_ref().unwrap()["time"],
+            json!({ "date": "2021-04-22" })
+        );
+
+        let mut feature = parsed;
+        feature.set_place(None);
+        feature.set_time(None);
+        feature.set_coord_ref_sys(None);
+        assert_eq!(feature.foreign_members, None);
+    }
+
+    #[test]
+    fn null_and_invalid_members() {
+        let feature = Feature::from_json_value(json!({
+            "type": "Feature",
+            "geometry": null,
+            "properties": null,
+            "time": null,
+            "coordRefSys": { "type": "Unknown" },
+        }))
+        .unwrap();
+        assert_eq!(feature.time().unwrap(), None);
+        assert_eq!(feature.place().unwrap(), None);
+        assert!(matches!(
+            feature.coord_ref_sys(),
+            Err(Error::InvalidJsonFgMember("coordRefSys", _))
+        ));
+
+        let collection = FeatureCollection::from_json_value(json!({
+            "type": "FeatureCollection",
+            "features": [],
+            "coordRefSys": "[EPSG:25832]",
+        }))
+        .unwrap();
+        assert_eq!(
+            collection.coord_ref_sys().unwrap(),
+            Some(CoordRefSys::Uri("[EPSG:25832]".to_string()))
+        );
+    }
+}
@@ -0,0 +1,209 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Typed access to the [GeoJSON-LD](https://geojson.org/geojson-ld/) `@context` member.
+//!
+//! A `@context` is parsed into, and serialized from, the `foreign_members` of a `Geometry`,
+//! `Feature` or `FeatureCollection` like any other foreign member, so linked data annotations
+//! round trip unchanged. The `context` and `set_context` accessors read and write it as a
+//! [`Context`](enum.Context.html).
+//!
+//! # Example
+//!
+//! ```
+//! use geojson::jsonld::{Context, GEOJSON_CONTEXT};
+//! use geojson::GeoJson;
+//!
+//! let geojson: GeoJson = r#"{
+//!     "@context": [
+//!         "https://geojson.org/geojson-ld/geojson-context.jsonld",
+//!         { "name": "http://schema.org/name" }
+//!     ],
+//!     "type": "FeatureCollection",
+//!     "features": []
+//! }"#
+//! .parse()
+//! .unwrap();
+//!
+//! match geojson.context().unwrap().unwrap() {
+//!     Context::List(contexts) => assert_eq!(contexts[0], Context::Iri(GEOJSON_CONTEXT.to_string())),
+//!     _ => unreachable!(),
+//! }
+//! assert!(geojson.to_string().contains("\"@context\""));
+//! ```
+
+use crate::json::{JsonObject, JsonValue};
+use crate::util::{foreign_member, set_foreign_member};
+use crate::{Error, Feature, FeatureCollection, GeoJson, Geometry};
+use std::convert::TryFrom;
+
+const CONTEXT: &str = "@context";
+
+/// The IRI of the context published alongside GeoJSON-LD, which maps the GeoJSON vocabulary
+/// to linked data terms
+pub const GEOJSON_CONTEXT: &str = "https://geojson.org/geojson-ld/geojson-context.jsonld";
+
+/// A JSON-LD `@context`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Context {
+    /// The IRI of a remote context document, such as [`GEOJSON_CONTEXT`]
+    ///
+    /// [`GEOJSON_CONTEXT`]: constant.GEOJSON_CONTEXT.html
+    Iri(String),
+    /// An embedded context definition, mapping terms to IRIs
+    Definition(JsonObject),
+    /// Several contexts, applied in order
+    List(Vec<Context>),
+}
+
+impl Context {
+    /// The context of GeoJSON-LD, [`GEOJSON_CONTEXT`]
+    ///
+    /// [`GEOJSON_CONTEXT`]: constant.GEOJSON_CONTEXT.html
+    pub fn geojson() -> Self {
+        Context::Iri(GEOJSON_CONTEXT.to_string())
+    }
+}
+
+impl TryFrom<&JsonValue> for Context {
+    type Error = Error;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        match value {
+            JsonValue::String(iri) => Ok(Context::Iri(iri.clone())),
+            JsonValue::Object(definition) => Ok(Context::Definition(definition.clone())),
+            JsonValue::Array(contexts) => contexts
+                .iter()
+                .map(Context::try_from)
+                .collect::<Result<_, _>>()
+                .map(Context::List),
+            _ => Err(Error::InvalidJsonLdContext(value.clone())),
+        }
+    }
+}
+
+impl From<&Context> for JsonValue {
+    fn from(context: &Context) -> Self {
+        match context {
+            Context::Iri(iri) => JsonValue::String(iri.clone()),
+            Context::Definition(definition) => JsonValue::Object(definition.clone()),
+            Context::List(contexts) => contexts.iter().map(JsonValue::from).collect(),
+        }
+    }
+}
+
+fn context(foreign_members: &Option<JsonObject>) -> Result<Option<Context>, Error> {
+    foreign_member(foreign_members, CONTEXT)
+        .map(Context::try_from)
+        .transpose()
+}
+
+fn set_context(foreign_members: &mut Option<JsonObject>, context: Option<&Context>) {
+    set_foreign_member(foreign_members, CONTEXT, context.map(JsonValue::from))
+}
+
+macro_rules! impl_context {
+    ($($type:ty),*) => {
+        $(
+            impl $type {
+                /// The GeoJSON-LD `@context` of this object, if it is present and not `null`.
+                pub fn context(&self) -> Result<Option<Context>, Error> {
+                    context(&self.foreign_members)
+                }
+
+                /// Set, or with `None` remove, the GeoJSON-LD `@context` of this object.
+                pub fn set_context(&mut self, context: Option<&Context>) {
+                    set_context(&mut self.foreign_members, context)
+                }
+            }
+        )*
+    };
+}
+
+impl_context!(Geometry, Feature, FeatureCollection);
+
+impl GeoJson {
+    /// The GeoJSON-LD `@context` of the top-level object, if it is present and not `null`.
+    pub fn context(&self) -> Result<Option<Context>, Error> {
+        match self {
+            GeoJson::Geometry(geometry) => geometry.context(),
+            GeoJson::Feature(feature) => feature.context(),
+            GeoJson::FeatureCollection(collection) => collection.context(),
+        }
+    }
+
+    /// Set, or with `None` remove, the GeoJSON-LD `@context` of the top-level object.
+    pub fn set_context(&mut self, context: Option<&Context>) {
+        match self {
+            GeoJson::Geometry(geometry) => geometry.set_context(context),
+            GeoJson::Feature(feature) => feature.set_context(context),
+            GeoJson::FeatureCollection(collection) => collection.set_context(context),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Context;
+    use crate::{Feature, GeoJson, Geometry, Value};
+    use serde_json::json;
+
+    #[test]
+    fn roundtrip() {
+        let json = json!({
+            "@context": { "name": "http://schema.org/name" },
+            "type": "Feature",
+            "geometry": { "type": "Point", "coordinates": [1.0, 2.0] },
+            "properties": { "name": "Dinagat Islands" }
+        });
+        let feature = Feature::from_json_value(json.clone()).unwrap();
+        match feature.context().unwrap() {
+            Some(Context::Definition(definition)) => {
+                assert_eq!(definition["name"], "http://schema.org/name")
+            }
+            context => panic!("unexpected context {:?}", context),
+        }
+        assert_eq!(serde_json::to_value(&feature).unwrap(), json);
+    }
+
+    #[test]
+    fn set_and_remove() {
+        let mut geojson = GeoJson::from(Geometry::new(Value::Point(position![1.0, 2.0])));
+        geojson.set_context(Some(&Context::List(vec![
+            Context::geojson(),
+            Context::Iri("https://example.com/context.jsonld".to_string()),
+        ])));
+        let json = serde_json::to_value(&geojson).unwrap();
+        assert_eq!(json["@context"][0], super::GEOJSON_CONTEXT);
+
+        geojson.set_context(None);
+        assert_eq!(geojson.context().unwrap(), None);
+        match geojson {
+            GeoJson::Geometry(geometry) => assert_eq!(geometry.foreign_members, None),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn invalid_context() {
+        let feature = Feature::from_json_value(json!({
+            "@context": 42,
+            "type": "Feature",
+            "geometry": null,
+            "properties": null
+        }))
+        .unwrap();
+        assert!(feature.context().is_err());
+    }
+}
@@ -0,0 +1,461 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Features whose geometries and properties are only parsed when they are first used.
+//!
+//! Most of the time spent parsing GeoJSON goes to coordinates, and much of the rest to
+//! properties. A [`LazyFeature`] keeps both as unparsed JSON until [`Lazy::get`] is called, so
+//! that work which only reads properties, such as filtering features or joining their
+//! attributes, skips the geometries, and work which only reads geometries, such as tiling or
+//! rendering, skips the properties. A lazy feature which is written again writes whatever it
+//! hasn't parsed exactly as it was read.
+//!
+//! Since these members aren't parsed up front, an invalid geometry or properties is only
+//! reported when it is first used.
+//!
+//! # Example
+//!
+//! ```
+//! use geojson::position;
+//! use geojson::lazy::LazyFeatureCollection;
+//!
+//! let json = r#"{
+//!     "type": "FeatureCollection",
+//!     "features": [
+//!         { "type": "Feature", "geometry": { "type": "Point", "coordinates": [1, 2] }, "properties": { "keep": true } },
+//!         { "type": "Feature", "geometry": { "type": "Point", "coordinates": [3, 4] }, "properties": { "keep": false } }
+//!     ]
+//! }"#;
+//! let mut collection: LazyFeatureCollection = serde_json::from_str(json).unwrap();
+//! collection
+//!     .features
+//!     .retain(|feature| feature.property("keep").unwrap() == Some(&serde_json::Value::Bool(true)));
+//! let geometry = collection.features[0].geometry.get().unwrap().as_ref().unwrap();
+//! assert_eq!(geometry.value, geojson::Value::Point(position![1.0, 2.0]));
+//! ```
+//!
+//! [`LazyFeature`]: struct.LazyFeature.html
+//! [`Lazy::get`]: struct.Lazy.html#method.get
+
+use crate::errors::Error;
+use crate::json::{Deserialize, Deserializer, JsonObject, JsonValue, Serialize, Serializer};
+use crate::{feature, util, Bbox, Feature, FeatureCollection, Geometry};
+use serde::de::{self, DeserializeOwned, MapAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde_json::value::RawValue;
+use std::convert::TryFrom;
+use std::fmt;
+use std::sync::OnceLock;
+
+/// A member of a feature which is parsed when it is first used
+///
+/// The parsed member is cached in a `OnceLock`, so lazy features can be shared between
+/// threads, e.g. behind an `Arc`.
+#[derive(Clone, Debug)]
+pub struct Lazy<T> {
+    raw: Box<RawValue>,
+    parsed: OnceLock<T>,
+}
+
+/// The `geometry` member of a [`LazyFeature`](struct.LazyFeature.html)
+pub type LazyGeometry = Lazy<Option<Geometry>>;
+
+/// The `properties` member of a [`LazyFeature`](struct.LazyFeature.html)
+pub type LazyProperties = Lazy<Option<JsonObject>>;
+
+impl<T: DeserializeOwned> Lazy<T> {
+    /// The unparsed JSON of the member, which may be `null`
+    pub fn raw(&self) -> &RawValue {
+        &self.raw
+    }
+
+    /// The member, parsing it if this is its first use.
+    ///
+    /// A member which fails to parse is parsed again, and fails again, on every call.
+    pub fn get(&self) -> Result<&T, Error> {
+        if let Some(parsed) = self.parsed.get() {
+            return Ok(parsed);
+        }
+        let parsed = parse(&self.raw)?;
+        Ok(self.parsed.get_or_init(|| parsed))
+    }
+
+    /// The member, parsing it unless it has been used already
+    pub fn into_parsed(self) -> Result<T, Error> {
+        match self.parsed.into_inner() {
+            Some(parsed) => Ok(parsed),
+            None => parse(&self.raw),
+        }
+    }
+}
+
+fn parse<T: DeserializeOwned>(raw: &RawValue) -> Result<T, Error> {
+    serde_json::from_str(raw.get()).map_err(Error::MalformedJson)
+}
+
+impl<T: Serialize> Lazy<T> {
+    fn from_parsed(parsed: T) -> Self {
+        Lazy {
+            raw: serde_json::value::to_raw_value(&parsed).unwrap(),
+            parsed: parsed.into(),
+        }
+    }
+}
+
+/// Members are equal if they parse to equal values, regardless of how their JSON is
+/// formatted. Comparing members parses them, unless their JSON is identical, and a member which
+/// fails to parse is only equal to one with identical JSON.
+impl<T: DeserializeOwned + PartialEq> PartialEq for Lazy<T> {
+    fn eq(&self, other: &Self) -> bool {
+        if self.raw.get() == other.raw.get() {
+            return true;
+        }
+        match (self.get(), other.get()) {
+            (Ok(parsed), Ok(other)) => parsed == other,
+            _ => false,
+        }
+    }
+}
+
+impl<T> Serialize for Lazy<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.raw.serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Lazy<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Lazy {
+            raw: Box::<RawValue>::deserialize(deserializer)?,
+            parsed: OnceLock::new(),
+        })
+    }
+}
+
+/// A [`Feature`](../struct.Feature.html) whose geometry and properties are only parsed when
+/// they are first used
+#[derive(Clone, Debug, PartialEq)]
+pub struct LazyFeature {
+    pub bbox: Option<Bbox>,
+    pub geometry: LazyGeometry,
+    pub id: Option<feature::Id>,
+    pub properties: LazyProperties,
+    pub foreign_members: Option<JsonObject>,
+}
+
+impl LazyFeature {
+    /// The property `key`, if there is one, parsing the properties if this is their first use
+    pub fn property(&self, key: impl AsRef<str>) -> Result<Option<&JsonValue>, Error> {
+        Ok(self
+            .properties
+            .get()?
+            .as_ref()
+            .and_then(|properties| properties.get(key.as_ref())))
+    }
+
+    /// Parse the geometry and properties, unless they have been used already, to get a
+    /// `Feature`.
+    pub fn into_feature(self) -> Result<Feature, Error> {
+        Ok(Feature {
+            bbox: self.bbox,
+            geometry: self.geometry.into_parsed()?,
+            id: self.id,
+            properties: self.properties.into_parsed()?,
+            foreign_members: self.foreign_members,
+        })
+    }
+}
+
+impl TryFrom<LazyFeature> for Feature {
+    type Error = Error;
+
+    fn try_from(feature: LazyFeature) -> Result<Self, Error> {
+        feature.into_feature()
+    }
+}
+
+impl From<&Feature> for LazyFeature {
+    fn from(feature: &Feature) -> Self {
+        LazyFeature {
+            bbox: feature.bbox.clone(),
+            geometry: Lazy::from_parsed(feature.geometry.clone()),
+            id: feature.id.clone(),
+            properties: Lazy::from_parsed(feature.properties.clone()),
+            foreign_members: feature.foreign_members.clone(),
+        }
+    }
+}
+
+impl Serialize for LazyFeature {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("type", "Feature")?;
+        map.serialize_entry("geometry", &self.geometry)?;
+        map.serialize_entry("properties", &self.properties)?;
+        if let Some(ref bbox) = self.bbox {
+            map.serialize_entry("bbox", bbox)?;
+        }
+        if let Some(ref id) = self.id {
+            map.serialize_entry("id", id)?;
+        }
+        if let Some(ref foreign_members) = self.foreign_members {
+            for (key, value) in foreign_members {
+                map.serialize_entry(key, value)?;
+            }
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for LazyFeature {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct LazyFeatureVisitor;
+
+        impl<'de> Visitor<'de> for LazyFeatureVisitor {
+            type Value = LazyFeature;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a GeoJSON Feature")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut geometry = None;
+                let mut properties = None;
+                let mut object = JsonObject::new();
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "geometry" => geometry = Some(map.next_value()?),
+                        "properties" => properties = Some(map.next_value()?),
+                        _ => {
+                            object.insert(key, map.next_value()?);
+                        }
+                    }
+                }
+                let geometry = geometry.ok_or_else(|| de::Error::custom(missing("geometry")))?;
+                let properties =
+                    properties.ok_or_else(|| de::Error::custom(missing("properties")))?;
+                lazy_feature(object, geometry, properties).map_err(de::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_map(LazyFeatureVisitor)
+    }
+}
+
+fn missing(name: &str) -> Error {
+    Error::ExpectedProperty(name.to_string())
+}
+
+fn lazy_feature(
+    mut object: JsonObject,
+    geometry: LazyGeometry,
+    properties: LazyProperties,
+) -> Result<LazyFeature, Error> {
+    let type_ = util::expect_type(&mut object)?;
+    if type_ != "Feature" {
+        return Err(Error::NotAFeature(type_));
+    }
+    Ok(LazyFeature {
+        geometry,
+        properties,
+        id: util::get_id(&mut object)?,
+        bbox: util::get_bbox(&mut object)?,
+        foreign_members: util::get_foreign_members(object)?,
+    })
+}
+
+/// A [`FeatureCollection`](../struct.FeatureCollection.html) of
+/// [`LazyFeature`](struct.LazyFeature.html)s
+#[derive(Clone, Debug, PartialEq)]
+pub struct LazyFeatureCollection {
+    pub bbox: Option<Bbox>,
+    pub features: Vec<LazyFeature>,
+    pub foreign_members: Option<JsonObject>,
+}
+
+impl LazyFeatureCollection {
+    /// Parse the geometries and properties which haven't been used yet, to get a
+    /// `FeatureCollection`.
+    pub fn into_feature_collection(self) -> Result<FeatureCollection, Error> {
+        Ok(FeatureCollection {
+            bbox: self.bbox,
+            features: self
+                .features
+                .into_iter()
+                .map(LazyFeature::into_feature)
+                .collect::<Result<_, _>>()?,
+            foreign_members: self.foreign_members,
+        })
+    }
+}
+
+impl TryFrom<LazyFeatureCollection> for FeatureCollection {
+    type Error = Error;
+
+    fn try_from(collection: LazyFeatureCollection) -> Result<Self, Error> {
+        collection.into_feature_collection()
+    }
+}
+
+impl Serialize for LazyFeatureCollection {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("type", "FeatureCollection")?;
+        map.serialize_entry("features", &self.features)?;
+        if let Some(ref bbox) = self.bbox {
+            map.serialize_entry("bbox", bbox)?;
+        }
+        if let Some(ref foreign_members) = self.foreign_members {
+            for (key, value) in foreign_members {
+                map.serialize_entry(key, value)?;
+            }
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for LazyFeatureCollection {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct LazyFeatureCollectionVisitor;
+
+        impl<'de> Visitor<'de> for LazyFeatureCollectionVisitor {
+            type Value = LazyFeatureCollection;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a GeoJSON FeatureCollection")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut features = None;
+                let mut object = JsonObject::new();
+                while let Some(key) = map.next_key::<String>()? {
+                    if key == "features" {
+                        features = Some(map.next_value()?);
+                    } else {
+                        object.insert(key, map.next_value()?);
+                    }
+                }
+                let features = features.ok_or_else(|| de::Error::custom(missing("features")))?;
+                lazy_feature_collection(object, features).map_err(de::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_map(LazyFeatureCollectionVisitor)
+    }
+}
+
+fn lazy_feature_collection(
+    mut object: JsonObject,
+    features: Vec<LazyFeature>,
+) -> Result<LazyFeatureCollection, Error> {
+    let type_ = util::expect_type(&mut object)?;
+    if type_ != "FeatureCollection" {
+        return Err(Error::ExpectedType {
+            expected: "FeatureCollection".to_owned(),
+            actual: type_,
+        });
+    }
+    Ok(LazyFeatureCollection {
+        bbox: util::get_bbox(&mut object)?,
+        features,
+        foreign_members: util::get_foreign_members(object)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LazyFeature, LazyFeatureCollection};
+    use crate::{Feature, FeatureCollection};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn same_as_eager_parse() {
+        let json = include_str!("../tests/fixtures/countries.geojson");
+        let lazy: LazyFeatureCollection = serde_json::from_str(json).unwrap();
+        let eager: FeatureCollection = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            lazy.features[0].property("name").unwrap(),
+            eager.features[0].property("name")
+        );
+        assert_eq!(
+            lazy.features[0].geometry.get().unwrap(),
+            &eager.features[0].geometry
+        );
+        assert_eq!(
+            FeatureCollection::try_from(lazy.clone()).unwrap(),
+            eager.clone()
+        );
+
+        // Written again, lazy features are the same as eager ones
+        let rewritten: FeatureCollection =
+            serde_json::from_str(&serde_json::to_string(&lazy).unwrap()).unwrap();
+        assert_eq!(rewritten, eager);
+        let feature = LazyFeature::from(&eager.features[0]);
+        assert_eq!(
+            serde_json::to_value(&feature).unwrap(),
+            serde_json::to_value(&eager.features[0]).unwrap()
+        );
+    }
+
+    #[test]
+    fn invalid_members_are_reported_on_use() {
+        let json = r#"{"type":"Feature","geometry":{"type":"Point"},"properties":null}"#;
+        let feature: LazyFeature = serde_json::from_str(json).unwrap();
+        assert_eq!(feature.geometry.raw().get(), r#"{"type":"Point"}"#);
+        assert!(feature.geometry.get().is_err());
+        assert!(Feature::try_from(feature).is_err());
+
+        let feature: LazyFeature =
+            serde_json::from_str(r#"{"type":"Feature","geometry":null,"properties":{}}"#).unwrap();
+        assert_eq!(feature.geometry.get().unwrap(), &None);
+        assert_eq!(feature.property("a").unwrap(), None);
+
+        let json = r#"{"type":"Feature","geometry":null,"properties":[1]}"#;
+        let feature: LazyFeature = serde_json::from_str(json).unwrap();
+        assert!(feature.property("a").is_err());
+        assert!(feature.clone().into_feature().is_err());
+        assert_eq!(feature.properties.raw().get(), "[1]");
+
+        assert!(
+            serde_json::from_str::<LazyFeature>(r#"{"type":"Feature","properties":{}}"#).is_err()
+        );
+        assert!(
+            serde_json::from_str::<LazyFeature>(r#"{"type":"Feature","geometry":null}"#).is_err()
+        );
+        assert!(matches!(feature.geometry.into_parsed(), Ok(None)));
+    }
+
+    #[test]
+    fn parsed_equality_across_threads() {
+        fn assert_sync<T: Send + Sync>() {}
+        assert_sync::<LazyFeature>();
+        assert_sync::<LazyFeatureCollection>();
+
+        let compact: LazyFeature = serde_json::from_str(
+            r#"{"type":"Feature","geometry":{"type":"Point","coordinates":[1,2]},"properties":{"a":1}}"#,
+        )
+        .unwrap();
+        let spaced: LazyFeature = serde_json::from_str(
+            r#"{ "type": "Feature", "geometry": { "coordinates": [1.0, 2.0], "type": "Point" }, "properties": { "a": 1 } }"#,
+        )
+        .unwrap();
+        assert_eq!(compact, spaced);
+        let shared = std::sync::Arc::new(spaced);
+        let other = std::sync::Arc::clone(&shared);
+        let geometry = std::thread::spawn(move || other.geometry.get().unwrap().clone())
+            .join()
+            .unwrap();
+        assert_eq!(&geometry, compact.geometry.get().unwrap());
+    }
+}
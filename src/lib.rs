@@ -119,7 +119,7 @@
 //! `GeoJson` can then be serialized by calling `to_string`:
 //!
 //! ```rust
-//! use geojson::{Feature, GeoJson, Geometry, Value};
+//! use geojson::{position, Feature, GeoJson, Geometry, Value};
 //! # fn properties() -> ::serde_json::Map<String, ::serde_json::Value> {
 //! # let mut properties = ::serde_json::Map::new();
 //! # properties.insert(
@@ -131,7 +131,7 @@
 //! # fn main() {
 //! # let properties = properties();
 //!
-//! let geometry = Geometry::new(Value::Point(vec![-120.66029, 35.2812]));
+//! let geometry = Geometry::new(Value::Point(position![-120.66029, 35.2812]));
 //!
 //! let geojson = GeoJson::Feature(Feature {
 //!     bbox: None,
@@ -145,6 +145,9 @@
 //! # }
 //! ```
 //!
+//! `to_string` writes everything on a single line; `to_string_with` writes it according to
+//! [`WriterOptions`](struct.WriterOptions.html), e.g. indented for human readers.
+//!
 //! ## Parsing
 //!
 //! GeoJSON's [spec](https://tools.ietf.org/html/rfc7946) is quite simple, but
@@ -315,21 +318,46 @@
 //! ```
 //! ### Caveats
 //! - Round-tripping with intermediate processing using the `geo` types may not produce identical output,
-//! as e.g. outer `Polygon` rings are automatically closed.
+//!   as e.g. outer `Polygon` rings are automatically closed.
 //! - `geojson` attempts to output valid geometries. In particular, it may re-orient `Polygon` rings when serialising.
 //!
 //! The [`geojson_example`](https://github.com/urschrei/geojson_example) and
 //! [`polylabel_cmd`](https://github.com/urschrei/polylabel_cmd/blob/master/src/main.rs) crates contain example
 //! implementations which may be useful if you wish to perform this kind of processing yourself and require
 //! more granular control over performance and / or memory allocation.
+//!
+//! # `no_std`
+//!
+//! The `std` feature is enabled by default. Without it, the crate only needs `alloc`, and
+//! provides the GeoJSON types and their conversions from and to JSON with `serde` and
+//! `serde_json`, for targets without an operating system, such as the firmware of a device
+//! which logs its track as GeoJSON. Reading from `std::io`, the writer options, the algorithms
+//! and the other modules, and every other optional feature apart from `compact`, require `std`.
+//!
+//! ```toml
+//! [dependencies]
+//! geojson = { version = "0.22", default-features = false }
+//! ```
+//!
+//! # Binary formats
+//!
+//! GeoJSON objects are serialized as the same maps of members in every serde format. In formats
+//! which are not self-describing, such as bincode, they can't be deserialized from those maps.
+//! The `compact` feature instead writes them as structs and enums of their members in formats
+//! which are not human readable, such as bincode, CBOR and MessagePack, which is also smaller.
+//! JSON is written as GeoJSON either way. Data written with and without the feature can't be
+//! read by the other.
 
 // only enables the `doc_cfg` feature when
 // the `docsrs` configuration attribute is defined
 #![cfg_attr(docsrs, feature(doc_cfg))]
-#[cfg(feature = "geo-types")]
-use geo_types;
-use serde;
-use serde_json;
+// `Error` carries the offending `Feature` or `Value` for context, which makes it large
+#![allow(clippy::result_large_err)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
 
 /// Bounding Boxes
 ///
@@ -338,35 +366,233 @@ pub type Bbox = Vec<f64>;
 
 /// Positions
 ///
+/// Positions of up to three coordinates are stored inline, without a heap allocation of their
+/// own. Create them with [`position!`](macro.position.html), or with `From<Vec<f64>>`.
+///
 /// [GeoJSON Format Specification § 3.1.1](https://tools.ietf.org/html/rfc7946#section-3.1.1)
-pub type Position = Vec<f64>;
+pub type Position = smallvec::SmallVec<[f64; 3]>;
 
 pub type PointType = Position;
 pub type LineStringType = Vec<Position>;
 pub type PolygonType = Vec<Vec<Position>>;
 
+/// Creates a [`Position`](type.Position.html) of its coordinates, as `vec!` creates a `Vec`
+///
+/// ```
+/// let position: geojson::Position = geojson::position![1.0, 2.0];
+/// assert_eq!(position.as_slice(), &[1.0, 2.0]);
+/// ```
+#[macro_export]
+macro_rules! position {
+    ($($coordinate:expr),* $(,)?) => {
+        $crate::Position::from_slice(&[$($coordinate),*])
+    };
+}
+
 mod util;
 
 mod geojson;
-pub use crate::geojson::GeoJson;
+pub use crate::geojson::{GeoJson, ReaderOptions};
 
 mod geometry;
-pub use crate::geometry::{Geometry, Value};
+pub use crate::geometry::{Geometry, GeometryType, Value};
 
 pub mod feature;
 
 mod feature_collection;
 pub use crate::feature_collection::FeatureCollection;
+#[cfg(feature = "std")]
+pub use crate::feature_collection::IndexedFeatureCollection;
 
 pub mod errors;
 pub use crate::errors::Error;
 
+#[cfg(feature = "std")]
+mod algorithm;
+#[cfg(feature = "compact")]
+mod compact;
+mod de;
+#[cfg(feature = "std")]
+mod patch;
+#[cfg(feature = "std")]
+mod ser;
+#[cfg(feature = "std")]
+mod size;
+#[cfg(feature = "std")]
+pub use crate::algorithm::affine::AffineTransform;
+#[cfg(feature = "std")]
+pub use crate::algorithm::merge::MergePolicy;
+#[cfg(feature = "std")]
+pub use crate::algorithm::stats::VertexStats;
+pub use crate::de::from_slice;
+#[cfg(feature = "std")]
+pub use crate::ser::{
+    geometry_to_string, geometry_to_writer, AsGeoJson, BboxPolicy, CrsPolicy, IdPolicy,
+    SerializeGeometry, WriterOptions,
+};
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod borrowed;
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod cql2;
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod diff;
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod esri;
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod events;
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod flat;
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod geohash;
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod geojsonl;
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod hash;
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod interned;
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod jsonfg;
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod lazy;
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod jsonld;
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod ogcapi;
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod overpass;
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod schema;
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod shared;
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod summary;
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod tiles;
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod view;
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod visit;
+
 #[cfg(feature = "geo-types")]
 mod conversion;
 
 #[cfg(feature = "geo-types")]
 pub use conversion::quick_collection;
 
+#[cfg(feature = "h3")]
+#[cfg_attr(docsrs, doc(cfg(feature = "h3")))]
+pub mod h3;
+
+#[cfg(feature = "s2")]
+#[cfg_attr(docsrs, doc(cfg(feature = "s2")))]
+pub mod s2;
+
+#[cfg(feature = "shapefile")]
+#[cfg_attr(docsrs, doc(cfg(feature = "shapefile")))]
+pub mod shapefile;
+
+#[cfg(feature = "postgis")]
+#[cfg_attr(docsrs, doc(cfg(feature = "postgis")))]
+pub mod postgis;
+
+#[cfg(feature = "bumpalo")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bumpalo")))]
+pub mod arena;
+
+#[cfg(feature = "rayon")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+pub mod parallel;
+
+#[cfg(feature = "wasm")]
+#[cfg_attr(docsrs, doc(cfg(feature = "wasm")))]
+pub mod wasm;
+
+#[cfg(feature = "proptest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "proptest")))]
+pub mod proptest;
+
+#[cfg(feature = "ffi")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ffi")))]
+pub mod ffi;
+
+#[cfg(feature = "rstar")]
+mod rstar;
+
+#[cfg(feature = "rstar")]
+pub use crate::rstar::IndexedFeature;
+
+#[cfg(feature = "geo")]
+mod geo;
+
+#[cfg(feature = "geo")]
+pub use crate::geo::Invalidity;
+
+#[cfg(feature = "proj")]
+mod proj;
+
+#[cfg(feature = "approx")]
+mod approx;
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+
+#[cfg(feature = "schemars")]
+mod schemars;
+
+#[cfg(feature = "chrono")]
+mod chrono;
+
+#[cfg(feature = "time")]
+mod time;
+
+#[cfg(feature = "uuid")]
+mod uuid;
+
+#[cfg(feature = "rust_decimal")]
+mod rust_decimal;
+
 /// Feature Objects
 ///
 /// [GeoJSON Format Specification § 3.2](https://tools.ietf.org/html/rfc7946#section-3.2)
@@ -395,6 +621,7 @@ pub struct Feature {
 }
 
 mod json {
+    use alloc::string::String;
     pub use serde::{Deserialize, Deserializer, Serialize, Serializer};
     pub use serde_json::{Map, Value as JsonValue};
     pub type JsonObject = Map<String, JsonValue>;
@@ -0,0 +1,401 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parsing of [OGC API – Features](https://ogcapi.ogc.org/features/) items responses.
+//!
+//! An items response is a FeatureCollection with additional `links`, `numberMatched`,
+//! `numberReturned` and `timeStamp` members. Large results are split into pages, linked by
+//! their `next` links; [`FeaturesResponse::pages`] follows them using a function you supply
+//! to fetch each page, so any HTTP client can be used.
+//!
+//! # Example
+//!
+//! ```
+//! use geojson::ogcapi::{FeaturesResponse, Link};
+//! use geojson::Error;
+//!
+//! let first: FeaturesResponse = r#"{
+//!     "type": "FeatureCollection",
+//!     "features": [],
+//!     "numberMatched": 0,
+//!     "links": [{ "href": "https://example.com/items?offset=10", "rel": "next" }]
+//! }"#
+//! .parse()
+//! .unwrap();
+//!
+//! // e.g. `|link| client.get(&link.href).send()?.text()?.parse()`
+//! let fetch = |link: &Link| -> Result<FeaturesResponse, Error> {
+//!     assert_eq!(link.href, "https://example.com/items?offset=10");
+//!     r#"{ "type": "FeatureCollection", "features": [] }"#.parse()
+//! };
+//!
+//! let pages: Vec<_> = first.pages(fetch).collect::<Result<_, _>>().unwrap();
+//! assert_eq!(pages.len(), 2);
+//! ```
+//!
+//! [`FeaturesResponse::pages`]: struct.FeaturesResponse.html#method.pages
+
+use crate::json::{Deserialize, Deserializer, JsonObject, JsonValue, Serialize, Serializer};
+use crate::{Error, FeatureCollection};
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+/// A link to a related resource, such as the next page of a response
+#[derive(Clone, Debug, PartialEq)]
+pub struct Link {
+    pub href: String,
+    /// The relation type, such as `"next"`, `"prev"` or `"self"`
+    pub rel: Option<String>,
+    /// The media type of the linked resource, from the `type` member
+    pub media_type: Option<String>,
+    pub title: Option<String>,
+    /// Other members of the link, such as `hreflang` or `length`
+    pub foreign_members: Option<JsonObject>,
+}
+
+impl Link {
+    /// A link to `href` with the relation type `rel`
+    pub fn new(href: impl Into<String>, rel: impl Into<String>) -> Self {
+        Link {
+            href: href.into(),
+            rel: Some(rel.into()),
+            media_type: None,
+            title: None,
+            foreign_members: None,
+        }
+    }
+}
+
+impl TryFrom<JsonValue> for Link {
+    type Error = Error;
+
+    fn try_from(value: JsonValue) -> Result<Self, Self::Error> {
+        let invalid = |value| Error::InvalidOgcApiMember("links", value);
+        let mut object = match value {
+            JsonValue::Object(object) => object,
+            other => return Err(invalid(other)),
+        };
+        let mut string = |key: &str| match object.remove(key) {
+            None | Some(JsonValue::Null) => Ok(None),
+            Some(JsonValue::String(s)) => Ok(Some(s)),
+            Some(other) => Err(invalid(other)),
+        };
+        let href = string("href")?;
+        let rel = string("rel")?;
+        let media_type = string("type")?;
+        let title = string("title")?;
+        Ok(Link {
+            href: href.ok_or_else(|| Error::ExpectedProperty("href".to_string()))?,
+            rel,
+            media_type,
+            title,
+            foreign_members: Some(object).filter(|object| !object.is_empty()),
+        })
+    }
+}
+
+impl From<&Link> for JsonValue {
+    fn from(link: &Link) -> Self {
+        let mut object = link.foreign_members.clone().unwrap_or_default();
+        object.insert("href".to_string(), link.href.clone().into());
+        let optional = [
+            ("rel", &link.rel),
+            ("type", &link.media_type),
+            ("title", &link.title),
+        ];
+        for (key, value) in optional {
+            if let Some(value) = value {
+                object.insert(key.to_string(), value.clone().into());
+            }
+        }
+        JsonValue::Object(object)
+    }
+}
+
+/// An OGC API – Features items response.
+///
+/// The response members are removed from the `foreign_members` of `collection` when parsing,
+/// and restored when serializing.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeaturesResponse {
+    pub collection: FeatureCollection,
+    pub links: Vec<Link>,
+    /// The number of features matching the request, across all pages
+    pub number_matched: Option<u64>,
+    /// The number of features in this page
+    pub number_returned: Option<u64>,
+    /// When the response was generated, as an RFC 3339 date-time
+    pub time_stamp: Option<String>,
+}
+
+impl FeaturesResponse {
+    pub fn from_json_value(value: JsonValue) -> Result<Self, Error> {
+        Self::try_from(FeatureCollection::from_json_value(value)?)
+    }
+
+    /// The first link with the relation type `rel`
+    pub fn link(&self, rel: &str) -> Option<&Link> {
+        self.links
+            .iter()
+            .find(|link| link.rel.as_deref() == Some(rel))
+    }
+
+    /// The link to the next page of results, if there is one
+    pub fn next_link(&self) -> Option<&Link> {
+        self.link("next")
+    }
+
+    /// An iterator over this page and each following page, calling `fetch` with each `next`
+    /// link to retrieve the page it refers to.
+    ///
+    /// Iteration ends after a page without a `next` link, a page whose `next` link is the one
+    /// it was fetched from, or the first error returned by `fetch`.
+    pub fn pages<F, E>(self, fetch: F) -> Pages<F>
+    where
+        F: FnMut(&Link) -> Result<FeaturesResponse, E>,
+    {
+        Pages {
+            pending: Some(Pending::Page(self, None)),
+            fetch,
+        }
+    }
+}
+
+fn take_count(object: &mut JsonObject, key: &'static str) -> Result<Option<u64>, Error> {
+    match object.remove(key) {
+        None | Some(JsonValue::Null) => Ok(None),
+        Some(value) => match value.as_u64() {
+            Some(count) => Ok(Some(count)),
+            None => Err(Error::InvalidOgcApiMember(key, value)),
+        },
+    }
+}
+
+impl TryFrom<FeatureCollection> for FeaturesResponse {
+    type Error = Error;
+
+    fn try_from(mut collection: FeatureCollection) -> Result<Self, Self::Error> {
+        let mut members = collection.foreign_members.take().unwrap_or_default();
+        let links = match members.remove("links") {
+            None | Some(JsonValue::Null) => vec![],
+            Some(JsonValue::Array(links)) => links
+                .into_iter()
+                .map(Link::try_from)
+                .collect::<Result<_, _>>()?,
+            Some(other) => return Err(Error::InvalidOgcApiMember("links", other)),
+        };
+        let number_matched = take_count(&mut members, "numberMatched")?;
+        let number_returned = take_count(&mut members, "numberReturned")?;
+        let time_stamp = match members.remove("timeStamp") {
+            None | Some(JsonValue::Null) => None,
+            Some(JsonValue::String(s)) => Some(s),
+            Some(other) => return Err(Error::InvalidOgcApiMember("timeStamp", other)),
+        };
+        collection.foreign_members = Some(members).filter(|members| !members.is_empty());
+        Ok(FeaturesResponse {
+            collection,
+            links,
+            number_matched,
+            number_returned,
+            time_stamp,
+        })
+    }
+}
+
+impl TryFrom<JsonValue> for FeaturesResponse {
+    type Error = Error;
+
+    fn try_from(value: JsonValue) -> Result<Self, Self::Error> {
+        Self::from_json_value(value)
+    }
+}
+
+impl From<FeaturesResponse> for FeatureCollection {
+    fn from(response: FeaturesResponse) -> Self {
+        let mut collection = response.collection;
+        let mut members = collection.foreign_members.take().unwrap_or_default();
+        if !response.links.is_empty() {
+            let links = response.links.iter().map(JsonValue::from).collect();
+            members.insert("links".to_string(), links);
+        }
+        if let Some(count) = response.number_matched {
+            members.insert("numberMatched".to_string(), count.into());
+        }
+        if let Some(count) = response.number_returned {
+            members.insert("numberReturned".to_string(), count.into());
+        }
+        if let Some(time_stamp) = response.time_stamp {
+            members.insert("timeStamp".to_string(), time_stamp.into());
+        }
+        collection.foreign_members = Some(members).filter(|members| !members.is_empty());
+        collection
+    }
+}
+
+impl FromStr for FeaturesResponse {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = serde_json::from_str(s).map_err(Error::MalformedJson)?;
+        Self::from_json_value(value)
+    }
+}
+
+impl Serialize for FeaturesResponse {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        FeatureCollection::from(self.clone()).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for FeaturesResponse {
+    fn deserialize<D>(deserializer: D) -> Result<FeaturesResponse, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error as SerdeError;
+
+        let collection = FeatureCollection::deserialize(deserializer)?;
+
+        FeaturesResponse::try_from(collection).map_err(|e| D::Error::custom(e.to_string()))
+    }
+}
+
+enum Pending {
+    /// A page, and the `href` it was fetched from
+    Page(FeaturesResponse, Option<String>),
+    Link(Link),
+}
+
+/// An iterator over the pages of a response, created by
+/// [`FeaturesResponse::pages`](struct.FeaturesResponse.html#method.pages)
+pub struct Pages<F> {
+    pending: Option<Pending>,
+    fetch: F,
+}
+
+impl<F, E> Iterator for Pages<F>
+where
+    F: FnMut(&Link) -> Result<FeaturesResponse, E>,
+{
+    type Item = Result<FeaturesResponse, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (page, fetched_from) = match self.pending.take()? {
+            Pending::Page(page, fetched_from) => (page, fetched_from),
+            Pending::Link(link) => match (self.fetch)(&link) {
+                Ok(page) => (page, Some(link.href)),
+                Err(e) => return Some(Err(e)),
+            },
+        };
+        self.pending = page
+            .next_link()
+            .filter(|next| Some(&next.href) != fetched_from.as_ref())
+            .cloned()
+            .map(Pending::Link);
+        Some(Ok(page))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FeaturesResponse, Link};
+    use crate::{Error, FeatureCollection};
+    use serde_json::json;
+
+    fn page(offset: u64, next: Option<&str>) -> FeaturesResponse {
+        FeaturesResponse {
+            collection: FeatureCollection {
+                bbox: None,
+                features: vec![],
+                foreign_members: None,
+            },
+            links: next
+                .map(|href| Link::new(href, "next"))
+                .into_iter()
+                .collect(),
+            number_matched: Some(30),
+            number_returned: Some(offset),
+            time_stamp: None,
+        }
+    }
+
+    #[test]
+    fn parse_and_serialize() {
+        let value = json!({
+            "type": "FeatureCollection",
+            "features": [],
+            "links": [
+                {
+                    "href": "https://example.com/items?f=json",
+                    "rel": "self",
+                    "type": "application/geo+json",
+                    "hreflang": "en"
+                }
+            ],
+            "numberMatched": 100,
+            "numberReturned": 0,
+            "timeStamp": "2024-01-01T00:00:00Z",
+            "other": true
+        });
+        let response = FeaturesResponse::from_json_value(value.clone()).unwrap();
+        assert_eq!(response.number_matched, Some(100));
+        assert_eq!(response.time_stamp.as_deref(), Some("2024-01-01T00:00:00Z"));
+        let link = response.link("self").unwrap();
+        assert_eq!(link.media_type.as_deref(), Some("application/geo+json"));
+        assert_eq!(link.foreign_members.as_ref().unwrap()["hreflang"], "en");
+        assert_eq!(
+            response.collection.foreign_members.as_ref().unwrap().len(),
+            1
+        );
+        assert!(response.next_link().is_none());
+        assert_eq!(serde_json::to_value(&response).unwrap(), value);
+
+        let invalid = json!({ "type": "FeatureCollection", "features": [], "numberMatched": -1 });
+        assert!(matches!(
+            FeaturesResponse::from_json_value(invalid),
+            Err(Error::InvalidOgcApiMember("numberMatched", _))
+        ));
+    }
+
+    #[test]
+    fn follow_next_links() {
+        let mut fetched = vec![];
+        let pages: Vec<_> = page(10, Some("/items?offset=10"))
+            .pages(|link: &Link| {
+                fetched.push(link.href.clone());
+                Ok::<_, Error>(match link.href.as_str() {
+                    "/items?offset=10" => page(20, Some("/items?offset=20")),
+                    _ => page(30, None),
+                })
+            })
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(pages.len(), 3);
+        assert_eq!(fetched, vec!["/items?offset=10", "/items?offset=20"]);
+
+        // A page linking to itself ends the iteration
+        let pages = page(10, Some("/items"))
+            .pages(|_: &Link| Ok::<_, Error>(page(20, Some("/items"))))
+            .count();
+        assert_eq!(pages, 2);
+
+        let mut pages = page(10, Some("/items")).pages(|_: &Link| Err("offline"));
+        assert!(pages.next().unwrap().is_ok());
+        assert_eq!(pages.next().unwrap().unwrap_err(), "offline");
+        assert!(pages.next().is_none());
+    }
+}
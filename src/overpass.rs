@@ -0,0 +1,473 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversion of [Overpass API](https://wiki.openstreetmap.org/wiki/Overpass_API) JSON
+//! responses (`[out:json]`) into GeoJSON.
+//!
+//! Every element becomes a feature with an id of `"node/<id>"`, `"way/<id>"` or
+//! `"relation/<id>"` and the element's tags as its properties, except for untagged nodes which
+//! only serve as vertices of a way in the response.
+//!
+//! - Nodes become points.
+//! - Ways become line strings, or polygons when they are closed and tagged as an area (see
+//!   [`is_area`](fn.is_area.html)). Their coordinates come from their node references, or from
+//!   their `geometry` member when the query used `out geom`.
+//! - `multipolygon` and `boundary` relations become polygons or multipolygons assembled from
+//!   their `outer` and `inner` member ways. Other relations become geometry collections of
+//!   their node and way members.
+//!
+//! A feature whose geometry can't be built, e.g. because the way's nodes are missing from the
+//! response or a relation's rings aren't closed, has no geometry.
+//!
+//! # Example
+//!
+//! ```
+//! use geojson::{feature::Id, overpass, position, Value};
+//! use serde_json::json;
+//!
+//! let response = json!({
+//!     "version": 0.6,
+//!     "elements": [
+//!         { "type": "node", "id": 1, "lat": 0.0, "lon": 0.0 },
+//!         { "type": "node", "id": 2, "lat": 0.0, "lon": 1.0 },
+//!         { "type": "node", "id": 3, "lat": 1.0, "lon": 1.0 },
+//!         { "type": "way", "id": 10, "nodes": [1, 2, 3], "tags": { "highway": "residential" } }
+//!     ]
+//! });
+//!
+//! let collection = overpass::feature_collection_from_overpass(&response).unwrap();
+//! assert_eq!(collection.features.len(), 1);
+//!
+//! let way = &collection.features[0];
+//! assert_eq!(way.id, Some(Id::String("way/10".to_string())));
+//! assert_eq!(way.property("highway").unwrap(), "residential");
+//! assert_eq!(
+//!     way.geometry.as_ref().unwrap().value,
+//!     Value::LineString(vec![position![0.0, 0.0], position![1.0, 0.0], position![1.0, 1.0]])
+//! );
+//! ```
+
+use crate::algorithm::intersects::point_in_ring;
+use crate::algorithm::signed_area;
+use crate::feature::Id;
+use crate::json::{JsonObject, JsonValue};
+use crate::{Error, Feature, FeatureCollection, Geometry, Position, Value};
+use std::collections::{HashMap, HashSet};
+
+/// Tag keys which make a closed way an area, unless it's tagged `area=no`
+pub const AREA_KEYS: &[&str] = &[
+    "aeroway",
+    "amenity",
+    "building",
+    "building:part",
+    "craft",
+    "historic",
+    "landuse",
+    "leisure",
+    "man_made",
+    "military",
+    "natural",
+    "office",
+    "place",
+    "shop",
+    "tourism",
+];
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::InvalidOverpassJson(message.into())
+}
+
+/// Whether a closed way with `tags` describes an area rather than a closed line.
+///
+/// A way is an area when it's tagged `area=yes`, or when it has one of the [`AREA_KEYS`] and
+/// isn't tagged `area=no`.
+///
+/// [`AREA_KEYS`]: constant.AREA_KEYS.html
+pub fn is_area(tags: &JsonObject) -> bool {
+    match tags.get("area").and_then(JsonValue::as_str) {
+        Some("yes") => true,
+        Some("no") => false,
+        _ => AREA_KEYS.iter().any(|key| tags.contains_key(*key)),
+    }
+}
+
+struct Element<'a> {
+    kind: &'a str,
+    id: i64,
+    object: &'a JsonObject,
+}
+
+impl<'a> Element<'a> {
+    fn parse(value: &'a JsonValue) -> Result<Self, Error> {
+        let object = value
+            .as_object()
+            .ok_or_else(|| invalid(format!("expected an element object, got `{}`", value)))?;
+        let kind = object
+            .get("type")
+            .and_then(JsonValue::as_str)
+            .ok_or_else(|| invalid(format!("element has no `type`: `{}`", value)))?;
+        let id = object
+            .get("id")
+            .and_then(JsonValue::as_i64)
+            .ok_or_else(|| invalid(format!("element has no `id`: `{}`", value)))?;
+        Ok(Element { kind, id, object })
+    }
+
+    fn tags(&self) -> Option<&'a JsonObject> {
+        self.object.get("tags").and_then(JsonValue::as_object)
+    }
+
+    fn node_refs(&self) -> impl Iterator<Item = i64> + 'a {
+        self.object
+            .get("nodes")
+            .and_then(JsonValue::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(JsonValue::as_i64)
+    }
+
+    fn to_feature(&self, value: Option<Value>) -> Feature {
+        Feature {
+            bbox: None,
+            geometry: value.map(Geometry::new),
+            id: Some(Id::String(format!("{}/{}", self.kind, self.id))),
+            properties: self.tags().cloned(),
+            foreign_members: None,
+        }
+    }
+}
+
+/// Read the `lat` and `lon` members of a node, or of a vertex of an `out geom` way
+fn lat_lon(object: &JsonObject) -> Option<Position> {
+    let lat = object.get("lat").and_then(JsonValue::as_f64)?;
+    let lon = object.get("lon").and_then(JsonValue::as_f64)?;
+    Some(position![lon, lat])
+}
+
+/// The coordinates of a way, or a way member of a relation, given in its `geometry` member
+fn inline_positions(object: &JsonObject) -> Option<Vec<Position>> {
+    object
+        .get("geometry")?
+        .as_array()?
+        .iter()
+        .map(|vertex| vertex.as_object().and_then(lat_lon))
+        .collect()
+}
+
+fn way_positions(way: &Element, nodes: &HashMap<i64, Position>) -> Option<Vec<Position>> {
+    let positions = match inline_positions(way.object) {
+        Some(positions) => positions,
+        None => way
+            .node_refs()
+            .map(|id| nodes.get(&id).cloned())
+            .collect::<Option<_>>()?,
+    };
+    Some(positions).filter(|positions| positions.len() >= 2)
+}
+
+fn member_positions(
+    member: &JsonObject,
+    nodes: &HashMap<i64, Position>,
+    ways: &HashMap<i64, &Element>,
+) -> Option<Vec<Position>> {
+    inline_positions(member).or_else(|| {
+        let way = ways.get(&member.get("ref")?.as_i64()?)?;
+        way_positions(way, nodes)
+    })
+}
+
+fn way_value(way: &Element, positions: Vec<Position>) -> Value {
+    let closed = positions.len() >= 4 && positions.first() == positions.last();
+    if closed && way.tags().is_some_and(is_area) {
+        Value::Polygon(vec![orient(positions, false)])
+    } else {
+        Value::LineString(positions)
+    }
+}
+
+/// Wind `ring` counter-clockwise, or clockwise if it's a hole
+fn orient(mut ring: Vec<Position>, hole: bool) -> Vec<Position> {
+    if (signed_area(&ring) < 0.0) != hole {
+        ring.reverse();
+    }
+    ring
+}
+
+/// Join way segments end to end into closed rings, or `None` if any ring can't be closed
+fn assemble_rings(mut segments: Vec<Vec<Position>>) -> Option<Vec<Vec<Position>>> {
+    let mut rings = Vec::new();
+    while let Some(mut ring) = segments.pop() {
+        while ring.first() != ring.last() {
+            let end = ring.last()?.clone();
+            let next = segments.iter().position(|segment| {
+                segment.first() == Some(&end) || segment.last() == Some(&end)
+            })?;
+            let mut segment = segments.swap_remove(next);
+            if segment.first() != Some(&end) {
+                segment.reverse();
+            }
+            ring.extend(segment.into_iter().skip(1));
+        }
+        if ring.len() >= 4 {
+            rings.push(ring);
+        }
+    }
+    Some(rings)
+}
+
+fn multipolygon_value(
+    members: &[JsonValue],
+    nodes: &HashMap<i64, Position>,
+    ways: &HashMap<i64, &Element>,
+) -> Option<Value> {
+    let mut outers = Vec::new();
+    let mut inners = Vec::new();
+    for member in members.iter().filter_map(JsonValue::as_object) {
+        if member.get("type").and_then(JsonValue::as_str) != Some("way") {
+            continue;
+        }
+        let positions = member_positions(member, nodes, ways)?;
+        match member.get("role").and_then(JsonValue::as_str) {
+            Some("inner") => inners.push(positions),
+            _ => outers.push(positions),
+        }
+    }
+    let mut polygons: Vec<Vec<Vec<Position>>> = assemble_rings(outers)?
+        .into_iter()
+        .map(|ring| vec![orient(ring, false)])
+        .collect();
+    for inner in assemble_rings(inners)? {
+        let (x, y) = (inner[0][0], inner[0][1]);
+        if let Some(polygon) = polygons
+            .iter_mut()
+            .find(|polygon| point_in_ring(x, y, &polygon[0]))
+        {
+            polygon.push(orient(inner, true));
+        }
+    }
+    match polygons.len() {
+        0 => None,
+        1 => Some(Value::Polygon(polygons.remove(0))),
+        _ => Some(Value::MultiPolygon(polygons)),
+    }
+}
+
+fn relation_value(
+    relation: &Element,
+    nodes: &HashMap<i64, Position>,
+    ways: &HashMap<i64, &Element>,
+) -> Option<Value> {
+    let members = relation.object.get("members")?.as_array()?;
+    let kind = relation
+        .tags()
+        .and_then(|tags| tags.get("type"))
+        .and_then(JsonValue::as_str);
+    if let Some("multipolygon") | Some("boundary") = kind {
+        return multipolygon_value(members, nodes, ways);
+    }
+    let geometries: Vec<Geometry> = members
+        .iter()
+        .filter_map(JsonValue::as_object)
+        .filter_map(|member| match member.get("type")?.as_str()? {
+            "node" => lat_lon(member)
+                .or_else(|| nodes.get(&member.get("ref")?.as_i64()?).cloned())
+                .map(Value::Point),
+            "way" => member_positions(member, nodes, ways).map(Value::LineString),
+            _ => None,
+        })
+        .map(Geometry::new)
+        .collect();
+    if geometries.is_empty() {
+        None
+    } else {
+        Some(Value::GeometryCollection(geometries))
+    }
+}
+
+/// Convert an Overpass API JSON response into a `FeatureCollection`.
+///
+/// The response must have an `elements` array whose members all have a `type` and an `id`.
+/// Elements of unknown types, such as the `area` elements of `is_in` queries, are skipped.
+pub fn feature_collection_from_overpass(response: &JsonValue) -> Result<FeatureCollection, Error> {
+    let elements = response
+        .get("elements")
+        .and_then(JsonValue::as_array)
+        .ok_or_else(|| invalid("expected an `elements` array"))?
+        .iter()
+        .map(Element::parse)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut nodes = HashMap::new();
+    let mut ways = HashMap::new();
+    let mut way_nodes = HashSet::new();
+    for element in &elements {
+        match element.kind {
+            "node" => {
+                if let Some(position) = lat_lon(element.object) {
+                    nodes.insert(element.id, position);
+                }
+            }
+            "way" => {
+                way_nodes.extend(element.node_refs());
+                ways.insert(element.id, element);
+            }
+            _ => {}
+        }
+    }
+
+    let features = elements
+        .iter()
+        .filter_map(|element| match element.kind {
+            "node" => {
+                let tagged = element.tags().is_some_and(|tags| !tags.is_empty());
+                if tagged || !way_nodes.contains(&element.id) {
+                    let value = nodes.get(&element.id).cloned().map(Value::Point);
+                    Some(element.to_feature(value))
+                } else {
+                    None
+                }
+            }
+            "way" => {
+                let value =
+                    way_positions(element, &nodes).map(|positions| way_value(element, positions));
+                Some(element.to_feature(value))
+            }
+            "relation" => Some(element.to_feature(relation_value(element, &nodes, &ways))),
+            _ => None,
+        })
+        .collect();
+
+    Ok(FeatureCollection {
+        bbox: None,
+        features,
+        foreign_members: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::feature_collection_from_overpass;
+    use crate::feature::Id;
+    use crate::Value;
+    use serde_json::json;
+
+    fn node(id: i64, lon: f64, lat: f64) -> serde_json::Value {
+        json!({ "type": "node", "id": id, "lat": lat, "lon": lon })
+    }
+
+    #[test]
+    fn nodes_and_closed_ways() {
+        let response = json!({
+            "elements": [
+                node(1, 0.0, 0.0),
+                node(2, 0.0, 1.0),
+                node(3, 1.0, 1.0),
+                { "type": "node", "id": 4, "lat": 5.0, "lon": 6.0, "tags": { "amenity": "cafe" } },
+                // Clockwise, so the polygon is reversed
+                { "type": "way", "id": 10, "nodes": [1, 2, 3, 1], "tags": { "building": "yes" } },
+                { "type": "way", "id": 11, "nodes": [1, 2, 3, 1], "tags": { "barrier": "fence" } },
+                { "type": "way", "id": 12, "nodes": [1, 99], "tags": { "highway": "path" } }
+            ]
+        });
+        let collection = feature_collection_from_overpass(&response).unwrap();
+        let ids: Vec<_> = collection.features.iter().map(|f| f.id.clone()).collect();
+        assert_eq!(
+            ids,
+            ["node/4", "way/10", "way/11", "way/12"]
+                .iter()
+                .map(|id| Some(Id::String(id.to_string())))
+                .collect::<Vec<_>>()
+        );
+        let values: Vec<_> = collection
+            .features
+            .iter()
+            .map(|f| f.geometry.as_ref().map(|g| g.value.clone()))
+            .collect();
+        assert_eq!(values[0], Some(Value::Point(position![6.0, 5.0])));
+        assert_eq!(
+            values[1],
+            Some(Value::Polygon(vec![vec![
+                position![0.0, 0.0],
+                position![1.0, 1.0],
+                position![0.0, 1.0],
+                position![0.0, 0.0],
+            ]]))
+        );
+        assert!(matches!(values[2], Some(Value::LineString(_))));
+        assert_eq!(values[3], None);
+        assert_eq!(collection.features[0].property("amenity").unwrap(), "cafe");
+    }
+
+    #[test]
+    fn multipolygon_relation() {
+        let square = |id: i64, min: f64, max: f64| {
+            vec![
+                node(id, min, min),
+                node(id + 1, max, min),
+                node(id + 2, max, max),
+                node(id + 3, min, max),
+            ]
+        };
+        let mut elements = square(1, 0.0, 4.0);
+        elements.extend(square(5, 1.0, 2.0));
+        elements.extend(vec![
+            // The outer ring is split across two ways, one of them running backwards
+            json!({ "type": "way", "id": 10, "nodes": [1, 2, 3] }),
+            json!({ "type": "way", "id": 11, "nodes": [1, 4, 3] }),
+            json!({ "type": "way", "id": 12, "nodes": [5, 6, 7, 8, 5] }),
+            json!({
+                "type": "relation",
+                "id": 20,
+                "members": [
+                    { "type": "way", "ref": 10, "role": "outer" },
+                    { "type": "way", "ref": 11, "role": "outer" },
+                    { "type": "way", "ref": 12, "role": "inner" }
+                ],
+                "tags": { "type": "multipolygon", "landuse": "forest" }
+            }),
+        ]);
+        let collection =
+            feature_collection_from_overpass(&json!({ "elements": elements })).unwrap();
+        let relation = collection.features.last().unwrap();
+        assert_eq!(relation.id, Some(Id::String("relation/20".to_string())));
+        match &relation.geometry.as_ref().unwrap().value {
+            Value::Polygon(rings) => {
+                assert_eq!(rings.len(), 2);
+                assert_eq!(rings[0].len(), 5);
+                assert!(crate::algorithm::signed_area(&rings[0]) > 0.0);
+                assert!(crate::algorithm::signed_area(&rings[1]) < 0.0);
+            }
+            value => panic!("expected a polygon, got {:?}", value),
+        }
+    }
+
+    #[test]
+    fn out_geom_and_errors() {
+        let response = json!({
+            "elements": [{
+                "type": "way",
+                "id": 1,
+                "geometry": [{ "lat": 1.0, "lon": 2.0 }, { "lat": 3.0, "lon": 4.0 }]
+            }]
+        });
+        let collection = feature_collection_from_overpass(&response).unwrap();
+        assert_eq!(
+            collection.features[0].geometry.as_ref().unwrap().value,
+            Value::LineString(vec![position![2.0, 1.0], position![4.0, 3.0]])
+        );
+
+        assert!(feature_collection_from_overpass(&json!({})).is_err());
+        assert!(feature_collection_from_overpass(&json!({ "elements": [{ "id": 1 }] })).is_err());
+    }
+}
@@ -0,0 +1,268 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parsing and serialization of feature collections on every core, with
+//! [rayon](https://docs.rs/rayon).
+//!
+//! To parse a collection, it is first scanned once, only to find where each of its features
+//! begins and ends. The features are then parsed in parallel, on rayon's global thread pool.
+//!
+//! To serialize a collection, its features are split into chunks which are each serialized into
+//! a buffer of their own in parallel. The buffers are then written out in order.
+//!
+//! # Example
+//!
+//! ```
+//! let json = r#"{
+//!     "type": "FeatureCollection",
+//!     "features": [
+//!         { "type": "Feature", "geometry": null, "properties": { "a": 1 } },
+//!         { "type": "Feature", "geometry": null, "properties": { "a": 2 } }
+//!     ]
+//! }"#;
+//! let collection = geojson::parallel::feature_collection_from_str(json).unwrap();
+//! assert_eq!(collection.features.len(), 2);
+//!
+//! let serialized = geojson::parallel::feature_collection_to_string(&collection).unwrap();
+//! assert_eq!(serialized, serde_json::to_string(&collection).unwrap());
+//! ```
+
+use crate::errors::Error;
+use crate::json::{Deserialize, Deserializer, JsonObject, JsonValue};
+use crate::{util, Feature, FeatureCollection};
+use rayon::prelude::*;
+use serde::de::{self, MapAccess, Visitor};
+use serde_json::value::RawValue;
+use std::fmt;
+use std::io::Write;
+
+/// The fewest features parsed as one task, so that tiny features aren't each scheduled alone
+const MIN_FEATURES_PER_TASK: usize = 16;
+
+/// The number of features serialized into each buffer
+const FEATURES_PER_CHUNK: usize = 256;
+
+/// The number of chunks serialized before their buffers are written out, per thread, which
+/// bounds the memory held by buffers for very large collections
+const CHUNKS_PER_THREAD: usize = 4;
+
+/// A feature collection whose features are still unparsed JSON
+struct RawFeatureCollection<'a> {
+    features: Vec<&'a RawValue>,
+    object: JsonObject,
+}
+
+impl<'de> Deserialize<'de> for RawFeatureCollection<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct RawFeatureCollectionVisitor;
+
+        impl<'de> Visitor<'de> for RawFeatureCollectionVisitor {
+            type Value = RawFeatureCollection<'de>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a GeoJSON FeatureCollection")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut features = None;
+                let mut object = JsonObject::new();
+                while let Some(key) = map.next_key::<String>()? {
+                    if key == "features" {
+                        features = Some(map.next_value()?);
+                    } else {
+                        object.insert(key, map.next_value()?);
+                    }
+                }
+                let features = features.ok_or_else(|| {
+                    de::Error::custom(Error::ExpectedProperty("features".to_string()))
+                })?;
+                Ok(RawFeatureCollection { features, object })
+            }
+        }
+
+        deserializer.deserialize_map(RawFeatureCollectionVisitor)
+    }
+}
+
+impl RawFeatureCollection<'_> {
+    fn parse(self) -> Result<FeatureCollection, Error> {
+        let mut object = self.object;
+        let type_ = util::expect_type(&mut object)?;
+        if type_ != "FeatureCollection" {
+            return Err(Error::ExpectedType {
+                expected: "FeatureCollection".to_owned(),
+                actual: type_,
+            });
+        }
+        let features = self
+            .features
+            .par_iter()
+            .with_min_len(MIN_FEATURES_PER_TASK)
+            .map(|feature| serde_json::from_str::<Feature>(feature.get()))
+            .collect::<Result<_, _>>()
+            .map_err(Error::MalformedJson)?;
+        Ok(FeatureCollection {
+            bbox: util::get_bbox(&mut object)?,
+            features,
+            foreign_members: util::get_foreign_members(object)?,
+        })
+    }
+}
+
+/// Parse a `FeatureCollection` from `json`, parsing its features in parallel.
+///
+/// This returns the same collection as parsing with `serde_json::from_str`.
+pub fn feature_collection_from_str(json: &str) -> Result<FeatureCollection, Error> {
+    serde_json::from_str::<RawFeatureCollection>(json)
+        .map_err(Error::MalformedJson)?
+        .parse()
+}
+
+/// Parse a `FeatureCollection` from the UTF-8 JSON `bytes`, parsing its features in parallel.
+pub fn feature_collection_from_slice(bytes: &[u8]) -> Result<FeatureCollection, Error> {
+    serde_json::from_slice::<RawFeatureCollection>(bytes)
+        .map_err(Error::MalformedJson)?
+        .parse()
+}
+
+/// Serialize `collection` to `writer`, serializing its features in parallel.
+///
+/// This writes the same JSON as `serde_json::to_writer`.
+pub fn feature_collection_to_writer<W: Write>(
+    collection: &FeatureCollection,
+    mut writer: W,
+) -> Result<(), serde_json::Error> {
+    // The members other than the features, in the order in which they are serialized, with a
+    // placeholder for the features
+    let mut object = JsonObject::new();
+    object.insert("type".to_string(), "FeatureCollection".into());
+    object.insert("features".to_string(), JsonValue::Null);
+    if let Some(bbox) = &collection.bbox {
+        object.insert("bbox".to_string(), serde_json::to_value(bbox)?);
+    }
+    if let Some(foreign_members) = &collection.foreign_members {
+        for (key, value) in foreign_members {
+            object.insert(key.clone(), value.clone());
+        }
+    }
+
+    writer.write_all(b"{").map_err(serde_json::Error::io)?;
+    for (index, (key, value)) in object.iter().enumerate() {
+        if index > 0 {
+            writer.write_all(b",").map_err(serde_json::Error::io)?;
+        }
+        serde_json::to_writer(&mut writer, key)?;
+        writer.write_all(b":").map_err(serde_json::Error::io)?;
+        if key == "features" && value.is_null() {
+            write_features(&collection.features, &mut writer)?;
+        } else {
+            serde_json::to_writer(&mut writer, value)?;
+        }
+    }
+    writer.write_all(b"}").map_err(serde_json::Error::io)
+}
+
+fn write_features<W: Write>(features: &[Feature], mut writer: W) -> Result<(), serde_json::Error> {
+    let features_per_batch = FEATURES_PER_CHUNK * CHUNKS_PER_THREAD * rayon::current_num_threads();
+    writer.write_all(b"[").map_err(serde_json::Error::io)?;
+    for (batch_index, batch) in features.chunks(features_per_batch).enumerate() {
+        let buffers = batch
+            .par_chunks(FEATURES_PER_CHUNK)
+            .map(|chunk| {
+                let mut buffer = Vec::new();
+                for (index, feature) in chunk.iter().enumerate() {
+                    if index > 0 {
+                        buffer.push(b',');
+                    }
+                    serde_json::to_writer(&mut buffer, feature)?;
+                }
+                Ok(buffer)
+            })
+            .collect::<Result<Vec<_>, serde_json::Error>>()?;
+        for (index, buffer) in buffers.iter().enumerate() {
+            if batch_index > 0 || index > 0 {
+                writer.write_all(b",").map_err(serde_json::Error::io)?;
+            }
+            writer.write_all(buffer).map_err(serde_json::Error::io)?;
+        }
+    }
+    writer.write_all(b"]").map_err(serde_json::Error::io)
+}
+
+/// Serialize `collection` to a string, serializing its features in parallel.
+pub fn feature_collection_to_string(
+    collection: &FeatureCollection,
+) -> Result<String, serde_json::Error> {
+    let mut bytes = Vec::new();
+    feature_collection_to_writer(collection, &mut bytes)?;
+    Ok(String::from_utf8(bytes).expect("serde_json writes UTF-8"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        feature_collection_from_slice, feature_collection_from_str, feature_collection_to_string,
+    };
+    use crate::{Error, FeatureCollection};
+
+    #[test]
+    fn same_as_serial_parse() {
+        let json = include_str!("../tests/fixtures/countries.geojson");
+        let expected: FeatureCollection = serde_json::from_str(json).unwrap();
+        assert_eq!(feature_collection_from_str(json).unwrap(), expected);
+        assert_eq!(
+            feature_collection_from_slice(json.as_bytes()).unwrap(),
+            expected
+        );
+
+        let json = r#"{"type":"FeatureCollection","bbox":[0,0,1,1],"title":"x","features":[]}"#;
+        let expected: FeatureCollection = serde_json::from_str(json).unwrap();
+        assert_eq!(feature_collection_from_str(json).unwrap(), expected);
+    }
+
+    #[test]
+    fn invalid_collections() {
+        assert!(matches!(
+            feature_collection_from_str(r#"{"type":"Feature","features":[]}"#),
+            Err(Error::ExpectedType { .. })
+        ));
+        assert!(feature_collection_from_str(r#"{"type":"FeatureCollection"}"#).is_err());
+        assert!(feature_collection_from_str(
+            r#"{"type":"FeatureCollection","features":[{"type":"Point","coordinates":[]}]}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn same_as_serial_serialization() {
+        let json = include_str!("../tests/fixtures/countries.geojson");
+        let mut collection: FeatureCollection = serde_json::from_str(json).unwrap();
+        let features = collection.features.clone();
+        for _ in 0..20 {
+            collection.features.extend(features.iter().cloned());
+        }
+        assert_eq!(
+            feature_collection_to_string(&collection).unwrap(),
+            serde_json::to_string(&collection).unwrap()
+        );
+
+        let json =
+            r#"{"type":"FeatureCollection","bbox":[0,0,1,1],"title":"x","zz":1,"features":[]}"#;
+        let collection: FeatureCollection = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            feature_collection_to_string(&collection).unwrap(),
+            serde_json::to_string(&collection).unwrap()
+        );
+    }
+}
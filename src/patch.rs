@@ -0,0 +1,356 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! [RFC 7386](https://tools.ietf.org/html/rfc7386) JSON merge patches of features.
+
+use crate::errors::Error;
+use crate::json::{JsonObject, JsonValue};
+use crate::{util, Feature};
+
+/// Apply the merge patch `patch` to `target`
+fn merge_patch(target: &mut JsonValue, patch: &JsonValue) {
+    match patch {
+        JsonValue::Object(patch) => {
+            if !target.is_object() {
+                *target = JsonValue::Object(JsonObject::new());
+            }
+            if let JsonValue::Object(target) = target {
+                merge_patch_object(target, patch);
+            }
+        }
+        patch => *target = patch.clone(),
+    }
+}
+
+fn merge_patch_object(target: &mut JsonObject, patch: &JsonObject) {
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(key);
+        } else {
+            merge_patch(target.entry(key.clone()).or_insert(JsonValue::Null), value);
+        }
+    }
+}
+
+/// The merge patch which turns `old` into `new`
+fn diff_object(old: &JsonObject, new: &JsonObject) -> JsonObject {
+    let mut patch = JsonObject::new();
+    for key in old.keys().filter(|key| !new.contains_key(*key)) {
+        patch.insert(key.clone(), JsonValue::Null);
+    }
+    for (key, value) in new {
+        match (old.get(key), value) {
+            (Some(old), value) if old == value => {}
+            (Some(JsonValue::Object(old)), JsonValue::Object(value)) => {
+                patch.insert(key.clone(), JsonValue::Object(diff_object(old, value)));
+            }
+            _ => {
+                patch.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    patch
+}
+
+/// The member `key` of `patch`, parsed by `get` from an object of just that member, or
+/// `Some(None)` if it is `null`
+fn patched_member<T>(
+    patch: &JsonObject,
+    key: &str,
+    get: impl FnOnce(&mut JsonObject) -> Result<Option<T>, Error>,
+) -> Result<Option<Option<T>>, Error> {
+    match patch.get(key) {
+        Some(JsonValue::Null) => Ok(Some(None)),
+        Some(value) => {
+            let mut object = JsonObject::new();
+            object.insert(key.to_string(), value.clone());
+            get(&mut object).map(Some)
+        }
+        None => Ok(None),
+    }
+}
+
+impl Feature {
+    /// Apply an [RFC 7386](https://tools.ietf.org/html/rfc7386) JSON merge patch to this
+    /// feature, as if to its GeoJSON.
+    ///
+    /// The `properties` and foreign members of the patch are merged into those of the feature,
+    /// and a `null` removes a member. Its `geometry`, `id` and `bbox`, if it has them, replace
+    /// those of the feature, or remove them if they are `null`. A patch which isn't an object
+    /// replaces the whole feature. The feature is unchanged if the patch is invalid, such as
+    /// when its `geometry` isn't a valid geometry.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geojson::{position, Feature, Value};
+    /// use serde_json::json;
+    ///
+    /// let mut feature = Feature::from(Value::Point(position![1.0, 2.0]));
+    /// feature.set_property("name", "well");
+    /// feature.set_property("depth", 20);
+    ///
+    /// feature
+    ///     .apply_merge_patch(&json!({
+    ///         "geometry": { "type": "Point", "coordinates": [1.5, 2.0] },
+    ///         "properties": { "depth": null, "status": "dry" }
+    ///     }))
+    ///     .unwrap();
+    /// assert_eq!(feature.property("depth"), None);
+    /// assert_eq!(feature.property("status").unwrap(), "dry");
+    /// ```
+    pub fn apply_merge_patch(&mut self, patch: &JsonValue) -> Result<(), Error> {
+        let patch = match patch {
+            JsonValue::Object(patch) => patch,
+            patch => {
+                *self = Feature::from_json_value(patch.clone())?;
+                return Ok(());
+            }
+        };
+        if let Some(type_) = patch.get("type") {
+            if type_ != "Feature" {
+                return Err(Error::ExpectedType {
+                    expected: "Feature".to_string(),
+                    actual: type_
+                        .as_str()
+                        .map_or_else(|| type_.to_string(), str::to_owned),
+                });
+            }
+        }
+        let geometry = patched_member(patch, "geometry", util::get_geometry)?;
+        let id = patched_member(patch, "id", util::get_id)?;
+        let bbox = patched_member(patch, "bbox", util::get_bbox)?;
+        let properties = match patch.get("properties") {
+            Some(JsonValue::Object(properties)) => Some(Some(properties)),
+            Some(JsonValue::Null) => Some(None),
+            Some(properties) => {
+                return Err(Error::PropertiesExpectedObjectOrNull(properties.clone()))
+            }
+            None => None,
+        };
+
+        if let Some(geometry) = geometry {
+            self.geometry = geometry;
+        }
+        if let Some(id) = id {
+            self.id = id;
+        }
+        if let Some(bbox) = bbox {
+            self.bbox = bbox;
+        }
+        match properties {
+            Some(Some(properties)) => merge_patch_object(
+                self.properties.get_or_insert_with(JsonObject::new),
+                properties,
+            ),
+            Some(None) => self.properties = None,
+            None => {}
+        }
+        let foreign_members = patch.iter().filter(|(key, _)| {
+            !matches!(
+                key.as_str(),
+                "type" | "geometry" | "id" | "bbox" | "properties"
+            )
+        });
+        for (key, value) in foreign_members {
+            let members = self.foreign_members.get_or_insert_with(JsonObject::new);
+            if value.is_null() {
+                members.remove(key);
+            } else {
+                merge_patch(members.entry(key.clone()).or_insert(JsonValue::Null), value);
+            }
+        }
+        if self
+            .foreign_members
+            .as_ref()
+            .is_some_and(JsonObject::is_empty)
+        {
+            self.foreign_members = None;
+        }
+        Ok(())
+    }
+
+    /// The [RFC 7386](https://tools.ietf.org/html/rfc7386) JSON merge patch which turns this
+    /// feature into `new` when applied with
+    /// [`apply_merge_patch`](#method.apply_merge_patch).
+    ///
+    /// Merge patches can't set a value to `null`, as `null` removes it, so a property whose
+    /// value is `null` in `new` is removed by the patch instead.
+    ///
+    /// ```
+    /// use geojson::{position, Feature, Value};
+    /// use serde_json::json;
+    ///
+    /// let mut old = Feature::from(Value::Point(position![1.0, 2.0]));
+    /// old.set_property("name", "well");
+    /// let mut new = old.clone();
+    /// new.set_property("status", "dry");
+    ///
+    /// let patch = old.merge_patch_to(&new);
+    /// assert_eq!(patch, json!({ "properties": { "status": "dry" } }));
+    /// old.apply_merge_patch(&patch).unwrap();
+    /// assert_eq!(old, new);
+    /// ```
+    pub fn merge_patch_to(&self, new: &Feature) -> JsonValue {
+        let mut patch = JsonObject::new();
+        let json = |value: Option<JsonValue>| value.unwrap_or(JsonValue::Null);
+        if self.geometry != new.geometry {
+            let geometry = new
+                .geometry
+                .as_ref()
+                .map(|geometry| serde_json::to_value(geometry).unwrap());
+            patch.insert("geometry".to_string(), json(geometry));
+        }
+        if self.id != new.id {
+            let id = new.id.as_ref().map(|id| serde_json::to_value(id).unwrap());
+            patch.insert("id".to_string(), json(id));
+        }
+        if self.bbox != new.bbox {
+            let bbox = new.bbox.as_ref().map(|bbox| bbox.as_slice().into());
+            patch.insert("bbox".to_string(), json(bbox));
+        }
+        match (&self.properties, &new.properties) {
+            (old, new) if old == new => {}
+            (Some(old), Some(new)) => {
+                patch.insert(
+                    "properties".to_string(),
+                    JsonValue::Object(diff_object(old, new)),
+                );
+            }
+            (_, new) => {
+                patch.insert(
+                    "properties".to_string(),
+                    json(new.clone().map(JsonValue::Object)),
+                );
+            }
+        }
+        let empty = JsonObject::new();
+        let old_members = self.foreign_members.as_ref().unwrap_or(&empty);
+        let new_members = new.foreign_members.as_ref().unwrap_or(&empty);
+        patch.extend(diff_object(old_members, new_members));
+        JsonValue::Object(patch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Feature, Value};
+    use serde_json::json;
+
+    #[test]
+    fn rfc_7386_examples() {
+        // The examples of Appendix A, applied to the properties of a feature
+        let examples = [
+            (json!({"a":"b"}), json!({"a":"c"}), json!({"a":"c"})),
+            (json!({"a":"b"}), json!({"b":"c"}), json!({"a":"b","b":"c"})),
+            (json!({"a":"b"}), json!({"a":null}), json!({})),
+            (
+                json!({"a":"b","b":"c"}),
+                json!({"a":null}),
+                json!({"b":"c"}),
+            ),
+            (json!({"a":["b"]}), json!({"a":"c"}), json!({"a":"c"})),
+            (json!({"a":"c"}), json!({"a":["b"]}), json!({"a":["b"]})),
+            (
+                json!({"a":{"b":"c"}}),
+                json!({"a":{"b":"d","c":null}}),
+                json!({"a":{"b":"d"}}),
+            ),
+            (json!({"a":[{"b":"c"}]}), json!({"a":[1]}), json!({"a":[1]})),
+            (json!({"e":null}), json!({"a":1}), json!({"e":null,"a":1})),
+            (
+                json!({}),
+                json!({"a":{"bb":{"ccc":null}}}),
+                json!({"a":{"bb":{}}}),
+            ),
+        ];
+        for (properties, patch, expected) in examples {
+            let mut feature = Feature::from(Value::Point(position![0.0, 0.0]));
+            feature.properties = properties.as_object().cloned();
+            feature
+                .apply_merge_patch(&json!({ "properties": patch }))
+                .unwrap();
+            assert_eq!(feature.properties, expected.as_object().cloned());
+        }
+    }
+
+    #[test]
+    fn patch_features() {
+        let old: Feature = serde_json::from_value(json!({
+            "type": "Feature",
+            "id": 1,
+            "geometry": { "type": "Point", "coordinates": [0, 0] },
+            "properties": { "name": "a", "tags": { "x": 1, "y": 2 } },
+            "source": { "name": "survey", "year": 2019 }
+        }))
+        .unwrap();
+        let new: Feature = serde_json::from_value(json!({
+            "type": "Feature",
+            "id": "one",
+            "bbox": [1, 1, 2, 2],
+            "geometry": { "type": "LineString", "coordinates": [[1, 1], [2, 2]] },
+            "properties": { "name": "a", "tags": { "x": 1, "z": 3 } },
+            "source": { "name": "survey", "year": 2020 }
+        }))
+        .unwrap();
+
+        let patch = old.merge_patch_to(&new);
+        assert_eq!(
+            patch,
+            json!({
+                "id": "one",
+                "bbox": [1.0, 1.0, 2.0, 2.0],
+                "geometry": { "type": "LineString", "coordinates": [[1.0, 1.0], [2.0, 2.0]] },
+                "properties": { "tags": { "y": null, "z": 3 } },
+                "source": { "year": 2020 }
+            })
+        );
+        let mut patched = old.clone();
+        patched.apply_merge_patch(&patch).unwrap();
+        assert_eq!(patched, new);
+        assert_eq!(new.merge_patch_to(&new), json!({}));
+
+        let mut patched = new.clone();
+        patched
+            .apply_merge_patch(&new.merge_patch_to(&old))
+            .unwrap();
+        assert_eq!(patched, old);
+
+        let mut removed = old.clone();
+        removed
+            .apply_merge_patch(&json!({ "geometry": null, "properties": null, "source": null }))
+            .unwrap();
+        assert_eq!(
+            (
+                removed.geometry,
+                removed.properties,
+                removed.foreign_members
+            ),
+            (None, None, None)
+        );
+
+        for invalid in [
+            json!({ "type": "Point" }),
+            json!({ "geometry": { "type": "Point" } }),
+            json!({ "id": true }),
+            json!({ "bbox": "0, 0, 1, 1" }),
+            json!({ "properties": [] }),
+            json!(1),
+        ] {
+            let mut patched = old.clone();
+            assert!(patched.apply_merge_patch(&invalid).is_err(), "{}", invalid);
+            assert_eq!(patched, old);
+        }
+    }
+}
@@ -0,0 +1,289 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversion between GeoJSON geometries and the EWKB geometry types of the
+//! [`postgis`](https://docs.rs/postgis) crate, which can be read from and written to
+//! PostGIS columns with [`postgres`](https://docs.rs/postgres).
+//!
+//! Any of the `ewkb` geometry types can be converted into a `Value` or `Geometry`; M values
+//! are dropped. GeoJSON geometries can be converted into the two and three dimensional `ewkb`
+//! types ([`ewkb::GeometryT<Point>`] and [`ewkb::GeometryT<PointZ>`]), which are given the
+//! [`SRID`](constant.SRID.html) of WGS 84, the coordinate reference system of GeoJSON.
+//!
+//! # Example
+//!
+//! ```
+//! use geojson::{position, Feature, Geometry, Value};
+//! use postgis::ewkb;
+//! use std::convert::TryFrom;
+//!
+//! // e.g. from `row.get::<_, ewkb::Geometry>("geom")`
+//! let geom = ewkb::Geometry::Point(ewkb::Point::new(1.0, 2.0, Some(4326)));
+//!
+//! let feature = Feature::from(Geometry::from(&geom));
+//! assert_eq!(
+//!     feature.geometry.as_ref().unwrap().value,
+//!     Value::Point(position![1.0, 2.0])
+//! );
+//!
+//! let back = ewkb::Geometry::try_from(feature.geometry.as_ref().unwrap()).unwrap();
+//! assert!(matches!(back, ewkb::Geometry::Point(p) if p.srid == Some(4326)));
+//! ```
+//!
+//! [`ewkb::GeometryT<Point>`]: https://docs.rs/postgis/0.9/postgis/ewkb/type.Geometry.html
+//! [`ewkb::GeometryT<PointZ>`]: https://docs.rs/postgis/0.9/postgis/ewkb/type.GeometryZ.html
+
+use crate::{Error, Geometry, Position, Value};
+use ::postgis::ewkb::{
+    self, EwkbRead, GeometryCollectionT, GeometryT, LineStringT, MultiLineStringT, MultiPointT,
+    MultiPolygonT, PolygonT,
+};
+use std::convert::TryFrom;
+
+/// The SRID of WGS 84, given to geometries converted from GeoJSON
+pub const SRID: i32 = 4326;
+
+fn position<P: ::postgis::Point>(point: &P) -> Position {
+    match point.opt_z() {
+        Some(z) => position![point.x(), point.y(), z],
+        None => position![point.x(), point.y()],
+    }
+}
+
+fn positions<P: ::postgis::Point + EwkbRead>(line: &LineStringT<P>) -> Vec<Position> {
+    line.points.iter().map(position).collect()
+}
+
+fn rings<P: ::postgis::Point + EwkbRead>(polygon: &PolygonT<P>) -> Vec<Vec<Position>> {
+    polygon.rings.iter().map(positions).collect()
+}
+
+macro_rules! impl_from_ewkb_point {
+    ($($point:ident),*) => {
+        $(
+            impl From<&ewkb::$point> for Value {
+                fn from(point: &ewkb::$point) -> Self {
+                    Value::Point(position(point))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_ewkb_point!(Point, PointZ, PointM, PointZM);
+
+impl<P: ::postgis::Point + EwkbRead> From<&LineStringT<P>> for Value {
+    fn from(line: &LineStringT<P>) -> Self {
+        Value::LineString(positions(line))
+    }
+}
+
+impl<P: ::postgis::Point + EwkbRead> From<&PolygonT<P>> for Value {
+    fn from(polygon: &PolygonT<P>) -> Self {
+        Value::Polygon(rings(polygon))
+    }
+}
+
+impl<P: ::postgis::Point + EwkbRead> From<&MultiPointT<P>> for Value {
+    fn from(points: &MultiPointT<P>) -> Self {
+        Value::MultiPoint(points.points.iter().map(position).collect())
+    }
+}
+
+impl<P: ::postgis::Point + EwkbRead> From<&MultiLineStringT<P>> for Value {
+    fn from(lines: &MultiLineStringT<P>) -> Self {
+        Value::MultiLineString(lines.lines.iter().map(positions).collect())
+    }
+}
+
+impl<P: ::postgis::Point + EwkbRead> From<&MultiPolygonT<P>> for Value {
+    fn from(polygons: &MultiPolygonT<P>) -> Self {
+        Value::MultiPolygon(polygons.polygons.iter().map(rings).collect())
+    }
+}
+
+impl<P: ::postgis::Point + EwkbRead> From<&GeometryCollectionT<P>> for Value {
+    fn from(collection: &GeometryCollectionT<P>) -> Self {
+        Value::GeometryCollection(collection.geometries.iter().map(Geometry::from).collect())
+    }
+}
+
+impl<P: ::postgis::Point + EwkbRead> From<&GeometryT<P>> for Value {
+    fn from(geometry: &GeometryT<P>) -> Self {
+        match geometry {
+            GeometryT::Point(point) => Value::Point(position(point)),
+            GeometryT::LineString(line) => line.into(),
+            GeometryT::Polygon(polygon) => polygon.into(),
+            GeometryT::MultiPoint(points) => points.into(),
+            GeometryT::MultiLineString(lines) => lines.into(),
+            GeometryT::MultiPolygon(polygons) => polygons.into(),
+            GeometryT::GeometryCollection(collection) => collection.into(),
+        }
+    }
+}
+
+/// `ewkb` point types which can be built from a GeoJSON position
+pub trait FromPosition: ::postgis::Point + EwkbRead {
+    /// Build a point from `position`, which must have enough dimensions for the point type.
+    fn from_position(position: &[f64], srid: Option<i32>) -> Result<Self, Error>;
+}
+
+impl FromPosition for ewkb::Point {
+    fn from_position(position: &[f64], srid: Option<i32>) -> Result<Self, Error> {
+        match position {
+            [x, y, ..] => Ok(ewkb::Point::new(*x, *y, srid)),
+            _ => Err(Error::ExpectedF64Value),
+        }
+    }
+}
+
+impl FromPosition for ewkb::PointZ {
+    fn from_position(position: &[f64], srid: Option<i32>) -> Result<Self, Error> {
+        match position {
+            [x, y, z, ..] => Ok(ewkb::PointZ::new(*x, *y, *z, srid)),
+            _ => Err(Error::ExpectedF64Value),
+        }
+    }
+}
+
+fn line_string<P: FromPosition>(positions: &[Position]) -> Result<LineStringT<P>, Error> {
+    Ok(LineStringT {
+        points: positions
+            .iter()
+            .map(|p| P::from_position(p, Some(SRID)))
+            .collect::<Result<_, _>>()?,
+        srid: Some(SRID),
+    })
+}
+
+fn polygon<P: FromPosition>(rings: &[Vec<Position>]) -> Result<PolygonT<P>, Error> {
+    Ok(PolygonT {
+        rings: rings
+            .iter()
+            .map(|ring| line_string(ring))
+            .collect::<Result<_, _>>()?,
+        srid: Some(SRID),
+    })
+}
+
+impl<P: FromPosition> TryFrom<&Value> for GeometryT<P> {
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        Ok(match value {
+            Value::Point(p) => GeometryT::Point(P::from_position(p, Some(SRID))?),
+            Value::MultiPoint(points) => GeometryT::MultiPoint(MultiPointT {
+                points: points
+                    .iter()
+                    .map(|p| P::from_position(p, Some(SRID)))
+                    .collect::<Result<_, _>>()?,
+                srid: Some(SRID),
+            }),
+            Value::LineString(line) => GeometryT::LineString(line_string(line)?),
+            Value::MultiLineString(lines) => GeometryT::MultiLineString(MultiLineStringT {
+                lines: lines
+                    .iter()
+                    .map(|line| line_string(line))
+                    .collect::<Result<_, _>>()?,
+                srid: Some(SRID),
+            }),
+            Value::Polygon(rings) => GeometryT::Polygon(polygon(rings)?),
+            Value::MultiPolygon(polygons) => GeometryT::MultiPolygon(MultiPolygonT {
+                polygons: polygons
+                    .iter()
+                    .map(|rings| polygon(rings))
+                    .collect::<Result<_, _>>()?,
+                srid: Some(SRID),
+            }),
+            Value::GeometryCollection(geometries) => {
+                GeometryT::GeometryCollection(GeometryCollectionT {
+                    geometries: geometries
+                        .iter()
+                        .map(GeometryT::try_from)
+                        .collect::<Result<_, _>>()?,
+                    srid: Some(SRID),
+                })
+            }
+        })
+    }
+}
+
+impl<P: FromPosition> TryFrom<&Geometry> for GeometryT<P> {
+    type Error = Error;
+
+    fn try_from(geometry: &Geometry) -> Result<Self, Self::Error> {
+        GeometryT::try_from(&geometry.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Geometry, Value};
+    use ::postgis::ewkb;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn polygon_roundtrip() {
+        let value = Value::Polygon(vec![vec![
+            position![0.0, 0.0, 1.0],
+            position![1.0, 0.0, 1.0],
+            position![1.0, 1.0, 1.0],
+            position![0.0, 0.0, 1.0],
+        ]]);
+        let ewkb = ewkb::GeometryZ::try_from(&value).unwrap();
+        assert_eq!(Value::from(&ewkb), value);
+
+        match ewkb::Geometry::try_from(&value).unwrap() {
+            ewkb::Geometry::Polygon(polygon) => {
+                assert_eq!(polygon.srid, Some(super::SRID));
+                assert_eq!(
+                    polygon.rings[0].points[1],
+                    ewkb::Point::new(1.0, 0.0, Some(4326))
+                );
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn collection_from_ewkb() {
+        let collection = ewkb::GeometryCollectionM {
+            geometries: vec![
+                ewkb::GeometryM::Point(ewkb::PointM::new(1.0, 2.0, 3.0, None)),
+                ewkb::GeometryM::MultiLineString(ewkb::MultiLineStringM {
+                    lines: vec![[(0.0, 0.0), (1.0, 1.0)]
+                        .iter()
+                        .map(|&(x, y)| ewkb::PointM::new(x, y, 0.0, None))
+                        .collect()],
+                    srid: None,
+                }),
+            ],
+            srid: None,
+        };
+        let geometry = Geometry::from(&ewkb::GeometryM::GeometryCollection(collection));
+        assert_eq!(
+            geometry.value,
+            Value::GeometryCollection(vec![
+                Value::Point(position![1.0, 2.0]).into(),
+                Value::MultiLineString(vec![vec![position![0.0, 0.0], position![1.0, 1.0]]]).into(),
+            ])
+        );
+    }
+
+    #[test]
+    fn missing_z() {
+        let value = Value::Point(position![1.0, 2.0]);
+        assert!(ewkb::GeometryZ::try_from(&value).is_err());
+    }
+}
@@ -0,0 +1,193 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Reprojection of GeoJSON coordinates with [PROJ](https://proj.org), using the
+//! [`proj`](https://docs.rs/proj) crate.
+
+use crate::algorithm::try_for_each_position_mut;
+use crate::{Error, Feature, FeatureCollection, GeoJson, Geometry, Value};
+use ::proj::Proj;
+
+fn transform(from: &str, to: &str) -> Result<Proj, Error> {
+    Proj::new_known_crs(from, to, None).map_err(|e| Error::Reprojection(e.to_string()))
+}
+
+impl Value {
+    /// Transform every position from the coordinate reference system `from` to `to`, such as
+    /// `"EPSG:27700"` to `"EPSG:4326"`.
+    ///
+    /// Coordinates are transformed in the axis order GeoJSON uses, longitude (or easting)
+    /// first. Only the first two elements of each position are transformed; any others are
+    /// left as they are. If a position fails to transform, the error is returned and earlier
+    /// positions are left transformed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use geojson::{position, Value};
+    ///
+    /// let mut value = Value::Point(position![530000.0, 180000.0]);
+    /// value.reproject("EPSG:27700", "EPSG:4326").unwrap();
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "proj")))]
+    pub fn reproject(&mut self, from: &str, to: &str) -> Result<(), Error> {
+        self.reproject_with(&transform(from, to)?)
+    }
+
+    /// Transform every position with an existing `Proj` transformation.
+    ///
+    /// This avoids creating a new transformation for each geometry. See
+    /// [`reproject`](#method.reproject).
+    #[cfg_attr(docsrs, doc(cfg(feature = "proj")))]
+    pub fn reproject_with(&mut self, proj: &Proj) -> Result<(), Error> {
+        try_for_each_position_mut(self, &mut |position| {
+            if let [x, y, ..] = position.as_mut_slice() {
+                let (new_x, new_y) = proj
+                    .convert((*x, *y))
+                    .map_err(|e| Error::Reprojection(e.to_string()))?;
+                *x = new_x;
+                *y = new_y;
+            }
+            Ok(())
+        })
+    }
+}
+
+impl Geometry {
+    /// Transform every position from the coordinate reference system `from` to `to`.
+    /// See [`Value::reproject`](enum.Value.html#method.reproject).
+    ///
+    /// The `bbox` is cleared, as it would no longer be accurate.
+    #[cfg_attr(docsrs, doc(cfg(feature = "proj")))]
+    pub fn reproject(&mut self, from: &str, to: &str) -> Result<(), Error> {
+        self.reproject_with(&transform(from, to)?)
+    }
+
+    /// Transform every position with an existing `Proj` transformation.
+    /// See [`Value::reproject`](enum.Value.html#method.reproject).
+    #[cfg_attr(docsrs, doc(cfg(feature = "proj")))]
+    pub fn reproject_with(&mut self, proj: &Proj) -> Result<(), Error> {
+        self.bbox = None;
+        self.value.reproject_with(proj)
+    }
+}
+
+impl Feature {
+    /// Transform every position from the coordinate reference system `from` to `to`.
+    /// See [`Value::reproject`](enum.Value.html#method.reproject).
+    ///
+    /// The `bbox` is cleared, as it would no longer be accurate.
+    #[cfg_attr(docsrs, doc(cfg(feature = "proj")))]
+    pub fn reproject(&mut self, from: &str, to: &str) -> Result<(), Error> {
+        self.reproject_with(&transform(from, to)?)
+    }
+
+    /// Transform every position with an existing `Proj` transformation.
+    /// See [`Value::reproject`](enum.Value.html#method.reproject).
+    #[cfg_attr(docsrs, doc(cfg(feature = "proj")))]
+    pub fn reproject_with(&mut self, proj: &Proj) -> Result<(), Error> {
+        self.bbox = None;
+        match &mut self.geometry {
+            Some(geometry) => geometry.reproject_with(proj),
+            None => Ok(()),
+        }
+    }
+}
+
+impl FeatureCollection {
+    /// Transform every position of every feature from the coordinate reference system `from`
+    /// to `to`. See [`Value::reproject`](enum.Value.html#method.reproject).
+    ///
+    /// The `bbox` of the collection and of each feature is cleared, as it would no longer be
+    /// accurate.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use geojson::FeatureCollection;
+    ///
+    /// let mut collection: FeatureCollection = serde_json::from_str(
+    ///     r#"{
+    ///         "type": "FeatureCollection",
+    ///         "features": []
+    ///     }"#,
+    /// )
+    /// .unwrap();
+    /// collection.reproject("EPSG:3857", "EPSG:4326").unwrap();
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "proj")))]
+    pub fn reproject(&mut self, from: &str, to: &str) -> Result<(), Error> {
+        self.reproject_with(&transform(from, to)?)
+    }
+
+    /// Transform every position of every feature with an existing `Proj` transformation.
+    /// See [`Value::reproject`](enum.Value.html#method.reproject).
+    #[cfg_attr(docsrs, doc(cfg(feature = "proj")))]
+    pub fn reproject_with(&mut self, proj: &Proj) -> Result<(), Error> {
+        self.bbox = None;
+        self.features
+            .iter_mut()
+            .try_for_each(|feature| feature.reproject_with(proj))
+    }
+}
+
+impl GeoJson {
+    /// Transform every position from the coordinate reference system `from` to `to`.
+    /// See [`Value::reproject`](enum.Value.html#method.reproject).
+    #[cfg_attr(docsrs, doc(cfg(feature = "proj")))]
+    pub fn reproject(&mut self, from: &str, to: &str) -> Result<(), Error> {
+        self.reproject_with(&transform(from, to)?)
+    }
+
+    /// Transform every position with an existing `Proj` transformation.
+    /// See [`Value::reproject`](enum.Value.html#method.reproject).
+    #[cfg_attr(docsrs, doc(cfg(feature = "proj")))]
+    pub fn reproject_with(&mut self, proj: &Proj) -> Result<(), Error> {
+        match self {
+            GeoJson::Geometry(geometry) => geometry.reproject_with(proj),
+            GeoJson::Feature(feature) => feature.reproject_with(proj),
+            GeoJson::FeatureCollection(collection) => collection.reproject_with(proj),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Feature, Value};
+
+    #[test]
+    fn reproject_web_mercator() {
+        let mut feature = Feature::from(Value::LineString(vec![
+            position![0.0, 0.0, 12.0],
+            position![20037508.342789244, 0.0],
+        ]));
+        feature.bbox = Some(vec![0.0, 0.0, 20037508.342789244, 0.0]);
+        feature.reproject("EPSG:3857", "EPSG:4326").unwrap();
+        assert_eq!(feature.bbox, None);
+        match feature.geometry.unwrap().value {
+            Value::LineString(line) => {
+                assert!(line[0][0].abs() < 1e-9 && line[0][1].abs() < 1e-9);
+                assert_eq!(line[0][2], 12.0);
+                assert!((line[1][0] - 180.0).abs() < 1e-6);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn unknown_crs() {
+        let mut value = Value::Point(position![0.0, 0.0]);
+        assert!(value.reproject("EPSG:0", "EPSG:4326").is_err());
+    }
+}
@@ -0,0 +1,270 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! [`proptest`](https://docs.rs/proptest) strategies which generate GeoJSON objects, for
+//! property-based tests.
+//!
+//! The strategies compose: [`feature`] takes a strategy of geometries and one of properties,
+//! and [`feature_collection`] a strategy of features, so each part can be narrowed down to what
+//! a test needs. The shape of coordinates is set by [`Coordinates`]. Geometries are
+//! structurally valid: `LineString`s have at least two positions and polygon rings are
+//! closed, with at least four, though rings may cross themselves.
+//!
+//! # Example
+//!
+//! ```
+//! use geojson::proptest::{self as geo_strategy, Coordinates};
+//! use geojson::Feature;
+//! use proptest::prelude::*;
+//!
+//! let features = geo_strategy::feature(
+//!     geo_strategy::geometry(Coordinates::default().dimensions(2..=3)),
+//!     geo_strategy::properties(0..4),
+//! );
+//! proptest!(|(feature in features)| {
+//!     let parsed: Feature = serde_json::from_str(&feature.to_string()).unwrap();
+//!     prop_assert_eq!(parsed, feature);
+//! });
+//! ```
+//!
+//! [`feature`]: fn.feature.html
+//! [`feature_collection`]: fn.feature_collection.html
+//! [`Coordinates`]: struct.Coordinates.html
+
+use crate::json::{JsonObject, JsonValue};
+use crate::{Feature, FeatureCollection, GeoJson, Geometry, Position, Value};
+use ::proptest::collection::{vec, SizeRange};
+use ::proptest::prelude::*;
+use std::ops::{Range, RangeInclusive};
+
+/// The most positions in a generated `LineString`, `MultiPoint` or polygon ring, and the most
+/// members of any other generated array or object
+const MAX_LEN: usize = 8;
+
+/// The deepest nesting of generated `GeometryCollection`s, and of arrays and objects in
+/// generated JSON values
+const MAX_DEPTH: u32 = 3;
+
+/// The shape of generated positions
+#[derive(Clone, Debug, PartialEq)]
+pub struct Coordinates {
+    /// The number of coordinates of each position, which is at least two
+    pub dimensions: RangeInclusive<usize>,
+    /// The range of the first coordinate, the longitude
+    pub x: Range<f64>,
+    /// The range of the second coordinate, the latitude
+    pub y: Range<f64>,
+    /// The range of the third coordinate, the altitude, and of any further coordinates
+    pub z: Range<f64>,
+}
+
+impl Default for Coordinates {
+    /// Two-dimensional longitudes and latitudes
+    fn default() -> Self {
+        Coordinates {
+            dimensions: 2..=2,
+            x: -180.0..180.0,
+            y: -90.0..90.0,
+            z: -1000.0..1000.0,
+        }
+    }
+}
+
+impl Coordinates {
+    /// Positions of `dimensions` coordinates
+    pub fn dimensions(mut self, dimensions: RangeInclusive<usize>) -> Self {
+        self.dimensions = dimensions;
+        self
+    }
+
+    /// Longitudes in `x` and latitudes in `y`
+    pub fn bounds(mut self, x: Range<f64>, y: Range<f64>) -> Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+}
+
+/// Positions shaped by `coordinates`
+pub fn position(coordinates: Coordinates) -> impl Strategy<Value = Position> + Clone {
+    let Coordinates {
+        dimensions,
+        x,
+        y,
+        z,
+    } = coordinates;
+    (
+        x,
+        y,
+        vec(
+            z,
+            dimensions.start().max(&2) - 2..=dimensions.end().max(&2) - 2,
+        ),
+    )
+        .prop_map(|(x, y, rest)| {
+            let mut position = position![x, y];
+            position.extend(rest);
+            position
+        })
+}
+
+fn positions(coordinates: Coordinates, min: usize) -> impl Strategy<Value = Vec<Position>> + Clone {
+    vec(position(coordinates), min..=MAX_LEN)
+}
+
+fn line_string(coordinates: Coordinates) -> impl Strategy<Value = Vec<Position>> + Clone {
+    positions(coordinates, 2)
+}
+
+fn polygon(coordinates: Coordinates) -> impl Strategy<Value = Vec<Vec<Position>>> + Clone {
+    let ring = positions(coordinates, 3).prop_map(|mut ring| {
+        ring.push(ring[0].clone());
+        ring
+    });
+    vec(ring, 1..=3)
+}
+
+/// `Point`s, `LineString`s, `Polygon`s and their `Multi` variants, without
+/// `GeometryCollection`s
+pub fn simple_value(coordinates: Coordinates) -> BoxedStrategy<Value> {
+    prop_oneof![
+        position(coordinates.clone()).prop_map(Value::Point),
+        positions(coordinates.clone(), 0).prop_map(Value::MultiPoint),
+        line_string(coordinates.clone()).prop_map(Value::LineString),
+        vec(line_string(coordinates.clone()), 0..=MAX_LEN).prop_map(Value::MultiLineString),
+        polygon(coordinates.clone()).prop_map(Value::Polygon),
+        vec(polygon(coordinates), 0..=MAX_LEN).prop_map(Value::MultiPolygon),
+    ]
+    .boxed()
+}
+
+/// Geometry values of every type, including nested `GeometryCollection`s
+pub fn value(coordinates: Coordinates) -> BoxedStrategy<Value> {
+    simple_value(coordinates)
+        .prop_recursive(MAX_DEPTH, 64, MAX_LEN as u32, |value| {
+            vec(value.prop_map(Geometry::new), 0..=MAX_LEN).prop_map(Value::GeometryCollection)
+        })
+        .boxed()
+}
+
+/// Geometries of every type, without a `bbox` or foreign members
+pub fn geometry(coordinates: Coordinates) -> impl Strategy<Value = Geometry> + Clone {
+    value(coordinates).prop_map(Geometry::new)
+}
+
+/// JSON values of every type, with finite numbers and arrays and objects nested up to a few
+/// levels deep
+pub fn json_value() -> BoxedStrategy<JsonValue> {
+    let leaf = prop_oneof![
+        Just(JsonValue::Null),
+        any::<bool>().prop_map(JsonValue::from),
+        any::<i64>().prop_map(JsonValue::from),
+        (-1e9..1e9).prop_map(JsonValue::from),
+        ".*".prop_map(JsonValue::from),
+    ];
+    leaf.prop_recursive(MAX_DEPTH, 64, MAX_LEN as u32, |value| {
+        prop_oneof![
+            vec(value.clone(), 0..=MAX_LEN).prop_map(JsonValue::Array),
+            vec((".*", value), 0..=MAX_LEN)
+                .prop_map(|members| JsonValue::Object(members.into_iter().collect())),
+        ]
+    })
+    .boxed()
+}
+
+/// Properties with `size` members of any name and any JSON value
+pub fn properties(size: impl Into<SizeRange>) -> impl Strategy<Value = JsonObject> + Clone {
+    vec(("[a-z_]{1,12}", json_value()), size).prop_map(|members| members.into_iter().collect())
+}
+
+/// Properties with a member for each of `schema`, whose value is generated by its strategy
+///
+/// ```
+/// use geojson::proptest::properties_of;
+/// use proptest::prelude::*;
+/// use serde_json::Value;
+///
+/// let properties = properties_of(vec![
+///     ("name", "[A-Z][a-z]{2,8}".prop_map(Value::from).boxed()),
+///     ("lanes", (1..6u64).prop_map(Value::from).boxed()),
+/// ]);
+/// ```
+pub fn properties_of(
+    schema: Vec<(&str, BoxedStrategy<JsonValue>)>,
+) -> impl Strategy<Value = JsonObject> + Clone {
+    let (keys, values): (Vec<String>, Vec<_>) = schema
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value))
+        .unzip();
+    values.prop_map(move |values| keys.iter().cloned().zip(values).collect())
+}
+
+/// Features with a geometry of `geometry`, or none, and properties of `properties`
+pub fn feature(
+    geometry: impl Strategy<Value = Geometry>,
+    properties: impl Strategy<Value = JsonObject>,
+) -> impl Strategy<Value = Feature> {
+    (::proptest::option::of(geometry), properties).prop_map(|(geometry, properties)| Feature {
+        bbox: None,
+        geometry,
+        id: None,
+        properties: Some(properties),
+        foreign_members: None,
+    })
+}
+
+/// Feature collections of `size` features of `feature`
+pub fn feature_collection(
+    feature: impl Strategy<Value = Feature>,
+    size: impl Into<SizeRange>,
+) -> impl Strategy<Value = FeatureCollection> {
+    vec(feature, size).prop_map(|features| FeatureCollection {
+        bbox: None,
+        features,
+        foreign_members: None,
+    })
+}
+
+/// Geometries, features and feature collections shaped by `coordinates`
+pub fn geojson(coordinates: Coordinates) -> impl Strategy<Value = GeoJson> {
+    let feature = || feature(geometry(coordinates.clone()), properties(0..=MAX_LEN));
+    prop_oneof![
+        geometry(coordinates.clone()).prop_map(GeoJson::Geometry),
+        feature().prop_map(GeoJson::Feature),
+        feature_collection(feature(), 0..=MAX_LEN).prop_map(GeoJson::FeatureCollection),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{geojson, position, Coordinates};
+    use crate::GeoJson;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn positions_are_shaped_by_coordinates(
+            position in position(Coordinates::default().dimensions(2..=4).bounds(0.0..1.0, 5.0..6.0))
+        ) {
+            prop_assert!((2..=4).contains(&position.len()));
+            prop_assert!((0.0..1.0).contains(&position[0]) && (5.0..6.0).contains(&position[1]));
+        }
+
+        #[test]
+        fn geojson_round_trips(geojson in geojson(Coordinates::default().dimensions(2..=3))) {
+            let parsed: GeoJson = geojson.to_string().parse().unwrap();
+            prop_assert_eq!(parsed, geojson);
+        }
+    }
+}
@@ -0,0 +1,226 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::algorithm::{bounding_rect, distance::distance_2};
+use crate::{Feature, FeatureCollection};
+use ::rstar::{Envelope, PointDistance, RTree, RTreeObject, AABB};
+
+/// Features are indexed by the extent of their geometry's positions, which is what their
+/// distances are measured from, so a stale or loose `bbox` member doesn't affect the results
+/// of queries. Features without any positions have an empty envelope.
+///
+/// # Example
+///
+/// ```
+/// use geojson::{position, Feature, Value};
+/// use rstar::RTree;
+///
+/// let features = vec![
+///     Feature::from(Value::Point(position![0.0, 0.0])),
+///     Feature::from(Value::Point(position![5.0, 5.0])),
+/// ];
+/// let tree = RTree::bulk_load(features);
+/// let nearest = tree.nearest_neighbor(&[4.0, 4.0]).unwrap();
+/// assert_eq!(nearest.geometry.as_ref().unwrap().value, Value::Point(position![5.0, 5.0]));
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "rstar")))]
+impl RTreeObject for Feature {
+    type Envelope = AABB<[f64; 2]>;
+
+    fn envelope(&self) -> Self::Envelope {
+        self.geometry
+            .as_ref()
+            .and_then(|geometry| bounding_rect(&geometry.value))
+            .map_or_else(AABB::new_empty, |rect| {
+                AABB::from_corners([rect.min_x, rect.min_y], [rect.max_x, rect.max_y])
+            })
+    }
+}
+
+/// Distances are planar, measured to the nearest point of the feature's geometry, and zero
+/// within polygons. Features without a geometry are infinitely far away.
+#[cfg_attr(docsrs, doc(cfg(feature = "rstar")))]
+impl PointDistance for Feature {
+    fn distance_2(&self, point: &[f64; 2]) -> f64 {
+        self.geometry
+            .as_ref()
+            .and_then(|geometry| distance_2(&geometry.value, point[0], point[1]))
+            .unwrap_or(f64::INFINITY)
+    }
+}
+
+/// A reference to a feature of a [`FeatureCollection`](struct.FeatureCollection.html), with its
+/// index in the collection and its envelope, as stored in the R-tree built by
+/// [`FeatureCollection::to_rtree`](struct.FeatureCollection.html#method.to_rtree)
+///
+/// The envelope is computed once, when the tree is built, rather than every time the tree
+/// compares it.
+#[cfg_attr(docsrs, doc(cfg(feature = "rstar")))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IndexedFeature<'a> {
+    feature: &'a Feature,
+    index: usize,
+    envelope: AABB<[f64; 2]>,
+}
+
+impl<'a> IndexedFeature<'a> {
+    /// The feature
+    pub fn feature(&self) -> &'a Feature {
+        self.feature
+    }
+
+    /// The index of the feature in its collection
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl RTreeObject for IndexedFeature<'_> {
+    type Envelope = AABB<[f64; 2]>;
+
+    fn envelope(&self) -> Self::Envelope {
+        self.envelope
+    }
+}
+
+impl PointDistance for IndexedFeature<'_> {
+    fn distance_2(&self, point: &[f64; 2]) -> f64 {
+        self.feature.distance_2(point)
+    }
+}
+
+impl FeatureCollection {
+    /// Build an R-tree of the features of this collection, indexed as
+    /// [`Feature`](struct.Feature.html#impl-RTreeObject-for-Feature) is, which refers back to
+    /// the features rather than copying them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geojson::{position, Feature, FeatureCollection, Value};
+    /// use rstar::AABB;
+    ///
+    /// let collection = FeatureCollection {
+    ///     bbox: None,
+    ///     features: vec![
+    ///         Feature::from(Value::Point(position![0.0, 0.0])),
+    ///         Feature::from(Value::Point(position![5.0, 5.0])),
+    ///     ],
+    ///     foreign_members: None,
+    /// };
+    /// let tree = collection.to_rtree();
+    /// assert_eq!(tree.nearest_neighbor(&[4.0, 4.0]).unwrap().index(), 1);
+    /// let found = tree.locate_in_envelope(&AABB::from_corners([-1.0, -1.0], [1.0, 1.0]));
+    /// assert_eq!(found.map(|indexed| indexed.index()).collect::<Vec<_>>(), vec![0]);
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "rstar")))]
+    pub fn to_rtree(&self) -> RTree<IndexedFeature<'_>> {
+        RTree::bulk_load(
+            self.features
+                .iter()
+                .enumerate()
+                .map(|(index, feature)| IndexedFeature {
+                    feature,
+                    index,
+                    envelope: feature.envelope(),
+                })
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Feature, FeatureCollection, Value};
+    use rstar::{RTree, RTreeObject, AABB};
+
+    fn square(min: f64, max: f64) -> Value {
+        Value::Polygon(vec![vec![
+            position![min, min],
+            position![max, min],
+            position![max, max],
+            position![min, max],
+            position![min, min],
+        ]])
+    }
+
+    #[test]
+    fn envelope_from_geometry() {
+        let mut feature = Feature::from(square(0.0, 2.0));
+        assert_eq!(
+            feature.envelope(),
+            AABB::from_corners([0.0, 0.0], [2.0, 2.0])
+        );
+        // The declared bbox is ignored
+        feature.bbox = Some(vec![-1.0, -1.0, 3.0, 3.0]);
+        assert_eq!(
+            feature.envelope(),
+            AABB::from_corners([0.0, 0.0], [2.0, 2.0])
+        );
+    }
+
+    #[test]
+    fn spatial_queries() {
+        let tree = RTree::bulk_load(vec![
+            Feature::from(square(0.0, 2.0)),
+            Feature::from(square(10.0, 12.0)),
+            Feature::from(Value::LineString(vec![
+                position![0.0, 20.0],
+                position![10.0, 20.0],
+            ])),
+            Feature {
+                bbox: None,
+                geometry: None,
+                id: None,
+                properties: None,
+                foreign_members: None,
+            },
+        ]);
+        let found: Vec<_> = tree
+            .locate_in_envelope_intersecting(&AABB::from_corners([1.0, 1.0], [11.0, 11.0]))
+            .collect();
+        assert_eq!(found.len(), 2);
+
+        let nearest = tree.nearest_neighbor(&[5.0, 19.0]).unwrap();
+        assert!(matches!(
+            nearest.geometry.as_ref().unwrap().value,
+            Value::LineString(_)
+        ));
+        assert_eq!(tree.locate_all_at_point(&[1.0, 1.0]).count(), 1);
+    }
+
+    #[test]
+    fn collection_rtree() {
+        let collection = FeatureCollection {
+            bbox: None,
+            features: vec![
+                Feature::from(square(0.0, 2.0)),
+                Feature::from(square(10.0, 12.0)),
+                Feature::from(square(1.0, 11.0)),
+            ],
+            foreign_members: None,
+        };
+        let tree = collection.to_rtree();
+        assert_eq!(tree.size(), 3);
+        let mut found: Vec<_> = tree
+            .locate_all_at_point(&[1.5, 1.5])
+            .map(|indexed| indexed.index())
+            .collect();
+        found.sort_unstable();
+        assert_eq!(found, vec![0, 2]);
+        let nearest = tree.nearest_neighbor(&[13.0, 13.0]).unwrap();
+        assert_eq!(nearest.index(), 1);
+        assert!(std::ptr::eq(nearest.feature(), &collection.features[1]));
+    }
+}
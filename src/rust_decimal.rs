@@ -0,0 +1,168 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Properties as [`rust_decimal::Decimal`](https://docs.rs/rust_decimal)s.
+//!
+//! JSON numbers with a fractional part are held as `f64`s, whose shortest representation
+//! reads back as the number written for up to 15 significant digits. Decimals are read from
+//! that representation rather than converted from the `f64`, so `0.1` reads as exactly `0.1`,
+//! and only written as numbers when they read back exactly. Decimals with more digits can be
+//! kept exactly as strings, which are read too.
+
+use crate::errors::Error;
+use crate::json::JsonValue;
+use crate::Feature;
+use ::rust_decimal::Decimal;
+use serde_json::Number;
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+fn parse(value: &str) -> Option<Decimal> {
+    Decimal::from_str_exact(value)
+        .or_else(|_| Decimal::from_scientific(value))
+        .ok()
+}
+
+fn to_decimal(value: &JsonValue) -> Option<Decimal> {
+    match value {
+        JsonValue::Number(number) => parse(&number.to_string()),
+        JsonValue::String(value) => parse(value),
+        _ => None,
+    }
+}
+
+impl Feature {
+    /// The property `key` as a decimal, from a number or a string such as `"19.99"` or
+    /// `"1.5e-3"`, or `None` if it isn't set or is `null`.
+    ///
+    /// # Errors
+    ///
+    /// If the property is set to anything else, or to a number or string which isn't a decimal
+    /// of at most 28 digits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geojson::{position, Feature, Value};
+    /// use rust_decimal::Decimal;
+    ///
+    /// let mut feature = Feature::from(Value::Point(position![1.0, 2.0]));
+    /// feature.set_property("price", 0.1);
+    /// feature.set_property("total", "12345678901234567890.12");
+    ///
+    /// let price = feature.decimal_property("price").unwrap().unwrap();
+    /// assert_eq!(price, Decimal::new(1, 1));
+    /// let total = feature.decimal_property("total").unwrap().unwrap();
+    /// assert_eq!(total, Decimal::from_i128_with_scale(1234567890123456789012, 2));
+    /// ```
+    pub fn decimal_property(&self, key: impl AsRef<str>) -> Result<Option<Decimal>, Error> {
+        let key = key.as_ref();
+        match self.property(key) {
+            Some(value) if !value.is_null() => to_decimal(value)
+                .map(Some)
+                .ok_or_else(|| Error::InvalidDecimalProperty(key.to_string(), value.clone())),
+            _ => Ok(None),
+        }
+    }
+
+    /// Set the property `key` to `decimal` as a number.
+    ///
+    /// Its scale isn't kept, so `1.50` is written as `1.5`.
+    ///
+    /// # Errors
+    ///
+    /// If the number wouldn't read back as `decimal`, as it has more significant digits than an
+    /// `f64` holds, in which case the property is unchanged. Such a decimal can be set as a
+    /// string with `set_property(key, decimal.to_string())` instead.
+    pub fn set_decimal_property(
+        &mut self,
+        key: impl Into<String>,
+        decimal: Decimal,
+    ) -> Result<(), Error> {
+        let number = if decimal.is_integer() {
+            i64::try_from(decimal).ok().map(Number::from)
+        } else {
+            None
+        };
+        let number = number
+            .or_else(|| {
+                let number = Number::from_f64(f64::from_str(&decimal.to_string()).ok()?)?;
+                Some(number).filter(|number| parse(&number.to_string()) == Some(decimal))
+            })
+            .ok_or_else(|| Error::InexactDecimal(decimal.to_string()))?;
+        self.set_property(key, number);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Feature, Value};
+    use rust_decimal::Decimal;
+    use serde_json::json;
+    use std::str::FromStr;
+
+    #[test]
+    fn decimal_properties() {
+        let feature: Feature = serde_json::from_value(json!({
+            "type": "Feature",
+            "geometry": null,
+            "properties": {
+                "a": 0.3,
+                "b": -42,
+                "c": 1.5e-7,
+                "d": "99999999999999999999.99",
+                "e": null,
+                "f": true,
+                "g": "ten",
+            }
+        }))
+        .unwrap();
+        let decimal = |value: &str| Decimal::from_str(value).unwrap();
+        assert_eq!(feature.decimal_property("a").unwrap(), Some(decimal("0.3")));
+        assert_eq!(feature.decimal_property("b").unwrap(), Some(decimal("-42")));
+        assert_eq!(
+            feature.decimal_property("c").unwrap(),
+            Some(decimal("0.00000015"))
+        );
+        assert_eq!(
+            feature.decimal_property("d").unwrap(),
+            Some(decimal("99999999999999999999.99"))
+        );
+        assert_eq!(feature.decimal_property("e").unwrap(), None);
+        assert_eq!(feature.decimal_property("missing").unwrap(), None);
+        assert!(feature.decimal_property("f").is_err());
+        assert!(feature.decimal_property("g").is_err());
+
+        let mut feature = Feature::from(Value::Point(position![0.0, 0.0]));
+        for value in [
+            "19.99",
+            "-0.000001",
+            "1.50",
+            "9007199254740993",
+            "123456789.123456",
+        ] {
+            feature.set_decimal_property("x", decimal(value)).unwrap();
+            assert_eq!(feature.decimal_property("x").unwrap(), Some(decimal(value)));
+        }
+        assert_eq!(
+            feature.property("x").unwrap().to_string(),
+            "123456789.123456"
+        );
+        assert!(feature
+            .set_decimal_property("y", decimal("1234567890.1234567890"))
+            .is_err());
+        assert!(!feature.contains_property("y"));
+    }
+}
@@ -0,0 +1,173 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversion between [S2](http://s2geometry.io) cell coverings and GeoJSON, using the
+//! [`s2`](https://docs.rs/s2) crate.
+//!
+//! Cell boundaries are geodesic, whereas GeoJSON edges are straight lines in
+//! longitude/latitude, so both conversions are approximations which err on the side of
+//! including cells: coverings may contain cells which only come close to a geometry.
+//!
+//! # Example
+//!
+//! ```
+//! use geojson::{position, s2, Value};
+//!
+//! let line = Value::LineString(vec![position![-0.15, 51.5], position![-0.1, 51.52]]);
+//! let covering = line.to_s2_covering(12);
+//! assert!(covering.0.iter().all(|cell| cell.level() == 12));
+//!
+//! let collection = s2::cells_to_feature_collection(covering.0);
+//! assert!(!collection.features.is_empty());
+//! ```
+
+use crate::algorithm::intersects::rect_intersects_value;
+use crate::algorithm::{bounding_rect, Rect};
+use crate::{Feature, FeatureCollection, Geometry, Value};
+use ::s2::cell::Cell;
+use ::s2::cellid::{CellID, MAX_LEVEL};
+use ::s2::cellunion::CellUnion;
+use ::s2::latlng::LatLng;
+use ::s2::rect::Rect as LatLngRect;
+use ::s2::region::RegionCoverer;
+
+/// The property under which a cell's token is stored by [`cell_to_feature`](fn.cell_to_feature.html)
+pub const S2_PROPERTY: &str = "s2";
+
+/// Convert an S2 cell into a `Feature` with a `Polygon` geometry joining the cell's vertices.
+///
+/// The cell token is stored in the `"s2"` property.
+pub fn cell_to_feature(cell_id: CellID) -> Feature {
+    let cell = Cell::from(cell_id);
+    let mut ring: Vec<_> = (0..4)
+        .map(|k| {
+            let vertex = LatLng::from(cell.vertex(k));
+            position![vertex.lng.deg(), vertex.lat.deg()]
+        })
+        .collect();
+    ring.push(ring[0].clone());
+    let mut feature = Feature::from(Value::Polygon(vec![ring]));
+    feature.set_property(S2_PROPERTY, cell_id.to_token());
+    feature
+}
+
+/// Convert S2 cells into a `FeatureCollection` of `Polygon` Features.
+///
+/// See [`cell_to_feature`](fn.cell_to_feature.html).
+pub fn cells_to_feature_collection(cells: impl IntoIterator<Item = CellID>) -> FeatureCollection {
+    FeatureCollection {
+        bbox: None,
+        features: cells.into_iter().map(cell_to_feature).collect(),
+        foreign_members: None,
+    }
+}
+
+fn cell_rect(cell: &Cell) -> Rect {
+    let bound = cell.rect_bound();
+    let (lo, hi) = (bound.lo(), bound.hi());
+    Rect {
+        min_x: lo.lng.deg(),
+        min_y: lo.lat.deg(),
+        max_x: hi.lng.deg(),
+        max_y: hi.lat.deg(),
+    }
+}
+
+impl Value {
+    /// The S2 cells at `level` which cover this geometry, as a `CellUnion`.
+    ///
+    /// Levels greater than 30 are treated as 30. Geometries without any positions produce an
+    /// empty union.
+    #[cfg_attr(docsrs, doc(cfg(feature = "s2")))]
+    pub fn to_s2_covering(&self, level: u8) -> CellUnion {
+        let level = level.min(MAX_LEVEL as u8);
+        let rect = match bounding_rect(self) {
+            Some(rect) => rect,
+            None => return CellUnion(vec![]),
+        };
+        let coverer = RegionCoverer {
+            min_level: level,
+            max_level: level,
+            level_mod: 1,
+            max_cells: usize::MAX,
+        };
+        let region = LatLngRect::from_degrees(rect.min_y, rect.min_x, rect.max_y, rect.max_x);
+        let mut union = coverer.covering(&region);
+        // Coverings may be normalized into coarser cells, so expand them back to `level`
+        union.denormalize(level as u64, 1);
+        union
+            .0
+            .retain(|cell_id| rect_intersects_value(&cell_rect(&Cell::from(cell_id)), self));
+        union
+    }
+}
+
+impl Geometry {
+    /// The S2 cells covering this geometry.
+    /// See [`Value::to_s2_covering`](enum.Value.html#method.to_s2_covering).
+    #[cfg_attr(docsrs, doc(cfg(feature = "s2")))]
+    pub fn to_s2_covering(&self, level: u8) -> CellUnion {
+        self.value.to_s2_covering(level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cell_to_feature, S2_PROPERTY};
+    use crate::Value;
+    use ::s2::cellid::CellID;
+    use ::s2::latlng::LatLng;
+
+    #[test]
+    fn cell_feature() {
+        let cell_id = CellID::from(LatLng::from_degrees(51.5, -0.12)).parent(10);
+        let feature = cell_to_feature(cell_id);
+        assert_eq!(
+            feature.property(S2_PROPERTY),
+            Some(&cell_id.to_token().into())
+        );
+        match feature.geometry.unwrap().value {
+            Value::Polygon(rings) => {
+                assert_eq!(rings[0].len(), 5);
+                assert_eq!(rings[0].first(), rings[0].last());
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn point_covering() {
+        let point = Value::Point(position![-0.12, 51.5]);
+        let covering = point.to_s2_covering(15);
+        let expected = CellID::from(LatLng::from_degrees(51.5, -0.12)).parent(15);
+        assert!(covering.0.contains(&expected));
+        assert!(covering.0.iter().all(|cell| cell.level() == 15));
+    }
+
+    #[test]
+    fn polygon_covering_contains_interior() {
+        let polygon = Value::Polygon(vec![vec![
+            position![10.0, 10.0],
+            position![11.0, 10.0],
+            position![11.0, 11.0],
+            position![10.0, 11.0],
+            position![10.0, 10.0],
+        ]]);
+        let covering = polygon.to_s2_covering(8);
+        let centre = CellID::from(LatLng::from_degrees(10.5, 10.5)).parent(8);
+        assert!(covering.0.contains(&centre));
+        assert!(covering.0.iter().all(|cell| cell.level() == 8));
+        assert!(Value::MultiPoint(vec![]).to_s2_covering(8).0.is_empty());
+    }
+}
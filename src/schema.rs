@@ -0,0 +1,367 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Inference of the schema of the properties of features.
+//!
+//! A [`Schema`] is built up one feature at a time, so it can be inferred from a
+//! [`FeatureCollection`](../struct.FeatureCollection.html) with
+//! [`infer_schema`](../struct.FeatureCollection.html#method.infer_schema), or from a stream of
+//! features too large to hold in memory. It records the name of every property, the JSON types
+//! of its values, whether it is ever `null` or missing, and a few example values: what an
+//! importer needs to create a table for the features.
+//!
+//! # Example
+//!
+//! ```
+//! use geojson::geojsonl::LineDelimitedReader;
+//! use geojson::schema::{PropertyType, Schema};
+//!
+//! let input = r#"{"type":"Feature","geometry":null,"properties":{"name":"a","height":3}}
+//! {"type":"Feature","geometry":null,"properties":{"name":"b","height":null}}
+//! {"type":"Feature","geometry":null,"properties":{"name":"c","height":4.5}}
+//! "#;
+//! let mut schema = Schema::new();
+//! for feature in LineDelimitedReader::new(input.as_bytes()).features() {
+//!     schema.add_feature(&feature.unwrap());
+//! }
+//!
+//! let height = &schema.properties["height"];
+//! assert!(height.nullable);
+//! assert!(height.types.contains(&PropertyType::Integer));
+//! assert!(height.types.contains(&PropertyType::Number));
+//! assert!(schema.is_required("name"));
+//! ```
+//!
+//! [`Schema`]: struct.Schema.html
+
+use crate::json::{JsonObject, JsonValue};
+use crate::{Feature, FeatureCollection};
+use std::collections::{BTreeMap, BTreeSet};
+use std::iter::FromIterator;
+
+/// The number of distinct example values kept for each property
+pub const MAX_EXAMPLES: usize = 3;
+
+/// The JSON Schema dialect of [`Schema::to_json_schema`](struct.Schema.html#method.to_json_schema)
+const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// The type of a JSON value which isn't `null`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PropertyType {
+    Boolean,
+    /// A number without a fractional part or exponent, which fits in an `i64` or a `u64`
+    Integer,
+    /// Any other number
+    Number,
+    String,
+    Array,
+    Object,
+}
+
+impl PropertyType {
+    /// The type of `value`, or `None` if it is `null`
+    pub fn of(value: &JsonValue) -> Option<PropertyType> {
+        Some(match value {
+            JsonValue::Null => return None,
+            JsonValue::Bool(_) => PropertyType::Boolean,
+            JsonValue::Number(number) if number.is_f64() => PropertyType::Number,
+            JsonValue::Number(_) => PropertyType::Integer,
+            JsonValue::String(_) => PropertyType::String,
+            JsonValue::Array(_) => PropertyType::Array,
+            JsonValue::Object(_) => PropertyType::Object,
+        })
+    }
+
+    /// The name of this type in [JSON Schema](https://json-schema.org), e.g. `"integer"`
+    pub fn json_schema_name(self) -> &'static str {
+        match self {
+            PropertyType::Boolean => "boolean",
+            PropertyType::Integer => "integer",
+            PropertyType::Number => "number",
+            PropertyType::String => "string",
+            PropertyType::Array => "array",
+            PropertyType::Object => "object",
+        }
+    }
+}
+
+/// What is known of one property from the features seen
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PropertySchema {
+    /// The types of the values which aren't `null`
+    pub types: BTreeSet<PropertyType>,
+    /// Whether the value of any feature is `null`
+    pub nullable: bool,
+    /// The number of features which have the property, including with `null`
+    pub count: usize,
+    /// The first [`MAX_EXAMPLES`](constant.MAX_EXAMPLES.html) distinct values which aren't
+    /// `null`
+    pub examples: Vec<JsonValue>,
+}
+
+impl PropertySchema {
+    /// The [JSON Schema](https://json-schema.org) of the values of this property, with their
+    /// types and examples.
+    ///
+    /// An `integer` type is left out if `number` is in, as every integer is a number, and a
+    /// `null` type is added if the property is nullable.
+    pub fn to_json_schema(&self) -> JsonValue {
+        let mut types: Vec<JsonValue> = self
+            .types
+            .iter()
+            .filter(|&&type_| {
+                type_ != PropertyType::Integer || !self.types.contains(&PropertyType::Number)
+            })
+            .map(|type_| type_.json_schema_name().into())
+            .collect();
+        if self.nullable {
+            types.push("null".into());
+        }
+        let mut schema = JsonObject::new();
+        match types.len() {
+            0 => {}
+            1 => {
+                schema.insert("type".to_string(), types.remove(0));
+            }
+            _ => {
+                schema.insert("type".to_string(), JsonValue::Array(types));
+            }
+        }
+        if !self.examples.is_empty() {
+            schema.insert(
+                "examples".to_string(),
+                JsonValue::Array(self.examples.clone()),
+            );
+        }
+        JsonValue::Object(schema)
+    }
+
+    fn add(&mut self, value: &JsonValue) {
+        self.count += 1;
+        match PropertyType::of(value) {
+            Some(type_) => {
+                self.types.insert(type_);
+                if self.examples.len() < MAX_EXAMPLES && !self.examples.contains(value) {
+                    self.examples.push(value.clone());
+                }
+            }
+            None => self.nullable = true,
+        }
+    }
+}
+
+/// The properties of the features seen, by name
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Schema {
+    /// The number of features seen
+    pub features: usize,
+    pub properties: BTreeMap<String, PropertySchema>,
+}
+
+impl Schema {
+    pub fn new() -> Self {
+        Schema::default()
+    }
+
+    /// Add what `feature` shows of the properties
+    pub fn add_feature(&mut self, feature: &Feature) {
+        self.features += 1;
+        for (key, value) in feature.properties_iter() {
+            if let Some(property) = self.properties.get_mut(key) {
+                property.add(value);
+            } else {
+                let mut property = PropertySchema::default();
+                property.add(value);
+                self.properties.insert(key.clone(), property);
+            }
+        }
+    }
+
+    /// Whether any feature seen lacks the property `key`
+    pub fn is_optional(&self, key: &str) -> bool {
+        self.properties
+            .get(key)
+            .map_or(true, |property| property.count < self.features)
+    }
+
+    /// Whether every feature seen has the property `key`, and it is never `null`, so it may
+    /// be a `NOT NULL` column
+    pub fn is_required(&self, key: &str) -> bool {
+        !self.is_optional(key) && !self.properties[key].nullable
+    }
+
+    /// A [JSON Schema](https://json-schema.org) (draft 2020-12) document of the `properties`
+    /// object of the features seen, to publish or validate the properties of features with.
+    ///
+    /// Every property is described by
+    /// [`PropertySchema::to_json_schema`](struct.PropertySchema.html#method.to_json_schema),
+    /// and those which every feature has are `required`. Properties which weren't seen are
+    /// allowed.
+    ///
+    /// ```
+    /// use geojson::{position, Feature, FeatureCollection, Value};
+    /// use serde_json::json;
+    ///
+    /// let mut feature = Feature::from(Value::Point(position![0.0, 0.0]));
+    /// feature.set_property("name", "well");
+    /// let collection = FeatureCollection {
+    ///     bbox: None,
+    ///     features: vec![feature],
+    ///     foreign_members: None,
+    /// };
+    ///
+    /// assert_eq!(
+    ///     collection.infer_schema().to_json_schema(),
+    ///     json!({
+    ///         "$schema": "https://json-schema.org/draft/2020-12/schema",
+    ///         "type": "object",
+    ///         "properties": {
+    ///             "name": { "type": "string", "examples": ["well"] }
+    ///         },
+    ///         "required": ["name"]
+    ///     })
+    /// );
+    /// ```
+    pub fn to_json_schema(&self) -> JsonValue {
+        let properties: JsonObject = self
+            .properties
+            .iter()
+            .map(|(key, property)| (key.clone(), property.to_json_schema()))
+            .collect();
+        let required: Vec<JsonValue> = self
+            .properties
+            .keys()
+            .filter(|key| !self.is_optional(key))
+            .map(|key| key.as_str().into())
+            .collect();
+        let mut schema = JsonObject::new();
+        schema.insert("$schema".to_string(), JSON_SCHEMA_DIALECT.into());
+        schema.insert("type".to_string(), "object".into());
+        schema.insert("properties".to_string(), JsonValue::Object(properties));
+        schema.insert("required".to_string(), JsonValue::Array(required));
+        JsonValue::Object(schema)
+    }
+}
+
+impl<'a> FromIterator<&'a Feature> for Schema {
+    fn from_iter<I: IntoIterator<Item = &'a Feature>>(features: I) -> Self {
+        let mut schema = Schema::new();
+        for feature in features {
+            schema.add_feature(feature);
+        }
+        schema
+    }
+}
+
+impl FeatureCollection {
+    /// Infer the schema of the properties of the features. See the
+    /// [`schema`](schema/index.html) module.
+    pub fn infer_schema(&self) -> Schema {
+        self.features.iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PropertySchema, PropertyType};
+    use crate::FeatureCollection;
+    use serde_json::json;
+
+    #[test]
+    fn infer_schema() {
+        let collection: FeatureCollection = serde_json::from_value(json!({
+                "type": "FeatureCollection",
+                "features": [
+                    { "type": "Feature", "geometry": null, "properties": { "id": 1, "tags": ["a"], "name": "x" } },
+                    { "type": "Feature", "geometry": null, "properties": { "id": 2, "tags": { "a": 1 }, "name": "x", "flag": null } },
+                    { "type": "Feature", "geometry": null, "properties": null },
+                    { "type": "Feature", "geometry": null, "properties": { "id": 2, "name": "y", "flag": true } },
+                    { "type": "Feature", "geometry": null, "properties": { "id": 4, "name": "z" } },
+                    { "type": "Feature", "geometry": null, "properties": { "id": 5.5, "name": "z" } }
+                ]
+        }))
+        .unwrap();
+        let schema = collection.infer_schema();
+
+        assert_eq!(schema.features, 6);
+        assert_eq!(
+            schema.properties.keys().collect::<Vec<_>>(),
+            ["flag", "id", "name", "tags"]
+        );
+        assert_eq!(
+            schema.properties["id"],
+            PropertySchema {
+                types: [PropertyType::Integer, PropertyType::Number]
+                    .iter()
+                    .copied()
+                    .collect(),
+                nullable: false,
+                count: 5,
+                examples: vec![json!(1), json!(2), json!(4)],
+            }
+        );
+        assert_eq!(
+            schema.properties["tags"].types,
+            [PropertyType::Array, PropertyType::Object]
+                .iter()
+                .copied()
+                .collect()
+        );
+        assert!(schema.properties["flag"].nullable);
+        assert_eq!(schema.properties["flag"].examples, vec![json!(true)]);
+        assert!(schema.is_optional("id") && !schema.is_required("id"));
+        assert!(schema.is_optional("missing"));
+        assert_eq!(PropertyType::of(&json!(-3)), Some(PropertyType::Integer));
+        assert_eq!(PropertyType::of(&json!(1e3)), Some(PropertyType::Number));
+    }
+
+    #[test]
+    fn json_schema() {
+        let collection: FeatureCollection = serde_json::from_value(json!({
+            "type": "FeatureCollection",
+            "features": [
+                { "type": "Feature", "geometry": null, "properties": { "id": 1, "ref": null, "note": "a" } },
+                { "type": "Feature", "geometry": null, "properties": { "id": 2.5, "ref": null } }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(
+            collection.infer_schema().to_json_schema(),
+            json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "type": "object",
+                "properties": {
+                    "id": { "type": "number", "examples": [1, 2.5] },
+                    "note": { "type": "string", "examples": ["a"] },
+                    "ref": { "type": "null" }
+                },
+                "required": ["id", "ref"]
+            })
+        );
+
+        let nullable = PropertySchema {
+            types: [PropertyType::Integer, PropertyType::String]
+                .iter()
+                .copied()
+                .collect(),
+            nullable: true,
+            count: 1,
+            examples: vec![],
+        };
+        assert_eq!(
+            nullable.to_json_schema(),
+            json!({ "type": ["integer", "string", "null"] })
+        );
+    }
+}
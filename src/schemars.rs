@@ -0,0 +1,229 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Implementations of [`schemars::JsonSchema`](https://docs.rs/schemars), following the
+//! [official GeoJSON schemas](https://geojson.org/schema/GeoJSON.json), so that APIs can
+//! document GeoJSON request and response bodies.
+//!
+//! `Geometry`, `Feature`, `FeatureCollection` and `GeoJson` are each a definition, which the
+//! others refer to, so a `GeometryCollection` refers to `Geometry` rather than nesting it.
+
+use crate::json::JsonValue;
+use crate::{Feature, FeatureCollection, GeoJson, Geometry};
+use ::schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+use serde_json::json;
+use std::borrow::Cow;
+use std::convert::TryFrom;
+
+fn bbox() -> JsonValue {
+    json!({
+        "type": "array",
+        "minItems": 4,
+        "items": { "type": "number" }
+    })
+}
+
+fn position() -> JsonValue {
+    json!({
+        "type": "array",
+        "minItems": 2,
+        "items": { "type": "number" }
+    })
+}
+
+fn array_of(items: JsonValue, min_items: usize) -> JsonValue {
+    let mut array = json!({ "type": "array", "items": items });
+    if min_items > 0 {
+        array["minItems"] = min_items.into();
+    }
+    array
+}
+
+/// The schema of an object with the member `type` set to `type_`, a `bbox`, and `members`,
+/// which are required
+fn object(type_: &str, members: JsonValue) -> JsonValue {
+    let mut required = vec![JsonValue::from("type")];
+    let mut properties = json!({
+        "type": { "type": "string", "enum": [type_] },
+        "bbox": bbox(),
+    });
+    if let JsonValue::Object(members) = members {
+        for (key, value) in members {
+            required.push(key.clone().into());
+            properties[key] = value;
+        }
+    }
+    json!({
+        "title": format!("GeoJSON {}", type_),
+        "type": "object",
+        "required": required,
+        "properties": properties,
+    })
+}
+
+fn geometry(type_: &str, coordinates: JsonValue) -> JsonValue {
+    object(type_, json!({ "coordinates": coordinates }))
+}
+
+/// `schema`, or `null`
+fn nullable(schema: Schema) -> JsonValue {
+    json!({ "oneOf": [{ "type": "null" }, schema] })
+}
+
+impl JsonSchema for Geometry {
+    fn schema_name() -> Cow<'static, str> {
+        "Geometry".into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        "geojson::Geometry".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        let line_string = array_of(position(), 2);
+        let polygon = array_of(array_of(position(), 4), 0);
+        json_schema!({
+            "title": "GeoJSON Geometry",
+            "oneOf": [
+                geometry("Point", position()),
+                geometry("LineString", line_string.clone()),
+                geometry("Polygon", polygon.clone()),
+                geometry("MultiPoint", array_of(position(), 0)),
+                geometry("MultiLineString", array_of(line_string, 0)),
+                geometry("MultiPolygon", array_of(polygon, 0)),
+                object(
+                    "GeometryCollection",
+                    json!({ "geometries": array_of(generator.subschema_for::<Geometry>().to_value(), 0) }),
+                ),
+            ]
+        })
+    }
+}
+
+impl JsonSchema for Feature {
+    fn schema_name() -> Cow<'static, str> {
+        "Feature".into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        "geojson::Feature".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        let mut feature = object(
+            "Feature",
+            json!({
+                "geometry": nullable(generator.subschema_for::<Geometry>()),
+                "properties": { "oneOf": [{ "type": "null" }, { "type": "object" }] },
+            }),
+        );
+        feature["properties"]["id"] =
+            json!({ "oneOf": [{ "type": "number" }, { "type": "string" }] });
+        Schema::try_from(feature).unwrap()
+    }
+}
+
+impl JsonSchema for FeatureCollection {
+    fn schema_name() -> Cow<'static, str> {
+        "FeatureCollection".into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        "geojson::FeatureCollection".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        let features = array_of(generator.subschema_for::<Feature>().to_value(), 0);
+        Schema::try_from(object("FeatureCollection", json!({ "features": features }))).unwrap()
+    }
+}
+
+impl JsonSchema for GeoJson {
+    fn schema_name() -> Cow<'static, str> {
+        "GeoJson".into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        "geojson::GeoJson".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "title": "GeoJSON",
+            "oneOf": [
+                generator.subschema_for::<Geometry>(),
+                generator.subschema_for::<Feature>(),
+                generator.subschema_for::<FeatureCollection>(),
+            ]
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FeatureCollection, GeoJson};
+    use serde_json::json;
+
+    #[test]
+    fn geojson_schema() {
+        let schema = ::schemars::schema_for!(GeoJson).to_value();
+        let refs: Vec<_> = schema["oneOf"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|schema| schema["$ref"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            refs,
+            [
+                "#/$defs/Geometry",
+                "#/$defs/Feature",
+                "#/$defs/FeatureCollection"
+            ]
+        );
+
+        let definitions = &schema["$defs"];
+        let geometries = definitions["Geometry"]["oneOf"].as_array().unwrap();
+        assert_eq!(geometries.len(), 7);
+        assert_eq!(
+            geometries[2]["properties"]["coordinates"],
+            json!({
+                "type": "array",
+                "items": { "type": "array", "minItems": 4, "items": super::position() }
+            })
+        );
+        assert_eq!(
+            geometries[6]["properties"]["geometries"]["items"],
+            json!({ "$ref": "#/$defs/Geometry" })
+        );
+
+        let feature = &definitions["Feature"];
+        assert_eq!(
+            feature["required"],
+            json!(["type", "geometry", "properties"])
+        );
+        assert_eq!(
+            feature["properties"]["geometry"]["oneOf"][1],
+            json!({ "$ref": "#/$defs/Geometry" })
+        );
+        assert_eq!(
+            definitions["FeatureCollection"]["properties"]["features"]["items"],
+            json!({ "$ref": "#/$defs/Feature" })
+        );
+
+        let schema = ::schemars::schema_for!(FeatureCollection).to_value();
+        assert_eq!(schema["title"], "GeoJSON FeatureCollection");
+        assert!(schema["$defs"]["Feature"].is_object());
+    }
+}
@@ -0,0 +1,1051 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::algorithm::round::round;
+use crate::json::{JsonObject, JsonValue, Serialize};
+use crate::{Bbox, Feature, FeatureCollection, GeoJson, Geometry, GeometryType};
+use serde::ser::SerializeMap;
+use serde::Serializer;
+use serde_json::ser::PrettyFormatter;
+use std::io::Write;
+
+/// Which `bbox` members to write when serializing
+///
+/// # Example
+///
+/// ```
+/// use geojson::{position, BboxPolicy, Feature, Value, WriterOptions};
+///
+/// let feature = Feature::from(Value::LineString(vec![position![0.0, 1.0], position![2.0, -1.0]]));
+/// let options = WriterOptions::new().bbox(BboxPolicy::Features);
+/// assert_eq!(
+///     feature.to_string_with(&options).unwrap(),
+///     concat!(
+///         r#"{"bbox":[0.0,-1.0,2.0,1.0],"#,
+///         r#""geometry":{"coordinates":[[0.0,1.0],[2.0,-1.0]],"type":"LineString"},"#,
+///         r#""properties":{},"type":"Feature"}"#
+///     )
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BboxPolicy {
+    /// Write the `bbox` members of the objects as they are
+    #[default]
+    Keep,
+    /// Write no `bbox` members at all
+    Omit,
+    /// Write a `bbox` computed from the geometry of every feature, replacing any existing one
+    Features,
+    /// Write a `bbox` computed from all of the features of a feature collection
+    Collection,
+    /// Compute the `bbox` of every feature and of the feature collection
+    FeaturesAndCollection,
+}
+
+/// Which `crs` members to write when serializing
+///
+/// RFC 7946 removed the `crs` member of the 2008 GeoJSON specification, but some consumers
+/// still expect, or reject, one.
+///
+/// # Example
+///
+/// ```
+/// use geojson::{position, CrsPolicy, Geometry, Value, WriterOptions};
+///
+/// let geometry = Geometry::new(Value::Point(position![1.0, 2.0]));
+/// let options = WriterOptions::new().crs(CrsPolicy::crs84());
+/// assert_eq!(
+///     geometry.to_string_with(&options).unwrap(),
+///     concat!(
+///         r#"{"coordinates":[1.0,2.0],"#,
+///         r#""crs":{"properties":{"name":"urn:ogc:def:crs:OGC:1.3:CRS84"},"type":"name"},"#,
+///         r#""type":"Point"}"#
+///     )
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CrsPolicy {
+    /// Write the `crs` members of the objects as they are
+    #[default]
+    Keep,
+    /// Write no `crs` members at all
+    Omit,
+    /// Write a single named `crs` member on the outermost object, such as
+    /// `"urn:ogc:def:crs:EPSG::3857"`, replacing any others
+    Named(String),
+}
+
+impl CrsPolicy {
+    /// The name of OGC CRS84, the longitude and latitude WGS 84 coordinates of RFC 7946
+    pub const CRS84: &'static str = "urn:ogc:def:crs:OGC:1.3:CRS84";
+
+    /// Write a named `crs` member for [`CRS84`](#associatedconstant.CRS84), as the 2008
+    /// specification recommends for WGS 84 coordinates.
+    pub fn crs84() -> Self {
+        CrsPolicy::Named(Self::CRS84.to_string())
+    }
+}
+
+/// Which type to write the `id` members of features as when serializing
+///
+/// # Example
+///
+/// ```
+/// use geojson::{feature::Id, position, Feature, IdPolicy, Value, WriterOptions};
+///
+/// let mut feature = Feature::from(Value::Point(position![1.0, 2.0]));
+/// feature.id = Some(Id::String("42".to_string()));
+/// let options = WriterOptions::new().id(IdPolicy::NumberWhenLossless);
+/// assert!(feature.to_string_with(&options).unwrap().contains(r#""id":42"#));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum IdPolicy {
+    /// Write the `id`s of features as they are
+    #[default]
+    Keep,
+    /// Write every numeric `id` as a string, e.g. `7` as `"7"`
+    String,
+    /// Write string `id`s which are integers, such as `"7"` but not `"07"`, as numbers, and
+    /// other `id`s as they are
+    NumberWhenLossless,
+}
+
+/// Options for how to write GeoJSON, for use with the `to_string_with` and `to_writer_with`
+/// methods of [`GeoJson`](enum.GeoJson.html), [`Geometry`](struct.Geometry.html),
+/// [`Feature`](struct.Feature.html) and [`FeatureCollection`](struct.FeatureCollection.html)
+///
+/// The default options write the same JSON as `to_string`.
+///
+/// # Example
+///
+/// ```
+/// use geojson::{position, BboxPolicy, Feature, Value, WriterOptions};
+///
+/// let mut feature = Feature::from(Value::Point(position![-0.127758123, 51.507351234]));
+/// feature.set_property("name", serde_json::Value::Null);
+/// let options = WriterOptions::new()
+///     .precision(3)
+///     .omit_nulls(true)
+///     .bbox(BboxPolicy::Features);
+/// assert_eq!(
+///     feature.to_string_with(&options).unwrap(),
+///     concat!(
+///         r#"{"bbox":[-0.128,51.507,-0.128,51.507],"#,
+///         r#""geometry":{"coordinates":[-0.128,51.507],"type":"Point"},"#,
+///         r#""properties":{"name":null},"type":"Feature"}"#
+///     )
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct WriterOptions {
+    indent: Option<usize>,
+    precision: Option<u8>,
+    omit_nulls: bool,
+    omit_empty_properties: bool,
+    bbox: BboxPolicy,
+    crs: CrsPolicy,
+    id: IdPolicy,
+    canonical: bool,
+}
+
+impl WriterOptions {
+    /// Options which write the same JSON as `to_string`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pretty-print the JSON, with each member and array element on its own line, indented by
+    /// `indent` spaces per level.
+    ///
+    /// ```
+    /// use geojson::{position, Geometry, Value, WriterOptions};
+    ///
+    /// let geometry = Geometry::new(Value::Point(position![1.0, 2.0]));
+    /// assert_eq!(
+    ///     geometry.to_string_with(&WriterOptions::new().pretty(2)).unwrap(),
+    ///     "{\n  \"coordinates\": [\n    1.0,\n    2.0\n  ],\n  \"type\": \"Point\"\n}"
+    /// );
+    /// ```
+    pub fn pretty(mut self, indent: usize) -> Self {
+        self.indent = Some(indent);
+        self
+    }
+
+    /// Round every coordinate, including those of any `bbox`, to at most `decimals` decimal
+    /// places.
+    ///
+    /// Unlike [`Geometry::round_coords`](struct.Geometry.html#method.round_coords), this leaves
+    /// the object unchanged, so that data can be kept at full precision in memory but published
+    /// compactly. Properties and foreign members are written unchanged.
+    ///
+    /// ```
+    /// use geojson::{position, Geometry, Value, WriterOptions};
+    ///
+    /// let geometry = Geometry::new(Value::Point(position![-0.127758123, 51.507351234]));
+    /// assert_eq!(
+    ///     geometry.to_string_with(&WriterOptions::new().precision(5)).unwrap(),
+    ///     r#"{"coordinates":[-0.12776,51.50735],"type":"Point"}"#
+    /// );
+    /// ```
+    pub fn precision(mut self, decimals: u8) -> Self {
+        self.precision = Some(decimals);
+        self
+    }
+
+    /// Write every coordinate in full, in the shortest form which parses back to exactly the
+    /// same `f64`, undoing [`precision`](#method.precision).
+    ///
+    /// This is the default, so parsing and writing GeoJSON again never changes its
+    /// coordinates.
+    pub fn full_precision(mut self) -> Self {
+        self.precision = None;
+        self
+    }
+
+    /// Skip foreign members whose value is `null`, on the object and on the features and
+    /// geometries within it, for consumers which treat a missing member and `null` alike.
+    ///
+    /// The `geometry` member of features, which RFC 7946 requires, is always written, and
+    /// `null` values within `properties` are kept.
+    ///
+    /// ```
+    /// use geojson::{position, Feature, Value, WriterOptions};
+    ///
+    /// let mut feature = Feature::from(Value::Point(position![1.0, 2.0]));
+    /// feature.foreign_members = serde_json::json!({ "title": null }).as_object().cloned();
+    /// assert_eq!(
+    ///     feature.to_string_with(&WriterOptions::new().omit_nulls(true)).unwrap(),
+    ///     r#"{"geometry":{"coordinates":[1.0,2.0],"type":"Point"},"properties":{},"type":"Feature"}"#
+    /// );
+    /// ```
+    pub fn omit_nulls(mut self, omit_nulls: bool) -> Self {
+        self.omit_nulls = omit_nulls;
+        self
+    }
+
+    /// Skip the `properties` of features whose properties are `None`, `null` or empty, which is
+    /// smaller but not strictly valid GeoJSON.
+    pub fn omit_empty_properties(mut self, omit_empty_properties: bool) -> Self {
+        self.omit_empty_properties = omit_empty_properties;
+        self
+    }
+
+    /// Write `bbox` members according to `policy`, e.g. computed from the coordinates so that
+    /// published files always carry accurate extents. The object itself is left unchanged.
+    pub fn bbox(mut self, policy: BboxPolicy) -> Self {
+        self.bbox = policy;
+        self
+    }
+
+    /// Write `crs` members according to `policy`.
+    pub fn crs(mut self, policy: CrsPolicy) -> Self {
+        self.crs = policy;
+        self
+    }
+
+    /// Write the `id`s of features according to `policy`.
+    pub fn id(mut self, policy: IdPolicy) -> Self {
+        self.id = policy;
+        self
+    }
+
+    /// Write canonical JSON, so that equal objects always produce identical bytes, e.g. for
+    /// content hashing, caching or reproducible builds. This takes precedence over
+    /// [`pretty`](#method.pretty).
+    ///
+    /// The output follows the JSON Canonicalization Scheme of RFC 8785: members are sorted by
+    /// name, there is no whitespace, and numbers are written in their shortest form, with
+    /// integral values such as `1.0` written as `1`, regardless of how serde_json is
+    /// configured. Combine this with
+    /// [`Geometry::canonicalize`](struct.Geometry.html#method.canonicalize) to also normalize
+    /// the geometries.
+    ///
+    /// ```
+    /// use geojson::{position, Geometry, Value, WriterOptions};
+    ///
+    /// let geometry = Geometry::new(Value::Point(position![1.0, -0.5, 1e21]));
+    /// assert_eq!(
+    ///     geometry.to_string_with(&WriterOptions::new().canonical(true)).unwrap(),
+    ///     r#"{"coordinates":[1,-0.5,1e+21],"type":"Point"}"#
+    /// );
+    /// ```
+    pub fn canonical(mut self, canonical: bool) -> Self {
+        self.canonical = canonical;
+        self
+    }
+
+    /// Whether the options write the same members as `to_string`, so that the object can be
+    /// serialized without converting it to a `JsonObject` first
+    fn keeps_members(&self) -> bool {
+        self.precision.is_none()
+            && !self.omit_nulls
+            && !self.omit_empty_properties
+            && self.bbox == BboxPolicy::Keep
+            && self.crs == CrsPolicy::Keep
+            && self.id == IdPolicy::Keep
+    }
+}
+
+/// The object being written, whose `bbox`es can be computed
+#[derive(Clone, Copy)]
+enum Source<'a> {
+    Geometry,
+    Feature(&'a Feature),
+    FeatureCollection(&'a FeatureCollection),
+}
+
+impl<'a> From<&'a GeoJson> for Source<'a> {
+    fn from(geojson: &'a GeoJson) -> Self {
+        match geojson {
+            GeoJson::Geometry(_) => Source::Geometry,
+            GeoJson::Feature(feature) => Source::Feature(feature),
+            GeoJson::FeatureCollection(collection) => Source::FeatureCollection(collection),
+        }
+    }
+}
+
+impl<'a> From<&'a Geometry> for Source<'a> {
+    fn from(_: &'a Geometry) -> Self {
+        Source::Geometry
+    }
+}
+
+impl<'a> From<&'a Feature> for Source<'a> {
+    fn from(feature: &'a Feature) -> Self {
+        Source::Feature(feature)
+    }
+}
+
+impl<'a> From<&'a FeatureCollection> for Source<'a> {
+    fn from(collection: &'a FeatureCollection) -> Self {
+        Source::FeatureCollection(collection)
+    }
+}
+
+fn set_bbox(object: &mut JsonObject, bbox: Option<Bbox>) {
+    match bbox {
+        Some(bbox) => object.insert("bbox".to_string(), JsonValue::from(bbox)),
+        None => object.remove("bbox"),
+    };
+}
+
+fn apply_bbox_policy(object: &mut JsonObject, source: Source, policy: BboxPolicy) {
+    let (features, collection) = match policy {
+        BboxPolicy::Keep => return,
+        BboxPolicy::Omit => {
+            for_each_object(object, &mut |_, object| {
+                object.remove("bbox");
+            });
+            return;
+        }
+        BboxPolicy::Features => (true, false),
+        BboxPolicy::Collection => (false, true),
+        BboxPolicy::FeaturesAndCollection => (true, true),
+    };
+    match source {
+        Source::Geometry => {}
+        Source::Feature(feature) => {
+            if features {
+                set_bbox(object, feature.compute_bbox());
+            }
+        }
+        Source::FeatureCollection(source) => {
+            if collection {
+                set_bbox(object, source.compute_bbox());
+            }
+            if features {
+                let members = object.get_mut("features").and_then(JsonValue::as_array_mut);
+                for (member, feature) in members.into_iter().flatten().zip(&source.features) {
+                    if let JsonValue::Object(member) = member {
+                        set_bbox(member, feature.compute_bbox());
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Geometries which can be serialized as GeoJSON geometry objects directly, without first
+/// building a [`Value`](enum.Value.html) and its nested `Vec`s of positions.
+///
+/// With the `geo-types` feature, this is implemented for every `geo_types` geometry.
+/// Implement it for other geometry types to write them as GeoJSON with
+/// [`geometry_to_writer`](fn.geometry_to_writer.html), or within other serde data with
+/// [`AsGeoJson`](struct.AsGeoJson.html).
+///
+/// # Example
+///
+/// ```
+/// use geojson::{geometry_to_string, GeometryType, SerializeGeometry};
+/// use serde::ser::SerializeMap;
+///
+/// struct Segment([f64; 2], [f64; 2]);
+///
+/// impl SerializeGeometry for Segment {
+///     fn geometry_type(&self) -> GeometryType {
+///         GeometryType::LineString
+///     }
+///
+///     fn serialize_members<M: SerializeMap>(&self, map: &mut M) -> Result<(), M::Error> {
+///         map.serialize_entry("coordinates", &[self.0, self.1])
+///     }
+/// }
+///
+/// assert_eq!(
+///     geometry_to_string(&Segment([0.0, 0.0], [1.0, 2.0])).unwrap(),
+///     r#"{"coordinates":[[0.0,0.0],[1.0,2.0]],"type":"LineString"}"#
+/// );
+/// ```
+pub trait SerializeGeometry {
+    /// The GeoJSON `type` of this geometry, such as `GeometryType::Point`
+    fn geometry_type(&self) -> GeometryType;
+
+    /// Serialize the members of the geometry object other than its `type`: the `coordinates`,
+    /// or the `geometries` of a `GeometryCollection`.
+    fn serialize_members<M: SerializeMap>(&self, map: &mut M) -> Result<(), M::Error>;
+}
+
+/// Serialize a [`SerializeGeometry`](trait.SerializeGeometry.html) as a GeoJSON geometry
+/// object, e.g. as a member of another serde data structure.
+///
+/// Members are written in the same order as they are for a [`Geometry`](struct.Geometry.html).
+pub struct AsGeoJson<'a, G: ?Sized>(pub &'a G);
+
+impl<G: SerializeGeometry + ?Sized> Serialize for AsGeoJson<'_, G> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        self.0.serialize_members(&mut map)?;
+        map.serialize_entry("type", self.0.geometry_type().as_str())?;
+        map.end()
+    }
+}
+
+/// Serialize a geometry as a GeoJSON geometry object to `writer`, without building a
+/// [`Value`](enum.Value.html).
+///
+/// See [`SerializeGeometry`](trait.SerializeGeometry.html).
+pub fn geometry_to_writer<W: Write, G: SerializeGeometry + ?Sized>(
+    writer: W,
+    geometry: &G,
+) -> Result<(), serde_json::Error> {
+    serde_json::to_writer(writer, &AsGeoJson(geometry))
+}
+
+/// Serialize a geometry as a string containing a GeoJSON geometry object, without building a
+/// [`Value`](enum.Value.html).
+///
+/// See [`SerializeGeometry`](trait.SerializeGeometry.html).
+pub fn geometry_to_string<G: SerializeGeometry + ?Sized>(
+    geometry: &G,
+) -> Result<String, serde_json::Error> {
+    to_string(|bytes| geometry_to_writer(bytes, geometry))
+}
+
+fn to_writer_pretty<W: Write>(
+    writer: W,
+    value: &impl Serialize,
+    indent: usize,
+) -> Result<(), serde_json::Error> {
+    let indent = vec![b' '; indent];
+    let mut serializer =
+        serde_json::Serializer::with_formatter(writer, PrettyFormatter::with_indent(&indent));
+    value.serialize(&mut serializer)
+}
+
+fn round_numbers(value: &mut JsonValue, factor: f64) {
+    match value {
+        JsonValue::Array(values) => values
+            .iter_mut()
+            .for_each(|value| round_numbers(value, factor)),
+        JsonValue::Number(number) => {
+            if let Some(mut coordinate) = number.as_f64() {
+                round(&mut coordinate, factor);
+                if let Some(rounded) = serde_json::Number::from_f64(coordinate) {
+                    *number = rounded;
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Call `f` with the type of, and on, a serialized GeoJSON object and every GeoJSON object
+/// nested within it, but not on objects within properties or foreign members
+fn for_each_object(object: &mut JsonObject, f: &mut impl FnMut(&str, &mut JsonObject)) {
+    let type_ = match object.get("type") {
+        Some(JsonValue::String(type_)) => type_.clone(),
+        _ => return,
+    };
+    f(&type_, object);
+    let members = match type_.as_str() {
+        "Feature" => object.get_mut("geometry").map(std::slice::from_mut),
+        "GeometryCollection" => object
+            .get_mut("geometries")
+            .and_then(JsonValue::as_array_mut)
+            .map(Vec::as_mut_slice),
+        "FeatureCollection" => object
+            .get_mut("features")
+            .and_then(JsonValue::as_array_mut)
+            .map(Vec::as_mut_slice),
+        _ => None,
+    };
+    for member in members.into_iter().flatten() {
+        if let JsonValue::Object(member) = member {
+            for_each_object(member, f);
+        }
+    }
+}
+
+/// Round the coordinates and `bbox`es of a serialized GeoJSON object, and of the objects
+/// nested within it, leaving properties and foreign members unchanged
+fn round_object(object: &mut JsonObject, factor: f64) {
+    for_each_object(object, &mut |type_, object| {
+        if let Some(bbox) = object.get_mut("bbox") {
+            round_numbers(bbox, factor);
+        }
+        if !matches!(
+            type_,
+            "Feature" | "FeatureCollection" | "GeometryCollection"
+        ) {
+            if let Some(coordinates) = object.get_mut("coordinates") {
+                round_numbers(coordinates, factor);
+            }
+        }
+    });
+}
+
+/// Remove the members which are `null` from a serialized GeoJSON object and the objects nested
+/// within it if `omit_nulls` is set, apart from the `geometry` of features, which RFC 7946
+/// requires
+fn omit_nulls(object: &mut JsonObject, omit_nulls: bool, omit_empty_properties: bool) {
+    for_each_object(object, &mut |type_, object| {
+        let is_feature = type_ == "Feature";
+        object.retain(|key, value| match (key.as_str(), value) {
+            ("geometry", _) if is_feature => true,
+            ("properties", JsonValue::Object(properties)) if is_feature => {
+                !(omit_empty_properties && properties.is_empty())
+            }
+            ("properties", JsonValue::Null) if is_feature => !omit_empty_properties,
+            (_, value) => !(omit_nulls && value.is_null()),
+        });
+    });
+}
+
+fn to_string(
+    write: impl FnOnce(&mut Vec<u8>) -> Result<(), serde_json::Error>,
+) -> Result<String, serde_json::Error> {
+    let mut bytes = Vec::new();
+    write(&mut bytes)?;
+    // serde_json only writes valid UTF-8
+    Ok(String::from_utf8(bytes).expect("serde_json wrote invalid UTF-8"))
+}
+
+/// Format a number as ECMAScript does, which RFC 8785 uses for canonical JSON: integers
+/// without a fraction, and exponents only for very large or small numbers
+fn canonical_number(number: f64) -> String {
+    if number == 0.0 {
+        return "0".to_string();
+    }
+    // The shortest digits which round trip, e.g. "-1.25e2"
+    let scientific = format!("{:e}", number);
+    let (mantissa, exponent) = scientific.split_once('e').unwrap_or((&scientific, "0"));
+    let exponent: i32 = exponent.parse().unwrap_or(0);
+    let (sign, mantissa) = match mantissa.strip_prefix('-') {
+        Some(mantissa) => ("-", mantissa),
+        None => ("", mantissa),
+    };
+    let digits = mantissa.replace('.', "");
+    let digit_count = digits.len() as i32;
+    // The position of the decimal point relative to the start of the digits
+    let point = exponent + 1;
+    let formatted = if digit_count <= point && point <= 21 {
+        format!("{}{}", digits, "0".repeat((point - digit_count) as usize))
+    } else if 0 < point && point <= 21 {
+        let (integer, fraction) = digits.split_at(point as usize);
+        format!("{}.{}", integer, fraction)
+    } else if -6 < point && point <= 0 {
+        format!("0.{}{}", "0".repeat(-point as usize), digits)
+    } else {
+        let (first, rest) = digits.split_at(1);
+        let fraction = if rest.is_empty() {
+            String::new()
+        } else {
+            format!(".{}", rest)
+        };
+        let sign = if exponent < 0 { '-' } else { '+' };
+        format!("{}{}e{}{}", first, fraction, sign, exponent.abs())
+    };
+    format!("{}{}", sign, formatted)
+}
+
+pub(crate) fn write_canonical<W: Write>(
+    writer: &mut W,
+    value: &JsonValue,
+) -> Result<(), serde_json::Error> {
+    match value {
+        JsonValue::Number(number) => {
+            let formatted = match (number.as_i64(), number.as_u64(), number.as_f64()) {
+                (Some(integer), _, _) => integer.to_string(),
+                (_, Some(integer), _) => integer.to_string(),
+                (_, _, Some(float)) => canonical_number(float),
+                _ => number.to_string(),
+            };
+            writer
+                .write_all(formatted.as_bytes())
+                .map_err(serde_json::Error::io)
+        }
+        JsonValue::Array(values) => {
+            writer.write_all(b"[").map_err(serde_json::Error::io)?;
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    writer.write_all(b",").map_err(serde_json::Error::io)?;
+                }
+                write_canonical(writer, value)?;
+            }
+            writer.write_all(b"]").map_err(serde_json::Error::io)
+        }
+        JsonValue::Object(object) => {
+            // RFC 8785 sorts members by the UTF-16 code units of their names
+            let mut members: Vec<_> = object.iter().collect();
+            members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+            writer.write_all(b"{").map_err(serde_json::Error::io)?;
+            for (i, (key, value)) in members.into_iter().enumerate() {
+                if i > 0 {
+                    writer.write_all(b",").map_err(serde_json::Error::io)?;
+                }
+                serde_json::to_writer(&mut *writer, key)?;
+                writer.write_all(b":").map_err(serde_json::Error::io)?;
+                write_canonical(writer, value)?;
+            }
+            writer.write_all(b"}").map_err(serde_json::Error::io)
+        }
+        // Nulls, booleans and strings, which serde_json already writes canonically
+        value => serde_json::to_writer(writer, value),
+    }
+}
+
+fn to_writer_canonical<W: Write>(
+    mut writer: W,
+    object: impl Into<JsonObject>,
+) -> Result<(), serde_json::Error> {
+    write_canonical(&mut writer, &JsonValue::Object(object.into()))
+}
+
+fn apply_crs_policy(object: &mut JsonObject, policy: &CrsPolicy) {
+    if *policy == CrsPolicy::Keep {
+        return;
+    }
+    for_each_object(object, &mut |_, object| {
+        object.remove("crs");
+    });
+    if let CrsPolicy::Named(name) = policy {
+        object.insert(
+            "crs".to_string(),
+            serde_json::json!({ "type": "name", "properties": { "name": name } }),
+        );
+    }
+}
+
+/// The integer which `id` is written as, if formatting the integer gives `id` back
+fn integer_id(id: &str) -> Option<serde_json::Number> {
+    let number = match id.parse::<i64>() {
+        Ok(integer) => serde_json::Number::from(integer),
+        Err(_) => serde_json::Number::from(id.parse::<u64>().ok()?),
+    };
+    (number.to_string() == id).then_some(number)
+}
+
+fn apply_id_policy(object: &mut JsonObject, policy: IdPolicy) {
+    if policy == IdPolicy::Keep {
+        return;
+    }
+    for_each_object(object, &mut |type_, object| {
+        if type_ != "Feature" {
+            return;
+        }
+        let id = match object.get_mut("id") {
+            Some(id) => id,
+            None => return,
+        };
+        let converted = match (policy, &*id) {
+            (IdPolicy::String, JsonValue::Number(number)) => {
+                Some(JsonValue::String(number.to_string()))
+            }
+            (IdPolicy::NumberWhenLossless, JsonValue::String(string)) => {
+                integer_id(string).map(JsonValue::Number)
+            }
+            _ => None,
+        };
+        if let Some(converted) = converted {
+            *id = converted;
+        }
+    });
+}
+
+fn write_with<W: Write>(
+    writer: W,
+    value: &impl Serialize,
+    options: &WriterOptions,
+) -> Result<(), serde_json::Error> {
+    match options.indent {
+        Some(indent) => to_writer_pretty(writer, value, indent),
+        None => serde_json::to_writer(writer, value),
+    }
+}
+
+fn to_writer_with<'a, W: Write>(
+    writer: W,
+    source: impl Into<Source<'a>> + Into<JsonObject> + Serialize + Copy,
+    options: &WriterOptions,
+) -> Result<(), serde_json::Error> {
+    if options.keeps_members() && !options.canonical {
+        return write_with(writer, &source, options);
+    }
+    let mut object = source.into();
+    apply_bbox_policy(&mut object, source.into(), options.bbox);
+    apply_crs_policy(&mut object, &options.crs);
+    apply_id_policy(&mut object, options.id);
+    if options.omit_nulls || options.omit_empty_properties {
+        omit_nulls(
+            &mut object,
+            options.omit_nulls,
+            options.omit_empty_properties,
+        );
+    }
+    if let Some(decimals) = options.precision {
+        round_object(&mut object, 10f64.powi(i32::from(decimals)));
+    }
+    if options.canonical {
+        to_writer_canonical(writer, object)
+    } else {
+        write_with(writer, &object, options)
+    }
+}
+
+macro_rules! impl_writer_options {
+    ($($type:ty),*) => {
+        $(
+            impl $type {
+                /// Serialize to a string of JSON written according to `options`.
+                ///
+                /// See [`WriterOptions`](struct.WriterOptions.html).
+                pub fn to_string_with(
+                    &self,
+                    options: &WriterOptions,
+                ) -> Result<String, serde_json::Error> {
+                    to_string(|bytes| to_writer_with(bytes, self, options))
+                }
+
+                /// Serialize JSON written according to `options` to `writer`.
+                ///
+                /// With the default options, or only [`pretty`](struct.WriterOptions.html#method.pretty),
+                /// the object is streamed to `writer` as it is serialized. Any other option first
+                /// converts the whole object to a `JsonObject`, which holds a copy of it in
+                /// memory until it has been written.
+                ///
+                /// See [`WriterOptions`](struct.WriterOptions.html).
+                pub fn to_writer_with<W: Write>(
+                    &self,
+                    writer: W,
+                    options: &WriterOptions,
+                ) -> Result<(), serde_json::Error> {
+                    to_writer_with(writer, self, options)
+                }
+            }
+        )*
+    };
+}
+
+impl_writer_options!(GeoJson, Geometry, Feature, FeatureCollection);
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        BboxPolicy, CrsPolicy, Feature, FeatureCollection, GeoJson, Geometry, IdPolicy, Value,
+        WriterOptions,
+    };
+
+    #[test]
+    fn precision_of_collection() {
+        let mut feature = Feature::from(Geometry {
+            bbox: None,
+            value: Value::GeometryCollection(vec![Geometry::new(Value::LineString(vec![
+                position![1.23456, 2.0, 3.33333],
+                position![0.1 + 0.2, 1e300],
+            ]))]),
+            foreign_members: None,
+        });
+        feature.bbox = Some(vec![0.123, 1.987, 0.5, 2.0]);
+        feature.set_property("value", 1.23456);
+        let collection = FeatureCollection {
+            bbox: None,
+            features: vec![feature.clone()],
+            foreign_members: None,
+        };
+
+        assert_eq!(
+            collection
+                .to_string_with(&WriterOptions::new().precision(2))
+                .unwrap(),
+            concat!(
+                r#"{"features":[{"bbox":[0.12,1.99,0.5,2.0],"geometry":{"geometries":[{"#,
+                r#""coordinates":[[1.23,2.0,3.33],[0.3,1e+300]],"type":"LineString"}],"#,
+                r#""type":"GeometryCollection"},"properties":{"value":1.23456},"#,
+                r#""type":"Feature"}],"type":"FeatureCollection"}"#
+            )
+        );
+        assert_eq!(collection.features[0], feature);
+    }
+
+    #[test]
+    fn omit_nulls_from_collection() {
+        let mut feature = Feature::from(Value::Point(position![1.0, 2.0]));
+        feature.set_property("empty", serde_json::Value::Null);
+        let mut empty = Feature::from(Value::Point(position![1.0, 2.0]));
+        empty.geometry = None;
+        let collection = FeatureCollection {
+            bbox: None,
+            features: vec![feature, empty],
+            foreign_members: Some(
+                vec![("title".to_string(), serde_json::Value::Null)]
+                    .into_iter()
+                    .collect(),
+            ),
+        };
+
+        assert_eq!(
+            collection
+                .to_string_with(&WriterOptions::new().omit_nulls(true))
+                .unwrap(),
+            concat!(
+                r#"{"features":[{"geometry":{"coordinates":[1.0,2.0],"type":"Point"},"#,
+                r#""properties":{"empty":null},"type":"Feature"},"#,
+                r#"{"geometry":null,"properties":{},"type":"Feature"}],"#,
+                r#""type":"FeatureCollection"}"#
+            )
+        );
+        assert_eq!(
+            collection
+                .to_string_with(
+                    &WriterOptions::new()
+                        .omit_nulls(true)
+                        .omit_empty_properties(true)
+                )
+                .unwrap(),
+            concat!(
+                r#"{"features":[{"geometry":{"coordinates":[1.0,2.0],"type":"Point"},"#,
+                r#""properties":{"empty":null},"type":"Feature"},"#,
+                r#"{"geometry":null,"type":"Feature"}],"type":"FeatureCollection"}"#
+            )
+        );
+    }
+
+    #[test]
+    fn canonical_numbers() {
+        let cases = [
+            (0.0, "0"),
+            (-0.0, "0"),
+            (1.0, "1"),
+            (-2.5, "-2.5"),
+            (123456789.0, "123456789"),
+            (1e20, "100000000000000000000"),
+            (1e21, "1e+21"),
+            (1.5e300, "1.5e+300"),
+            (0.000001, "0.000001"),
+            (0.0000001, "1e-7"),
+            (-1.25e-10, "-1.25e-10"),
+            (0.1 + 0.2, "0.30000000000000004"),
+        ];
+        for (number, expected) in cases {
+            assert_eq!(super::canonical_number(number), expected);
+        }
+    }
+
+    #[test]
+    fn canonical_feature() {
+        let mut feature = Feature::from(Value::Point(position![1.0, 2.5]));
+        feature.properties = serde_json::json!({ "z": [1, 2.0], "a": { "c": true, "b": "x" } })
+            .as_object()
+            .cloned();
+        feature.id = Some(crate::feature::Id::Number(7.into()));
+        assert_eq!(
+            feature
+                .to_string_with(&WriterOptions::new().canonical(true))
+                .unwrap(),
+            concat!(
+                r#"{"geometry":{"coordinates":[1,2.5],"type":"Point"},"id":7,"#,
+                r#""properties":{"a":{"b":"x","c":true},"z":[1,2]},"type":"Feature"}"#
+            )
+        );
+    }
+
+    #[test]
+    fn bbox_policies() {
+        let mut empty = Feature::from(Value::MultiPoint(vec![]));
+        empty.bbox = Some(vec![0.0, 0.0, 1.0, 1.0]);
+        let collection = GeoJson::from(FeatureCollection {
+            bbox: Some(vec![9.0, 9.0, 9.0, 9.0]),
+            features: vec![
+                Feature::from(Value::Point(position![1.0, 2.0])),
+                Feature::from(Value::Point(position![-1.0, 3.0, 5.0])),
+                empty,
+            ],
+            foreign_members: None,
+        });
+        let bboxes = |policy| {
+            let json: serde_json::Value = serde_json::from_str(
+                &collection
+                    .to_string_with(&WriterOptions::new().bbox(policy))
+                    .unwrap(),
+            )
+            .unwrap();
+            let features = json["features"].as_array().unwrap();
+            (
+                json.get("bbox").cloned(),
+                features
+                    .iter()
+                    .map(|feature| feature.get("bbox").cloned())
+                    .collect::<Vec<_>>(),
+            )
+        };
+        let json = |bbox: &[f64]| Some(serde_json::json!(bbox));
+
+        assert_eq!(
+            bboxes(BboxPolicy::Keep),
+            (
+                json(&[9.0, 9.0, 9.0, 9.0]),
+                vec![None, None, json(&[0.0, 0.0, 1.0, 1.0])]
+            )
+        );
+        assert_eq!(bboxes(BboxPolicy::Omit), (None, vec![None, None, None]));
+        assert_eq!(
+            bboxes(BboxPolicy::FeaturesAndCollection),
+            (
+                json(&[-1.0, 2.0, 1.0, 3.0]),
+                vec![
+                    json(&[1.0, 2.0, 1.0, 2.0]),
+                    json(&[-1.0, 3.0, 5.0, -1.0, 3.0, 5.0]),
+                    None
+                ]
+            )
+        );
+        assert_eq!(
+            bboxes(BboxPolicy::Collection).1,
+            vec![None, None, json(&[0.0, 0.0, 1.0, 1.0])]
+        );
+    }
+
+    #[test]
+    fn pretty_with_indent() {
+        let feature = Feature::from(Value::Point(position![1.0, 2.0]));
+        let expected = "{\n    \"geometry\": {\n        \"coordinates\": [\n            1.0,\n            2.0\n        ],\n        \"type\": \"Point\"\n    },\n    \"properties\": {},\n    \"type\": \"Feature\"\n}";
+        let options = WriterOptions::new().pretty(4);
+        assert_eq!(feature.to_string_with(&options).unwrap(), expected);
+
+        let mut bytes = Vec::new();
+        GeoJson::from(feature.clone())
+            .to_writer_with(&mut bytes, &options)
+            .unwrap();
+        assert_eq!(String::from_utf8(bytes).unwrap(), expected);
+    }
+
+    #[test]
+    fn writer_options() {
+        let mut geometry = Geometry::new(Value::Point(position![1.0, 2.0]));
+        geometry.foreign_members = serde_json::json!({ "crs": { "type": "name" } })
+            .as_object()
+            .cloned();
+        let mut feature = Feature::from(geometry);
+        feature.id = Some(crate::feature::Id::String("a".to_string()));
+        let collection = GeoJson::from(FeatureCollection {
+            bbox: None,
+            features: vec![feature],
+            foreign_members: None,
+        });
+
+        assert_eq!(
+            collection.to_string_with(&WriterOptions::new()).unwrap(),
+            collection.to_string()
+        );
+        let options = WriterOptions::new()
+            .crs(CrsPolicy::Named(
+                "urn:ogc:def:crs:OGC:1.3:CRS84".to_string(),
+            ))
+            .omit_empty_properties(true)
+            .pretty(2)
+            .canonical(true);
+        assert_eq!(
+            collection.to_string_with(&options).unwrap(),
+            concat!(
+                r#"{"crs":{"properties":{"name":"urn:ogc:def:crs:OGC:1.3:CRS84"},"type":"name"},"#,
+                r#""features":[{"geometry":{"coordinates":[1,2],"type":"Point"},"id":"a","#,
+                r#""type":"Feature"}],"type":"FeatureCollection"}"#
+            )
+        );
+        let json: serde_json::Value = serde_json::from_str(
+            &collection
+                .to_string_with(&WriterOptions::new().crs(CrsPolicy::Omit))
+                .unwrap(),
+        )
+        .unwrap();
+        assert!(json["features"][0]["geometry"].get("crs").is_none());
+    }
+
+    #[test]
+    fn id_policies() {
+        let ids = ["7", "07", "-3", "18446744073709551615", "1.5", "a"];
+        let collection = FeatureCollection {
+            bbox: None,
+            features: ids
+                .iter()
+                .map(|id| {
+                    let mut feature = Feature::from(Value::Point(position![1.0, 2.0]));
+                    feature.id = Some(crate::feature::Id::String(id.to_string()));
+                    feature
+                })
+                .chain(std::iter::once(Feature {
+                    id: serde_json::Number::from_f64(2.5).map(crate::feature::Id::Number),
+                    ..Feature::from(Value::Point(position![1.0, 2.0]))
+                }))
+                .collect(),
+            foreign_members: None,
+        };
+        let written_ids = |policy| {
+            let options = WriterOptions::new().id(policy);
+            let json: serde_json::Value =
+                serde_json::from_str(&collection.to_string_with(&options).unwrap()).unwrap();
+            json["features"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|feature| feature["id"].clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            written_ids(IdPolicy::NumberWhenLossless),
+            serde_json::json!([7, "07", -3, 18446744073709551615u64, "1.5", "a", 2.5])
+                .as_array()
+                .unwrap()
+                .clone()
+        );
+        assert_eq!(
+            written_ids(IdPolicy::String),
+            serde_json::json!(["7", "07", "-3", "18446744073709551615", "1.5", "a", "2.5"])
+                .as_array()
+                .unwrap()
+                .clone()
+        );
+    }
+}
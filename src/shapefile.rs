@@ -0,0 +1,535 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Reading and writing [Esri Shapefiles](https://en.wikipedia.org/wiki/Shapefile), using the
+//! [`shapefile`](https://docs.rs/shapefile) crate.
+//!
+//! Each shape and its DBF record become a `Feature`, with the record's fields stored as
+//! properties. Shapefile rings are wound clockwise, so rings are reversed in both
+//! directions to follow the [RFC 7946](https://tools.ietf.org/html/rfc7946#section-3.1.6)
+//! right-hand rule. M values are dropped when reading.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use geojson::shapefile;
+//!
+//! let collection = shapefile::read("countries.shp").unwrap();
+//! println!("{} countries", collection.features.len());
+//!
+//! shapefile::write(&collection, "copy.shp").unwrap();
+//! ```
+
+use crate::json::JsonValue;
+use crate::{Error, Feature, FeatureCollection, Geometry, Position, Value};
+use ::shapefile::dbase::{self, FieldName, FieldValue, TableWriterBuilder};
+use ::shapefile::{
+    Multipoint, Point, Polygon, PolygonRing, Polyline, Reader, Shape, ShapeWriter, Writer,
+};
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::io::{Read, Seek, Write};
+use std::path::Path;
+
+/// Width of the DBF character fields used for string and structured properties
+const CHARACTER_FIELD_LENGTH: u8 = 254;
+
+fn shapefile_error(e: impl std::fmt::Display) -> Error {
+    Error::Shapefile(e.to_string())
+}
+
+/// Read the `.shp` file at `path`, and the `.dbf` file alongside it, into a `FeatureCollection`.
+pub fn read<P: AsRef<Path>>(path: P) -> Result<FeatureCollection, Error> {
+    let mut reader = Reader::from_path(path).map_err(shapefile_error)?;
+    from_reader(&mut reader)
+}
+
+/// Read every shape and record from an open `shapefile::Reader` into a `FeatureCollection`.
+pub fn from_reader<T: Read + Seek, D: Read + Seek>(
+    reader: &mut Reader<T, D>,
+) -> Result<FeatureCollection, Error> {
+    let features = reader
+        .iter_shapes_and_records()
+        .map(|result| {
+            let (shape, record) = result.map_err(shapefile_error)?;
+            let properties = record
+                .into_iter()
+                .map(|(name, value)| (name, field_value_to_json(value)))
+                .collect();
+            Ok(Feature {
+                bbox: None,
+                geometry: shape_to_geometry(&shape)?,
+                id: None,
+                properties: Some(properties),
+                foreign_members: None,
+            })
+        })
+        .collect::<Result<_, Error>>()?;
+    Ok(FeatureCollection {
+        bbox: None,
+        features,
+        foreign_members: None,
+    })
+}
+
+/// Convert a shape into a `Geometry`, returning `None` for a `NullShape`.
+///
+/// Polylines with a single part become a `LineString`, and polygons with a single outer ring
+/// become a `Polygon`. `Multipatch` shapes have no GeoJSON equivalent and produce an error.
+pub fn shape_to_geometry(shape: &Shape) -> Result<Option<Geometry>, Error> {
+    let xy = |p: &Point| position![p.x, p.y];
+    let xym = |p: &::shapefile::PointM| position![p.x, p.y];
+    let xyz = |p: &::shapefile::PointZ| position![p.x, p.y, p.z];
+    let value = match shape {
+        Shape::NullShape => return Ok(None),
+        Shape::Point(p) => Value::Point(xy(p)),
+        Shape::PointM(p) => Value::Point(xym(p)),
+        Shape::PointZ(p) => Value::Point(xyz(p)),
+        Shape::Multipoint(m) => Value::MultiPoint(m.points().iter().map(xy).collect()),
+        Shape::MultipointM(m) => Value::MultiPoint(m.points().iter().map(xym).collect()),
+        Shape::MultipointZ(m) => Value::MultiPoint(m.points().iter().map(xyz).collect()),
+        Shape::Polyline(l) => parts_to_value(l.parts(), xy),
+        Shape::PolylineM(l) => parts_to_value(l.parts(), xym),
+        Shape::PolylineZ(l) => parts_to_value(l.parts(), xyz),
+        Shape::Polygon(p) => rings_to_value(p.rings(), xy),
+        Shape::PolygonM(p) => rings_to_value(p.rings(), xym),
+        Shape::PolygonZ(p) => rings_to_value(p.rings(), xyz),
+        Shape::Multipatch(_) => {
+            return Err(Error::Shapefile(
+                "Multipatch shapes cannot be represented as GeoJSON".to_string(),
+            ))
+        }
+    };
+    Ok(Some(Geometry::new(value)))
+}
+
+fn parts_to_value<P>(parts: &[Vec<P>], position: impl Fn(&P) -> Position) -> Value {
+    let mut lines: Vec<Vec<Position>> = parts
+        .iter()
+        .map(|part| part.iter().map(&position).collect())
+        .collect();
+    if lines.len() == 1 {
+        Value::LineString(lines.remove(0))
+    } else {
+        Value::MultiLineString(lines)
+    }
+}
+
+fn rings_to_value<P>(rings: &[PolygonRing<P>], position: impl Fn(&P) -> Position) -> Value {
+    let mut polygons: Vec<Vec<Vec<Position>>> = vec![];
+    for ring in rings {
+        let positions = ring.points().iter().rev().map(&position).collect();
+        match (ring, polygons.last_mut()) {
+            (PolygonRing::Inner(_), Some(polygon)) => polygon.push(positions),
+            _ => polygons.push(vec![positions]),
+        }
+    }
+    if polygons.len() == 1 {
+        Value::Polygon(polygons.remove(0))
+    } else {
+        Value::MultiPolygon(polygons)
+    }
+}
+
+fn field_value_to_json(value: FieldValue) -> JsonValue {
+    match value {
+        FieldValue::Character(s) => s.map_or(JsonValue::Null, JsonValue::String),
+        FieldValue::Memo(s) => JsonValue::String(s),
+        FieldValue::Numeric(n) => n.map_or(JsonValue::Null, number_to_json),
+        FieldValue::Float(n) => n.map_or(JsonValue::Null, |n| number_to_json(n.into())),
+        FieldValue::Double(n) | FieldValue::Currency(n) => number_to_json(n),
+        FieldValue::Integer(n) => n.into(),
+        FieldValue::Logical(b) => b.map_or(JsonValue::Null, JsonValue::Bool),
+        FieldValue::Date(d) => d.map_or(JsonValue::Null, |d| {
+            format!("{:04}-{:02}-{:02}", d.year(), d.month(), d.day()).into()
+        }),
+        FieldValue::DateTime(dt) => {
+            let (d, t) = (dt.date(), dt.time());
+            format!(
+                "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+                d.year(),
+                d.month(),
+                d.day(),
+                t.hours(),
+                t.minutes(),
+                t.seconds()
+            )
+            .into()
+        }
+    }
+}
+
+/// DBF numeric fields are read back as floats, so whole numbers are restored as integers
+fn number_to_json(n: f64) -> JsonValue {
+    if n.fract() == 0.0 && n.abs() < (1u64 << 53) as f64 {
+        (n as i64).into()
+    } else {
+        serde_json::Number::from_f64(n).map_or(JsonValue::Null, JsonValue::Number)
+    }
+}
+
+/// Write a `FeatureCollection` to a `.shp` file at `path`, with `.shx` and `.dbf` files
+/// alongside it.
+///
+/// See [`to_writer`](fn.to_writer.html) for the restrictions on the collection.
+pub fn write<P: AsRef<Path>>(collection: &FeatureCollection, path: P) -> Result<(), Error> {
+    let fields = infer_fields(collection);
+    let writer = Writer::from_path(path, table_builder(&fields)?).map_err(shapefile_error)?;
+    write_features(collection, &fields, writer)
+}
+
+/// Write a `FeatureCollection` to the given `.shp`, `.shx` and `.dbf` destinations.
+///
+/// A shapefile holds a single kind of shape, so the features must all have `Point`,
+/// `MultiPoint`, (`Multi`)`LineString` or (`Multi`)`Polygon` geometries; a mix of `Point` and
+/// `MultiPoint` is written as multipoints. Shapes are written in two dimensions.
+///
+/// Numeric and boolean properties become numeric and logical DBF fields. Other properties
+/// are written to character fields, with arrays and objects serialized as JSON text. DBF
+/// field names are limited to 11 bytes, and longer property names produce an error.
+pub fn to_writer<T: Write + Seek>(
+    collection: &FeatureCollection,
+    shp: T,
+    shx: T,
+    dbf: T,
+) -> Result<(), Error> {
+    let fields = infer_fields(collection);
+    let writer = Writer::new(
+        ShapeWriter::with_shx(shp, shx),
+        table_builder(&fields)?.build_with_dest(dbf),
+    );
+    write_features(collection, &fields, writer)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum FieldKind {
+    Numeric,
+    Logical,
+    Character,
+}
+
+/// Each property's field kind, from its non-null values. Properties whose values are all null,
+/// or of more than one kind, are written as character fields.
+fn infer_fields(collection: &FeatureCollection) -> BTreeMap<String, FieldKind> {
+    let mut fields: BTreeMap<String, Option<FieldKind>> = BTreeMap::new();
+    for (name, value) in collection
+        .features
+        .iter()
+        .filter_map(|feature| feature.properties.as_ref())
+        .flatten()
+    {
+        let entry = fields.entry(name.clone()).or_insert(None);
+        let kind = match value {
+            JsonValue::Null => continue,
+            JsonValue::Number(_) => FieldKind::Numeric,
+            JsonValue::Bool(_) => FieldKind::Logical,
+            _ => FieldKind::Character,
+        };
+        *entry = match *entry {
+            Some(existing) if existing != kind => Some(FieldKind::Character),
+            _ => Some(kind),
+        };
+    }
+    fields
+        .into_iter()
+        .map(|(name, kind)| (name, kind.unwrap_or(FieldKind::Character)))
+        .collect()
+}
+
+fn field_name(name: &str) -> Result<FieldName, Error> {
+    FieldName::try_from(name)
+        .map_err(|e| Error::Shapefile(format!("Invalid DBF field name `{}`: {}", name, e)))
+}
+
+fn table_builder(fields: &BTreeMap<String, FieldKind>) -> Result<TableWriterBuilder, Error> {
+    fields
+        .iter()
+        .try_fold(TableWriterBuilder::new(), |builder, (name, kind)| {
+            let name = field_name(name)?;
+            Ok(match kind {
+                FieldKind::Numeric => builder.add_numeric_field(name, 24, 15),
+                FieldKind::Logical => builder.add_logical_field(name),
+                FieldKind::Character => builder.add_character_field(name, CHARACTER_FIELD_LENGTH),
+            })
+        })
+}
+
+fn record(feature: &Feature, fields: &BTreeMap<String, FieldKind>) -> dbase::Record {
+    let mut record = dbase::Record::default();
+    for (name, kind) in fields {
+        let value = feature.property(name).filter(|value| !value.is_null());
+        let field_value = match kind {
+            FieldKind::Numeric => FieldValue::Numeric(value.and_then(JsonValue::as_f64)),
+            FieldKind::Logical => FieldValue::Logical(value.and_then(JsonValue::as_bool)),
+            FieldKind::Character => FieldValue::Character(value.map(|value| match value {
+                JsonValue::String(s) => s.clone(),
+                other => other.to_string(),
+            })),
+        };
+        record.insert(name.clone(), field_value);
+    }
+    record
+}
+
+enum Shapes {
+    Points(Vec<Point>),
+    Multipoints(Vec<Multipoint>),
+    Polylines(Vec<Polyline>),
+    Polygons(Vec<Polygon>),
+}
+
+fn point(position: &[f64]) -> Result<Point, Error> {
+    match position {
+        [x, y, ..] => Ok(Point::new(*x, *y)),
+        _ => Err(Error::ExpectedF64Value),
+    }
+}
+
+fn points(positions: &[Position]) -> Result<Vec<Point>, Error> {
+    positions.iter().map(|p| point(p)).collect()
+}
+
+fn polyline(lines: &[Vec<Position>]) -> Result<Polyline, Error> {
+    let parts = lines
+        .iter()
+        .map(|line| points(line))
+        .collect::<Result<Vec<_>, _>>()?;
+    if parts.is_empty() || parts.iter().any(|part| part.len() < 2) {
+        return Err(Error::Shapefile(
+            "Polyline parts must have at least 2 positions".to_string(),
+        ));
+    }
+    Ok(Polyline::with_parts(parts))
+}
+
+fn polygon<'a>(polygons: impl Iterator<Item = &'a Vec<Vec<Position>>>) -> Result<Polygon, Error> {
+    let mut rings = vec![];
+    for polygon in polygons {
+        for (i, ring) in polygon.iter().enumerate() {
+            let ring = points(ring)?;
+            rings.push(if i == 0 {
+                PolygonRing::Outer(ring)
+            } else {
+                PolygonRing::Inner(ring)
+            });
+        }
+    }
+    if rings.is_empty() {
+        return Err(Error::Shapefile("Polygons must have a ring".to_string()));
+    }
+    Ok(Polygon::with_rings(rings))
+}
+
+fn collect_shapes(collection: &FeatureCollection) -> Result<Shapes, Error> {
+    let values = collection
+        .features
+        .iter()
+        .map(|feature| match &feature.geometry {
+            Some(geometry) => Ok(&geometry.value),
+            None => Err(Error::FeatureHasNoGeometry(feature.clone())),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let mismatch = |value: &Value, expected: &str| Error::ExpectedType {
+        expected: expected.to_string(),
+        actual: value.type_str().to_string(),
+    };
+
+    match values.first() {
+        None | Some(Value::Point(_)) if values.iter().all(|v| matches!(v, Value::Point(_))) => {
+            values
+                .iter()
+                .map(|value| match value {
+                    Value::Point(p) => point(p),
+                    _ => unreachable!(),
+                })
+                .collect::<Result<_, _>>()
+                .map(Shapes::Points)
+        }
+        Some(Value::Point(_)) | Some(Value::MultiPoint(_)) => values
+            .iter()
+            .map(|value| match value {
+                Value::Point(p) => Ok(Multipoint::new(vec![point(p)?])),
+                Value::MultiPoint(ps) => Ok(Multipoint::new(points(ps)?)),
+                other => Err(mismatch(other, "MultiPoint")),
+            })
+            .collect::<Result<_, _>>()
+            .map(Shapes::Multipoints),
+        Some(Value::LineString(_)) | Some(Value::MultiLineString(_)) => values
+            .iter()
+            .map(|value| match value {
+                Value::LineString(line) => polyline(std::slice::from_ref(line)),
+                Value::MultiLineString(lines) => polyline(lines),
+                other => Err(mismatch(other, "MultiLineString")),
+            })
+            .collect::<Result<_, _>>()
+            .map(Shapes::Polylines),
+        Some(Value::Polygon(_)) | Some(Value::MultiPolygon(_)) => values
+            .iter()
+            .map(|value| match value {
+                Value::Polygon(rings) => polygon(std::iter::once(rings)),
+                Value::MultiPolygon(polygons) => polygon(polygons.iter()),
+                other => Err(mismatch(other, "MultiPolygon")),
+            })
+            .collect::<Result<_, _>>()
+            .map(Shapes::Polygons),
+        Some(other) => Err(Error::Shapefile(format!(
+            "{} geometries cannot be written to a shapefile",
+            other.type_str()
+        ))),
+        None => unreachable!(),
+    }
+}
+
+fn write_features<T: Write + Seek>(
+    collection: &FeatureCollection,
+    fields: &BTreeMap<String, FieldKind>,
+    mut writer: Writer<T>,
+) -> Result<(), Error> {
+    let records = collection
+        .features
+        .iter()
+        .map(|feature| record(feature, fields));
+    match collect_shapes(collection)? {
+        Shapes::Points(shapes) => write_all(&mut writer, shapes, records),
+        Shapes::Multipoints(shapes) => write_all(&mut writer, shapes, records),
+        Shapes::Polylines(shapes) => write_all(&mut writer, shapes, records),
+        Shapes::Polygons(shapes) => write_all(&mut writer, shapes, records),
+    }
+}
+
+fn write_all<T: Write + Seek, S: ::shapefile::record::EsriShape>(
+    writer: &mut Writer<T>,
+    shapes: Vec<S>,
+    records: impl Iterator<Item = dbase::Record>,
+) -> Result<(), Error> {
+    for (shape, record) in shapes.iter().zip(records) {
+        writer
+            .write_shape_and_record(shape, &record)
+            .map_err(shapefile_error)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_reader, to_writer};
+    use crate::json::JsonObject;
+    use crate::{Feature, FeatureCollection, Value};
+    use serde_json::json;
+    use std::io::Cursor;
+
+    fn feature(value: Value, properties: serde_json::Value) -> Feature {
+        let mut feature = Feature::from(value);
+        feature.properties = Some(properties.as_object().unwrap().clone());
+        feature
+    }
+
+    fn collection(features: Vec<Feature>) -> FeatureCollection {
+        FeatureCollection {
+            bbox: None,
+            features,
+            foreign_members: None,
+        }
+    }
+
+    fn roundtrip(collection: &FeatureCollection) -> FeatureCollection {
+        let (mut shp, mut shx, mut dbf) = (
+            Cursor::new(vec![]),
+            Cursor::new(vec![]),
+            Cursor::new(vec![]),
+        );
+        to_writer(collection, &mut shp, &mut shx, &mut dbf).unwrap();
+        let mut reader = ::shapefile::Reader::new(
+            ::shapefile::ShapeReader::new(Cursor::new(shp.into_inner())).unwrap(),
+            ::shapefile::dbase::Reader::new(Cursor::new(dbf.into_inner())).unwrap(),
+        );
+        from_reader(&mut reader).unwrap()
+    }
+
+    #[test]
+    fn polygon_roundtrip() {
+        let ring = vec![
+            position![0.0, 0.0],
+            position![10.0, 0.0],
+            position![10.0, 10.0],
+            position![0.0, 10.0],
+            position![0.0, 0.0],
+        ];
+        let hole = vec![
+            position![2.0, 2.0],
+            position![2.0, 4.0],
+            position![4.0, 4.0],
+            position![4.0, 2.0],
+            position![2.0, 2.0],
+        ];
+        let collection = collection(vec![
+            feature(
+                Value::Polygon(vec![ring.clone(), hole]),
+                json!({"name": "holey", "area": 96, "open": true, "tags": ["a"]}),
+            ),
+            feature(
+                Value::MultiPolygon(vec![vec![ring.clone()], vec![ring]]),
+                json!({"name": null, "area": 1.5}),
+            ),
+        ]);
+
+        let read = roundtrip(&collection);
+        assert_eq!(read.features[0].geometry, collection.features[0].geometry);
+        assert_eq!(read.features[1].geometry, collection.features[1].geometry);
+        let expected: JsonObject =
+            json!({"name": "holey", "area": 96, "open": true, "tags": "[\"a\"]"})
+                .as_object()
+                .unwrap()
+                .clone();
+        assert_eq!(read.features[0].properties, Some(expected));
+        assert_eq!(read.features[1].property("name"), Some(&json!(null)));
+        assert_eq!(read.features[1].property("area"), Some(&json!(1.5)));
+        assert_eq!(read.features[1].property("open"), Some(&json!(null)));
+    }
+
+    #[test]
+    fn points_and_lines() {
+        let points = collection(vec![
+            feature(Value::Point(position![1.0, 2.0]), json!({})),
+            feature(Value::MultiPoint(vec![position![3.0, 4.0]]), json!({})),
+        ]);
+        let read = roundtrip(&points);
+        assert_eq!(
+            read.features[0].geometry.as_ref().unwrap().value,
+            Value::MultiPoint(vec![position![1.0, 2.0]])
+        );
+
+        let line = Value::LineString(vec![position![0.0, 0.0], position![1.0, 1.0]]);
+        let lines = collection(vec![feature(line.clone(), json!({}))]);
+        let read = roundtrip(&lines);
+        assert_eq!(read.features[0].geometry.as_ref().unwrap().value, line);
+    }
+
+    #[test]
+    fn mixed_geometries_error() {
+        let collection = collection(vec![
+            feature(Value::Point(position![1.0, 2.0]), json!({})),
+            feature(
+                Value::LineString(vec![position![0.0, 0.0], position![1.0, 1.0]]),
+                json!({}),
+            ),
+        ]);
+        let (mut shp, mut shx, mut dbf) = (
+            Cursor::new(vec![]),
+            Cursor::new(vec![]),
+            Cursor::new(vec![]),
+        );
+        assert!(to_writer(&collection, &mut shp, &mut shx, &mut dbf).is_err());
+    }
+}
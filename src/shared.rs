@@ -0,0 +1,236 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Feature collections whose features are shared between copies of the collection.
+//!
+//! Cloning a [`FeatureCollection`](../struct.FeatureCollection.html) copies the coordinates and
+//! properties of every feature. A [`SharedFeatureCollection`] keeps each feature in an
+//! `Arc<Feature>`, so cloning it, or building new collections out of some of its features, only
+//! copies pointers, and collections can be handed between the stages of a pipeline or the
+//! threads of a server without copying their features. A feature is only copied when it is
+//! modified through [`feature_mut`](struct.SharedFeatureCollection.html#method.feature_mut)
+//! while another collection still shares it.
+//!
+//! # Example
+//!
+//! ```
+//! use geojson::shared::SharedFeatureCollection;
+//! use std::sync::Arc;
+//!
+//! let json = r#"{
+//!     "type": "FeatureCollection",
+//!     "features": [
+//!         { "type": "Feature", "geometry": null, "properties": { "name": "a" } },
+//!         { "type": "Feature", "geometry": null, "properties": { "name": "b" } }
+//!     ]
+//! }"#;
+//! let collection: SharedFeatureCollection = json.parse().unwrap();
+//!
+//! // A second stage keeps the features named "b", without copying them
+//! let named_b: SharedFeatureCollection = collection
+//!     .features
+//!     .iter()
+//!     .filter(|feature| feature.property("name").unwrap() == "b")
+//!     .cloned()
+//!     .collect();
+//! assert!(Arc::ptr_eq(&named_b.features[0], &collection.features[1]));
+//! ```
+//!
+//! [`SharedFeatureCollection`]: struct.SharedFeatureCollection.html
+
+use crate::errors::Error;
+use crate::json::{Deserialize, Deserializer, JsonObject, JsonValue, Serialize, Serializer};
+use crate::{Bbox, Feature, FeatureCollection};
+use serde::ser::SerializeMap;
+use serde_json::json;
+use std::iter::FromIterator;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// A [`FeatureCollection`](../struct.FeatureCollection.html) of shared features, which
+/// serializes and deserializes as one
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SharedFeatureCollection {
+    pub bbox: Option<Bbox>,
+    pub features: Vec<Arc<Feature>>,
+    pub foreign_members: Option<JsonObject>,
+}
+
+impl SharedFeatureCollection {
+    /// The feature at `index`, which is copied first if another collection shares it, so that
+    /// modifying it doesn't modify the other collection
+    pub fn feature_mut(&mut self, index: usize) -> Option<&mut Feature> {
+        self.features.get_mut(index).map(Arc::make_mut)
+    }
+
+    /// Unwrap the features, to get a `FeatureCollection`. Features which another collection
+    /// still shares are copied.
+    pub fn into_feature_collection(self) -> FeatureCollection {
+        FeatureCollection {
+            bbox: self.bbox,
+            features: self
+                .features
+                .into_iter()
+                .map(|feature| Arc::try_unwrap(feature).unwrap_or_else(|arc| (*arc).clone()))
+                .collect(),
+            foreign_members: self.foreign_members,
+        }
+    }
+}
+
+impl From<FeatureCollection> for SharedFeatureCollection {
+    fn from(collection: FeatureCollection) -> Self {
+        SharedFeatureCollection {
+            bbox: collection.bbox,
+            features: collection.features.into_iter().map(Arc::new).collect(),
+            foreign_members: collection.foreign_members,
+        }
+    }
+}
+
+impl From<SharedFeatureCollection> for FeatureCollection {
+    fn from(collection: SharedFeatureCollection) -> Self {
+        collection.into_feature_collection()
+    }
+}
+
+impl FromIterator<Arc<Feature>> for SharedFeatureCollection {
+    fn from_iter<I: IntoIterator<Item = Arc<Feature>>>(iter: I) -> Self {
+        SharedFeatureCollection {
+            bbox: None,
+            features: iter.into_iter().collect(),
+            foreign_members: None,
+        }
+    }
+}
+
+impl FromIterator<Feature> for SharedFeatureCollection {
+    fn from_iter<I: IntoIterator<Item = Feature>>(iter: I) -> Self {
+        iter.into_iter().map(Arc::new).collect()
+    }
+}
+
+impl IntoIterator for SharedFeatureCollection {
+    type Item = Arc<Feature>;
+    type IntoIter = std::vec::IntoIter<Arc<Feature>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.features.into_iter()
+    }
+}
+
+impl FromStr for SharedFeatureCollection {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let collection: FeatureCollection =
+            serde_json::from_str(s).map_err(Error::MalformedJson)?;
+        Ok(collection.into())
+    }
+}
+
+/// The features of a collection, serialized as a sequence without unwrapping them
+struct Features<'a>(&'a [Arc<Feature>]);
+
+impl Serialize for Features<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.iter().map(|feature| &**feature))
+    }
+}
+
+impl Serialize for SharedFeatureCollection {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[cfg(feature = "compact")]
+        if !serializer.is_human_readable() {
+            return crate::compact::serialize_feature_collection_parts(
+                self.bbox.as_ref(),
+                Features(&self.features),
+                self.foreign_members.as_ref(),
+                serializer,
+            );
+        }
+
+        // The members of a `FeatureCollection`'s object, with the features serialized in
+        // place of the placeholder under "features"
+        let mut object = JsonObject::new();
+        object.insert(String::from("type"), json!("FeatureCollection"));
+        object.insert(String::from("features"), JsonValue::Null);
+        if let Some(ref bbox) = self.bbox {
+            object.insert(String::from("bbox"), json!(bbox));
+        }
+        if let Some(ref foreign_members) = self.foreign_members {
+            for (key, value) in foreign_members {
+                object.insert(key.to_owned(), value.to_owned());
+            }
+        }
+
+        let mut map = serializer.serialize_map(Some(object.len()))?;
+        for (key, value) in &object {
+            if key == "features" {
+                map.serialize_entry(key, &Features(&self.features))?;
+            } else {
+                map.serialize_entry(key, value)?;
+            }
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for SharedFeatureCollection {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        FeatureCollection::deserialize(deserializer).map(SharedFeatureCollection::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SharedFeatureCollection;
+    use crate::{Feature, FeatureCollection, Value};
+    use std::sync::Arc;
+
+    #[test]
+    fn shares_features() {
+        let json = r#"{
+            "type": "FeatureCollection",
+            "bbox": [0, 0, 1, 1],
+            "features": [
+                { "type": "Feature", "geometry": { "type": "Point", "coordinates": [1, 1] }, "properties": { "a": 1 } },
+                { "type": "Feature", "id": "x", "geometry": null, "properties": null }
+            ],
+            "title": "foreign"
+        }"#;
+        let expected: FeatureCollection = serde_json::from_str(json).unwrap();
+        let mut collection: SharedFeatureCollection = json.parse().unwrap();
+        assert_eq!(
+            serde_json::to_string(&collection).unwrap(),
+            serde_json::to_string(&expected).unwrap()
+        );
+        assert_eq!(
+            serde_cbor::to_vec(&collection).unwrap(),
+            serde_cbor::to_vec(&expected).unwrap()
+        );
+
+        let copy = collection.clone();
+        assert!(Arc::ptr_eq(&copy.features[0], &collection.features[0]));
+        collection.feature_mut(0).unwrap().geometry = None;
+        assert!(!Arc::ptr_eq(&copy.features[0], &collection.features[0]));
+        assert!(Arc::ptr_eq(&copy.features[1], &collection.features[1]));
+        assert_eq!(copy.into_feature_collection(), expected);
+
+        let points: SharedFeatureCollection = (0..2)
+            .map(|x| Feature::from(Value::Point(position![x as f64, 0.0])))
+            .collect();
+        assert_eq!(points.into_iter().count(), 2);
+    }
+}
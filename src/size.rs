@@ -0,0 +1,297 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Estimates of the length of the compact JSON which objects serialize to, without
+//! serializing them.
+
+use crate::json::{JsonObject, JsonValue};
+use crate::{feature, Bbox, Feature, FeatureCollection, GeoJson, Geometry, Position, Value};
+
+/// The length of the longest number serialized in exponent notation, e.g.
+/// `-1.2345678901234567e-308`
+const MAX_EXPONENT_NUMBER_LEN: usize = 24;
+
+fn u64_len(mut n: u64) -> usize {
+    let mut len = 1;
+    while n >= 10 {
+        n /= 10;
+        len += 1;
+    }
+    len
+}
+
+/// The length of `x` as written by serde_json, which writes the shortest decimal that parses
+/// back to `x`
+fn f64_len(x: f64) -> usize {
+    if !x.is_finite() {
+        // Written as `null`
+        return 4;
+    }
+    let sign = usize::from(x.is_sign_negative());
+    let x = x.abs();
+    if x == 0.0 {
+        return sign + 3;
+    }
+    if !(1e-5..1e16).contains(&x) {
+        return MAX_EXPONENT_NUMBER_LEN;
+    }
+    let integer_len = u64_len(x.trunc() as u64);
+    if x.fract() == 0.0 {
+        // e.g. `12.0`
+        return sign + integer_len + 2;
+    }
+    let mut scale = 1.0;
+    let mut decimals = 1;
+    while decimals < 22 {
+        scale *= 10.0;
+        if (x * scale).round() / scale == x {
+            break;
+        }
+        decimals += 1;
+    }
+    sign + integer_len + 1 + decimals
+}
+
+fn number_len(number: &serde_json::Number) -> usize {
+    if let Some(n) = number.as_u64() {
+        u64_len(n)
+    } else if let Some(n) = number.as_i64() {
+        1 + u64_len(n.unsigned_abs())
+    } else {
+        number.as_f64().map_or(4, f64_len)
+    }
+}
+
+fn string_len(string: &str) -> usize {
+    let escaped: usize = string
+        .bytes()
+        .map(|byte| match byte {
+            b'"' | b'\\' | b'\n' | b'\r' | b'\t' | 0x08 | 0x0c => 2,
+            0..=0x1f => 6,
+            _ => 1,
+        })
+        .sum();
+    escaped + 2
+}
+
+fn array_len(items: impl IntoIterator<Item = usize>) -> usize {
+    let (count, len) = items
+        .into_iter()
+        .fold((0usize, 0), |(count, len), item| (count + 1, len + item));
+    2 + len + count.saturating_sub(1)
+}
+
+fn object_len<'a>(members: impl IntoIterator<Item = (&'a str, usize)>) -> usize {
+    array_len(
+        members
+            .into_iter()
+            .map(|(key, value)| string_len(key) + 1 + value),
+    )
+}
+
+fn json_object_len(object: &JsonObject) -> usize {
+    object_len(
+        object
+            .iter()
+            .map(|(key, value)| (key.as_str(), json_len(value))),
+    )
+}
+
+fn json_len(value: &JsonValue) -> usize {
+    match value {
+        JsonValue::Null => 4,
+        JsonValue::Bool(true) => 4,
+        JsonValue::Bool(false) => 5,
+        JsonValue::Number(number) => number_len(number),
+        JsonValue::String(string) => string_len(string),
+        JsonValue::Array(values) => array_len(values.iter().map(json_len)),
+        JsonValue::Object(object) => json_object_len(object),
+    }
+}
+
+fn foreign_members_len(
+    foreign_members: &Option<JsonObject>,
+) -> impl Iterator<Item = (&str, usize)> {
+    foreign_members
+        .iter()
+        .flatten()
+        .map(|(key, value)| (key.as_str(), json_len(value)))
+}
+
+fn bbox_len(bbox: &Option<Bbox>) -> Option<(&'static str, usize)> {
+    bbox.as_ref()
+        .map(|bbox| ("bbox", array_len(bbox.iter().copied().map(f64_len))))
+}
+
+fn position_len(position: &Position) -> usize {
+    array_len(position.iter().copied().map(f64_len))
+}
+
+fn positions_len(positions: &[Position]) -> usize {
+    array_len(positions.iter().map(position_len))
+}
+
+fn rings_len(rings: &[Vec<Position>]) -> usize {
+    array_len(rings.iter().map(|ring| positions_len(ring)))
+}
+
+/// The length of the `coordinates` or `geometries` member of `value`
+fn coordinates_len(value: &Value) -> usize {
+    match value {
+        Value::Point(position) => position_len(position),
+        Value::MultiPoint(positions) | Value::LineString(positions) => positions_len(positions),
+        Value::MultiLineString(rings) | Value::Polygon(rings) => rings_len(rings),
+        Value::MultiPolygon(polygons) => array_len(polygons.iter().map(|rings| rings_len(rings))),
+        Value::GeometryCollection(geometries) => {
+            array_len(geometries.iter().map(Geometry::serialized_size_hint))
+        }
+    }
+}
+
+impl Geometry {
+    /// An estimate of the length in bytes of the compact JSON this geometry serializes to,
+    /// computed without serializing it.
+    ///
+    /// This is exact, except that numbers outside of `1e-5..1e16` are counted at their
+    /// longest, so it may be used to reserve a buffer, or to turn away a payload over a size
+    /// limit before it is written.
+    ///
+    /// ```
+    /// use geojson::{position, Geometry, Value};
+    ///
+    /// let geometry = Geometry::new(Value::Point(position![1.5, -2.25]));
+    /// assert_eq!(geometry.serialized_size_hint(), geometry.to_string().len());
+    /// ```
+    pub fn serialized_size_hint(&self) -> usize {
+        let coordinates = match self.value {
+            Value::GeometryCollection(_) => "geometries",
+            _ => "coordinates",
+        };
+        object_len(
+            IntoIterator::into_iter([
+                ("type", string_len(self.value.type_str())),
+                (coordinates, coordinates_len(&self.value)),
+            ])
+            .chain(bbox_len(&self.bbox))
+            .chain(foreign_members_len(&self.foreign_members)),
+        )
+    }
+}
+
+impl Feature {
+    /// An estimate of the length in bytes of the compact JSON this feature serializes to. See
+    /// [`Geometry::serialized_size_hint`](struct.Geometry.html#method.serialized_size_hint).
+    pub fn serialized_size_hint(&self) -> usize {
+        let id = self.id.as_ref().map(|id| {
+            let len = match id {
+                feature::Id::String(string) => string_len(string),
+                feature::Id::Number(number) => number_len(number),
+            };
+            ("id", len)
+        });
+        object_len(
+            IntoIterator::into_iter([
+                ("type", string_len("Feature")),
+                (
+                    "geometry",
+                    self.geometry
+                        .as_ref()
+                        .map_or(4, Geometry::serialized_size_hint),
+                ),
+                (
+                    "properties",
+                    self.properties.as_ref().map_or(2, json_object_len),
+                ),
+            ])
+            .chain(bbox_len(&self.bbox))
+            .chain(id)
+            .chain(foreign_members_len(&self.foreign_members)),
+        )
+    }
+}
+
+impl FeatureCollection {
+    /// An estimate of the length in bytes of the compact JSON this collection serializes to.
+    /// See [`Geometry::serialized_size_hint`](struct.Geometry.html#method.serialized_size_hint).
+    pub fn serialized_size_hint(&self) -> usize {
+        object_len(
+            IntoIterator::into_iter([
+                ("type", string_len("FeatureCollection")),
+                (
+                    "features",
+                    array_len(self.features.iter().map(Feature::serialized_size_hint)),
+                ),
+            ])
+            .chain(bbox_len(&self.bbox))
+            .chain(foreign_members_len(&self.foreign_members)),
+        )
+    }
+}
+
+impl GeoJson {
+    /// An estimate of the length in bytes of the compact JSON this object serializes to. See
+    /// [`Geometry::serialized_size_hint`](struct.Geometry.html#method.serialized_size_hint).
+    pub fn serialized_size_hint(&self) -> usize {
+        match self {
+            GeoJson::Geometry(geometry) => geometry.serialized_size_hint(),
+            GeoJson::Feature(feature) => feature.serialized_size_hint(),
+            GeoJson::FeatureCollection(collection) => collection.serialized_size_hint(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::f64_len;
+    use crate::GeoJson;
+
+    #[test]
+    fn number_lengths() {
+        for x in [
+            0.0,
+            -0.0,
+            1.0,
+            -12.0,
+            0.1,
+            0.3,
+            2.349014,
+            -48.864716,
+            1e-5,
+            123456.789,
+            1e15,
+            0.1 + 0.2,
+        ] {
+            assert_eq!(
+                f64_len(x),
+                serde_json::to_string(&x).unwrap().len(),
+                "{}",
+                x
+            );
+        }
+        assert!(f64_len(1.5e-7) >= serde_json::to_string(&1.5e-7).unwrap().len());
+    }
+
+    #[test]
+    fn same_as_serialized_length() {
+        let fixtures = [
+            include_str!("../tests/fixtures/countries.geojson"),
+            r#"{"type":"Feature","id":"a\"b\n\u0001","geometry":null,"properties":null,"bbox":[0,0,1,1],"x":[true,false,null,{"y":-3}]}"#,
+            r#"{"type":"GeometryCollection","geometries":[{"type":"MultiPolygon","coordinates":[[[[0,0],[1,0],[0.5,0.5],[0,0]]]]}]}"#,
+        ];
+        for json in fixtures {
+            let geojson: GeoJson = json.parse().unwrap();
+            assert_eq!(geojson.serialized_size_hint(), geojson.to_string().len());
+        }
+    }
+}
@@ -0,0 +1,198 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Statistics of the values of the properties of features.
+//!
+//! A [`Summary`] is built up one feature at a time, like a [`Schema`](../schema/struct.Schema.html),
+//! so it can be computed for a [`FeatureCollection`](../struct.FeatureCollection.html) with
+//! [`summarize_properties`](../struct.FeatureCollection.html#method.summarize_properties), or
+//! for a stream of features. For every property it records the range and mean of its numeric
+//! values, how often each of its string values occurs, and how often it is `null`: what is
+//! needed to choose the class breaks of a map style, or to spot gaps and typos in the data.
+//!
+//! # Example
+//!
+//! ```
+//! use geojson::geojsonl::LineDelimitedReader;
+//! use geojson::summary::Summary;
+//!
+//! let input = r#"{"type":"Feature","geometry":null,"properties":{"kind":"oak","height":3}}
+//! {"type":"Feature","geometry":null,"properties":{"kind":"elm","height":null}}
+//! {"type":"Feature","geometry":null,"properties":{"kind":"oak","height":4.5}}
+//! "#;
+//! let mut summary = Summary::new();
+//! for feature in LineDelimitedReader::new(input.as_bytes()).features() {
+//!     summary.add_feature(&feature.unwrap());
+//! }
+//!
+//! let height = &summary.properties["height"];
+//! assert_eq!((height.min, height.max, height.mean()), (Some(3.0), Some(4.5), Some(3.75)));
+//! assert_eq!(height.nulls, 1);
+//! assert_eq!(summary.properties["kind"].strings["oak"], 2);
+//! ```
+//!
+//! [`Summary`]: struct.Summary.html
+
+use crate::json::JsonValue;
+use crate::{Feature, FeatureCollection};
+use std::collections::BTreeMap;
+use std::iter::FromIterator;
+
+/// Statistics of the values of one property from the features seen
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PropertySummary {
+    /// The number of features which have the property, including with `null`
+    pub count: usize,
+    /// The number of features whose value is `null`
+    pub nulls: usize,
+    /// The number of numeric values
+    pub numbers: usize,
+    /// The least numeric value, or `None` if there are none
+    pub min: Option<f64>,
+    /// The greatest numeric value, or `None` if there are none
+    pub max: Option<f64>,
+    /// The sum of the numeric values
+    pub sum: f64,
+    /// The number of features with each distinct string value.
+    ///
+    /// Every distinct string is kept, so this grows with properties such as names or
+    /// identifiers, which have a value of their own for most features.
+    pub strings: BTreeMap<String, usize>,
+}
+
+impl PropertySummary {
+    fn add(&mut self, value: &JsonValue) {
+        self.count += 1;
+        match value {
+            JsonValue::Null => self.nulls += 1,
+            JsonValue::Number(number) => {
+                if let Some(x) = number.as_f64() {
+                    self.numbers += 1;
+                    self.min = Some(self.min.map_or(x, |min| min.min(x)));
+                    self.max = Some(self.max.map_or(x, |max| max.max(x)));
+                    self.sum += x;
+                }
+            }
+            JsonValue::String(string) => {
+                if let Some(count) = self.strings.get_mut(string) {
+                    *count += 1;
+                } else {
+                    self.strings.insert(string.clone(), 1);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The mean of the numeric values, or `None` if there are none
+    pub fn mean(&self) -> Option<f64> {
+        if self.numbers == 0 {
+            None
+        } else {
+            Some(self.sum / self.numbers as f64)
+        }
+    }
+}
+
+/// Statistics of the properties of the features seen, by name
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Summary {
+    /// The number of features seen
+    pub features: usize,
+    pub properties: BTreeMap<String, PropertySummary>,
+}
+
+impl Summary {
+    pub fn new() -> Self {
+        Summary::default()
+    }
+
+    /// Add the values of the properties of `feature`
+    pub fn add_feature(&mut self, feature: &Feature) {
+        self.features += 1;
+        for (key, value) in feature.properties_iter() {
+            if let Some(property) = self.properties.get_mut(key) {
+                property.add(value);
+            } else {
+                let mut property = PropertySummary::default();
+                property.add(value);
+                self.properties.insert(key.clone(), property);
+            }
+        }
+    }
+
+    /// The number of features seen whose property `key` is `null` or missing
+    pub fn missing(&self, key: &str) -> usize {
+        self.properties.get(key).map_or(self.features, |property| {
+            self.features - property.count + property.nulls
+        })
+    }
+}
+
+impl<'a> FromIterator<&'a Feature> for Summary {
+    fn from_iter<I: IntoIterator<Item = &'a Feature>>(features: I) -> Self {
+        let mut summary = Summary::new();
+        for feature in features {
+            summary.add_feature(feature);
+        }
+        summary
+    }
+}
+
+impl FeatureCollection {
+    /// Compute statistics of the values of the properties of the features. See the
+    /// [`summary`](summary/index.html) module.
+    pub fn summarize_properties(&self) -> Summary {
+        self.features.iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FeatureCollection;
+    use serde_json::json;
+
+    #[test]
+    fn summarize_properties() {
+        let collection: FeatureCollection = serde_json::from_value(json!({
+            "type": "FeatureCollection",
+            "features": [
+                { "type": "Feature", "geometry": null, "properties": { "lanes": 2, "surface": "asphalt" } },
+                { "type": "Feature", "geometry": null, "properties": { "lanes": -1, "surface": "gravel" } },
+                { "type": "Feature", "geometry": null, "properties": null },
+                { "type": "Feature", "geometry": null, "properties": { "lanes": "4", "surface": "asphalt" } },
+                { "type": "Feature", "geometry": null, "properties": { "lanes": null, "surface": null } },
+                { "type": "Feature", "geometry": null, "properties": { "lanes": 5.5, "surface": ["asphalt"] } }
+            ]
+        }))
+        .unwrap();
+        let summary = collection.summarize_properties();
+
+        assert_eq!(summary.features, 6);
+        let lanes = &summary.properties["lanes"];
+        assert_eq!((lanes.count, lanes.nulls, lanes.numbers), (5, 1, 3));
+        assert_eq!((lanes.min, lanes.max), (Some(-1.0), Some(5.5)));
+        assert_eq!(lanes.mean(), Some(6.5 / 3.0));
+        assert_eq!(lanes.strings["4"], 1);
+
+        let surface = &summary.properties["surface"];
+        assert_eq!(surface.mean(), None);
+        assert_eq!(
+            surface.strings.iter().collect::<Vec<_>>(),
+            [(&"asphalt".to_string(), &2), (&"gravel".to_string(), &1)]
+        );
+        assert_eq!(summary.missing("surface"), 2);
+        assert_eq!(summary.missing("width"), 6);
+    }
+}
@@ -0,0 +1,323 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Splitting features into the tiles of the
+//! [web mercator tiling scheme](https://wiki.openstreetmap.org/wiki/Slippy_map_tilenames)
+//!
+//! Tiles are addressed by zoom level, column and row, counted from the north-west corner, or
+//! by their [quadkey](https://learn.microsoft.com/en-us/bingmaps/articles/bing-maps-tile-system).
+//! Coordinates are interpreted as WGS 84 longitude and latitude, and latitudes beyond
+//! ±85.0511°, the edge of the projection, fall into the northern- and southernmost tiles.
+//!
+//! # Example
+//!
+//! ```
+//! use geojson::tiles::Tile;
+//! use geojson::{position, Feature, FeatureCollection, Value};
+//!
+//! let collection = FeatureCollection {
+//!     bbox: None,
+//!     features: vec![Feature::from(Value::LineString(vec![
+//!         position![-10.0, 10.0],
+//!         position![10.0, 10.0],
+//!     ]))],
+//!     foreign_members: None,
+//! };
+//!
+//! let tiles = collection.split_into_tiles(1).unwrap();
+//! let keys: Vec<String> = tiles.keys().map(Tile::to_string).collect();
+//! assert_eq!(keys, ["1/0/0", "1/1/0"]);
+//! assert_eq!(Tile::new(1, 1, 0).unwrap().quadkey(), "1");
+//! ```
+
+use crate::algorithm::bounding_rect;
+use crate::errors::Error;
+use crate::{Feature, FeatureCollection};
+use std::collections::BTreeMap;
+use std::f64::consts::PI;
+use std::fmt;
+
+/// The deepest supported zoom level, at which tiles are a few centimetres across
+pub const MAX_ZOOM: u8 = 30;
+
+/// The latitude of the northern edge of the web mercator projection
+const MAX_LATITUDE: f64 = 85.051_128_779_806_59;
+
+/// A web mercator tile, ordered by zoom level, then column, then row
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Tile {
+    pub z: u8,
+    /// The column, counted eastwards from the antimeridian
+    pub x: u32,
+    /// The row, counted southwards from the northern edge
+    pub y: u32,
+}
+
+fn check_zoom(z: u8) -> Result<(), Error> {
+    if z > MAX_ZOOM {
+        Err(Error::TileInvalidZoom(z))
+    } else {
+        Ok(())
+    }
+}
+
+/// The column containing longitude `x` at zoom level `z`
+fn column(x: f64, z: u8) -> u32 {
+    let n = (1u64 << z) as f64;
+    (((x + 180.0) / 360.0 * n).floor().max(0.0) as u64).min((1 << z) - 1) as u32
+}
+
+/// The row containing latitude `y` at zoom level `z`
+fn row(y: f64, z: u8) -> u32 {
+    let n = (1u64 << z) as f64;
+    let y = y.clamp(-MAX_LATITUDE, MAX_LATITUDE).to_radians();
+    let row = (1.0 - y.tan().asinh() / PI) / 2.0 * n;
+    (row.floor().max(0.0) as u64).min((1 << z) - 1) as u32
+}
+
+/// The latitude of the northern edge of row `y` at zoom level `z`
+fn latitude(y: u64, z: u8) -> f64 {
+    let n = (1u64 << z) as f64;
+    (PI * (1.0 - 2.0 * y as f64 / n)).sinh().atan().to_degrees()
+}
+
+impl Tile {
+    /// The tile at column `x` and row `y` of zoom level `z`
+    pub fn new(z: u8, x: u32, y: u32) -> Result<Tile, Error> {
+        check_zoom(z)?;
+        let tile = Tile { z, x, y };
+        if u64::from(x) >> z != 0 || u64::from(y) >> z != 0 {
+            return Err(Error::InvalidTile(tile.to_string()));
+        }
+        Ok(tile)
+    }
+
+    /// The tile of zoom level `z` containing a longitude/latitude pair
+    pub fn containing(x: f64, y: f64, z: u8) -> Result<Tile, Error> {
+        check_zoom(z)?;
+        if !(-180.0..=180.0).contains(&x) || !(-90.0..=90.0).contains(&y) {
+            return Err(Error::GeohashCoordinateOutOfRange(x, y));
+        }
+        Ok(Tile {
+            z,
+            x: column(x, z),
+            y: row(y, z),
+        })
+    }
+
+    /// The `[min_x, min_y, max_x, max_y]` extent of this tile in longitude and latitude
+    pub fn bbox(&self) -> [f64; 4] {
+        let n = (1u64 << self.z) as f64;
+        let (x, y) = (u64::from(self.x), u64::from(self.y));
+        [
+            x as f64 / n * 360.0 - 180.0,
+            latitude(y + 1, self.z),
+            (x + 1) as f64 / n * 360.0 - 180.0,
+            latitude(y, self.z),
+        ]
+    }
+
+    /// The quadkey of this tile, with a digit per zoom level, or `""` at zoom level 0
+    pub fn quadkey(&self) -> String {
+        (1..=self.z)
+            .rev()
+            .map(|bit| {
+                let digit = ((self.x >> (bit - 1)) & 1) | (((self.y >> (bit - 1)) & 1) << 1);
+                char::from(b'0' + digit as u8)
+            })
+            .collect()
+    }
+
+    /// The tile of a quadkey, such as `"0231"`
+    pub fn from_quadkey(quadkey: &str) -> Result<Tile, Error> {
+        if quadkey.len() > usize::from(MAX_ZOOM) {
+            return Err(Error::InvalidTile(quadkey.to_string()));
+        }
+        let mut tile = Tile {
+            z: quadkey.len() as u8,
+            x: 0,
+            y: 0,
+        };
+        for c in quadkey.chars() {
+            let digit = c
+                .to_digit(4)
+                .ok_or_else(|| Error::InvalidTile(quadkey.to_string()))?;
+            tile.x = (tile.x << 1) | (digit & 1);
+            tile.y = (tile.y << 1) | (digit >> 1);
+        }
+        Ok(tile)
+    }
+}
+
+impl fmt::Display for Tile {
+    /// Write the tile as `z/x/y`, as in the paths of tile servers
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}/{}", self.z, self.x, self.y)
+    }
+}
+
+/// Pass each tile of zoom level `z` which `feature` intersects to `add`, with the feature, or
+/// the part of it inside the tile if `clip` is set
+fn split_feature(feature: &Feature, z: u8, clip: bool, mut add: impl FnMut(Tile, Feature)) {
+    let geometry = match &feature.geometry {
+        Some(geometry) => geometry,
+        None => return,
+    };
+    let rect = match bounding_rect(&geometry.value) {
+        Some(rect) => rect,
+        None => return,
+    };
+    for x in column(rect.min_x, z)..=column(rect.max_x, z) {
+        for y in row(rect.max_y, z)..=row(rect.min_y, z) {
+            let tile = Tile { z, x, y };
+            if clip {
+                if let Some(clipped) = feature.clip_to_bbox(tile.bbox()) {
+                    add(tile, clipped);
+                }
+            } else if feature.intersects_bbox(tile.bbox()) {
+                add(tile, feature.clone());
+            }
+        }
+    }
+}
+
+impl FeatureCollection {
+    /// Split this collection into a collection per web mercator tile of zoom level `z`, each
+    /// with the features which intersect that tile, in order.
+    ///
+    /// Features are copied whole into every tile they intersect, and features without a
+    /// geometry are left out. The collections have the foreign members of this collection,
+    /// and no `bbox`. Tiles without features are left out, but a feature spanning a large
+    /// extent at a deep zoom level may still intersect a great number of tiles.
+    ///
+    /// See the [`tiles`](tiles/index.html) module.
+    pub fn split_into_tiles(&self, z: u8) -> Result<BTreeMap<Tile, FeatureCollection>, Error> {
+        self.split(z, false)
+    }
+
+    /// Split this collection into a collection per web mercator tile of zoom level `z`, with
+    /// the features which intersect that tile clipped to its extent.
+    ///
+    /// The clipped features have no `bbox`. See
+    /// [`split_into_tiles`](#method.split_into_tiles) and
+    /// [`Feature::clip_to_bbox`](struct.Feature.html#method.clip_to_bbox).
+    pub fn split_into_clipped_tiles(
+        &self,
+        z: u8,
+    ) -> Result<BTreeMap<Tile, FeatureCollection>, Error> {
+        self.split(z, true)
+    }
+
+    fn split(&self, z: u8, clip: bool) -> Result<BTreeMap<Tile, FeatureCollection>, Error> {
+        check_zoom(z)?;
+        let mut tiles = BTreeMap::new();
+        for feature in &self.features {
+            split_feature(feature, z, clip, |tile, feature| {
+                tiles
+                    .entry(tile)
+                    .or_insert_with(|| FeatureCollection {
+                        bbox: None,
+                        features: vec![],
+                        foreign_members: self.foreign_members.clone(),
+                    })
+                    .features
+                    .push(feature)
+            });
+        }
+        Ok(tiles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Tile;
+    use crate::{Feature, FeatureCollection, Value};
+
+    #[test]
+    fn tile_addresses() {
+        let tile = Tile::containing(-0.1276, 51.5072, 10).unwrap();
+        assert_eq!(tile, Tile::new(10, 511, 340).unwrap());
+        assert_eq!(tile.quadkey(), "0313131311");
+        assert_eq!(Tile::from_quadkey("0313131311").unwrap(), tile);
+        let [min_x, min_y, max_x, max_y] = tile.bbox();
+        assert!(min_x <= -0.1276 && -0.1276 < max_x && min_y <= 51.5072 && 51.5072 < max_y);
+
+        assert_eq!(
+            Tile::containing(180.0, 90.0, 2).unwrap().to_string(),
+            "2/3/0"
+        );
+        let world = Tile::from_quadkey("").unwrap().bbox();
+        assert!((world[3] - super::MAX_LATITUDE).abs() < 1e-9);
+        assert!(Tile::new(2, 4, 0).is_err());
+        assert!(Tile::new(31, 0, 0).is_err());
+        assert!(Tile::from_quadkey("0124").is_err());
+    }
+
+    #[test]
+    fn split_collections() {
+        let polygon = Value::Polygon(vec![vec![
+            position![-10.0, -10.0],
+            position![10.0, -10.0],
+            position![10.0, 5.0],
+            position![-10.0, -10.0],
+        ]]);
+        let collection = FeatureCollection {
+            bbox: None,
+            features: vec![
+                Feature::from(polygon),
+                Feature::from(Value::Point(position![100.0, 45.0])),
+                Feature {
+                    bbox: None,
+                    geometry: None,
+                    id: None,
+                    properties: None,
+                    foreign_members: None,
+                },
+            ],
+            foreign_members: None,
+        };
+
+        // The triangle misses the north-western quarter around the origin
+        let tiles = collection.split_into_tiles(1).unwrap();
+        let counts: Vec<_> = tiles
+            .iter()
+            .map(|(tile, tile_collection)| (tile.to_string(), tile_collection.features.len()))
+            .collect();
+        assert_eq!(
+            counts,
+            [
+                ("1/0/1".to_string(), 1),
+                ("1/1/0".to_string(), 2),
+                ("1/1/1".to_string(), 1)
+            ]
+        );
+        assert_eq!(
+            tiles.values().next().unwrap().features[0],
+            collection.features[0]
+        );
+
+        let clipped = collection.split_into_clipped_tiles(1).unwrap();
+        assert_eq!(clipped.len(), 3);
+        let tile = Tile::new(1, 1, 1).unwrap();
+        let [min_x, min_y, max_x, max_y] = tile.bbox();
+        let bbox = clipped[&tile].features[0]
+            .geometry
+            .as_ref()
+            .unwrap()
+            .compute_bbox();
+        let bbox = bbox.unwrap();
+        assert!(bbox[0] >= min_x && bbox[1] >= min_y && bbox[2] <= max_x && bbox[3] <= max_y);
+        assert!(collection.split_into_tiles(31).is_err());
+    }
+}
@@ -0,0 +1,114 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! RFC 3339 datetime properties as [`time`](https://docs.rs/time) datetimes.
+
+use crate::errors::Error;
+use crate::Feature;
+use ::time::format_description::well_known::Rfc3339;
+use ::time::OffsetDateTime;
+
+impl Feature {
+    /// The property `key` parsed as an [RFC 3339](https://tools.ietf.org/html/rfc3339)
+    /// datetime, such as `"2024-05-01T12:30:00+02:00"`, keeping its offset, or `None` if it
+    /// isn't set or is `null`.
+    ///
+    /// # Errors
+    ///
+    /// If the property is set to anything other than `null` or an RFC 3339 string.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geojson::{position, Feature, Value};
+    /// use time::macros::datetime;
+    ///
+    /// let mut feature = Feature::from(Value::Point(position![1.0, 2.0]));
+    /// feature.set_property("observed", "2024-05-01T12:30:00+02:00");
+    ///
+    /// let observed = feature.offset_datetime_property("observed").unwrap().unwrap();
+    /// assert_eq!(observed, datetime!(2024-05-01 10:30 UTC));
+    /// assert_eq!(feature.offset_datetime_property("updated").unwrap(), None);
+    /// ```
+    pub fn offset_datetime_property(
+        &self,
+        key: impl AsRef<str>,
+    ) -> Result<Option<OffsetDateTime>, Error> {
+        let key = key.as_ref();
+        let value = match self.property(key) {
+            Some(value) if !value.is_null() => value,
+            _ => return Ok(None),
+        };
+        value
+            .as_str()
+            .and_then(|value| OffsetDateTime::parse(value, &Rfc3339).ok())
+            .map(Some)
+            .ok_or_else(|| Error::InvalidDateTimeProperty(key.to_string(), value.clone()))
+    }
+
+    /// Set the property `key` to `datetime` as an
+    /// [RFC 3339](https://tools.ietf.org/html/rfc3339) string, with its offset.
+    ///
+    /// # Errors
+    ///
+    /// If RFC 3339 can't represent `datetime`, as its year is before 0 or after 9999, or its
+    /// offset has seconds, in which case the property is unchanged.
+    pub fn set_offset_datetime_property(
+        &mut self,
+        key: impl Into<String>,
+        datetime: OffsetDateTime,
+    ) -> Result<(), Error> {
+        let formatted = datetime
+            .format(&Rfc3339)
+            .map_err(|err| Error::UnformattableDateTime(err.to_string()))?;
+        self.set_property(key, formatted);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Feature, Value};
+    use time::macros::datetime;
+
+    #[test]
+    fn offset_datetime_properties() {
+        let mut feature = Feature::from(Value::Point(position![0.0, 0.0]));
+        let datetime = datetime!(2020-02-29 23:59:59.25 -05:00);
+        feature.set_offset_datetime_property("a", datetime).unwrap();
+        assert_eq!(
+            feature.property("a").unwrap(),
+            "2020-02-29T23:59:59.25-05:00"
+        );
+        assert_eq!(
+            feature.offset_datetime_property("a").unwrap(),
+            Some(datetime)
+        );
+        assert!(feature
+            .set_offset_datetime_property("b", datetime!(2020-01-01 0:00 +01:00:30))
+            .is_err());
+        assert!(!feature.contains_property("b"));
+
+        feature.set_property("c", serde_json::Value::Null);
+        assert_eq!(feature.offset_datetime_property("c").unwrap(), None);
+        for invalid in [
+            serde_json::json!("2020-02-30T00:00:00Z"),
+            serde_json::json!("yesterday"),
+            serde_json::json!(1_582_934_399),
+        ] {
+            feature.set_property("d", invalid);
+            assert!(feature.offset_datetime_property("d").is_err());
+        }
+    }
+}
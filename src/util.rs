@@ -15,6 +15,8 @@
 use crate::errors::Error;
 use crate::json::{JsonObject, JsonValue};
 use crate::{feature, Bbox, Feature, Geometry, Position, Value};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 
 pub fn expect_type(value: &mut JsonObject) -> Result<String, Error> {
     let prop = expect_property(value, "type")?;
@@ -101,6 +103,42 @@ pub fn get_foreign_members(object: JsonObject) -> Result<Option<JsonObject>, Err
     }
 }
 
+/// The foreign member `key`, if it is present and not `null`
+#[cfg(feature = "std")]
+pub fn foreign_member<'a>(
+    foreign_members: &'a Option<JsonObject>,
+    key: &str,
+) -> Option<&'a JsonValue> {
+    foreign_members
+        .as_ref()?
+        .get(key)
+        .filter(|value| !value.is_null())
+}
+
+/// Insert the foreign member `key`, or remove it if `value` is `None`
+#[cfg(feature = "std")]
+pub fn set_foreign_member(
+    foreign_members: &mut Option<JsonObject>,
+    key: &str,
+    value: Option<JsonValue>,
+) {
+    match value {
+        Some(value) => {
+            foreign_members
+                .get_or_insert_with(JsonObject::new)
+                .insert(key.to_string(), value);
+        }
+        None => {
+            if let Some(members) = foreign_members {
+                members.remove(key);
+                if members.is_empty() {
+                    *foreign_members = None;
+                }
+            }
+        }
+    }
+}
+
 /// Used by Feature
 pub fn get_properties(object: &mut JsonObject) -> Result<Option<JsonObject>, Error> {
     let properties = expect_property(object, "properties")?;
@@ -207,16 +245,16 @@ pub fn get_features(object: &mut JsonObject) -> Result<Vec<Feature>, Error> {
     Ok(features)
 }
 
-fn json_to_position(json: &JsonValue) -> Result<Position, Error> {
+pub fn json_to_position(json: &JsonValue) -> Result<Position, Error> {
     let coords_array = expect_array(json)?;
-    let mut coords = Vec::with_capacity(coords_array.len());
+    let mut coords = Position::with_capacity(coords_array.len());
     for position in coords_array {
         coords.push(expect_f64(position)?);
     }
     Ok(coords)
 }
 
-fn json_to_1d_positions(json: &JsonValue) -> Result<Vec<Position>, Error> {
+pub fn json_to_1d_positions(json: &JsonValue) -> Result<Vec<Position>, Error> {
     let coords_array = expect_array(json)?;
     let mut coords = Vec::with_capacity(coords_array.len());
     for item in coords_array {
@@ -225,7 +263,7 @@ fn json_to_1d_positions(json: &JsonValue) -> Result<Vec<Position>, Error> {
     Ok(coords)
 }
 
-fn json_to_2d_positions(json: &JsonValue) -> Result<Vec<Vec<Position>>, Error> {
+pub fn json_to_2d_positions(json: &JsonValue) -> Result<Vec<Vec<Position>>, Error> {
     let coords_array = expect_array(json)?;
     let mut coords = Vec::with_capacity(coords_array.len());
     for item in coords_array {
@@ -234,7 +272,7 @@ fn json_to_2d_positions(json: &JsonValue) -> Result<Vec<Vec<Position>>, Error> {
     Ok(coords)
 }
 
-fn json_to_3d_positions(json: &JsonValue) -> Result<Vec<Vec<Vec<Position>>>, Error> {
+pub fn json_to_3d_positions(json: &JsonValue) -> Result<Vec<Vec<Vec<Position>>>, Error> {
     let coords_array = expect_array(json)?;
     let mut coords = Vec::with_capacity(coords_array.len());
     for item in coords_array {
@@ -0,0 +1,134 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversions between feature ids and [`uuid::Uuid`](https://docs.rs/uuid)s.
+//!
+//! A UUID id is a string in the hyphenated form, such as
+//! `"67e55044-10b1-426f-9247-bb680e5fe0c8"`.
+
+use crate::errors::Error;
+use crate::feature::Id;
+use crate::FeatureCollection;
+use ::uuid::Uuid;
+use std::convert::TryFrom;
+
+impl From<Uuid> for Id {
+    /// The id of `uuid` in the lowercase hyphenated form
+    fn from(uuid: Uuid) -> Self {
+        Id::String(uuid.hyphenated().to_string())
+    }
+}
+
+impl TryFrom<&Id> for Uuid {
+    type Error = Error;
+
+    /// The UUID of a string id, in any of the forms which
+    /// [`Uuid::parse_str`](https://docs.rs/uuid/1/uuid/struct.Uuid.html#method.parse_str)
+    /// accepts
+    fn try_from(id: &Id) -> Result<Self, Self::Error> {
+        match id {
+            Id::String(id) => Uuid::parse_str(id).map_err(|_| Error::IdNotUuid(id.clone())),
+            Id::Number(id) => Err(Error::IdNotUuid(id.to_string())),
+        }
+    }
+}
+
+impl TryFrom<Id> for Uuid {
+    type Error = Error;
+
+    fn try_from(id: Id) -> Result<Self, Self::Error> {
+        Uuid::try_from(&id)
+    }
+}
+
+impl FeatureCollection {
+    /// Give each feature without an `id` a new random (version 4) UUID, returning how many
+    /// features were given one.
+    ///
+    /// Features which already have an id, whether or not it is a UUID, are unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geojson::{position, Feature, FeatureCollection, Value};
+    /// use std::convert::TryFrom;
+    /// use uuid::Uuid;
+    ///
+    /// let mut collection = FeatureCollection {
+    ///     bbox: None,
+    ///     features: vec![Feature::from(Value::Point(position![1.0, 2.0]))],
+    ///     foreign_members: None,
+    /// };
+    /// assert_eq!(collection.assign_uuids(), 1);
+    /// assert_eq!(collection.assign_uuids(), 0);
+    ///
+    /// let id = collection.features[0].id.as_ref().unwrap();
+    /// assert_eq!(Uuid::try_from(id).unwrap().get_version_num(), 4);
+    /// ```
+    pub fn assign_uuids(&mut self) -> usize {
+        let mut assigned = 0;
+        for feature in self
+            .features
+            .iter_mut()
+            .filter(|feature| feature.id.is_none())
+        {
+            feature.id = Some(Uuid::new_v4().into());
+            assigned += 1;
+        }
+        assigned
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::feature::Id;
+    use crate::{Feature, FeatureCollection, Value};
+    use std::convert::TryFrom;
+    use uuid::Uuid;
+
+    #[test]
+    fn uuid_ids() {
+        let uuid = Uuid::parse_str("67E55044-10B1-426F-9247-BB680E5FE0C8").unwrap();
+        let id = Id::from(uuid);
+        assert_eq!(
+            id,
+            Id::String("67e55044-10b1-426f-9247-bb680e5fe0c8".to_string())
+        );
+        assert_eq!(Uuid::try_from(&id).unwrap(), uuid);
+        assert_eq!(
+            Uuid::try_from(Id::String("67e5504410b1426f9247bb680e5fe0c8".to_string())).unwrap(),
+            uuid
+        );
+        assert!(Uuid::try_from(Id::String("67e55044".to_string())).is_err());
+        assert!(Uuid::try_from(Id::Number(7.into())).is_err());
+
+        let point = || Feature::from(Value::Point(position![0.0, 0.0]));
+        let mut named = point();
+        named.id = Some(Id::Number(7.into()));
+        let mut collection = FeatureCollection {
+            bbox: None,
+            features: vec![point(), named, point()],
+            foreign_members: None,
+        };
+        assert_eq!(collection.assign_uuids(), 2);
+        let ids: Vec<_> = collection
+            .features
+            .iter()
+            .map(|feature| feature.id.clone().unwrap())
+            .collect();
+        assert_eq!(ids[1], Id::Number(7.into()));
+        assert_ne!(ids[0], ids[2]);
+        assert!(Uuid::try_from(&ids[0]).is_ok() && Uuid::try_from(&ids[2]).is_ok());
+    }
+}
@@ -0,0 +1,402 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Views of GeoJSON objects which borrow their members from objects parsed before.
+//!
+//! A [`FeatureRef`] has a reference to each member of a feature, and a
+//! [`FeatureCollectionRef`] has a `FeatureRef` for each of its features, so a function can
+//! return some of the features of a collection, or a feature with its properties replaced,
+//! without copying any coordinates. A [`GeoJsonRef`] is any of a geometry, feature or feature
+//! collection. Each serializes, and displays, exactly as the object with the same members
+//! does.
+//!
+//! # Example
+//!
+//! ```
+//! use geojson::view::FeatureCollectionRef;
+//! use geojson::FeatureCollection;
+//!
+//! let collection: FeatureCollection = serde_json::from_str(r#"{
+//!     "type": "FeatureCollection",
+//!     "features": [
+//!         { "type": "Feature", "geometry": { "type": "Point", "coordinates": [1, 2] }, "properties": null },
+//!         { "type": "Feature", "geometry": null, "properties": null }
+//!     ]
+//! }"#)
+//! .unwrap();
+//!
+//! fn located(collection: &FeatureCollection) -> FeatureCollectionRef<'_> {
+//!     let located = collection.features.iter().filter(|feature| feature.geometry.is_some());
+//!     FeatureCollectionRef::from(collection).with_features(located)
+//! }
+//!
+//! let view = located(&collection);
+//! assert_eq!(view.features.len(), 1);
+//! let geometry = collection.features[0].geometry.as_ref().unwrap();
+//! assert!(std::ptr::eq(view.features[0].geometry.unwrap(), geometry));
+//! assert_eq!(
+//!     view.to_string(),
+//!     r#"{"features":[{"geometry":{"coordinates":[1.0,2.0],"type":"Point"},"properties":{},"type":"Feature"}],"type":"FeatureCollection"}"#
+//! );
+//! ```
+//!
+//! [`FeatureRef`]: struct.FeatureRef.html
+//! [`FeatureCollectionRef`]: struct.FeatureCollectionRef.html
+//! [`GeoJsonRef`]: enum.GeoJsonRef.html
+
+use crate::feature::Id;
+use crate::json::{JsonObject, JsonValue, Serialize, Serializer};
+use crate::{Bbox, Feature, FeatureCollection, GeoJson, Geometry};
+use serde::ser::SerializeMap;
+use std::fmt;
+use std::iter::FromIterator;
+
+/// A view of a [`Feature`](../struct.Feature.html), with a reference to each of its members
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FeatureRef<'a> {
+    pub bbox: Option<&'a Bbox>,
+    pub geometry: Option<&'a Geometry>,
+    pub id: Option<&'a Id>,
+    pub properties: Option<&'a JsonObject>,
+    pub foreign_members: Option<&'a JsonObject>,
+}
+
+impl<'a> FeatureRef<'a> {
+    /// The value of the property `key`, if the feature has it
+    pub fn property(&self, key: impl AsRef<str>) -> Option<&'a JsonValue> {
+        self.properties?.get(key.as_ref())
+    }
+
+    /// Copy the members, to get a `Feature` which no longer borrows them
+    pub fn to_feature(&self) -> Feature {
+        Feature {
+            bbox: self.bbox.cloned(),
+            geometry: self.geometry.cloned(),
+            id: self.id.cloned(),
+            properties: self.properties.cloned(),
+            foreign_members: self.foreign_members.cloned(),
+        }
+    }
+}
+
+impl<'a> From<&'a Feature> for FeatureRef<'a> {
+    fn from(feature: &'a Feature) -> Self {
+        FeatureRef {
+            bbox: feature.bbox.as_ref(),
+            geometry: feature.geometry.as_ref(),
+            id: feature.id.as_ref(),
+            properties: feature.properties.as_ref(),
+            foreign_members: feature.foreign_members.as_ref(),
+        }
+    }
+}
+
+/// A view of a [`FeatureCollection`](../struct.FeatureCollection.html), with a reference to
+/// each of its members and a view of each of its features
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FeatureCollectionRef<'a> {
+    pub bbox: Option<&'a Bbox>,
+    pub features: Vec<FeatureRef<'a>>,
+    pub foreign_members: Option<&'a JsonObject>,
+}
+
+impl<'a> FeatureCollectionRef<'a> {
+    /// This view with `features` in place of its features, keeping its `bbox` and foreign
+    /// members
+    pub fn with_features<F: Into<FeatureRef<'a>>>(
+        self,
+        features: impl IntoIterator<Item = F>,
+    ) -> Self {
+        FeatureCollectionRef {
+            features: features.into_iter().map(Into::into).collect(),
+            ..self
+        }
+    }
+
+    /// Copy the members, to get a `FeatureCollection` which no longer borrows them
+    pub fn to_feature_collection(&self) -> FeatureCollection {
+        FeatureCollection {
+            bbox: self.bbox.cloned(),
+            features: self.features.iter().map(FeatureRef::to_feature).collect(),
+            foreign_members: self.foreign_members.cloned(),
+        }
+    }
+}
+
+impl<'a> From<&'a FeatureCollection> for FeatureCollectionRef<'a> {
+    fn from(collection: &'a FeatureCollection) -> Self {
+        FeatureCollectionRef {
+            bbox: collection.bbox.as_ref(),
+            features: collection.features.iter().map(FeatureRef::from).collect(),
+            foreign_members: collection.foreign_members.as_ref(),
+        }
+    }
+}
+
+impl<'a, F: Into<FeatureRef<'a>>> FromIterator<F> for FeatureCollectionRef<'a> {
+    fn from_iter<I: IntoIterator<Item = F>>(iter: I) -> Self {
+        FeatureCollectionRef::default().with_features(iter)
+    }
+}
+
+/// A view of a [`GeoJson`](../enum.GeoJson.html) object
+#[derive(Clone, Debug, PartialEq)]
+pub enum GeoJsonRef<'a> {
+    Geometry(&'a Geometry),
+    Feature(FeatureRef<'a>),
+    FeatureCollection(FeatureCollectionRef<'a>),
+}
+
+impl GeoJsonRef<'_> {
+    /// Copy the members, to get a `GeoJson` object which no longer borrows them
+    pub fn to_geojson(&self) -> GeoJson {
+        match self {
+            GeoJsonRef::Geometry(geometry) => GeoJson::Geometry((*geometry).clone()),
+            GeoJsonRef::Feature(feature) => GeoJson::Feature(feature.to_feature()),
+            GeoJsonRef::FeatureCollection(collection) => {
+                GeoJson::FeatureCollection(collection.to_feature_collection())
+            }
+        }
+    }
+}
+
+impl<'a> From<&'a GeoJson> for GeoJsonRef<'a> {
+    fn from(geojson: &'a GeoJson) -> Self {
+        match geojson {
+            GeoJson::Geometry(geometry) => GeoJsonRef::Geometry(geometry),
+            GeoJson::Feature(feature) => GeoJsonRef::Feature(feature.into()),
+            GeoJson::FeatureCollection(collection) => {
+                GeoJsonRef::FeatureCollection(collection.into())
+            }
+        }
+    }
+}
+
+impl<'a> From<&'a Geometry> for GeoJsonRef<'a> {
+    fn from(geometry: &'a Geometry) -> Self {
+        GeoJsonRef::Geometry(geometry)
+    }
+}
+
+impl<'a> From<FeatureRef<'a>> for GeoJsonRef<'a> {
+    fn from(feature: FeatureRef<'a>) -> Self {
+        GeoJsonRef::Feature(feature)
+    }
+}
+
+impl<'a> From<FeatureCollectionRef<'a>> for GeoJsonRef<'a> {
+    fn from(collection: FeatureCollectionRef<'a>) -> Self {
+        GeoJsonRef::FeatureCollection(collection)
+    }
+}
+
+/// The value of a member of a view
+enum Member<'a> {
+    Type(&'static str),
+    Json(&'a JsonValue),
+    Bbox(&'a Bbox),
+    Geometry(Option<&'a Geometry>),
+    Id(&'a Id),
+    Properties(Option<&'a JsonObject>),
+    Features(&'a [FeatureRef<'a>]),
+}
+
+impl Serialize for Member<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Member::Type(type_) => type_.serialize(serializer),
+            Member::Json(value) => value.serialize(serializer),
+            Member::Bbox(bbox) => bbox.serialize(serializer),
+            Member::Geometry(geometry) => geometry.serialize(serializer),
+            Member::Id(id) => id.serialize(serializer),
+            Member::Properties(Some(properties)) => properties.serialize(serializer),
+            Member::Properties(None) => serializer.serialize_map(Some(0))?.end(),
+            Member::Features(features) => serializer.collect_seq(*features),
+        }
+    }
+}
+
+/// Serialize the members of an object as its `JsonObject` would be, with a member for each of
+/// `keys`, in the order of a `JsonObject` of them, and `member` giving the value of each key
+fn serialize_object<'a, S: Serializer>(
+    serializer: S,
+    keys: impl IntoIterator<Item = &'a str>,
+    member: impl Fn(&str) -> Option<Member<'a>>,
+) -> Result<S::Ok, S::Error> {
+    let keys: JsonObject = keys
+        .into_iter()
+        .map(|key| (key.to_string(), JsonValue::Null))
+        .collect();
+    let mut map = serializer.serialize_map(Some(keys.len()))?;
+    for key in keys.keys() {
+        if let Some(value) = member(key) {
+            map.serialize_entry(key, &value)?;
+        }
+    }
+    map.end()
+}
+
+fn foreign_keys(foreign_members: Option<&JsonObject>) -> impl Iterator<Item = &str> {
+    foreign_members
+        .into_iter()
+        .flat_map(|members| members.keys().map(String::as_str))
+}
+
+impl Serialize for FeatureRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[cfg(feature = "compact")]
+        if !serializer.is_human_readable() {
+            return crate::compact::serialize_feature_parts(
+                self.bbox,
+                self.geometry,
+                self.id,
+                self.properties,
+                self.foreign_members,
+                serializer,
+            );
+        }
+        let keys = ["type", "geometry", "properties"]
+            .iter()
+            .copied()
+            .chain(self.bbox.map(|_| "bbox"))
+            .chain(self.id.map(|_| "id"))
+            .chain(foreign_keys(self.foreign_members));
+        serialize_object(serializer, keys, |key| match key {
+            "type" => Some(Member::Type("Feature")),
+            "geometry" => Some(Member::Geometry(self.geometry)),
+            "properties" => Some(Member::Properties(self.properties)),
+            "bbox" => self.bbox.map(Member::Bbox),
+            "id" => self.id.map(Member::Id),
+            key => self.foreign_members?.get(key).map(Member::Json),
+        })
+    }
+}
+
+impl Serialize for FeatureCollectionRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[cfg(feature = "compact")]
+        if !serializer.is_human_readable() {
+            return crate::compact::serialize_feature_collection_parts(
+                self.bbox,
+                &self.features,
+                self.foreign_members,
+                serializer,
+            );
+        }
+        let keys = ["type", "features"]
+            .iter()
+            .copied()
+            .chain(self.bbox.map(|_| "bbox"))
+            .chain(foreign_keys(self.foreign_members));
+        serialize_object(serializer, keys, |key| match key {
+            "type" => Some(Member::Type("FeatureCollection")),
+            "features" => Some(Member::Features(&self.features)),
+            "bbox" => self.bbox.map(Member::Bbox),
+            key => self.foreign_members?.get(key).map(Member::Json),
+        })
+    }
+}
+
+impl Serialize for GeoJsonRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[cfg(feature = "compact")]
+        if !serializer.is_human_readable() {
+            return match self {
+                GeoJsonRef::Geometry(geometry) => crate::compact::GeoJsonRef::Geometry(geometry),
+                GeoJsonRef::Feature(feature) => crate::compact::GeoJsonRef::Feature(feature),
+                GeoJsonRef::FeatureCollection(collection) => {
+                    crate::compact::GeoJsonRef::FeatureCollection(collection)
+                }
+            }
+            .serialize(serializer);
+        }
+        match self {
+            GeoJsonRef::Geometry(geometry) => geometry.serialize(serializer),
+            GeoJsonRef::Feature(feature) => feature.serialize(serializer),
+            GeoJsonRef::FeatureCollection(collection) => collection.serialize(serializer),
+        }
+    }
+}
+
+impl fmt::Display for FeatureRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        ::serde_json::to_string(self)
+            .map_err(|_| fmt::Error)
+            .and_then(|s| f.write_str(&s))
+    }
+}
+
+impl fmt::Display for FeatureCollectionRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        ::serde_json::to_string(self)
+            .map_err(|_| fmt::Error)
+            .and_then(|s| f.write_str(&s))
+    }
+}
+
+impl fmt::Display for GeoJsonRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        ::serde_json::to_string(self)
+            .map_err(|_| fmt::Error)
+            .and_then(|s| f.write_str(&s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FeatureCollectionRef, FeatureRef, GeoJsonRef};
+    use crate::json::JsonObject;
+    use crate::GeoJson;
+
+    #[test]
+    fn serializes_as_owned() {
+        let json = r#"{
+            "type": "FeatureCollection",
+            "bbox": [0, 0, 1, 1],
+            "features": [
+                { "type": "Feature", "id": 1, "bbox": [1, 1, 1, 1], "geometry": { "type": "Point", "coordinates": [1, 1] }, "properties": { "a": 1 }, "a": "foreign" },
+                { "type": "Feature", "geometry": null, "properties": null }
+            ],
+            "title": "foreign"
+        }"#;
+        let geojson: GeoJson = json.parse().unwrap();
+        let view = GeoJsonRef::from(&geojson);
+        assert_eq!(view.to_string(), geojson.to_string());
+        assert_eq!(
+            serde_cbor::to_vec(&view).unwrap(),
+            serde_cbor::to_vec(&geojson).unwrap()
+        );
+        assert_eq!(view.to_geojson(), geojson);
+
+        let collection = match &geojson {
+            GeoJson::FeatureCollection(collection) => collection,
+            _ => unreachable!(),
+        };
+        let first = &collection.features[0];
+        let properties = JsonObject::new();
+        let relabelled = FeatureRef {
+            properties: Some(&properties),
+            ..first.into()
+        };
+        assert_eq!(relabelled.property("a"), None);
+        assert_eq!(FeatureRef::from(first).property("a"), Some(&1.into()));
+        let mut expected = first.clone();
+        expected.properties = Some(properties.clone());
+        assert_eq!(relabelled.to_string(), expected.to_string());
+
+        let views: FeatureCollectionRef = collection.features.iter().rev().collect();
+        assert_eq!(views.features[1], FeatureRef::from(first));
+        assert_eq!(views.bbox, None);
+    }
+}
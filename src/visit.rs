@@ -0,0 +1,208 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Walking every feature, geometry and position of a GeoJSON object with a [`VisitMut`].
+//!
+//! A visitor implements the methods for the parts it is interested in, and
+//! [`GeoJson::walk`](../enum.GeoJson.html#method.walk) calls them on each part in document
+//! order, with mutable access. By default each method walks the parts nested within its
+//! object with the `walk_` function of the same name, so a visitor which overrides a method
+//! calls that function to carry on into the nested parts, or leaves it out to skip them. Any
+//! state the visitor accumulates, such as counts or extents, is read from it afterwards.
+//!
+//! # Example
+//!
+//! ```
+//! use geojson::visit::{self, VisitMut};
+//! use geojson::{Feature, GeoJson, Position};
+//!
+//! /// Rounds coordinates to 2 decimal places and removes the `"secret"` property
+//! #[derive(Default)]
+//! struct Scrub {
+//!     positions: usize,
+//! }
+//!
+//! impl VisitMut for Scrub {
+//!     fn visit_feature(&mut self, feature: &mut Feature) {
+//!         feature.remove_property("secret");
+//!         visit::walk_feature(self, feature);
+//!     }
+//!
+//!     fn visit_position(&mut self, position: &mut Position) {
+//!         for coordinate in position.iter_mut() {
+//!             *coordinate = (*coordinate * 100.0).round() / 100.0;
+//!         }
+//!         self.positions += 1;
+//!     }
+//! }
+//!
+//! let mut geojson: GeoJson = r#"{
+//!     "type": "Feature",
+//!     "geometry": { "type": "LineString", "coordinates": [[1.2345, 2], [3, 4.5678]] },
+//!     "properties": { "secret": 1 }
+//! }"#
+//! .parse()
+//! .unwrap();
+//!
+//! let mut scrub = Scrub::default();
+//! geojson.walk(&mut scrub);
+//! assert_eq!(scrub.positions, 2);
+//! assert_eq!(
+//!     geojson.to_string(),
+//!     r#"{"geometry":{"coordinates":[[1.23,2.0],[3.0,4.57]],"type":"LineString"},"properties":{},"type":"Feature"}"#
+//! );
+//! ```
+//!
+//! [`VisitMut`]: trait.VisitMut.html
+
+use crate::{Feature, FeatureCollection, GeoJson, Geometry, Position, Value};
+
+/// Callbacks for each part of a GeoJSON object, called by
+/// [`GeoJson::walk`](../enum.GeoJson.html#method.walk)
+///
+/// See the [module documentation](index.html).
+pub trait VisitMut {
+    /// Visit a feature collection, and by default each of its features
+    fn visit_feature_collection(&mut self, collection: &mut FeatureCollection) {
+        walk_feature_collection(self, collection)
+    }
+
+    /// Visit a feature, and by default its geometry
+    fn visit_feature(&mut self, feature: &mut Feature) {
+        walk_feature(self, feature)
+    }
+
+    /// Visit a geometry, and by default each of its positions, or each geometry of a
+    /// `GeometryCollection`
+    fn visit_geometry(&mut self, geometry: &mut Geometry) {
+        walk_geometry(self, geometry)
+    }
+
+    /// Visit a position
+    fn visit_position(&mut self, _position: &mut Position) {}
+}
+
+/// Visit each feature of `collection`
+pub fn walk_feature_collection<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    collection: &mut FeatureCollection,
+) {
+    for feature in &mut collection.features {
+        visitor.visit_feature(feature);
+    }
+}
+
+/// Visit the geometry of `feature`, if it has one
+pub fn walk_feature<V: VisitMut + ?Sized>(visitor: &mut V, feature: &mut Feature) {
+    if let Some(geometry) = &mut feature.geometry {
+        visitor.visit_geometry(geometry);
+    }
+}
+
+/// Visit each position of `geometry`, or each geometry of a `GeometryCollection`
+pub fn walk_geometry<V: VisitMut + ?Sized>(visitor: &mut V, geometry: &mut Geometry) {
+    match &mut geometry.value {
+        Value::Point(position) => visitor.visit_position(position),
+        Value::MultiPoint(positions) | Value::LineString(positions) => {
+            positions.iter_mut().for_each(|p| visitor.visit_position(p))
+        }
+        Value::MultiLineString(lines) | Value::Polygon(lines) => lines
+            .iter_mut()
+            .flatten()
+            .for_each(|p| visitor.visit_position(p)),
+        Value::MultiPolygon(polygons) => polygons
+            .iter_mut()
+            .flatten()
+            .flatten()
+            .for_each(|p| visitor.visit_position(p)),
+        Value::GeometryCollection(geometries) => geometries
+            .iter_mut()
+            .for_each(|geometry| visitor.visit_geometry(geometry)),
+    }
+}
+
+impl GeoJson {
+    /// Call the methods of `visitor` on this object and, unless they stop short, every
+    /// feature, geometry and position within it.
+    ///
+    /// See the [`visit`](visit/index.html) module.
+    pub fn walk<V: VisitMut + ?Sized>(&mut self, visitor: &mut V) {
+        match self {
+            GeoJson::Geometry(geometry) => visitor.visit_geometry(geometry),
+            GeoJson::Feature(feature) => visitor.visit_feature(feature),
+            GeoJson::FeatureCollection(collection) => visitor.visit_feature_collection(collection),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{walk_geometry, VisitMut};
+    use crate::{GeoJson, Geometry, Position, Value};
+
+    /// Records the depth of each geometry, and swaps the coordinates of each position
+    #[derive(Default)]
+    struct Depths {
+        depth: usize,
+        geometries: Vec<(usize, &'static str)>,
+        positions: usize,
+    }
+
+    impl VisitMut for Depths {
+        fn visit_geometry(&mut self, geometry: &mut Geometry) {
+            self.geometries
+                .push((self.depth, geometry.value.type_str()));
+            if let Value::GeometryCollection(_) = geometry.value {
+                self.depth += 1;
+                walk_geometry(self, geometry);
+                self.depth -= 1;
+            } else {
+                walk_geometry(self, geometry);
+            }
+        }
+
+        fn visit_position(&mut self, position: &mut Position) {
+            position.swap(0, 1);
+            self.positions += 1;
+        }
+    }
+
+    #[test]
+    fn walk_geojson() {
+        let mut geojson: GeoJson = r#"{"type":"FeatureCollection","features":[
+            {"type":"Feature","geometry":null,"properties":null},
+            {"type":"Feature","properties":null,"geometry":{"type":"GeometryCollection","geometries":[
+                {"type":"Polygon","coordinates":[[[0,0],[1,0],[1,1],[0,0]]]},
+                {"type":"GeometryCollection","geometries":[{"type":"Point","coordinates":[1,2]}]}
+            ]}}
+        ]}"#
+        .parse()
+        .unwrap();
+
+        let mut depths = Depths::default();
+        geojson.walk(&mut depths);
+        assert_eq!(
+            depths.geometries,
+            [
+                (0, "GeometryCollection"),
+                (1, "Polygon"),
+                (1, "GeometryCollection"),
+                (2, "Point")
+            ]
+        );
+        assert_eq!(depths.positions, 5);
+
+        assert!(geojson.to_string().contains(r#""coordinates":[2.0,1.0]"#));
+    }
+}
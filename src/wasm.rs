@@ -0,0 +1,88 @@
+// Copyright 2015 The GeoRust Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//  http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversion between GeoJSON objects and the JavaScript values of
+//! [`wasm-bindgen`](https://docs.rs/wasm-bindgen), to process GeoJSON in the browser with
+//! Rust compiled to WebAssembly.
+//!
+//! Objects are converted with [`serde-wasm-bindgen`](https://docs.rs/serde-wasm-bindgen),
+//! without writing and parsing JSON text, into the same plain objects and arrays which
+//! `JSON.parse` would create. Nothing this feature enables reads from or writes to the
+//! filesystem, so it builds for `wasm32-unknown-unknown`, e.g. with
+//! `cargo build --profile wasm --target wasm32-unknown-unknown --features wasm`.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use geojson::FeatureCollection;
+//! use std::convert::TryFrom;
+//! use wasm_bindgen::prelude::*;
+//!
+//! /// Keep the features of a collection from JavaScript which have a name
+//! #[wasm_bindgen]
+//! pub fn named_features(collection: JsValue) -> Result<JsValue, JsValue> {
+//!     let mut collection = FeatureCollection::try_from(collection)
+//!         .map_err(|error| JsValue::from_str(&error.to_string()))?;
+//!     collection.retain_features(|feature| feature.contains_property("name"));
+//!     collection
+//!         .to_js_value()
+//!         .map_err(|error| JsValue::from_str(&error.to_string()))
+//! }
+//! ```
+
+use crate::errors::Error;
+use crate::json::Serialize;
+use crate::{Feature, FeatureCollection, GeoJson, Geometry};
+use serde_wasm_bindgen::Serializer;
+use std::convert::TryFrom;
+use wasm_bindgen::JsValue;
+
+macro_rules! impl_js_value {
+    ($($type:ident),*) => {
+        $(
+            impl $type {
+                /// Convert to a JavaScript value, with the JSON objects of GeoJSON as plain
+                /// objects.
+                pub fn to_js_value(&self) -> Result<JsValue, Error> {
+                    self.serialize(&Serializer::json_compatible())
+                        .map_err(|error| Error::WasmConversion(error.to_string()))
+                }
+
+                /// Convert from a JavaScript value, such as one created by `JSON.parse`.
+                pub fn from_js_value(value: JsValue) -> Result<Self, Error> {
+                    serde_wasm_bindgen::from_value(value)
+                        .map_err(|error| Error::WasmConversion(error.to_string()))
+                }
+            }
+
+            impl TryFrom<JsValue> for $type {
+                type Error = Error;
+
+                fn try_from(value: JsValue) -> Result<Self, Self::Error> {
+                    $type::from_js_value(value)
+                }
+            }
+
+            impl TryFrom<&$type> for JsValue {
+                type Error = Error;
+
+                fn try_from(value: &$type) -> Result<Self, Self::Error> {
+                    value.to_js_value()
+                }
+            }
+        )*
+    };
+}
+
+impl_js_value!(GeoJson, Geometry, Feature, FeatureCollection);
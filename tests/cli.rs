@@ -0,0 +1,67 @@
+#![cfg(feature = "cli")]
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn geojson(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_geojson"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> &str {
+    std::str::from_utf8(&output.stdout).unwrap()
+}
+
+#[test]
+fn commands() {
+    let collection = r#"{"type":"FeatureCollection","features":[
+        {"type":"Feature","geometry":{"type":"Polygon","coordinates":[[[0,0],[2,2],[2,0],[0,2],[0,0]]]},"properties":{"a":1}},
+        {"type":"Feature","geometry":{"type":"Point","coordinates":[1.23456,2]},"properties":{}}
+    ]}"#;
+
+    let output = geojson(&["validate"], collection);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stdout(&output),
+        "/features/0/geometry/coordinates/0: ring intersects itself\n"
+    );
+    let point = r#"{"type":"Point","coordinates":[1,2]}"#;
+    assert!(geojson(&["validate", "-"], point).status.success());
+
+    let output = geojson(&["stats"], collection);
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with("features: 2\ngeometries: 2\nrings: 1\npositions: 6\n"));
+    assert!(stdout(&output).ends_with("bbox: [0.0,0.0,2.0,2.0]\n"));
+
+    let output = geojson(&["to-ndjson", "--precision", "1"], collection);
+    let lines = stdout(&output);
+    assert_eq!(lines.lines().count(), 2);
+    assert!(lines.contains(r#"{"coordinates":[1.2,2.0],"type":"Point"}"#));
+    let output = geojson(&["from-ndjson"], lines);
+    let roundtrip: geojson::FeatureCollection = serde_json::from_str(stdout(&output)).unwrap();
+    assert_eq!(roundtrip.features.len(), 2);
+
+    let output = geojson(&["format", "--pretty", "1"], point);
+    assert_eq!(
+        stdout(&output),
+        "{\n \"coordinates\": [\n  1.0,\n  2.0\n ],\n \"type\": \"Point\"\n}\n"
+    );
+
+    assert_eq!(
+        geojson(&["format", "--unknown"], point).status.code(),
+        Some(2)
+    );
+    assert_eq!(geojson(&["format"], "{").status.code(), Some(1));
+}
@@ -1,7 +1,6 @@
 #[cfg(test)]
 mod roundtrip_tests {
     use geojson::GeoJson;
-    use serde_json;
     use std::fs::File;
     use std::io::prelude::*;
 
@@ -48,7 +47,7 @@ mod roundtrip_tests {
     /// Verifies that we can parse and then reencode geojson back to the same representation
     /// without losing any data.
     fn test_round_trip(file_path: &str) {
-        let mut file = File::open(&file_path).unwrap();
+        let mut file = File::open(file_path).unwrap();
         let mut file_contents = String::new();
         let _ = file.read_to_string(&mut file_contents);
 
@@ -64,4 +63,63 @@ mod roundtrip_tests {
 
         assert_eq!(original_json, roundtrip_json)
     }
+
+    /// Coordinates are written in their shortest form which parses back to the same `f64`, so
+    /// repeatedly parsing and writing them never drifts.
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_float_round_trip() {
+        use geojson::{Geometry, Position, Value, WriterOptions};
+
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut coordinates: Vec<f64> = vec![
+            0.1 + 0.2,
+            f64::MIN_POSITIVE,
+            f64::MAX,
+            f64::EPSILON,
+            5e-324,
+            -2.225_073_858_507_201e-308,
+            9_007_199_254_740_992.0,
+            1e21,
+            1e-7,
+        ];
+        while coordinates.len() < 10_000 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let coordinate = f64::from_bits(state);
+            if coordinate.is_finite() {
+                coordinates.push(coordinate);
+            }
+        }
+
+        for chunk in coordinates.chunks(2) {
+            let geometry = Geometry::new(Value::Point(Position::from_slice(chunk)));
+            let written = geometry
+                .to_string_with(&WriterOptions::new().full_precision())
+                .unwrap();
+            assert_eq!(written, geometry.to_string());
+            let parsed: Geometry = serde_json::from_str(&written).unwrap();
+            match &parsed.value {
+                Value::Point(position) => {
+                    for (parsed, original) in position.iter().zip(chunk) {
+                        assert_eq!(parsed.to_bits(), original.to_bits(), "{}", written);
+                    }
+                }
+                value => panic!("unexpected value {:?}", value),
+            }
+            assert_eq!(parsed.to_string(), written);
+            for number in serde_json::from_str::<serde_json::Value>(&written).unwrap()
+                ["coordinates"]
+                .as_array()
+                .unwrap()
+            {
+                let digits = number.to_string();
+                // At most 17 significant digits are needed for any `f64`
+                let significant = digits.split('e').next().unwrap().replace(['-', '.'], "");
+                let significant = significant.trim_start_matches('0').trim_end_matches('0');
+                assert!(significant.len() <= 17, "{}", digits);
+            }
+        }
+    }
 }